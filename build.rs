@@ -0,0 +1,23 @@
+//! Build script: records the git commit this binary was built from
+//!
+//! Sets `HF_VALIDATOR_GIT_COMMIT` for `Provenance::capture`. Builds outside
+//! a git checkout (e.g. from a published crate) leave it unset.
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=.git/packed-refs");
+
+    let commit = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|commit| !commit.is_empty());
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=HF_VALIDATOR_GIT_COMMIT={}", commit);
+    }
+}
@@ -55,15 +55,19 @@ use cargo_metadata;
 use reqwest;
 
 
-use std::path::Path;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
 
 use arrow::array::{StringArray, UInt32Array, UInt64Array, Float32Array, BooleanArray};
 use arrow::datatypes::{DataType, Field, Schema};
-use arrow::record_batch::RecordBatch;
 use parquet::arrow::ArrowWriter;
 use parquet::file::properties::WriterProperties;
 use std::sync::Arc;
+use crate::dataset_io::{
+    build_record_batch, optional_string, optional_value, print_skipped_summary, read_parquet_batches, select_columns,
+    typed_column, write_provenance, write_run_manifest, Provenance, SkippedFile,
+};
+use crate::source_scan::mask_comments_and_strings;
 
 /// Represents different types of data extraction phases for Cargo projects
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -80,6 +84,8 @@ pub enum CargoExtractionPhase {
     EcosystemAnalysis,
     /// Analyze git history and development patterns
     VersionHistory,
+    /// One summary row per project, built from the other phases' records
+    Overview,
 }
 
 impl CargoExtractionPhase {
@@ -92,8 +98,20 @@ impl CargoExtractionPhase {
             CargoExtractionPhase::BuildAnalysis => "build_analysis",
             CargoExtractionPhase::EcosystemAnalysis => "ecosystem_analysis",
             CargoExtractionPhase::VersionHistory => "version_history",
+            CargoExtractionPhase::Overview => "overview",
         }
     }
+
+    /// Whether the phase can run on a single-file package (cargo-script)
+    /// 
+    /// Cargo metadata, build configuration and ecosystem lookups need a
+    /// `Cargo.toml` project, and version history needs a repository root.
+    pub fn supports_single_file_packages(&self) -> bool {
+        matches!(
+            self,
+            CargoExtractionPhase::ProjectMetadata | CargoExtractionPhase::SourceCodeAnalysis | CargoExtractionPhase::Overview
+        )
+    }
 }
 
 /// Main record structure for Cargo project analysis data
@@ -101,7 +119,7 @@ impl CargoExtractionPhase {
 /// This structure captures comprehensive information about Cargo projects
 /// and their dependencies, designed for machine learning applications
 /// focused on understanding Rust project patterns and ecosystem dynamics.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct CargoProjectRecord {
     // === Identification ===
     /// Unique identifier for this record
@@ -134,10 +152,22 @@ pub struct CargoProjectRecord {
     pub keywords: Option<String>, // JSON array as string
     /// Categories from Cargo.toml
     pub categories: Option<String>, // JSON array as string
+    /// Rust edition (e.g., "2021")
+    #[serde(default)]
+    pub edition: Option<String>,
+    /// Minimum supported Rust version (`rust-version` in Cargo.toml)
+    #[serde(default)]
+    pub msrv: Option<String>,
     
     // === Source Code Metrics ===
     /// Total lines of Rust code in the project
     pub lines_of_code: u32,
+    /// Lines containing code (the SLOC metric)
+    pub code_lines: u32,
+    /// Lines containing only comments (including doc comments)
+    pub comment_lines: u32,
+    /// Empty or whitespace-only lines
+    pub blank_lines: u32,
     /// Number of Rust source files
     pub source_file_count: u32,
     /// Number of test files
@@ -150,6 +180,8 @@ pub struct CargoProjectRecord {
     pub complexity_score: f32,
     /// Documentation coverage percentage
     pub documentation_coverage: f32,
+    /// Panic and error-handling pattern counts as JSON
+    pub error_handling_data: Option<String>,
     
     // === Dependency Information ===
     /// Number of direct dependencies
@@ -195,6 +227,10 @@ pub struct CargoProjectRecord {
     /// Release frequency (releases per year)
     pub release_frequency: Option<f32>,
     
+    // === Overview ===
+    /// Project health in [0, 1] (overview records only)
+    pub health_score: Option<f32>,
+    
     // === Processing Metadata ===
     /// Time taken to process this record (milliseconds)
     pub processing_time_ms: u64,
@@ -211,8 +247,11 @@ pub struct CargoProjectRecord {
 /// Detailed dependency information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyInfo {
-    /// Name of the dependency
+    /// Name of the dependency (the real package name, even when renamed)
     pub name: String,
+    /// Name the dependency is imported under (`alias = { package = "name" }`)
+    #[serde(default)]
+    pub rename: Option<String>,
     /// Version requirement (e.g., "^1.0", "=0.2.5")
     pub version_req: String,
     /// Resolved version (if available)
@@ -223,12 +262,71 @@ pub struct DependencyInfo {
     pub default_features: bool,
     /// Specific features enabled
     pub features: Vec<String>,
-    /// Dependency source (crates.io, git, path, etc.)
+    /// Dependency source, normalized by `DependencySource` (crates-io, registry, git, path)
     pub source: String,
     /// Whether this is a dev dependency
     pub is_dev: bool,
     /// Whether this is a build dependency
     pub is_build: bool,
+    /// Latest published (non-yanked) version on crates.io
+    #[serde(default)]
+    pub latest_version: Option<String>,
+    /// Number of published releases newer than the resolved version
+    #[serde(default)]
+    pub versions_behind: Option<u32>,
+    /// Whether a newer release than the resolved version exists
+    #[serde(default)]
+    pub is_outdated: Option<bool>,
+}
+
+/// Where a dependency comes from, normalized from cargo's source id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencySource {
+    CratesIo,
+    Registry,
+    Git,
+    Path,
+}
+
+impl DependencySource {
+    /// Classify a cargo source id such as `registry+https://github.com/rust-lang/crates.io-index`
+    /// (`None` for path dependencies)
+    pub fn from_source_id(source: Option<&str>) -> Self {
+        match source {
+            None => Self::Path,
+            Some(source) if source.starts_with("path+") => Self::Path,
+            Some(source) if source.starts_with("git+") => Self::Git,
+            Some("registry+https://github.com/rust-lang/crates.io-index") | Some("sparse+https://index.crates.io/") => Self::CratesIo,
+            Some(_) => Self::Registry,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CratesIo => "crates-io",
+            Self::Registry => "registry",
+            Self::Git => "git",
+            Self::Path => "path",
+        }
+    }
+}
+
+/// Compare a resolved version against the published releases of a crate
+/// 
+/// Returns the latest release and how many releases are newer than `resolved`.
+/// Pre-releases are ignored unless the resolved version is itself a pre-release.
+pub fn compute_dependency_freshness(resolved: &str, published: &[String]) -> Option<(String, u32)> {
+    use cargo_metadata::semver::Version;
+
+    let resolved = Version::parse(resolved).ok()?;
+    let releases: Vec<Version> = published.iter()
+        .filter_map(|v| Version::parse(v).ok())
+        .filter(|v| v.pre.is_empty() || !resolved.pre.is_empty())
+        .collect();
+
+    let latest = releases.iter().max()?.clone();
+    let versions_behind = releases.iter().filter(|v| **v > resolved).count() as u32;
+    Some((latest.to_string(), versions_behind))
 }
 
 /// Source code file analysis
@@ -258,6 +356,342 @@ pub struct SourceFileInfo {
     pub doc_coverage: f32,
 }
 
+/// HTTP settings for ecosystem (crates.io / GitHub) fetches
+/// 
+/// The client is built once per extractor so that a hung connection cannot
+/// stall the whole analysis: both connecting and the full request are bounded.
+#[derive(Debug, Clone)]
+pub struct HttpClientConfig {
+    /// Maximum time to establish a connection
+    pub connect_timeout: std::time::Duration,
+    /// Maximum time for a whole request, including reading the response body
+    pub request_timeout: std::time::Duration,
+    /// Optional HTTPS proxy URL (defaults to the `HTTPS_PROXY` environment variable)
+    pub proxy: Option<String>,
+    /// User-Agent header (required by the GitHub API)
+    pub user_agent: String,
+    /// Base URL of the crates.io API
+    pub crates_io_api_url: String,
+    /// Base URL of the GitHub API
+    pub github_api_url: String,
+    /// Skip all network requests (ecosystem fields are left empty)
+    pub offline: bool,
+    /// Minimum delay between two requests, to stay under API rate limits
+    /// 
+    /// Requests are issued one at a time, so this bounds the request rate.
+    /// Defaults to one second, the rate the crates.io crawler policy allows.
+    pub min_request_interval: std::time::Duration,
+    /// Directory persisting each package's ecosystem metrics as soon as they are fetched
+    /// 
+    /// Packages found here are not fetched again, so an interrupted run
+    /// (e.g. after hitting a rate limit) resumes with the remaining ones.
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: std::time::Duration::from_secs(10),
+            request_timeout: std::time::Duration::from_secs(30),
+            proxy: std::env::var("HTTPS_PROXY")
+                .or_else(|_| std::env::var("https_proxy"))
+                .ok()
+                .filter(|p| !p.is_empty()),
+            user_agent: "cargo2hf-extractor".to_string(),
+            crates_io_api_url: "https://crates.io/api/v1".to_string(),
+            github_api_url: "https://api.github.com".to_string(),
+            offline: false,
+            min_request_interval: std::time::Duration::from_secs(1),
+            cache_dir: None,
+        }
+    }
+}
+
+impl HttpClientConfig {
+    /// Build a `reqwest::Client` from this configuration
+    pub fn build_client(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.request_timeout)
+            .user_agent(self.user_agent.clone());
+
+        if let Some(proxy_url) = &self.proxy {
+            let proxy = reqwest::Proxy::https(proxy_url)
+                .with_context(|| format!("Invalid HTTPS proxy URL: {}", proxy_url))?;
+            builder = builder.proxy(proxy);
+        }
+
+        builder.build().context("Failed to build HTTP client")
+    }
+}
+
+/// Outcome of one JSON request
+enum JsonResponse {
+    Found(serde_json::Value),
+    /// HTTP 404: the service answered that there is nothing to fetch
+    NotFound,
+    /// Offline mode, network errors, timeouts, other statuses or invalid JSON
+    Failed,
+}
+
+/// Ecosystem metrics of one package, as persisted in `HttpClientConfig::cache_dir`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct CachedEcosystemMetrics {
+    download_count: Option<u64>,
+    github_stars: Option<u32>,
+    github_forks: Option<u32>,
+    github_issues: Option<u32>,
+    last_updated: Option<u64>,
+}
+
+impl CachedEcosystemMetrics {
+    fn from_record(record: &CargoProjectRecord) -> Self {
+        Self {
+            download_count: record.download_count,
+            github_stars: record.github_stars,
+            github_forks: record.github_forks,
+            github_issues: record.github_issues,
+            last_updated: record.last_updated,
+        }
+    }
+
+    fn apply_to(&self, record: &mut CargoProjectRecord) {
+        record.download_count = self.download_count;
+        record.github_stars = self.github_stars;
+        record.github_forks = self.github_forks;
+        record.github_issues = self.github_issues;
+        record.last_updated = self.last_updated;
+    }
+
+    /// Cache file of a package; names and versions only hold path-safe characters
+    fn cache_path(cache_dir: &Path, name: &str, version: &str) -> PathBuf {
+        cache_dir.join(format!("{}-{}.json", name, version))
+    }
+
+    /// Cached metrics, or `None` when missing or unreadable (e.g. cut off mid-write)
+    fn load(path: &Path) -> Option<Self> {
+        serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+    }
+
+    /// Write through a temporary file so an interrupted write never leaves a partial entry
+    fn store(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, serde_json::to_string(self)?)?;
+        std::fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to write ecosystem cache entry: {}", path.display()))
+    }
+}
+
+/// Health score in [0, 1] for an overview record: the mean of the signals it has
+/// 
+/// Signals: documentation coverage, having a license, having test files and,
+/// when known, GitHub stars (log scale, 10k = 1) and commits (log scale, 1k = 1).
+fn compute_health_score(record: &CargoProjectRecord) -> f32 {
+    let log_scale = |value: u32, full: f32| ((value as f32 + 1.0).log10() / full.log10()).min(1.0);
+    let mut signals = vec![
+        record.documentation_coverage.clamp(0.0, 1.0),
+        if record.license.is_some() { 1.0 } else { 0.0 },
+        if record.test_file_count > 0 { 1.0 } else { 0.0 },
+    ];
+    signals.extend(record.github_stars.map(|stars| log_scale(stars, 10_000.0)));
+    signals.extend(record.commit_count.map(|commits| log_scale(commits, 1_000.0)));
+    signals.iter().sum::<f32>() / signals.len() as f32
+}
+
+/// Per-category line counts for Rust source
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineCounts {
+    /// Lines containing any code (possibly with a trailing comment)
+    pub code: u32,
+    /// Lines containing only comments
+    pub comment: u32,
+    /// Empty or whitespace-only lines
+    pub blank: u32,
+}
+
+impl LineCounts {
+    /// Classify every line of a Rust source file as code, comment, or blank
+    /// 
+    /// Handles `//` line comments, nested `/* */` block comments spanning lines,
+    /// and comment markers inside string literals. A line with both code and a
+    /// comment counts as code.
+    pub fn classify(content: &str) -> Self {
+        let mut counts = Self::default();
+        let mut block_depth = 0u32;
+        let mut in_string = false;
+
+        for line in content.lines() {
+            if line.trim().is_empty() {
+                counts.blank += 1;
+                continue;
+            }
+
+            let chars: Vec<char> = line.chars().collect();
+            let mut has_code = in_string;
+            let mut has_comment = false;
+            let mut i = 0;
+            while i < chars.len() {
+                let next = chars.get(i + 1).copied();
+                if block_depth > 0 {
+                    has_comment = true;
+                    match (chars[i], next) {
+                        ('/', Some('*')) => { block_depth += 1; i += 2; }
+                        ('*', Some('/')) => { block_depth -= 1; i += 2; }
+                        _ => i += 1,
+                    }
+                    continue;
+                }
+                if in_string {
+                    match chars[i] {
+                        '\\' => i += 2,
+                        '"' => { in_string = false; i += 1; }
+                        _ => i += 1,
+                    }
+                    continue;
+                }
+                match (chars[i], next) {
+                    ('/', Some('/')) => {
+                        has_comment = true;
+                        break;
+                    }
+                    ('/', Some('*')) => {
+                        has_comment = true;
+                        block_depth += 1;
+                        i += 2;
+                    }
+                    // A `'"'` char literal does not start a string
+                    ('\'', Some('"')) if chars.get(i + 2) == Some(&'\'') => {
+                        has_code = true;
+                        i += 3;
+                    }
+                    ('"', _) => {
+                        has_code = true;
+                        in_string = true;
+                        i += 1;
+                    }
+                    (c, _) => {
+                        if !c.is_whitespace() {
+                            has_code = true;
+                        }
+                        i += 1;
+                    }
+                }
+            }
+
+            if has_code {
+                counts.code += 1;
+            } else if has_comment {
+                counts.comment += 1;
+            } else {
+                counts.blank += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Accumulate another file's counts into this one
+    pub fn add(&mut self, other: &LineCounts) {
+        self.code += other.code;
+        self.comment += other.comment;
+        self.blank += other.blank;
+    }
+}
+
+/// Counts of panic and error-handling patterns in Rust source
+/// 
+/// Gathered by token scanning with comments and string literals removed,
+/// so mentions such as `"call unwrap()"` or `// may panic!` are not counted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ErrorHandlingStats {
+    /// `panic!(..)` invocations
+    pub panic_count: u32,
+    /// `.unwrap()` calls
+    pub unwrap_count: u32,
+    /// `.expect(..)` calls
+    pub expect_count: u32,
+    /// `unreachable!(..)` invocations
+    pub unreachable_count: u32,
+    /// Uses of the `?` operator
+    pub question_mark_count: u32,
+    /// Number of `fn` items
+    pub function_count: u32,
+    /// Number of `fn` items whose return type mentions `Result`
+    pub result_function_count: u32,
+    /// Whether the crate uses `anyhow`
+    pub uses_anyhow: bool,
+    /// Whether the crate uses `thiserror`
+    pub uses_thiserror: bool,
+    /// Whether at least half of the functions return `Result`
+    pub uses_result_pervasively: bool,
+}
+
+impl ErrorHandlingStats {
+    /// Scan a Rust source file for panic and error-handling patterns
+    pub fn scan(content: &str) -> Self {
+        let code: String = mask_comments_and_strings(content).into_iter().collect();
+        let count = |pattern: &str| code.matches(pattern).count() as u32;
+
+        // `?` is the try operator unless it starts a `?Sized`-style relaxed bound
+        let chars: Vec<char> = code.chars().collect();
+        let question_mark_count = chars.iter().enumerate()
+            .filter(|&(i, &c)| c == '?' && !chars.get(i + 1).map_or(false, |n| n.is_alphabetic()))
+            .count() as u32;
+
+        // Count functions and those returning Result, looking at the signature up to its body
+        let mut function_count = 0;
+        let mut result_function_count = 0;
+        for (pos, _) in code.match_indices("fn ") {
+            if pos > 0 && code[..pos].chars().last().map_or(false, |c| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+            function_count += 1;
+            let signature = code[pos..].split(|c| c == '{' || c == ';').next().unwrap_or("");
+            if let Some(return_type) = signature.split("->").nth(1) {
+                if return_type.contains("Result") {
+                    result_function_count += 1;
+                }
+            }
+        }
+
+        let mut stats = Self {
+            panic_count: count("panic!("),
+            unwrap_count: count(".unwrap()"),
+            expect_count: count(".expect("),
+            unreachable_count: count("unreachable!("),
+            question_mark_count,
+            function_count,
+            result_function_count,
+            uses_anyhow: code.contains("anyhow::") || code.contains("anyhow!("),
+            uses_thiserror: code.contains("thiserror::") || code.contains("#[error("),
+            uses_result_pervasively: false,
+        };
+        stats.update_result_pervasiveness();
+        stats
+    }
+
+    /// Accumulate another file's counts into this one
+    pub fn add(&mut self, other: &ErrorHandlingStats) {
+        self.panic_count += other.panic_count;
+        self.unwrap_count += other.unwrap_count;
+        self.expect_count += other.expect_count;
+        self.unreachable_count += other.unreachable_count;
+        self.question_mark_count += other.question_mark_count;
+        self.function_count += other.function_count;
+        self.result_function_count += other.result_function_count;
+        self.uses_anyhow |= other.uses_anyhow;
+        self.uses_thiserror |= other.uses_thiserror;
+        self.update_result_pervasiveness();
+    }
+
+    fn update_result_pervasiveness(&mut self) {
+        self.uses_result_pervasively = self.function_count > 0 && self.result_function_count * 2 >= self.function_count;
+    }
+}
+
 /// Main extractor for Cargo project data
 /// 
 /// This extractor analyzes Cargo projects comprehensively, extracting metadata,
@@ -272,6 +706,22 @@ pub struct Cargo2HfExtractor {
     rust_version: String,
     /// Processing order counter
     processing_order: u32,
+    /// Number of source files found during the last run
+    files_found: usize,
+    /// Source files that could not be read during the last run
+    skipped_files: Vec<SkippedFile>,
+    /// HTTP settings for ecosystem fetches
+    http_config: HttpClientConfig,
+    /// Shared HTTP client for ecosystem fetches
+    http_client: reqwest::Client,
+    /// Column subset to write (None = all columns)
+    columns: Option<Vec<String>>,
+    /// Published versions per crate, cached across fetches
+    crate_versions_cache: HashMap<String, Vec<String>>,
+    /// Abort on the first unreadable source file instead of skipping it
+    fail_fast: bool,
+    /// When the last ecosystem request was sent (for `min_request_interval`)
+    last_request: Option<std::time::Instant>,
 }
 
 impl Cargo2HfExtractor {
@@ -281,13 +731,40 @@ impl Cargo2HfExtractor {
     /// This will query the system for Cargo and Rust versions to include in
     /// the generated dataset metadata.
     pub fn new() -> Result<Self> {
+        let http_config = HttpClientConfig::default();
         Ok(Self {
             extractor_version: env!("CARGO_PKG_VERSION").to_string(),
             cargo_version: Self::get_cargo_version()?,
             rust_version: Self::get_rust_version()?,
             processing_order: 0,
+            files_found: 0,
+            skipped_files: Vec::new(),
+            http_client: http_config.build_client()?,
+            http_config,
+            columns: None,
+            crate_versions_cache: HashMap::new(),
+            fail_fast: false,
+            last_request: None,
         })
     }
+
+    /// Use custom HTTP settings (timeouts, proxy, API endpoints) for ecosystem fetches
+    pub fn with_http_config(mut self, http_config: HttpClientConfig) -> Result<Self> {
+        self.http_client = http_config.build_client()?;
+        self.http_config = http_config;
+        Ok(self)
+    }
+
+    /// Abort the run on the first unreadable source file instead of skipping it
+    pub fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Source files that could not be read during the last run, with reasons
+    pub fn skipped_files(&self) -> &[SkippedFile] {
+        &self.skipped_files
+    }
     
     /// Get the current Cargo version
     fn get_cargo_version() -> Result<String> {
@@ -325,7 +802,8 @@ impl Cargo2HfExtractor {
     /// 
     /// # Arguments
     /// 
-    /// * `project_path` - Path to the Cargo project root (containing Cargo.toml)
+    /// * `project_path` - Path to the Cargo project root (containing Cargo.toml),
+    ///   or a single-file package `.rs` with an embedded manifest
     /// * `phases` - List of extraction phases to run
     /// * `output_dir` - Directory where Parquet files will be written
     /// * `include_dependencies` - Whether to recursively analyze dependencies
@@ -338,6 +816,11 @@ impl Cargo2HfExtractor {
     /// - **BuildAnalysis**: Build scripts and configuration
     /// - **EcosystemAnalysis**: Crates.io and GitHub metadata
     /// - **VersionHistory**: Git history and development patterns
+    /// - **Overview**: One summary row built from the other phases' records;
+    ///   it always runs last, and fields of phases not run stay empty
+    /// 
+    /// For a single-file package, phases that need a full project (see
+    /// `CargoExtractionPhase::supports_single_file_packages`) are skipped.
     pub async fn extract_project_to_parquet(
         &mut self,
         project_path: &Path,
@@ -348,23 +831,45 @@ impl Cargo2HfExtractor {
         println!("Analyzing Cargo project: {}", project_path.display());
         
         // Verify this is a Cargo project
-        let cargo_toml = project_path.join("Cargo.toml");
-        if !cargo_toml.exists() {
+        let single_file = Self::is_single_file_package(project_path);
+        if !single_file && !project_path.join("Cargo.toml").exists() {
             return Err(anyhow::anyhow!("No Cargo.toml found in {}", project_path.display()));
         }
         
         // Create output directory
         std::fs::create_dir_all(output_dir)?;
+        self.files_found = 0;
+        self.skipped_files.clear();
         
-        // Process each phase
-        for phase in phases {
+        // Process each phase; the overview summarizes the others, so it comes last
+        let overview = phases.contains(&CargoExtractionPhase::Overview);
+        let mut detailed_records = Vec::new();
+        for phase in phases.iter().filter(|phase| **phase != CargoExtractionPhase::Overview) {
+            if single_file && !phase.supports_single_file_packages() {
+                println!("Skipping phase {:?}: not available for single-file packages", phase);
+                continue;
+            }
             println!("Processing phase: {:?}", phase);
             let phase_records = self.extract_phase_data(project_path, phase, include_dependencies).await?;
             println!("Generated {} records for phase {:?}", phase_records.len(), phase);
             
             // Write to Parquet files
             self.write_phase_to_parquet(&phase_records, phase, output_dir)?;
+            if overview {
+                detailed_records.extend(phase_records);
+            }
         }
+        if overview {
+            println!("Processing phase: {:?}", CargoExtractionPhase::Overview);
+            let overview_record = self.build_overview_record(project_path, &detailed_records)?;
+            self.write_phase_to_parquet(&[overview_record], &CargoExtractionPhase::Overview, output_dir)?;
+        }
+        
+        // Report coverage gaps and record them in the manifest
+        print_skipped_summary(&self.skipped_files);
+        write_run_manifest(output_dir, self.files_found, &self.skipped_files, &BTreeMap::new(), None)?;
+        let phase_names: Vec<&str> = phases.iter().map(CargoExtractionPhase::as_str).collect();
+        write_provenance(output_dir, &Provenance::capture(&phase_names, &self.rust_version))?;
         
         Ok(())
     }
@@ -390,11 +895,15 @@ impl Cargo2HfExtractor {
                 self.extract_build_analysis(project_path)
             }
             CargoExtractionPhase::EcosystemAnalysis => {
-                self.extract_ecosystem_analysis(project_path).await
+                self.extract_ecosystem_analysis(project_path, include_dependencies).await
             }
             CargoExtractionPhase::VersionHistory => {
                 self.extract_version_history(project_path)
             }
+            CargoExtractionPhase::Overview => {
+                // On its own there are no other phases to summarize
+                Ok(vec![self.build_overview_record(project_path, &[])?])
+            }
         }
     }
     
@@ -406,6 +915,10 @@ impl Cargo2HfExtractor {
     /// 
     /// Handles both regular packages and workspace configurations.
     fn extract_project_metadata(&mut self, project_path: &Path) -> Result<Vec<CargoProjectRecord>> {
+        if Self::is_single_file_package(project_path) {
+            return self.extract_script_metadata(project_path);
+        }
+
         let cargo_toml_path = project_path.join("Cargo.toml");
         let cargo_toml_content = std::fs::read_to_string(&cargo_toml_path)
             .with_context(|| format!("Failed to read Cargo.toml: {}", cargo_toml_path.display()))?;
@@ -426,6 +939,62 @@ impl Cargo2HfExtractor {
         }
     }
     
+    /// Whether `path` is a single-file package: a `.rs` file, with or without an embedded manifest
+    pub fn is_single_file_package(path: &Path) -> bool {
+        path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("rs")
+    }
+
+    /// Extract metadata from the embedded manifest of a single-file package (cargo-script)
+    /// 
+    /// The manifest is the `---` frontmatter at the top of the `.rs` file. As
+    /// with `cargo -Zscript`, the package name defaults to the file stem, and a
+    /// script without frontmatter is a package with an empty manifest.
+    fn extract_script_metadata(&mut self, script_path: &Path) -> Result<Vec<CargoProjectRecord>> {
+        let source = std::fs::read_to_string(script_path)
+            .with_context(|| format!("Failed to read script: {}", script_path.display()))?;
+        let manifest: toml::Value = match Self::script_frontmatter(&source) {
+            Some(frontmatter) => toml::from_str(&frontmatter)
+                .with_context(|| format!("Failed to parse the embedded manifest of {}", script_path.display()))?,
+            None => toml::Value::Table(toml::map::Map::new()),
+        };
+
+        let mut package = manifest.get("package").cloned().unwrap_or_else(|| toml::Value::Table(toml::map::Map::new()));
+        if let (Some(table), Some(stem)) = (package.as_table_mut(), script_path.file_stem().and_then(|s| s.to_str())) {
+            if !table.contains_key("name") {
+                table.insert("name".to_string(), toml::Value::String(stem.to_string()));
+            }
+        }
+        self.extract_package_metadata(script_path, &package)
+    }
+
+    /// Body of the `---`-fenced frontmatter opening a single-file package, if any
+    /// 
+    /// The fence may follow a `#!` line and blank lines, may carry a `cargo`
+    /// info string (`---cargo`), and is closed by a line of the same dashes.
+    fn script_frontmatter(source: &str) -> Option<String> {
+        let mut lines = source.lines().peekable();
+        // `#![attribute]` is Rust, not a shebang
+        if lines.peek().is_some_and(|line| line.starts_with("#!") && !line.starts_with("#![")) {
+            lines.next();
+        }
+        let opening = lines.find(|line| !line.trim().is_empty())?.trim_end();
+        let dashes = opening.chars().take_while(|&c| c == '-').count();
+        let info_string = opening[dashes..].trim();
+        if dashes < 3 || !(info_string.is_empty() || info_string == "cargo") {
+            return None;
+        }
+
+        let fence = &opening[..dashes];
+        let mut body = Vec::new();
+        for line in lines {
+            if line.trim_end() == fence {
+                return Some(body.join("\n"));
+            }
+            body.push(line);
+        }
+        None // Unclosed frontmatter
+    }
+
     /// Extract metadata from a workspace Cargo.toml
     fn extract_workspace_metadata(&mut self, project_path: &Path, workspace: &toml::Value) -> Result<Vec<CargoProjectRecord>> {
         // For workspace, we'll create a record representing the workspace itself
@@ -460,15 +1029,21 @@ impl Cargo2HfExtractor {
             documentation: None,
             keywords: members.clone(), // Store members in keywords field for now
             categories: None,
+            edition: None,
+            msrv: None,
             
             // Initialize other fields with defaults
             lines_of_code: 0,
+            code_lines: 0,
+            comment_lines: 0,
+            blank_lines: 0,
             source_file_count: 0,
             test_file_count: 0,
             example_file_count: 0,
             benchmark_file_count: 0,
             complexity_score: 0.0,
             documentation_coverage: 0.0,
+            error_handling_data: None,
             direct_dependencies: 0,
             total_dependencies: 0,
             dev_dependencies: 0,
@@ -487,8 +1062,9 @@ impl Cargo2HfExtractor {
             contributor_count: None,
             project_age_days: None,
             release_frequency: None,
+            health_score: None,
             processing_time_ms: 1, // Mock timing
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            timestamp: crate::config::generation_timestamp(),
             extractor_version: self.extractor_version.clone(),
             cargo_version: self.cargo_version.clone(),
             rust_version: self.rust_version.clone(),
@@ -499,6 +1075,10 @@ impl Cargo2HfExtractor {
     
     /// Extract metadata from a regular package Cargo.toml
     fn extract_package_metadata(&mut self, project_path: &Path, package: &toml::Value) -> Result<Vec<CargoProjectRecord>> {
+        // Substitute `field.workspace = true` entries from the workspace root
+        let workspace_package = Self::find_workspace_package_table(project_path, package);
+        let package = &Self::resolve_workspace_inheritance(package, workspace_package.as_ref());
+
         // Extract basic metadata
         let project_name = package.get("name")
             .and_then(|v| v.as_str())
@@ -533,15 +1113,21 @@ impl Cargo2HfExtractor {
             categories: package.get("categories")
                 .and_then(|v| v.as_array())
                 .map(|arr| serde_json::to_string(arr).unwrap_or_default()),
+            edition: package.get("edition").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            msrv: package.get("rust-version").and_then(|v| v.as_str()).map(|s| s.to_string()),
             
             // Initialize other fields with defaults (will be filled in other phases)
             lines_of_code: 0,
+            code_lines: 0,
+            comment_lines: 0,
+            blank_lines: 0,
             source_file_count: 0,
             test_file_count: 0,
             example_file_count: 0,
             benchmark_file_count: 0,
             complexity_score: 0.0,
             documentation_coverage: 0.0,
+            error_handling_data: None,
             direct_dependencies: 0,
             total_dependencies: 0,
             dev_dependencies: 0,
@@ -560,8 +1146,9 @@ impl Cargo2HfExtractor {
             contributor_count: None,
             project_age_days: None,
             release_frequency: None,
+            health_score: None,
             processing_time_ms: 1, // Mock timing
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            timestamp: crate::config::generation_timestamp(),
             extractor_version: self.extractor_version.clone(),
             cargo_version: self.cargo_version.clone(),
             rust_version: self.rust_version.clone(),
@@ -570,6 +1157,46 @@ impl Cargo2HfExtractor {
         Ok(vec![record])
     }
     
+    /// Load the `[workspace.package]` table of the workspace `project_path` belongs to
+    /// 
+    /// The root is `package.workspace` when set, otherwise the nearest ancestor
+    /// directory whose Cargo.toml has a `[workspace]` section, as Cargo does.
+    fn find_workspace_package_table(project_path: &Path, package: &toml::Value) -> Option<toml::Value> {
+        let read_manifest = |dir: &Path| -> Option<toml::Value> {
+            toml::from_str(&std::fs::read_to_string(dir.join("Cargo.toml")).ok()?).ok()
+        };
+
+        let workspace_manifest = match package.get("workspace").and_then(|w| w.as_str()) {
+            Some(root) => read_manifest(&project_path.join(root))?,
+            None => project_path.ancestors().skip(1).find_map(|dir| {
+                read_manifest(dir).filter(|manifest| manifest.get("workspace").is_some())
+            })?,
+        };
+        workspace_manifest.get("workspace")?.get("package").cloned()
+    }
+
+    /// Replace each `field.workspace = true` in `package` with the workspace's value
+    /// 
+    /// Fields the workspace does not define are dropped, so they read as absent.
+    fn resolve_workspace_inheritance(package: &toml::Value, workspace_package: Option<&toml::Value>) -> toml::Value {
+        let mut resolved = package.clone();
+        let Some(table) = resolved.as_table_mut() else {
+            return resolved;
+        };
+
+        let inherited: Vec<String> = table.iter()
+            .filter(|(_, value)| value.get("workspace").and_then(|w| w.as_bool()) == Some(true))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in inherited {
+            match workspace_package.and_then(|workspace| workspace.get(&key)) {
+                Some(value) => table.insert(key, value.clone()),
+                None => table.remove(&key),
+            };
+        }
+        resolved
+    }
+    
     /// Placeholder implementations for other phases
     /// Implement comprehensive dependency analysis
     fn extract_dependency_analysis(&mut self, project_path: &Path, include_dependencies: bool) -> Result<Vec<CargoProjectRecord>> {
@@ -581,42 +1208,10 @@ impl Cargo2HfExtractor {
         let mut records = Vec::new();
 
         for package in &metadata.packages {
-            let mut direct_dependencies = 0;
-            let mut dev_dependencies = 0;
-            let mut build_dependencies = 0;
-            let mut dependency_data_vec = Vec::new();
-
-            for dep in &package.dependencies {
-                direct_dependencies += 1;
-                if dep.kind == cargo_metadata::DependencyKind::Development {
-                    dev_dependencies += 1;
-                }
-                if dep.kind == cargo_metadata::DependencyKind::Build {
-                    build_dependencies += 1;
-                }
-
-                let resolved_version = metadata.resolve.as_ref().and_then(|resolve| {
-                    resolve.nodes.iter().find(|node| node.id == package.id).and_then(|node| {
-                        node.dependencies.iter().find(|node_dep_id| {
-                            metadata.packages.iter().find(|p| p.id == **node_dep_id).map_or(false, |p| p.name == dep.name)
-                        }).and_then(|resolved_id| {
-                            metadata.packages.iter().find(|p| &p.id == resolved_id).map(|p| p.version.to_string())
-                        })
-                    })
-                });
-
-                dependency_data_vec.push(DependencyInfo {
-                    name: dep.name.clone(),
-                    version_req: dep.req.to_string(),
-                    resolved_version,
-                    optional: dep.optional,
-                    default_features: dep.uses_default_features,
-                    features: dep.features.clone(),
-                    source: dep.source.as_ref().map_or("path".to_string(), |s| s.to_string()),
-                    is_dev: dep.kind == cargo_metadata::DependencyKind::Development,
-                    is_build: dep.kind == cargo_metadata::DependencyKind::Build,
-                });
-            }
+            let dependency_data_vec = Self::collect_dependency_infos(&metadata, package);
+            let direct_dependencies = dependency_data_vec.len() as u32;
+            let dev_dependencies = dependency_data_vec.iter().filter(|d| d.is_dev).count() as u32;
+            let build_dependencies = dependency_data_vec.iter().filter(|d| d.is_build).count() as u32;
 
             let total_dependencies = metadata.resolve.as_ref().map_or(0, |resolve| {
                 resolve.nodes.iter().find(|node| node.id == package.id).map_or(0, |node| {
@@ -639,13 +1234,19 @@ impl Cargo2HfExtractor {
                 documentation: package.documentation.clone(),
                 keywords: Some(serde_json::to_string(&package.keywords).unwrap_or_default()),
                 categories: Some(serde_json::to_string(&package.categories).unwrap_or_default()),
+                edition: Some(package.edition.as_str().to_string()),
+                msrv: package.rust_version.as_ref().map(|v| v.to_string()),
                 lines_of_code: 0, // To be filled by SourceCodeAnalysis
+                code_lines: 0, // To be filled by SourceCodeAnalysis
+                comment_lines: 0, // To be filled by SourceCodeAnalysis
+                blank_lines: 0, // To be filled by SourceCodeAnalysis
                 source_file_count: 0, // To be filled by SourceCodeAnalysis
                 test_file_count: 0, // To be filled by SourceCodeAnalysis
                 example_file_count: 0, // To be filled by SourceCodeAnalysis
                 benchmark_file_count: 0, // To be filled by SourceCodeAnalysis
                 complexity_score: 0.0, // To be filled by SourceCodeAnalysis
                 documentation_coverage: 0.0, // To be filled by SourceCodeAnalysis
+                error_handling_data: None, // To be filled by SourceCodeAnalysis
                 direct_dependencies,
                 total_dependencies,
                 dev_dependencies,
@@ -664,8 +1265,9 @@ impl Cargo2HfExtractor {
                 contributor_count: None, // To be filled by VersionHistory
                 project_age_days: None, // To be filled by VersionHistory
                 release_frequency: None, // To be filled by VersionHistory
+                health_score: None,
                 processing_time_ms: 1, // Mock timing
-                timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+                timestamp: crate::config::generation_timestamp(),
                 extractor_version: self.extractor_version.clone(),
                 cargo_version: self.cargo_version.clone(),
                 rust_version: self.rust_version.clone(),
@@ -676,10 +1278,65 @@ impl Cargo2HfExtractor {
         Ok(records)
     }
     
+    /// Build `DependencyInfo` entries for a package's direct dependencies
+    fn collect_dependency_infos(metadata: &cargo_metadata::Metadata, package: &cargo_metadata::Package) -> Vec<DependencyInfo> {
+        let mut dependencies = Vec::new();
+
+        for dep in &package.dependencies {
+            let resolved_version = Self::resolve_dependency(metadata, package, dep).map(|p| p.version.to_string());
+
+            dependencies.push(DependencyInfo {
+                name: dep.name.clone(),
+                rename: dep.rename.clone(),
+                version_req: dep.req.to_string(),
+                resolved_version,
+                optional: dep.optional,
+                default_features: dep.uses_default_features,
+                features: dep.features.clone(),
+                source: DependencySource::from_source_id(dep.source.as_deref()).as_str().to_string(),
+                is_dev: dep.kind == cargo_metadata::DependencyKind::Development,
+                is_build: dep.kind == cargo_metadata::DependencyKind::Build,
+                latest_version: None, // To be filled by EcosystemAnalysis
+                versions_behind: None, // To be filled by EcosystemAnalysis
+                is_outdated: None, // To be filled by EcosystemAnalysis
+            });
+        }
+
+        dependencies
+    }
+
+    /// Find the package that `dep` of `package` resolved to
+    /// 
+    /// Resolve edges are keyed by the name the crate is imported under, which is
+    /// the `rename` for `package = "..."` dependencies, so a crate depended on
+    /// twice under different names maps each edge to the right version. Edges
+    /// whose name differs for other reasons (a custom `[lib] name`) fall back to
+    /// the package name and version requirement.
+    fn resolve_dependency<'a>(
+        metadata: &'a cargo_metadata::Metadata,
+        package: &cargo_metadata::Package,
+        dep: &cargo_metadata::Dependency,
+    ) -> Option<&'a cargo_metadata::Package> {
+        let node = metadata.resolve.as_ref()?.nodes.iter().find(|node| node.id == package.id)?;
+        let find_package = |id: &cargo_metadata::PackageId| metadata.packages.iter().find(|p| &p.id == id);
+
+        let extern_name = dep.rename.as_deref().unwrap_or(&dep.name).replace('-', "_");
+        node.deps.iter()
+            .find(|node_dep| node_dep.name == extern_name)
+            .and_then(|node_dep| find_package(&node_dep.pkg))
+            .or_else(|| {
+                node.deps.iter()
+                    .filter_map(|node_dep| find_package(&node_dep.pkg))
+                    .find(|p| p.name == dep.name && dep.req.matches(&p.version))
+            })
+    }
+
     /// Implement source code analysis with metrics
     fn extract_source_code_analysis(&mut self, project_path: &Path) -> Result<Vec<CargoProjectRecord>> {
         use walkdir::WalkDir;
         let mut lines_of_code = 0;
+        let mut line_counts = LineCounts::default();
+        let mut error_handling = ErrorHandlingStats::default();
         let mut source_file_count = 0;
         let mut test_file_count = 0;
         let mut example_file_count = 0;
@@ -691,8 +1348,21 @@ impl Cargo2HfExtractor {
         {
             let path = entry.path();
             if path.is_file() && path.extension().map_or(false, |ext| ext == "rs") {
-                let content = std::fs::read_to_string(path)?;
+                self.files_found += 1;
+                let content = match std::fs::read_to_string(path) {
+                    Ok(content) => content,
+                    Err(e) if self.fail_fast => {
+                        return Err(anyhow::Error::from(e)
+                            .context(format!("Aborting on {} (--fail-fast)", path.display())));
+                    }
+                    Err(e) => {
+                        self.skipped_files.push(SkippedFile::from_error(path, &e.into()));
+                        continue;
+                    }
+                };
                 lines_of_code += content.lines().count() as u32;
+                line_counts.add(&LineCounts::classify(&content));
+                error_handling.add(&ErrorHandlingStats::scan(&content));
                 source_file_count += 1;
 
                 if path.to_string_lossy().contains("/tests/") {
@@ -705,6 +1375,18 @@ impl Cargo2HfExtractor {
             }
         }
 
+        // Crates count as using anyhow/thiserror if they depend on them, even
+        // when the source only refers to them through re-exports
+        if let Ok(cargo_toml) = std::fs::read_to_string(project_path.join("Cargo.toml")) {
+            if let Ok(cargo_toml) = toml::from_str::<toml::Value>(&cargo_toml) {
+                let has_dependency = |name: &str| cargo_toml.get("dependencies")
+                    .and_then(|d| d.get(name))
+                    .is_some();
+                error_handling.uses_anyhow |= has_dependency("anyhow");
+                error_handling.uses_thiserror |= has_dependency("thiserror");
+            }
+        }
+
         let record = CargoProjectRecord {
             id: format!("{}:source_code_analysis", project_path.file_name().unwrap().to_string_lossy()),
             project_path: project_path.to_string_lossy().to_string(),
@@ -720,13 +1402,19 @@ impl Cargo2HfExtractor {
             documentation: None,
             keywords: None,
             categories: None,
+            edition: None,
+            msrv: None,
             lines_of_code,
+            code_lines: line_counts.code,
+            comment_lines: line_counts.comment,
+            blank_lines: line_counts.blank,
             source_file_count,
             test_file_count,
             example_file_count,
             benchmark_file_count,
             complexity_score: 0.0, // TODO: Implement actual complexity analysis
             documentation_coverage: 0.0, // TODO: Implement actual documentation coverage
+            error_handling_data: Some(serde_json::to_string(&error_handling)?),
             direct_dependencies: 0, // To be filled by DependencyAnalysis
             total_dependencies: 0, // To be filled by DependencyAnalysis
             dev_dependencies: 0, // To be filled by DependencyAnalysis
@@ -745,8 +1433,9 @@ impl Cargo2HfExtractor {
             contributor_count: None, // To be filled by VersionHistory
             project_age_days: None, // To be filled by VersionHistory
             release_frequency: None, // To be filled by VersionHistory
+            health_score: None,
             processing_time_ms: 1, // Mock timing
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            timestamp: crate::config::generation_timestamp(),
             extractor_version: self.extractor_version.clone(),
             cargo_version: self.cargo_version.clone(),
             rust_version: self.rust_version.clone(),
@@ -795,13 +1484,19 @@ impl Cargo2HfExtractor {
             documentation: None,
             keywords: None,
             categories: None,
+            edition: None,
+            msrv: None,
             lines_of_code: 0, // To be filled by SourceCodeAnalysis
+            code_lines: 0, // To be filled by SourceCodeAnalysis
+            comment_lines: 0, // To be filled by SourceCodeAnalysis
+            blank_lines: 0, // To be filled by SourceCodeAnalysis
             source_file_count: 0, // To be filled by SourceCodeAnalysis
             test_file_count: 0, // To be filled by SourceCodeAnalysis
             example_file_count: 0, // To be filled by SourceCodeAnalysis
             benchmark_file_count: 0, // To be filled by SourceCodeAnalysis
             complexity_score: 0.0, // To be filled by SourceCodeAnalysis
             documentation_coverage: 0.0, // To be filled by SourceCodeAnalysis
+            error_handling_data: None, // To be filled by SourceCodeAnalysis
             direct_dependencies: 0, // To be filled by DependencyAnalysis
             total_dependencies: 0, // To be filled by DependencyAnalysis
             dev_dependencies: 0, // To be filled by DependencyAnalysis
@@ -820,8 +1515,9 @@ impl Cargo2HfExtractor {
             contributor_count: None, // To be filled by VersionHistory
             project_age_days: None, // To be filled by VersionHistory
             release_frequency: None, // To be filled by VersionHistory
+            health_score: None,
             processing_time_ms: 1, // Mock timing
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            timestamp: crate::config::generation_timestamp(),
             extractor_version: self.extractor_version.clone(),
             cargo_version: self.cargo_version.clone(),
             rust_version: self.rust_version.clone(),
@@ -830,80 +1526,258 @@ impl Cargo2HfExtractor {
         Ok(vec![record])
     }
     
+    /// Fetch a JSON document with the shared HTTP client
+    /// 
+    /// Returns `None` on connection errors, timeouts, non-success statuses or
+    /// invalid JSON, so a slow or unavailable service never fails the phase.
+    async fn fetch_json(&mut self, url: &str) -> Option<serde_json::Value> {
+        match self.fetch_json_response(url).await {
+            JsonResponse::Found(json) => Some(json),
+            JsonResponse::NotFound | JsonResponse::Failed => None,
+        }
+    }
+
+    /// `fetch_json`, telling a 404 apart from a failed request
+    async fn fetch_json_response(&mut self, url: &str) -> JsonResponse {
+        if self.http_config.offline {
+            return JsonResponse::Failed;
+        }
+        if let Some(last_request) = self.last_request {
+            let wait = self.http_config.min_request_interval.saturating_sub(last_request.elapsed());
+            if !wait.is_zero() {
+                tokio::time::sleep(wait).await;
+            }
+        }
+        self.last_request = Some(std::time::Instant::now());
+        let response = match self.http_client.get(url).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                println!("Warning: request to {} failed: {}", url, e);
+                return JsonResponse::Failed;
+            }
+        };
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return JsonResponse::NotFound;
+        }
+        if !response.status().is_success() {
+            return JsonResponse::Failed;
+        }
+        match response.json().await {
+            Ok(json) => JsonResponse::Found(json),
+            Err(e) => {
+                println!("Warning: failed to read response from {}: {}", url, e);
+                JsonResponse::Failed
+            }
+        }
+    }
+
+    /// Fetch the published, non-yanked versions of a crate from crates.io
+    /// 
+    /// Results are cached per crate so each crate is fetched at most once per
+    /// extractor; offline mode and failed fetches return `None`.
+    async fn fetch_crate_versions(&mut self, crate_name: &str) -> Option<Vec<String>> {
+        if let Some(versions) = self.crate_versions_cache.get(crate_name) {
+            return Some(versions.clone());
+        }
+
+        let url = format!("{}/crates/{}/versions", self.http_config.crates_io_api_url.trim_end_matches('/'), crate_name);
+        let json = self.fetch_json(&url).await?;
+        let versions: Vec<String> = json.get("versions")?
+            .as_array()?
+            .iter()
+            .filter(|v| !v.get("yanked").and_then(|y| y.as_bool()).unwrap_or(false))
+            .filter_map(|v| v.get("num").and_then(|n| n.as_str()).map(|n| n.to_string()))
+            .collect();
+
+        self.crate_versions_cache.insert(crate_name.to_string(), versions.clone());
+        Some(versions)
+    }
+
+    /// Fill in `latest_version`, `versions_behind` and `is_outdated` for crates.io dependencies
+    async fn annotate_dependency_freshness(&mut self, dependencies: &mut [DependencyInfo]) {
+        for dependency in dependencies.iter_mut() {
+            if dependency.source != DependencySource::CratesIo.as_str() {
+                continue; // Only crates.io dependencies have published versions to compare
+            }
+            let resolved = match &dependency.resolved_version {
+                Some(resolved) => resolved.clone(),
+                None => continue,
+            };
+            let published = match self.fetch_crate_versions(&dependency.name).await {
+                Some(published) => published,
+                None => continue,
+            };
+            if let Some((latest, versions_behind)) = compute_dependency_freshness(&resolved, &published) {
+                dependency.latest_version = Some(latest);
+                dependency.versions_behind = Some(versions_behind);
+                dependency.is_outdated = Some(versions_behind > 0);
+            }
+        }
+    }
+
     /// Implement ecosystem metadata extraction
-    async fn extract_ecosystem_analysis(&mut self, project_path: &Path) -> Result<Vec<CargoProjectRecord>> {
+    /// 
+    /// Produces a record for the root package and, with `include_dependencies`,
+    /// one record per resolved dependency package from `cargo metadata`. In
+    /// offline mode only the (empty) root record is produced.
+    async fn extract_ecosystem_analysis(&mut self, project_path: &Path, include_dependencies: bool) -> Result<Vec<CargoProjectRecord>> {
         let cargo_toml_path = project_path.join("Cargo.toml");
         let cargo_toml_content = std::fs::read_to_string(&cargo_toml_path)
             .with_context(|| format!("Failed to read Cargo.toml: {}", cargo_toml_path.display()))?;
         let cargo_toml: toml::Value = toml::from_str(&cargo_toml_content)
             .with_context(|| "Failed to parse Cargo.toml")?;
 
-        let package_name = cargo_toml.get("package")
+        let package = cargo_toml.get("package").map(|package| {
+            let workspace_package = Self::find_workspace_package_table(project_path, package);
+            Self::resolve_workspace_inheritance(package, workspace_package.as_ref())
+        });
+
+        let package_name = package.as_ref()
             .and_then(|p| p.get("name"))
             .and_then(|n| n.as_str())
             .unwrap_or_default()
             .to_string();
+        let package_version = package.as_ref()
+            .and_then(|p| p.get("version"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let repository = package.as_ref()
+            .and_then(|p| p.get("repository"))
+            .and_then(|r| r.as_str());
 
-        let mut record = CargoProjectRecord {
-            id: format!("{}:ecosystem_analysis", package_name),
-            project_path: project_path.to_string_lossy().to_string(),
-            project_name: package_name.clone(),
-            project_version: cargo_toml.get("package")
-                .and_then(|p| p.get("version"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("unknown")
-                .to_string(),
+        let mut record = self.new_ecosystem_record(
+            format!("{}:ecosystem_analysis", package_name),
+            project_path.to_string_lossy().to_string(),
+            &package_name,
+            &package_version,
+        )?;
+        self.fetch_ecosystem_metrics(&mut record, repository).await?;
+
+        if self.http_config.offline {
+            return Ok(vec![record]);
+        }
+
+        let metadata = match cargo_metadata::MetadataCommand::new()
+            .manifest_path(project_path.join("Cargo.toml"))
+            .exec()
+        {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                println!("Warning: skipping dependency freshness and dependency records, cargo metadata failed: {}", e);
+                return Ok(vec![record]);
+            }
+        };
+
+        // Dependency freshness: compare resolved versions against crates.io releases
+        if let Some(package) = metadata.root_package() {
+            let mut dependencies = Self::collect_dependency_infos(&metadata, package);
+            self.annotate_dependency_freshness(&mut dependencies).await;
+            record.dependency_data = Some(serde_json::to_string(&dependencies)?);
+        }
+
+        let mut records = vec![record];
+        if include_dependencies {
+            let mut dependency_packages: Vec<&cargo_metadata::Package> = metadata.packages.iter()
+                .filter(|package| !metadata.workspace_members.contains(&package.id))
+                .collect();
+            dependency_packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+            println!("Fetching ecosystem metrics for {} dependencies", dependency_packages.len());
+
+            for package in dependency_packages {
+                let mut dependency_record = self.new_ecosystem_record(
+                    format!("{}:{}:ecosystem_analysis", package.name, package.version),
+                    package.manifest_path.parent().map(|dir| dir.to_string()).unwrap_or_default(),
+                    &package.name,
+                    &package.version.to_string(),
+                )?;
+                self.fetch_ecosystem_metrics(&mut dependency_record, package.repository.as_deref()).await?;
+                records.push(dependency_record);
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// Ecosystem record for one package, with the metrics still empty
+    fn new_ecosystem_record(&mut self, id: String, project_path: String, name: &str, version: &str) -> Result<CargoProjectRecord> {
+        Ok(CargoProjectRecord {
+            id,
+            project_path,
+            project_name: name.to_string(),
+            project_version: version.to_string(),
             phase: CargoExtractionPhase::EcosystemAnalysis.as_str().to_string(),
             processing_order: self.next_processing_order(),
             description: None, authors: None, license: None, repository: None, homepage: None,
-            documentation: None, keywords: None, categories: None, lines_of_code: 0,
+            documentation: None, keywords: None, categories: None, edition: None, msrv: None, lines_of_code: 0,
+            code_lines: 0, comment_lines: 0, blank_lines: 0,
             source_file_count: 0, test_file_count: 0, example_file_count: 0,
-            benchmark_file_count: 0, complexity_score: 0.0, documentation_coverage: 0.0,
+            benchmark_file_count: 0, complexity_score: 0.0, documentation_coverage: 0.0, error_handling_data: None,
             direct_dependencies: 0, total_dependencies: 0, dev_dependencies: 0,
             build_dependencies: 0, dependency_data: None, features: None, targets: None,
             has_build_script: false, build_script_complexity: 0,
             download_count: None, github_stars: None, github_forks: None,
             github_issues: None, last_updated: None, commit_count: None,
-            contributor_count: None, project_age_days: None, release_frequency: None,
+            contributor_count: None, project_age_days: None, release_frequency: None, health_score: None,
             processing_time_ms: 1,
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            timestamp: crate::config::generation_timestamp(),
             extractor_version: self.extractor_version.clone(),
             cargo_version: self.cargo_version.clone(),
             rust_version: self.rust_version.clone(),
-        };
+        })
+    }
+
+    /// Fill in crates.io downloads and GitHub repository metrics for `record`'s package
+    /// 
+    /// Timeouts and network errors leave the fields as `None`. With
+    /// `HttpClientConfig::cache_dir`, cached metrics are used instead of
+    /// fetching, and freshly fetched ones are cached right away unless a
+    /// request failed.
+    async fn fetch_ecosystem_metrics(&mut self, record: &mut CargoProjectRecord, repository: Option<&str>) -> Result<()> {
+        let cache_path = self.http_config.cache_dir.as_deref()
+            .map(|cache_dir| CachedEcosystemMetrics::cache_path(cache_dir, &record.project_name, &record.project_version));
+        if let Some(cached) = cache_path.as_deref().and_then(CachedEcosystemMetrics::load) {
+            cached.apply_to(record);
+            return Ok(());
+        }
+
+        let complete = self.fetch_uncached_ecosystem_metrics(record, repository).await;
+        if let (Some(cache_path), true) = (cache_path, complete) {
+            CachedEcosystemMetrics::from_record(record).store(&cache_path)?;
+        }
+        Ok(())
+    }
+
+    /// Fetch `record`'s ecosystem metrics, returning whether every request got an answer
+    /// 
+    /// A 404 is an answer: unpublished crates have no crates.io page, and the
+    /// GitHub metrics are still fetched for them.
+    async fn fetch_uncached_ecosystem_metrics(&mut self, record: &mut CargoProjectRecord, repository: Option<&str>) -> bool {
+        let mut complete = true;
 
         // Fetch from crates.io
-        let client = reqwest::Client::new();
-        let crate_url = format!("https://crates.io/api/v1/crates/{}", package_name);
-        if let Ok(response) = client.get(&crate_url).send().await {
-            if response.status().is_success() {
-                let json: serde_json::Value = response.json().await?;
+        let crate_url = format!("{}/crates/{}", self.http_config.crates_io_api_url.trim_end_matches('/'), record.project_name);
+        match self.fetch_json_response(&crate_url).await {
+            JsonResponse::Found(json) => {
                 if let Some(krate) = json.get("crate") {
                     record.download_count = krate.get("downloads").and_then(|d| d.as_u64());
                 }
             }
+            JsonResponse::NotFound => {}
+            JsonResponse::Failed => complete = false,
         }
 
         // Fetch from GitHub
-        if let Some(repo_url) = cargo_toml.get("package")
-            .and_then(|p| p.get("repository"))
-            .and_then(|r| r.as_str())
-        {
+        if let Some(repo_url) = repository {
             if repo_url.contains("github.com") {
                 let parts: Vec<&str> = repo_url.trim_end_matches('/').split('/').collect();
                 if parts.len() >= 2 {
                     let owner = parts[parts.len() - 2];
                     let repo = parts[parts.len() - 1].trim_end_matches(".git");
-                    let github_api_url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+                    let github_api_url = format!("{}/repos/{}/{}", self.http_config.github_api_url.trim_end_matches('/'), owner, repo);
 
-                    // GitHub API requires a User-Agent header
-                    let client = reqwest::Client::builder()
-                        .user_agent("cargo2hf-extractor")
-                        .build()?;
-
-                    if let Ok(response) = client.get(&github_api_url).send().await {
-                        if response.status().is_success() {
-                            let json: serde_json::Value = response.json().await?;
+                    match self.fetch_json_response(&github_api_url).await {
+                        JsonResponse::Found(json) => {
                             record.github_stars = json.get("stargazers_count").and_then(|s| s.as_u64()).map(|s| s as u32);
                             record.github_forks = json.get("forks_count").and_then(|f| f.as_u64()).map(|f| f as u32);
                             record.github_issues = json.get("open_issues_count").and_then(|i| i.as_u64()).map(|i| i as u32);
@@ -913,12 +1787,13 @@ impl Cargo2HfExtractor {
                                 }
                             }
                         }
+                        JsonResponse::NotFound => {}
+                        JsonResponse::Failed => complete = false,
                     }
                 }
             }
         }
-
-        Ok(vec![record])
+        complete
     }
     
     /// Implement version history analysis
@@ -947,8 +1822,7 @@ impl Cargo2HfExtractor {
         }
 
         let project_age_days = if let Some(first_time) = first_commit_time {
-            let now = chrono::Utc::now();
-            let duration = now.signed_duration_since(first_time);
+            let duration = crate::config::generation_time().signed_duration_since(first_time);
             Some(duration.num_days() as u32)
         } else {
             None
@@ -962,9 +1836,10 @@ impl Cargo2HfExtractor {
             phase: CargoExtractionPhase::VersionHistory.as_str().to_string(),
             processing_order: self.next_processing_order(),
             description: None, authors: None, license: None, repository: None, homepage: None,
-            documentation: None, keywords: None, categories: None, lines_of_code: 0,
+            documentation: None, keywords: None, categories: None, edition: None, msrv: None, lines_of_code: 0,
+            code_lines: 0, comment_lines: 0, blank_lines: 0,
             source_file_count: 0, test_file_count: 0, example_file_count: 0,
-            benchmark_file_count: 0, complexity_score: 0.0, documentation_coverage: 0.0,
+            benchmark_file_count: 0, complexity_score: 0.0, documentation_coverage: 0.0, error_handling_data: None,
             direct_dependencies: 0, total_dependencies: 0, dev_dependencies: 0,
             build_dependencies: 0, dependency_data: None, features: None, targets: None,
             has_build_script: false, build_script_complexity: 0,
@@ -974,8 +1849,9 @@ impl Cargo2HfExtractor {
             contributor_count: Some(contributors.len() as u32),
             project_age_days,
             release_frequency: None, // TODO: Implement more sophisticated release frequency
+            health_score: None,
             processing_time_ms: 1, // Mock timing
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            timestamp: crate::config::generation_timestamp(),
             extractor_version: self.extractor_version.clone(),
             cargo_version: self.cargo_version.clone(),
             rust_version: self.rust_version.clone(),
@@ -984,6 +1860,63 @@ impl Cargo2HfExtractor {
         Ok(vec![record])
     }
     
+    /// Summarize the root package's records from the other phases into one overview record
+    /// 
+    /// The package is the one named by the project metadata record (the
+    /// directory name without one). Fields whose phase is missing from
+    /// `detailed_records` are left empty.
+    fn build_overview_record(&mut self, project_path: &Path, detailed_records: &[CargoProjectRecord]) -> Result<CargoProjectRecord> {
+        let phase_records = |phase: CargoExtractionPhase| detailed_records.iter().filter(move |r| r.phase == phase.as_str());
+        let metadata = phase_records(CargoExtractionPhase::ProjectMetadata).next();
+        let project_name = metadata
+            .map(|m| m.project_name.clone())
+            .unwrap_or_else(|| project_path.file_name().unwrap_or_default().to_string_lossy().to_string());
+        let dependencies = phase_records(CargoExtractionPhase::DependencyAnalysis).find(|r| r.project_name == project_name);
+        let source = phase_records(CargoExtractionPhase::SourceCodeAnalysis).next();
+        let ecosystem_id = format!("{}:ecosystem_analysis", project_name);
+        let ecosystem = phase_records(CargoExtractionPhase::EcosystemAnalysis).find(|r| r.id == ecosystem_id);
+        let history = phase_records(CargoExtractionPhase::VersionHistory).next();
+
+        let mut record = CargoProjectRecord {
+            id: format!("{}:overview", project_name),
+            project_path: project_path.to_string_lossy().to_string(),
+            project_version: metadata.or(dependencies)
+                .map(|r| r.project_version.clone())
+                .unwrap_or_else(|| "unknown".to_string()),
+            project_name,
+            phase: CargoExtractionPhase::Overview.as_str().to_string(),
+            processing_order: self.next_processing_order(),
+            license: metadata.or(dependencies).and_then(|r| r.license.clone()),
+            repository: metadata.or(dependencies).and_then(|r| r.repository.clone()),
+            timestamp: crate::config::generation_timestamp(),
+            extractor_version: self.extractor_version.clone(),
+            cargo_version: self.cargo_version.clone(),
+            rust_version: self.rust_version.clone(),
+            ..CargoProjectRecord::default()
+        };
+        if let Some(source) = source {
+            record.lines_of_code = source.lines_of_code;
+            record.code_lines = source.code_lines;
+            record.source_file_count = source.source_file_count;
+            record.test_file_count = source.test_file_count;
+            record.documentation_coverage = source.documentation_coverage;
+        }
+        if let Some(dependencies) = dependencies {
+            record.direct_dependencies = dependencies.direct_dependencies;
+            record.total_dependencies = dependencies.total_dependencies;
+        }
+        if let Some(ecosystem) = ecosystem {
+            record.download_count = ecosystem.download_count;
+            record.github_stars = ecosystem.github_stars;
+        }
+        if let Some(history) = history {
+            record.commit_count = history.commit_count;
+            record.contributor_count = history.contributor_count;
+        }
+        record.health_score = Some(compute_health_score(&record));
+        Ok(record)
+    }
+
     /// Generate next processing order number
     fn next_processing_order(&mut self) -> u32 {
         self.processing_order += 1;
@@ -1018,10 +1951,10 @@ impl Cargo2HfExtractor {
         Ok(())
     }
     
-    /// Write records to a single Parquet file
-    fn write_records_to_parquet(&self, records: &[CargoProjectRecord], output_file: &Path) -> Result<()> {
+    /// The canonical Arrow schema for Cargo project Parquet output
+    pub fn parquet_schema() -> Arc<Schema> {
         // Define Arrow schema for Cargo project records
-        let schema = Arc::new(Schema::new(vec![
+        Arc::new(Schema::new(vec![
             // Identification fields
             Field::new("id", DataType::Utf8, false),
             Field::new("project_path", DataType::Utf8, false),
@@ -1039,15 +1972,21 @@ impl Cargo2HfExtractor {
             Field::new("documentation", DataType::Utf8, true),
             Field::new("keywords", DataType::Utf8, true),
             Field::new("categories", DataType::Utf8, true),
+            Field::new("edition", DataType::Utf8, true),
+            Field::new("msrv", DataType::Utf8, true),
             
             // Source code metrics
             Field::new("lines_of_code", DataType::UInt32, false),
+            Field::new("code_lines", DataType::UInt32, false),
+            Field::new("comment_lines", DataType::UInt32, false),
+            Field::new("blank_lines", DataType::UInt32, false),
             Field::new("source_file_count", DataType::UInt32, false),
             Field::new("test_file_count", DataType::UInt32, false),
             Field::new("example_file_count", DataType::UInt32, false),
             Field::new("benchmark_file_count", DataType::UInt32, false),
             Field::new("complexity_score", DataType::Float32, false),
             Field::new("documentation_coverage", DataType::Float32, false),
+            Field::new("error_handling_data", DataType::Utf8, true),
             
             // Dependency information
             Field::new("direct_dependencies", DataType::UInt32, false),
@@ -1075,13 +2014,29 @@ impl Cargo2HfExtractor {
             Field::new("project_age_days", DataType::UInt32, true),
             Field::new("release_frequency", DataType::Float32, true),
             
+            // Overview
+            Field::new("health_score", DataType::Float32, true),
+            
             // Processing metadata
             Field::new("processing_time_ms", DataType::UInt64, false),
             Field::new("timestamp", DataType::UInt64, false),
             Field::new("extractor_version", DataType::Utf8, false),
             Field::new("cargo_version", DataType::Utf8, false),
             Field::new("rust_version", DataType::Utf8, false),
-        ]));
+        ]))
+    }
+
+    /// Restrict Parquet output to a named subset of columns (validated against the schema)
+    pub fn with_columns(mut self, columns: Vec<String>) -> Result<Self> {
+        select_columns(&Self::parquet_schema(), &columns)?;
+        self.columns = Some(columns);
+        Ok(self)
+    }
+
+    /// Write records to a single Parquet file
+    fn write_records_to_parquet(&self, records: &[CargoProjectRecord], output_file: &Path) -> Result<()> {
+        let schema = Self::parquet_schema();
+        
         
         // Convert records to Arrow arrays (similar to rust-analyzer extractor)
         let ids: Vec<String> = records.iter().map(|r| r.id.clone()).collect();
@@ -1099,14 +2054,20 @@ impl Cargo2HfExtractor {
         let documentations: Vec<Option<String>> = records.iter().map(|r| r.documentation.clone()).collect();
         let keywords: Vec<Option<String>> = records.iter().map(|r| r.keywords.clone()).collect();
         let categories: Vec<Option<String>> = records.iter().map(|r| r.categories.clone()).collect();
+        let editions: Vec<Option<String>> = records.iter().map(|r| r.edition.clone()).collect();
+        let msrvs: Vec<Option<String>> = records.iter().map(|r| r.msrv.clone()).collect();
         
         let lines_of_code: Vec<u32> = records.iter().map(|r| r.lines_of_code).collect();
+        let code_lines: Vec<u32> = records.iter().map(|r| r.code_lines).collect();
+        let comment_lines: Vec<u32> = records.iter().map(|r| r.comment_lines).collect();
+        let blank_lines: Vec<u32> = records.iter().map(|r| r.blank_lines).collect();
         let source_file_counts: Vec<u32> = records.iter().map(|r| r.source_file_count).collect();
         let test_file_counts: Vec<u32> = records.iter().map(|r| r.test_file_count).collect();
         let example_file_counts: Vec<u32> = records.iter().map(|r| r.example_file_count).collect();
         let benchmark_file_counts: Vec<u32> = records.iter().map(|r| r.benchmark_file_count).collect();
         let complexity_scores: Vec<f32> = records.iter().map(|r| r.complexity_score).collect();
         let documentation_coverages: Vec<f32> = records.iter().map(|r| r.documentation_coverage).collect();
+        let error_handling_data: Vec<Option<String>> = records.iter().map(|r| r.error_handling_data.clone()).collect();
         
         let direct_dependencies: Vec<u32> = records.iter().map(|r| r.direct_dependencies).collect();
         let total_dependencies: Vec<u32> = records.iter().map(|r| r.total_dependencies).collect();
@@ -1129,6 +2090,7 @@ impl Cargo2HfExtractor {
         let contributor_counts: Vec<Option<u32>> = records.iter().map(|r| r.contributor_count).collect();
         let project_age_days: Vec<Option<u32>> = records.iter().map(|r| r.project_age_days).collect();
         let release_frequencies: Vec<Option<f32>> = records.iter().map(|r| r.release_frequency).collect();
+        let health_scores: Vec<Option<f32>> = records.iter().map(|r| r.health_score).collect();
         
         let processing_times: Vec<u64> = records.iter().map(|r| r.processing_time_ms).collect();
         let timestamps: Vec<u64> = records.iter().map(|r| r.timestamp).collect();
@@ -1152,14 +2114,20 @@ impl Cargo2HfExtractor {
         let documentation_array = Arc::new(StringArray::from(documentations));
         let keywords_array = Arc::new(StringArray::from(keywords));
         let categories_array = Arc::new(StringArray::from(categories));
+        let edition_array = Arc::new(StringArray::from(editions));
+        let msrv_array = Arc::new(StringArray::from(msrvs));
         
         let lines_of_code_array = Arc::new(UInt32Array::from(lines_of_code));
+        let code_lines_array = Arc::new(UInt32Array::from(code_lines));
+        let comment_lines_array = Arc::new(UInt32Array::from(comment_lines));
+        let blank_lines_array = Arc::new(UInt32Array::from(blank_lines));
         let source_file_count_array = Arc::new(UInt32Array::from(source_file_counts));
         let test_file_count_array = Arc::new(UInt32Array::from(test_file_counts));
         let example_file_count_array = Arc::new(UInt32Array::from(example_file_counts));
         let benchmark_file_count_array = Arc::new(UInt32Array::from(benchmark_file_counts));
         let complexity_score_array = Arc::new(Float32Array::from(complexity_scores));
         let documentation_coverage_array = Arc::new(Float32Array::from(documentation_coverages));
+        let error_handling_data_array = Arc::new(StringArray::from(error_handling_data));
         
         let direct_dependencies_array = Arc::new(UInt32Array::from(direct_dependencies));
         let total_dependencies_array = Arc::new(UInt32Array::from(total_dependencies));
@@ -1182,6 +2150,7 @@ impl Cargo2HfExtractor {
         let contributor_count_array = Arc::new(UInt32Array::from(contributor_counts));
         let project_age_days_array = Arc::new(UInt32Array::from(project_age_days));
         let release_frequency_array = Arc::new(Float32Array::from(release_frequencies));
+        let health_score_array = Arc::new(Float32Array::from(health_scores));
         
         let processing_time_array = Arc::new(UInt64Array::from(processing_times));
         let timestamp_array = Arc::new(UInt64Array::from(timestamps));
@@ -1190,7 +2159,7 @@ impl Cargo2HfExtractor {
         let rust_version_array = Arc::new(StringArray::from(rust_versions));
         
         // Create record batch with all arrays
-        let batch = RecordBatch::try_new(
+        let batch = build_record_batch(
             schema.clone(),
             vec![
                 id_array,
@@ -1207,13 +2176,19 @@ impl Cargo2HfExtractor {
                 documentation_array,
                 keywords_array,
                 categories_array,
+                edition_array,
+                msrv_array,
                 lines_of_code_array,
+                code_lines_array,
+                comment_lines_array,
+                blank_lines_array,
                 source_file_count_array,
                 test_file_count_array,
                 example_file_count_array,
                 benchmark_file_count_array,
                 complexity_score_array,
                 documentation_coverage_array,
+                error_handling_data_array,
                 direct_dependencies_array,
                 total_dependencies_array,
                 dev_dependencies_array,
@@ -1232,33 +2207,321 @@ impl Cargo2HfExtractor {
                 contributor_count_array,
                 project_age_days_array,
                 release_frequency_array,
+                health_score_array,
                 processing_time_array,
                 timestamp_array,
                 extractor_version_array,
                 cargo_version_array,
                 rust_version_array,
             ],
+            records.len(),
         )?;
         
+        // Project to the selected column subset, if any
+        let batch = match &self.columns {
+            Some(columns) => batch.project(&select_columns(&schema, columns)?)?,
+            None => batch,
+        };
+        
         // Write to Parquet file
         let file = std::fs::File::create(output_file)?;
         let props = WriterProperties::builder()
             .set_compression(parquet::basic::Compression::SNAPPY)
             .build();
         
-        let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
         writer.write(&batch)?;
         writer.close()?;
         
         Ok(())
     }
+
+    /// Read project records back from a Parquet file written with the full schema
+    pub fn read_records_from_parquet(path: &Path) -> Result<Vec<CargoProjectRecord>> {
+        let mut records = Vec::new();
+        for batch in read_parquet_batches(path)? {
+            let id = typed_column::<StringArray>(&batch, "id")?;
+            let project_path = typed_column::<StringArray>(&batch, "project_path")?;
+            let project_name = typed_column::<StringArray>(&batch, "project_name")?;
+            let project_version = typed_column::<StringArray>(&batch, "project_version")?;
+            let phase = typed_column::<StringArray>(&batch, "phase")?;
+            let processing_order = typed_column::<UInt32Array>(&batch, "processing_order")?;
+            let description = typed_column::<StringArray>(&batch, "description")?;
+            let authors = typed_column::<StringArray>(&batch, "authors")?;
+            let license = typed_column::<StringArray>(&batch, "license")?;
+            let repository = typed_column::<StringArray>(&batch, "repository")?;
+            let homepage = typed_column::<StringArray>(&batch, "homepage")?;
+            let documentation = typed_column::<StringArray>(&batch, "documentation")?;
+            let keywords = typed_column::<StringArray>(&batch, "keywords")?;
+            let categories = typed_column::<StringArray>(&batch, "categories")?;
+            // Added later; older datasets lack these columns
+            let edition = typed_column::<StringArray>(&batch, "edition").ok();
+            let msrv = typed_column::<StringArray>(&batch, "msrv").ok();
+            let lines_of_code = typed_column::<UInt32Array>(&batch, "lines_of_code")?;
+            let code_lines = typed_column::<UInt32Array>(&batch, "code_lines")?;
+            let comment_lines = typed_column::<UInt32Array>(&batch, "comment_lines")?;
+            let blank_lines = typed_column::<UInt32Array>(&batch, "blank_lines")?;
+            let source_file_count = typed_column::<UInt32Array>(&batch, "source_file_count")?;
+            let test_file_count = typed_column::<UInt32Array>(&batch, "test_file_count")?;
+            let example_file_count = typed_column::<UInt32Array>(&batch, "example_file_count")?;
+            let benchmark_file_count = typed_column::<UInt32Array>(&batch, "benchmark_file_count")?;
+            let complexity_score = typed_column::<Float32Array>(&batch, "complexity_score")?;
+            let documentation_coverage = typed_column::<Float32Array>(&batch, "documentation_coverage")?;
+            let error_handling_data = typed_column::<StringArray>(&batch, "error_handling_data")?;
+            let direct_dependencies = typed_column::<UInt32Array>(&batch, "direct_dependencies")?;
+            let total_dependencies = typed_column::<UInt32Array>(&batch, "total_dependencies")?;
+            let dev_dependencies = typed_column::<UInt32Array>(&batch, "dev_dependencies")?;
+            let build_dependencies = typed_column::<UInt32Array>(&batch, "build_dependencies")?;
+            let dependency_data = typed_column::<StringArray>(&batch, "dependency_data")?;
+            let features = typed_column::<StringArray>(&batch, "features")?;
+            let targets = typed_column::<StringArray>(&batch, "targets")?;
+            let has_build_script = typed_column::<BooleanArray>(&batch, "has_build_script")?;
+            let build_script_complexity = typed_column::<UInt32Array>(&batch, "build_script_complexity")?;
+            let download_count = typed_column::<UInt64Array>(&batch, "download_count")?;
+            let github_stars = typed_column::<UInt32Array>(&batch, "github_stars")?;
+            let github_forks = typed_column::<UInt32Array>(&batch, "github_forks")?;
+            let github_issues = typed_column::<UInt32Array>(&batch, "github_issues")?;
+            let last_updated = typed_column::<UInt64Array>(&batch, "last_updated")?;
+            let commit_count = typed_column::<UInt32Array>(&batch, "commit_count")?;
+            let contributor_count = typed_column::<UInt32Array>(&batch, "contributor_count")?;
+            let project_age_days = typed_column::<UInt32Array>(&batch, "project_age_days")?;
+            let release_frequency = typed_column::<Float32Array>(&batch, "release_frequency")?;
+            let health_score = typed_column::<Float32Array>(&batch, "health_score").ok();
+            let processing_time_ms = typed_column::<UInt64Array>(&batch, "processing_time_ms")?;
+            let timestamp = typed_column::<UInt64Array>(&batch, "timestamp")?;
+            let extractor_version = typed_column::<StringArray>(&batch, "extractor_version")?;
+            let cargo_version = typed_column::<StringArray>(&batch, "cargo_version")?;
+            let rust_version = typed_column::<StringArray>(&batch, "rust_version")?;
+
+            for row in 0..batch.num_rows() {
+                records.push(CargoProjectRecord {
+                    id: id.value(row).to_string(),
+                    project_path: project_path.value(row).to_string(),
+                    project_name: project_name.value(row).to_string(),
+                    project_version: project_version.value(row).to_string(),
+                    phase: phase.value(row).to_string(),
+                    processing_order: processing_order.value(row),
+                    description: optional_string(description, row),
+                    authors: optional_string(authors, row),
+                    license: optional_string(license, row),
+                    repository: optional_string(repository, row),
+                    homepage: optional_string(homepage, row),
+                    documentation: optional_string(documentation, row),
+                    keywords: optional_string(keywords, row),
+                    categories: optional_string(categories, row),
+                    edition: edition.and_then(|array| optional_string(array, row)),
+                    msrv: msrv.and_then(|array| optional_string(array, row)),
+                    lines_of_code: lines_of_code.value(row),
+                    code_lines: code_lines.value(row),
+                    comment_lines: comment_lines.value(row),
+                    blank_lines: blank_lines.value(row),
+                    source_file_count: source_file_count.value(row),
+                    test_file_count: test_file_count.value(row),
+                    example_file_count: example_file_count.value(row),
+                    benchmark_file_count: benchmark_file_count.value(row),
+                    complexity_score: complexity_score.value(row),
+                    documentation_coverage: documentation_coverage.value(row),
+                    error_handling_data: optional_string(error_handling_data, row),
+                    direct_dependencies: direct_dependencies.value(row),
+                    total_dependencies: total_dependencies.value(row),
+                    dev_dependencies: dev_dependencies.value(row),
+                    build_dependencies: build_dependencies.value(row),
+                    dependency_data: optional_string(dependency_data, row),
+                    features: optional_string(features, row),
+                    targets: optional_string(targets, row),
+                    has_build_script: has_build_script.value(row),
+                    build_script_complexity: build_script_complexity.value(row),
+                    download_count: optional_value(download_count, row),
+                    github_stars: optional_value(github_stars, row),
+                    github_forks: optional_value(github_forks, row),
+                    github_issues: optional_value(github_issues, row),
+                    last_updated: optional_value(last_updated, row),
+                    commit_count: optional_value(commit_count, row),
+                    contributor_count: optional_value(contributor_count, row),
+                    project_age_days: optional_value(project_age_days, row),
+                    release_frequency: optional_value(release_frequency, row),
+                    health_score: health_score.and_then(|array| optional_value(array, row)),
+                    processing_time_ms: processing_time_ms.value(row),
+                    timestamp: timestamp.value(row),
+                    extractor_version: extractor_version.value(row).to_string(),
+                    cargo_version: cargo_version.value(row).to_string(),
+                    rust_version: rust_version.value(row).to_string(),
+                });
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// Cross-crate statistics aggregated from many per-crate cargo2hf datasets
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EcosystemSummary {
+    /// Number of crate datasets aggregated
+    pub crate_count: usize,
+    /// Direct dependencies by the number of crates using them, most common first
+    pub dependencies: Vec<(String, u64)>,
+    /// Crates per license expression ("unspecified" when missing)
+    pub licenses: BTreeMap<String, u64>,
+    /// Mean `lines_of_code` over crates with a source code analysis record
+    pub average_lines_of_code: Option<f64>,
+    /// Crates per `rust-version` ("unspecified" when missing)
+    pub msrvs: BTreeMap<String, u64>,
+    /// Crates per edition ("unspecified" when missing)
+    pub editions: BTreeMap<String, u64>,
+    /// Dataset directories that could not be aggregated, with reasons
+    pub skipped: Vec<String>,
+}
+
+/// Read all records of one phase of a cargo2hf dataset (empty if the phase was not run)
+fn read_phase_records(dataset_dir: &Path, phase: &CargoExtractionPhase) -> Result<Vec<CargoProjectRecord>> {
+    let phase_dir = dataset_dir.join(format!("{}-phase", phase.as_str()));
+    if !phase_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<_> = std::fs::read_dir(&phase_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("parquet"))
+        .collect();
+    files.sort();
+
+    let mut records = Vec::new();
+    for file in files {
+        records.extend(Cargo2HfExtractor::read_records_from_parquet(&file)?);
+    }
+    Ok(records)
+}
+
+/// Aggregate the per-crate cargo2hf datasets found in the subdirectories of `root`
+/// 
+/// A subdirectory counts as a crate dataset when it has a `project_metadata-phase`
+/// directory. The crate is the package named by its project metadata record;
+/// only that package's direct dependencies are counted, once per crate.
+pub fn summarize_ecosystem(root: &Path) -> Result<EcosystemSummary> {
+    let mut dataset_dirs: Vec<_> = std::fs::read_dir(root)
+        .with_context(|| format!("Failed to read {}", root.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.join(format!("{}-phase", CargoExtractionPhase::ProjectMetadata.as_str())).is_dir())
+        .collect();
+    dataset_dirs.sort();
+
+    let mut summary = EcosystemSummary::default();
+    let mut dependency_counts: HashMap<String, u64> = HashMap::new();
+    let mut lines_of_code: Vec<u32> = Vec::new();
+    let unspecified = || "unspecified".to_string();
+
+    for dataset_dir in &dataset_dirs {
+        let load = |phase: CargoExtractionPhase| read_phase_records(dataset_dir, &phase);
+        let (metadata, dependencies, source) = match (
+            load(CargoExtractionPhase::ProjectMetadata),
+            load(CargoExtractionPhase::DependencyAnalysis),
+            load(CargoExtractionPhase::SourceCodeAnalysis),
+        ) {
+            (Ok(metadata), Ok(dependencies), Ok(source)) => (metadata, dependencies, source),
+            (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+                summary.skipped.push(format!("{}: {:#}", dataset_dir.display(), e));
+                continue;
+            }
+        };
+        let Some(project) = metadata.first() else {
+            summary.skipped.push(format!("{}: no project metadata record", dataset_dir.display()));
+            continue;
+        };
+
+        summary.crate_count += 1;
+        let package = dependencies.iter().find(|r| r.project_name == project.project_name);
+        *summary.licenses.entry(project.license.clone().unwrap_or_else(unspecified)).or_insert(0) += 1;
+        let edition = project.edition.clone().or_else(|| package.and_then(|p| p.edition.clone()));
+        *summary.editions.entry(edition.unwrap_or_else(unspecified)).or_insert(0) += 1;
+        let msrv = project.msrv.clone().or_else(|| package.and_then(|p| p.msrv.clone()));
+        *summary.msrvs.entry(msrv.unwrap_or_else(unspecified)).or_insert(0) += 1;
+
+        if let Some(dependency_data) = package.and_then(|p| p.dependency_data.as_deref()) {
+            let infos: Vec<DependencyInfo> = serde_json::from_str(dependency_data)
+                .with_context(|| format!("Invalid dependency_data in {}", dataset_dir.display()))?;
+            let names: BTreeSet<&str> = infos.iter().map(|d| d.name.as_str()).collect();
+            for name in names {
+                *dependency_counts.entry(name.to_string()).or_insert(0) += 1;
+            }
+        }
+        if let Some(record) = source.first() {
+            lines_of_code.push(record.lines_of_code);
+        }
+    }
+
+    summary.dependencies = dependency_counts.into_iter().collect();
+    summary.dependencies.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    if !lines_of_code.is_empty() {
+        let total: u64 = lines_of_code.iter().map(|&loc| loc as u64).sum();
+        summary.average_lines_of_code = Some(total as f64 / lines_of_code.len() as f64);
+    }
+    Ok(summary)
+}
+
+/// Write an ecosystem summary as a long-format Parquet table (`metric`, `key`, `value`)
+pub fn write_ecosystem_summary(summary: &EcosystemSummary, output_file: &Path) -> Result<()> {
+    use arrow::array::Float64Array;
+
+    let mut rows: Vec<(&str, String, f64)> = vec![("crate_count", String::new(), summary.crate_count as f64)];
+    if let Some(average) = summary.average_lines_of_code {
+        rows.push(("average_lines_of_code", String::new(), average));
+    }
+    rows.extend(summary.dependencies.iter().map(|(name, count)| ("dependency", name.clone(), *count as f64)));
+    rows.extend(summary.licenses.iter().map(|(license, count)| ("license", license.clone(), *count as f64)));
+    rows.extend(summary.msrvs.iter().map(|(msrv, count)| ("msrv", msrv.clone(), *count as f64)));
+    rows.extend(summary.editions.iter().map(|(edition, count)| ("edition", edition.clone(), *count as f64)));
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("metric", DataType::Utf8, false),
+        Field::new("key", DataType::Utf8, false),
+        Field::new("value", DataType::Float64, false),
+    ]));
+    let batch = build_record_batch(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from(rows.iter().map(|(metric, _, _)| *metric).collect::<Vec<_>>())),
+            Arc::new(StringArray::from(rows.iter().map(|(_, key, _)| key.as_str()).collect::<Vec<_>>())),
+            Arc::new(Float64Array::from(rows.iter().map(|(_, _, value)| *value).collect::<Vec<_>>())),
+        ],
+        rows.len(),
+    )?;
+
+    let mut writer = ArrowWriter::try_new(std::fs::File::create(output_file)?, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// Print an ecosystem summary, listing the `top_n` most common dependencies
+pub fn print_ecosystem_summary(summary: &EcosystemSummary, top_n: usize) {
+    let format_counts = |counts: &BTreeMap<String, u64>| {
+        counts.iter().map(|(key, count)| format!("{} ({})", key, count)).collect::<Vec<_>>().join(", ")
+    };
+
+    println!("Crates: {}", summary.crate_count);
+    match summary.average_lines_of_code {
+        Some(average) => println!("Average lines of code: {:.1}", average),
+        None => println!("Average lines of code: n/a (no source code analysis)"),
+    }
+    println!("Most common dependencies:");
+    for (name, count) in summary.dependencies.iter().take(top_n) {
+        println!("  {}: {} crates", name, count);
+    }
+    println!("Licenses: {}", format_counts(&summary.licenses));
+    println!("MSRV: {}", format_counts(&summary.msrvs));
+    println!("Editions: {}", format_counts(&summary.editions));
+    for skipped in &summary.skipped {
+        println!("  ⚠️ Skipped {}", skipped);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::spawn_mock_http;
+    use proptest::prelude::*;
     use tempfile::TempDir;
     use std::fs;
+    use std::time::Duration;
 
     #[test]
     fn test_cargo2hf_extractor_creation() {
@@ -1289,4 +2552,630 @@ license = "MIT"
         assert_eq!(records[0].description, Some("A test project".to_string()));
         assert_eq!(records[0].license, Some("MIT".to_string()));
     }
+
+    #[test]
+    fn test_single_file_script_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let script = temp_dir.path().join("hello.rs");
+        fs::write(&script, r#"#!/usr/bin/env -S cargo +nightly -Zscript
+---cargo
+[package]
+name = "hello-script"
+version = "0.2.0"
+edition = "2024"
+description = "Prints a greeting"
+
+[dependencies]
+regex = "1"
+---
+
+fn main() {
+    println!("hello");
+}
+"#).unwrap();
+
+        let mut extractor = Cargo2HfExtractor::new().unwrap();
+        let records = extractor.extract_project_metadata(&script).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].project_name, "hello-script");
+        assert_eq!(records[0].project_version, "0.2.0");
+        assert_eq!(records[0].edition.as_deref(), Some("2024"));
+        assert_eq!(records[0].description.as_deref(), Some("Prints a greeting"));
+
+        // Without frontmatter the file stem names the package
+        let bare = temp_dir.path().join("bare_script.rs");
+        fs::write(&bare, "fn main() {}\n").unwrap();
+        let records = extractor.extract_project_metadata(&bare).unwrap();
+        assert_eq!(records[0].project_name, "bare_script");
+        assert_eq!(records[0].project_version, "0.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_single_file_package_end_to_end() {
+        let temp_dir = TempDir::new().unwrap();
+        let script = temp_dir.path().join("hello.rs");
+        fs::write(&script, "---\n[package]\nname = \"hello-script\"\nversion = \"0.2.0\"\n---\n\nfn main() {\n    println!(\"hello\");\n}\n").unwrap();
+        let output_dir = temp_dir.path().join("dataset");
+
+        let mut extractor = Cargo2HfExtractor::new().unwrap();
+        extractor.extract_project_to_parquet(
+            &script,
+            &[
+                CargoExtractionPhase::ProjectMetadata,
+                CargoExtractionPhase::DependencyAnalysis,
+                CargoExtractionPhase::SourceCodeAnalysis,
+                CargoExtractionPhase::BuildAnalysis,
+                CargoExtractionPhase::VersionHistory,
+                CargoExtractionPhase::Overview,
+            ],
+            &output_dir,
+            false,
+        ).await.unwrap();
+
+        assert!(output_dir.join("project_metadata-phase/data.parquet").is_file());
+        assert!(output_dir.join("source_code_analysis-phase/data.parquet").is_file());
+        assert!(output_dir.join("overview-phase/data.parquet").is_file());
+        assert!(!output_dir.join("dependency_analysis-phase").exists());
+        assert!(!output_dir.join("version_history-phase").exists());
+
+        let provenance: Provenance = serde_json::from_str(&fs::read_to_string(output_dir.join("provenance.json")).unwrap()).unwrap();
+        assert_eq!(provenance.phases.first().map(String::as_str), Some("project_metadata"));
+        assert_eq!(provenance.tool_git_commit.as_deref(), option_env!("HF_VALIDATOR_GIT_COMMIT"));
+    }
+
+    #[test]
+    fn test_workspace_inherited_package_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), r#"
+[workspace]
+members = ["member"]
+
+[workspace.package]
+version = "1.2.3"
+license = "MIT OR Apache-2.0"
+"#).unwrap();
+        let member_dir = temp_dir.path().join("member");
+        fs::create_dir_all(&member_dir).unwrap();
+        fs::write(member_dir.join("Cargo.toml"), r#"
+[package]
+name = "member"
+version.workspace = true
+license.workspace = true
+edition.workspace = true
+description = "Own description"
+"#).unwrap();
+
+        let mut extractor = Cargo2HfExtractor::new().unwrap();
+        let records = extractor.extract_project_metadata(&member_dir).unwrap();
+        let record = &records[0];
+        assert_eq!(record.project_version, "1.2.3");
+        assert_eq!(record.id, "member:1.2.3:project_metadata");
+        assert_eq!(record.license.as_deref(), Some("MIT OR Apache-2.0"));
+        assert_eq!(record.description.as_deref(), Some("Own description"));
+        // Inherited, but not defined by the workspace
+        assert_eq!(record.edition, None);
+    }
+
+    #[test]
+    fn test_line_classification() {
+        let source = r#"//! Crate docs
+
+/* block comment
+   spanning lines */
+fn main() { // trailing comment
+    let s = "not // a comment";
+
+    /* inline */ let x = 1;
+}
+"#;
+        let counts = LineCounts::classify(source);
+        assert_eq!(counts.code, 4);
+        assert_eq!(counts.comment, 3);
+        assert_eq!(counts.blank, 2);
+    }
+
+    #[test]
+    fn test_error_handling_scan() {
+        let source = r##"
+use anyhow::Result;
+
+fn load(path: &str) -> Result<String> {
+    let text = std::fs::read_to_string(path)?;
+    let first = text.lines().next().unwrap();
+    let n: u32 = first.parse()?;
+    // a comment mentioning unwrap() and panic!() is ignored
+    println!("strings mentioning .unwrap() are ignored too");
+    println!(r#"say "x.unwrap()" now, even in raw strings"#);
+    Ok(format!("{}", n))
+}
+
+fn size<T: ?Sized>(value: &T) -> usize {
+    std::mem::size_of_val(value)
+}
+"##;
+        let stats = ErrorHandlingStats::scan(source);
+        assert_eq!(stats.unwrap_count, 1);
+        assert_eq!(stats.question_mark_count, 2);
+        assert_eq!(stats.panic_count, 0);
+        assert_eq!(stats.function_count, 2);
+        assert_eq!(stats.result_function_count, 1);
+        assert!(stats.uses_anyhow);
+        assert!(stats.uses_result_pervasively);
+    }
+
+    #[tokio::test]
+    async fn test_dependency_freshness() {
+        // Mock crates.io versions endpoint: 1.2.0 is resolved, 1.3.0 and 1.4.0 are newer
+        let mock_url = spawn_mock_http(|_| Some((200, r#"{"versions":[
+            {"num":"1.4.0","yanked":false},
+            {"num":"1.3.1","yanked":true},
+            {"num":"1.3.0","yanked":false},
+            {"num":"2.0.0-beta.1","yanked":false},
+            {"num":"1.2.0","yanked":false},
+            {"num":"1.1.0","yanked":false}
+        ]}"#.to_string())));
+
+        let http_config = HttpClientConfig {
+            proxy: None,
+            min_request_interval: Duration::ZERO,
+            crates_io_api_url: mock_url,
+            ..HttpClientConfig::default()
+        };
+        let mut extractor = Cargo2HfExtractor::new().unwrap().with_http_config(http_config).unwrap();
+
+        let mut dependencies = vec![DependencyInfo {
+            name: "serde".to_string(),
+            rename: None,
+            version_req: "^1.2".to_string(),
+            resolved_version: Some("1.2.0".to_string()),
+            optional: false,
+            default_features: true,
+            features: Vec::new(),
+            source: DependencySource::CratesIo.as_str().to_string(),
+            is_dev: false,
+            is_build: false,
+            latest_version: None,
+            versions_behind: None,
+            is_outdated: None,
+        }];
+        extractor.annotate_dependency_freshness(&mut dependencies).await;
+
+        assert_eq!(dependencies[0].versions_behind, Some(2));
+        assert_eq!(dependencies[0].is_outdated, Some(true));
+        assert_eq!(dependencies[0].latest_version.as_deref(), Some("1.4.0"));
+    }
+
+    #[tokio::test]
+    async fn test_ecosystem_fetch_times_out() {
+        use std::time::Instant;
+
+        // Mock crates.io that accepts connections but never responds
+        let mock_url = spawn_mock_http(|_| None);
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), r#"
+[package]
+name = "test-project"
+version = "0.1.0"
+"#).unwrap();
+
+        let http_config = HttpClientConfig {
+            connect_timeout: Duration::from_millis(200),
+            request_timeout: Duration::from_millis(300),
+            proxy: None,
+            min_request_interval: Duration::ZERO,
+            crates_io_api_url: mock_url.clone(),
+            github_api_url: mock_url,
+            ..HttpClientConfig::default()
+        };
+        let mut extractor = Cargo2HfExtractor::new().unwrap().with_http_config(http_config).unwrap();
+
+        let started = Instant::now();
+        let records = extractor.extract_ecosystem_analysis(temp_dir.path(), false).await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].download_count, None);
+    }
+
+    #[tokio::test]
+    async fn test_ecosystem_records_per_dependency() {
+        // Mock crates.io: downloads are looked up by crate name, other paths 404
+        let mock_url = spawn_mock_http(|path| {
+            let downloads = match path {
+                "/crates/app" => 1,
+                "/crates/dep-a" => 100,
+                "/crates/dep-b" => 200,
+                _ => return Some((404, String::new())),
+            };
+            Some((200, format!(r#"{{"crate":{{"downloads":{}}}}}"#, downloads)))
+        });
+
+        let temp_dir = TempDir::new().unwrap();
+        let write_crate = |dir: &str, manifest: &str| {
+            fs::create_dir_all(temp_dir.path().join(dir).join("src")).unwrap();
+            fs::write(temp_dir.path().join(dir).join("src/lib.rs"), "").unwrap();
+            fs::write(temp_dir.path().join(dir).join("Cargo.toml"), manifest).unwrap();
+        };
+        write_crate("dep-a", "[package]\nname = \"dep-a\"\nversion = \"0.1.0\"\nedition = \"2021\"\n");
+        write_crate("dep-b", "[package]\nname = \"dep-b\"\nversion = \"0.2.0\"\nedition = \"2021\"\n");
+        write_crate("app", r#"[package]
+name = "app"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+dep-a = { path = "../dep-a" }
+dep-b = { path = "../dep-b" }
+"#);
+
+        let http_config = HttpClientConfig {
+            proxy: None,
+            min_request_interval: Duration::ZERO,
+            crates_io_api_url: mock_url.clone(),
+            github_api_url: mock_url,
+            ..HttpClientConfig::default()
+        };
+        let mut extractor = Cargo2HfExtractor::new().unwrap().with_http_config(http_config).unwrap();
+        let records = extractor.extract_ecosystem_analysis(&temp_dir.path().join("app"), true).await.unwrap();
+
+        let downloads = |id: &str| records.iter().find(|r| r.id == id).unwrap().download_count;
+        assert_eq!(records.len(), 3);
+        assert_eq!(downloads("app:ecosystem_analysis"), Some(1));
+        assert_eq!(downloads("dep-a:0.1.0:ecosystem_analysis"), Some(100));
+        assert_eq!(downloads("dep-b:0.2.0:ecosystem_analysis"), Some(200));
+
+        // Without include_dependencies only the root package is recorded
+        let records = extractor.extract_ecosystem_analysis(&temp_dir.path().join("app"), false).await.unwrap();
+        assert_eq!(records.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ecosystem_cache_resumes_interrupted_run() {
+        use std::sync::{Arc, Mutex};
+
+        // Mock crates.io recording requested paths; `rate_limited` paths get a 429
+        let spawn_mock = |rate_limited: &'static [&'static str]| {
+            let requested = Arc::new(Mutex::new(Vec::new()));
+            let log = requested.clone();
+            let mock_url = spawn_mock_http(move |path| {
+                log.lock().unwrap().push(path.to_string());
+                if rate_limited.contains(&path) {
+                    Some((429, String::new()))
+                } else {
+                    Some((200, r#"{"crate":{"downloads":42}}"#.to_string()))
+                }
+            });
+            (mock_url, requested)
+        };
+
+        let temp_dir = TempDir::new().unwrap();
+        let write_crate = |dir: &str, manifest: &str| {
+            fs::create_dir_all(temp_dir.path().join(dir).join("src")).unwrap();
+            fs::write(temp_dir.path().join(dir).join("src/lib.rs"), "").unwrap();
+            fs::write(temp_dir.path().join(dir).join("Cargo.toml"), manifest).unwrap();
+        };
+        write_crate("dep-a", "[package]\nname = \"dep-a\"\nversion = \"0.1.0\"\nedition = \"2021\"\n");
+        write_crate("dep-b", "[package]\nname = \"dep-b\"\nversion = \"0.2.0\"\nedition = \"2021\"\n");
+        write_crate("app", r#"[package]
+name = "app"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+dep-a = { path = "../dep-a" }
+dep-b = { path = "../dep-b" }
+"#);
+        let cache_dir = temp_dir.path().join("cache");
+        let run = |mock_url: String| {
+            let http_config = HttpClientConfig {
+                proxy: None,
+                min_request_interval: Duration::ZERO,
+                crates_io_api_url: mock_url.clone(),
+                github_api_url: mock_url,
+                cache_dir: Some(cache_dir.clone()),
+                ..HttpClientConfig::default()
+            };
+            let mut extractor = Cargo2HfExtractor::new().unwrap().with_http_config(http_config).unwrap();
+            let project_path = temp_dir.path().join("app");
+            async move { extractor.extract_ecosystem_analysis(&project_path, true).await.unwrap() }
+        };
+
+        // First run is rate limited once dep-a is done, so dep-b is left uncached
+        let (mock_url, _) = spawn_mock(&["/crates/dep-b"]);
+        let records = run(mock_url).await;
+        let downloads = |records: &[CargoProjectRecord], id: &str| records.iter().find(|r| r.id == id).unwrap().download_count;
+        assert_eq!(downloads(&records, "dep-a:0.1.0:ecosystem_analysis"), Some(42));
+        assert_eq!(downloads(&records, "dep-b:0.2.0:ecosystem_analysis"), None);
+
+        // The resumed run only fetches dep-b and serves the rest from the cache
+        let (mock_url, requested) = spawn_mock(&[]);
+        let records = run(mock_url).await;
+        assert_eq!(*requested.lock().unwrap(), vec!["/crates/dep-b".to_string()]);
+        assert_eq!(downloads(&records, "app:ecosystem_analysis"), Some(42));
+        assert_eq!(downloads(&records, "dep-a:0.1.0:ecosystem_analysis"), Some(42));
+        assert_eq!(downloads(&records, "dep-b:0.2.0:ecosystem_analysis"), Some(42));
+    }
+
+    #[tokio::test]
+    async fn test_unpublished_crate_still_gets_github_metrics() {
+        // Mock crates.io answering 404 for every crate, and GitHub with repository metrics
+        let mock_url = spawn_mock_http(|path| match path {
+            "/repos/owner/app" => Some((200, r#"{"stargazers_count":7,"forks_count":2,"open_issues_count":1}"#.to_string())),
+            _ => Some((404, String::new())),
+        });
+
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), r#"
+[package]
+name = "app"
+version = "0.1.0"
+repository = "https://github.com/owner/app"
+"#).unwrap();
+
+        let cache_dir = temp_dir.path().join("cache");
+        let http_config = HttpClientConfig {
+            proxy: None,
+            min_request_interval: Duration::ZERO,
+            crates_io_api_url: mock_url.clone(),
+            github_api_url: mock_url,
+            cache_dir: Some(cache_dir.clone()),
+            ..HttpClientConfig::default()
+        };
+        let mut extractor = Cargo2HfExtractor::new().unwrap().with_http_config(http_config).unwrap();
+        let records = extractor.extract_ecosystem_analysis(temp_dir.path(), false).await.unwrap();
+
+        assert_eq!(records[0].download_count, None);
+        assert_eq!(records[0].github_stars, Some(7));
+        assert_eq!(records[0].github_forks, Some(2));
+        // A 404 is a complete answer, so a resumed run does not fetch it again
+        assert!(CachedEcosystemMetrics::cache_path(&cache_dir, "app", "0.1.0").is_file());
+    }
+
+    #[tokio::test]
+    async fn test_overview_matches_detailed_phases() {
+        let temp_dir = TempDir::new().unwrap();
+        let write_crate = |dir: &str, manifest: &str, lib: &str| {
+            fs::create_dir_all(temp_dir.path().join(dir).join("src")).unwrap();
+            fs::write(temp_dir.path().join(dir).join("src/lib.rs"), lib).unwrap();
+            fs::write(temp_dir.path().join(dir).join("Cargo.toml"), manifest).unwrap();
+        };
+        write_crate("dep-a", "[package]\nname = \"dep-a\"\nversion = \"0.1.0\"\nedition = \"2021\"\n", "");
+        write_crate("app", r#"[package]
+name = "app"
+version = "0.3.0"
+edition = "2021"
+license = "MIT"
+
+[dependencies]
+dep-a = { path = "../dep-a" }
+"#, "/// Adds one\npub fn inc(x: u32) -> u32 {\n    x + 1\n}\n");
+
+        let http_config = HttpClientConfig { offline: true, ..HttpClientConfig::default() };
+        let mut extractor = Cargo2HfExtractor::new().unwrap().with_http_config(http_config).unwrap();
+        let output_dir = temp_dir.path().join("out");
+        let phases = [
+            CargoExtractionPhase::Overview,
+            CargoExtractionPhase::ProjectMetadata,
+            CargoExtractionPhase::DependencyAnalysis,
+            CargoExtractionPhase::SourceCodeAnalysis,
+        ];
+        extractor.extract_project_to_parquet(&temp_dir.path().join("app"), &phases, &output_dir, false).await.unwrap();
+
+        let overview = read_phase_records(&output_dir, &CargoExtractionPhase::Overview).unwrap();
+        let source = read_phase_records(&output_dir, &CargoExtractionPhase::SourceCodeAnalysis).unwrap();
+        let dependencies = read_phase_records(&output_dir, &CargoExtractionPhase::DependencyAnalysis).unwrap();
+        let app_dependencies = dependencies.iter().find(|r| r.project_name == "app").unwrap();
+
+        assert_eq!(overview.len(), 1);
+        let overview = &overview[0];
+        assert_eq!(overview.id, "app:overview");
+        assert_eq!(overview.project_version, "0.3.0");
+        assert_eq!(overview.license.as_deref(), Some("MIT"));
+        assert!(overview.lines_of_code > 0);
+        assert_eq!(overview.lines_of_code, source[0].lines_of_code);
+        assert_eq!(overview.direct_dependencies, 1);
+        assert_eq!(overview.direct_dependencies, app_dependencies.direct_dependencies);
+        assert_eq!(overview.total_dependencies, app_dependencies.total_dependencies);
+        let health_score = overview.health_score.unwrap();
+        assert!((0.0..=1.0).contains(&health_score));
+    }
+
+    #[test]
+    fn test_renamed_dependencies_resolve_to_real_package() {
+        let temp_dir = TempDir::new().unwrap();
+        let write_crate = |dir: &str, manifest: &str| {
+            fs::create_dir_all(temp_dir.path().join(dir).join("src")).unwrap();
+            fs::write(temp_dir.path().join(dir).join("src/lib.rs"), "").unwrap();
+            fs::write(temp_dir.path().join(dir).join("Cargo.toml"), manifest).unwrap();
+        };
+        write_crate("dep-v1", "[package]\nname = \"real-dep\"\nversion = \"0.1.0\"\nedition = \"2021\"\n");
+        write_crate("dep-v2", "[package]\nname = \"real-dep\"\nversion = \"0.2.0\"\nedition = \"2021\"\n");
+        write_crate("app", r#"[package]
+name = "app"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+legacy = { package = "real-dep", path = "../dep-v1" }
+current = { package = "real-dep", path = "../dep-v2" }
+"#);
+
+        let metadata = cargo_metadata::MetadataCommand::new()
+            .manifest_path(temp_dir.path().join("app/Cargo.toml"))
+            .other_options(vec!["--offline".to_string()])
+            .exec()
+            .unwrap();
+        let app = metadata.packages.iter().find(|p| p.name == "app").unwrap();
+        let dependencies = Cargo2HfExtractor::collect_dependency_infos(&metadata, app);
+
+        let by_rename = |rename: &str| dependencies.iter().find(|d| d.rename.as_deref() == Some(rename)).unwrap();
+        assert_eq!(by_rename("legacy").name, "real-dep");
+        assert_eq!(by_rename("legacy").resolved_version.as_deref(), Some("0.1.0"));
+        assert_eq!(by_rename("current").resolved_version.as_deref(), Some("0.2.0"));
+        assert!(dependencies.iter().all(|d| d.source == "path"));
+    }
+
+    #[test]
+    fn test_dependency_source_normalization() {
+        assert_eq!(DependencySource::from_source_id(None), DependencySource::Path);
+        assert_eq!(DependencySource::from_source_id(Some("registry+https://github.com/rust-lang/crates.io-index")), DependencySource::CratesIo);
+        assert_eq!(DependencySource::from_source_id(Some("sparse+https://index.crates.io/")), DependencySource::CratesIo);
+        assert_eq!(DependencySource::from_source_id(Some("sparse+https://my-registry.example/index/")), DependencySource::Registry);
+        assert_eq!(DependencySource::from_source_id(Some("git+https://github.com/serde-rs/serde?rev=abc#abc")), DependencySource::Git);
+    }
+
+    #[test]
+    fn test_ecosystem_summary() {
+        let root = TempDir::new().unwrap();
+        let extractor = Cargo2HfExtractor::new().unwrap();
+        let dependency = |name: &str| DependencyInfo {
+            name: name.to_string(),
+            rename: None,
+            version_req: "^1".to_string(),
+            resolved_version: Some("1.0.0".to_string()),
+            optional: false,
+            default_features: true,
+            features: Vec::new(),
+            source: DependencySource::CratesIo.as_str().to_string(),
+            is_dev: false,
+            is_build: false,
+            latest_version: None,
+            versions_behind: None,
+            is_outdated: None,
+        };
+        let write_crate = |name: &str, license: &str, loc: u32, dependencies: Vec<DependencyInfo>| {
+            let dataset_dir = root.path().join(name);
+            let metadata = CargoProjectRecord {
+                project_name: name.to_string(),
+                license: Some(license.to_string()),
+                edition: Some("2021".to_string()),
+                ..Default::default()
+            };
+            let package = CargoProjectRecord {
+                project_name: name.to_string(),
+                dependency_data: Some(serde_json::to_string(&dependencies).unwrap()),
+                ..Default::default()
+            };
+            let source = CargoProjectRecord { project_name: name.to_string(), lines_of_code: loc, ..Default::default() };
+            extractor.write_phase_to_parquet(&[metadata], &CargoExtractionPhase::ProjectMetadata, &dataset_dir).unwrap();
+            extractor.write_phase_to_parquet(&[package], &CargoExtractionPhase::DependencyAnalysis, &dataset_dir).unwrap();
+            extractor.write_phase_to_parquet(&[source], &CargoExtractionPhase::SourceCodeAnalysis, &dataset_dir).unwrap();
+        };
+        write_crate("alpha", "MIT", 100, vec![dependency("serde"), dependency("anyhow")]);
+        write_crate("beta", "Apache-2.0", 300, vec![dependency("serde")]);
+        fs::create_dir_all(root.path().join("not-a-dataset")).unwrap();
+
+        let summary = summarize_ecosystem(root.path()).unwrap();
+        assert_eq!(summary.crate_count, 2);
+        assert_eq!(summary.dependencies[0], ("serde".to_string(), 2));
+        assert_eq!(summary.dependencies[1], ("anyhow".to_string(), 1));
+        assert_eq!(summary.licenses.get("MIT"), Some(&1));
+        assert_eq!(summary.editions.get("2021"), Some(&2));
+        assert_eq!(summary.msrvs.get("unspecified"), Some(&2));
+        assert_eq!(summary.average_lines_of_code, Some(200.0));
+
+        let output_file = root.path().join("ecosystem_summary.parquet");
+        write_ecosystem_summary(&summary, &output_file).unwrap();
+        use arrow::array::Array;
+        let batches = read_parquet_batches(&output_file).unwrap();
+        let keys = typed_column::<StringArray>(&batches[0], "key").unwrap();
+        assert!((0..keys.len()).any(|i| keys.value(i) == "serde"));
+    }
+
+    fn optional_text() -> impl Strategy<Value = Option<String>> {
+        proptest::option::of(".{0,40}")
+    }
+
+    /// Finite floats only: NaN never compares equal, which would fail the roundtrip for the wrong reason
+    fn finite_f32() -> impl Strategy<Value = f32> {
+        -1.0e9f32..1.0e9f32
+    }
+
+    fn arb_project_record() -> impl Strategy<Value = CargoProjectRecord> {
+        (
+            (".{0,30}", ".{0,30}", ".{0,30}", ".{0,30}", ".{0,30}", any::<u32>(), optional_text(), optional_text()),
+            (optional_text(), optional_text(), optional_text(), optional_text(), optional_text(), optional_text(), any::<u32>(), any::<u32>()),
+            (any::<u32>(), any::<u32>(), any::<u32>(), any::<u32>(), any::<u32>(), any::<u32>(), finite_f32(), finite_f32()),
+            (optional_text(), any::<u32>(), any::<u32>(), any::<u32>(), any::<u32>(), optional_text(), optional_text(), optional_text()),
+            (any::<bool>(), any::<u32>(), proptest::option::of(any::<u64>()), proptest::option::of(any::<u32>()), proptest::option::of(any::<u32>()), proptest::option::of(any::<u32>()), proptest::option::of(any::<u64>()), proptest::option::of(any::<u32>())),
+            (proptest::option::of(any::<u32>()), proptest::option::of(any::<u32>()), proptest::option::of(finite_f32()), any::<u64>(), any::<u64>(), ".{0,30}", ".{0,30}", ".{0,30}"),
+            (optional_text(), optional_text(), proptest::option::of(finite_f32())),
+        )
+            .prop_map(|(
+                (id, project_path, project_name, project_version, phase, processing_order, description, authors),
+                (license, repository, homepage, documentation, keywords, categories, lines_of_code, code_lines),
+                (comment_lines, blank_lines, source_file_count, test_file_count, example_file_count, benchmark_file_count, complexity_score, documentation_coverage),
+                (error_handling_data, direct_dependencies, total_dependencies, dev_dependencies, build_dependencies, dependency_data, features, targets),
+                (has_build_script, build_script_complexity, download_count, github_stars, github_forks, github_issues, last_updated, commit_count),
+                (contributor_count, project_age_days, release_frequency, processing_time_ms, timestamp, extractor_version, cargo_version, rust_version),
+                (edition, msrv, health_score),
+            )| CargoProjectRecord {
+                id,
+                project_path,
+                project_name,
+                project_version,
+                phase,
+                processing_order,
+                description,
+                authors,
+                license,
+                repository,
+                homepage,
+                documentation,
+                keywords,
+                categories,
+                edition,
+                msrv,
+                lines_of_code,
+                code_lines,
+                comment_lines,
+                blank_lines,
+                source_file_count,
+                test_file_count,
+                example_file_count,
+                benchmark_file_count,
+                complexity_score,
+                documentation_coverage,
+                error_handling_data,
+                direct_dependencies,
+                total_dependencies,
+                dev_dependencies,
+                build_dependencies,
+                dependency_data,
+                features,
+                targets,
+                has_build_script,
+                build_script_complexity,
+                download_count,
+                github_stars,
+                github_forks,
+                github_issues,
+                last_updated,
+                commit_count,
+                contributor_count,
+                project_age_days,
+                release_frequency,
+                health_score,
+                processing_time_ms,
+                timestamp,
+                extractor_version,
+                cargo_version,
+                rust_version,
+            })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_project_records_roundtrip_through_parquet(records in proptest::collection::vec(arb_project_record(), 0..20)) {
+            let temp_dir = TempDir::new().unwrap();
+            let output_file = temp_dir.path().join("data.parquet");
+            let extractor = Cargo2HfExtractor::new().unwrap();
+
+            extractor.write_records_to_parquet(&records, &output_file).unwrap();
+            let read_back = Cargo2HfExtractor::read_records_from_parquet(&output_file).unwrap();
+            prop_assert_eq!(read_back, records);
+        }
+    }
 }
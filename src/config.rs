@@ -0,0 +1,192 @@
+//! # Run configuration shared by all commands
+//!
+//! ## Seed
+//!
+//! A single `--seed <u64>` drives every randomized step, so a whole generation
+//! is reproducible with one knob. Each consumer draws from its own stream
+//! (`component_rng`), so adding a consumer never shifts the random
+//! sequence seen by the existing ones. Without `--seed`, `emit-pairs --shuffle`
+//! falls back to `DEFAULT_SEED` and `create-sample` takes the first terms of
+//! each group, so runs are still reproducible.
+//!
+//! Components consuming the seed:
+//!
+//! - `emit-pairs --shuffle`: order of the emitted training pairs
+//! - `create-sample`: which terms of each character group are sampled
+//!
+//! ## Clock
+//!
+//! Generation timestamps (the record `timestamp` columns of the rust-analyzer,
+//! cargo and LLVM IR extractors, project ages, generated READMEs and
+//! provenance) honour `SOURCE_DATE_EPOCH`; combined with a fixed seed, reruns
+//! over the same sources produce identical datasets.
+//!
+//! ## Parallelism
+//!
+//! A single `--jobs <n>` caps how much work runs at once, defaulting to the
+//! available parallelism. It sizes the async runtime's worker pool and bounds
+//! the blocking work fanned out with `run_jobs`:
+//!
+//! - `validate-parquet`: files whose metadata is read at the same time
+//! - `generate-hf-dataset`: phase writes in flight alongside extraction
+//!
+//! `--jobs 1` runs everything serially.
+//!
+//! ## Run limits
+//!
+//! `--max-duration <secs>` and `--max-memory <mb>` bound a `generate-hf-dataset`
+//! run. Both are checked between files and between phases; on a breach the
+//! extractor writes `checkpoint.json`, recording how far it got, and fails
+//! instead of hanging a CI job or being OOM-killed. The checkpoint is not a
+//! resume point: a rerun extracts every phase again. Memory is the process
+//! resident set size, read from `/proc/self/status`; where that is
+//! unavailable only the time limit applies.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Seed used when `--seed` is not given
+pub const DEFAULT_SEED: u64 = 0;
+
+/// Independent random stream for one named component, derived from the run seed
+///
+/// `StdRng` is deterministic for a given seed within the pinned `rand` release.
+pub fn component_rng(seed: u64, component: &str) -> StdRng {
+    StdRng::seed_from_u64(seed ^ crate::validator::fnv1a(crate::validator::FNV1A_OFFSET_BASIS, component.as_bytes()))
+}
+
+/// Current time, or `SOURCE_DATE_EPOCH` when set, for reproducible reruns
+pub fn generation_time() -> chrono::DateTime<chrono::Utc> {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.trim().parse::<i64>().ok())
+        .and_then(|seconds| chrono::DateTime::from_timestamp(seconds, 0))
+        .unwrap_or_else(chrono::Utc::now)
+}
+
+/// `generation_time` as Unix seconds
+pub fn generation_timestamp() -> u64 {
+    generation_time().timestamp().max(0) as u64
+}
+
+/// Wall-clock and memory bounds for a whole extraction run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RunLimits {
+    pub max_duration: Option<std::time::Duration>,
+    pub max_memory_bytes: Option<u64>,
+}
+
+impl RunLimits {
+    /// Why the run must stop, if a limit has been exceeded since `started`
+    pub fn breach(&self, started: std::time::Instant) -> Option<String> {
+        let elapsed = started.elapsed();
+        if let Some(max_duration) = self.max_duration.filter(|&max| elapsed > max) {
+            return Some(format!("ran for {:.1}s, over --max-duration of {}s", elapsed.as_secs_f64(), max_duration.as_secs_f64()));
+        }
+        let max_memory_bytes = self.max_memory_bytes?;
+        let rss = current_rss_bytes().filter(|&rss| rss > max_memory_bytes)?;
+        Some(format!("resident memory {} MB, over --max-memory of {} MB", rss / (1024 * 1024), max_memory_bytes / (1024 * 1024)))
+    }
+}
+
+/// Resident set size of this process, where the platform reports it
+pub fn current_rss_bytes() -> Option<u64> {
+    // `VmRSS:     123456 kB`
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kilobytes = status.lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Parallelism used when `--jobs` is not given
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Apply `task` to every item on at most `jobs` threads, returning results in input order
+///
+/// With `jobs <= 1` the items are processed on the calling thread, one at a time.
+pub fn run_jobs<T, R, F>(jobs: usize, items: Vec<T>, task: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    if jobs <= 1 || items.len() <= 1 {
+        return items.into_iter().map(task).collect();
+    }
+
+    let total = items.len();
+    let queue = std::sync::Mutex::new(items.into_iter().enumerate());
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.min(total))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                        let Some((index, item)) = next else {
+                            break;
+                        };
+                        done.push((index, task(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Highest number of tasks observed running at once
+    fn observed_concurrency(jobs: usize) -> usize {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+        let results = run_jobs(jobs, (0..8).collect(), |item: usize| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            running.fetch_sub(1, Ordering::SeqCst);
+            item * 2
+        });
+        assert_eq!(results, (0..8).map(|item| item * 2).collect::<Vec<_>>());
+        max_running.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_run_limits_breach() {
+        let started = std::time::Instant::now();
+        assert_eq!(RunLimits::default().breach(started), None);
+
+        let limits = RunLimits { max_duration: Some(std::time::Duration::from_secs(3600)), max_memory_bytes: None };
+        assert_eq!(limits.breach(started), None);
+        let limits = RunLimits { max_duration: Some(std::time::Duration::ZERO), max_memory_bytes: None };
+        assert!(limits.breach(started).unwrap().contains("--max-duration"));
+
+        // Any running process uses more than one byte, where RSS can be read
+        let limits = RunLimits { max_duration: None, max_memory_bytes: Some(1) };
+        assert_eq!(limits.breach(started).is_some(), current_rss_bytes().is_some());
+    }
+
+    #[test]
+    fn test_jobs_limit_concurrency() {
+        assert_eq!(observed_concurrency(1), 1);
+        let parallel = observed_concurrency(4);
+        assert!(parallel > 1 && parallel <= 4, "observed {} concurrent tasks", parallel);
+    }
+}
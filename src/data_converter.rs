@@ -1,14 +1,18 @@
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::Write;
 
-use crate::solfunmeme_validator::SolfunmemeDataAccess;
+use crate::config::component_rng;
+use crate::solfunmeme_validator::{normalize_embedding, SolfunmemeDataAccess};
 use crate::validator::{DataAccess, ValidationError};
 
 /// Data converter for exporting solfunmeme dataset to different formats
 pub struct DataConverter {
     data_access: SolfunmemeDataAccess,
+    normalize_embeddings: bool,
+    sample_seed: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +30,7 @@ pub struct TermRecord {
     pub prime_factor: Option<u64>,
     pub is_power_of_two: Option<bool>,
     pub numerical_address: Option<String>,
+    pub embedding_vectors: Option<Vec<f64>>,
     pub first_seen_timestamp: Option<u64>,
     pub last_seen_timestamp: Option<u64>,
     pub character_group: String,
@@ -35,7 +40,33 @@ impl DataConverter {
     pub fn new(base_path: &str) -> Result<Self, ValidationError> {
         let data_access = SolfunmemeDataAccess::new(base_path);
         data_access.health_check()?;
-        Ok(Self { data_access })
+        Ok(Self {
+            data_access,
+            normalize_embeddings: false,
+            sample_seed: None,
+        })
+    }
+
+    /// Scale exported embedding vectors to unit L2 norm
+    pub fn with_normalized_embeddings(mut self, normalize_embeddings: bool) -> Self {
+        self.normalize_embeddings = normalize_embeddings;
+        self
+    }
+
+    /// Sample a seeded random subset of each character group instead of its first terms
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.sample_seed = Some(seed);
+        self
+    }
+
+    /// Prepare an embedding for export, normalizing it if requested
+    /// Vectors that cannot be normalized (zero or non-finite) are exported unchanged
+    fn prepare_embedding(&self, term_id: &str, embedding: Option<Vec<f64>>) -> Option<Vec<f64>> {
+        let mut vector = embedding?;
+        if self.normalize_embeddings && !normalize_embedding(&mut vector) {
+            eprintln!("Warning: Could not normalize embedding for term {}", term_id);
+        }
+        Some(vector)
     }
 
     /// Export all terms from a character group to JSON Lines format
@@ -130,6 +161,7 @@ impl DataConverter {
                             prime_factor: term.prime_factor,
                             is_power_of_two: term.is_power_of_two,
                             numerical_address: term.numerical_address,
+                            embedding_vectors: self.prepare_embedding(&term_id, term.embedding_vectors),
                             first_seen_timestamp: term.first_seen_timestamp,
                             last_seen_timestamp: term.last_seen_timestamp,
                             character_group: character.clone(),
@@ -210,6 +242,7 @@ impl DataConverter {
 
         let mut total_exported = 0;
         let sample_per_char = (sample_size / characters.len()).max(1);
+        let mut rng = self.sample_seed.map(|seed| component_rng(seed, "data_converter"));
 
         for character in characters {
             let term_ids = match self.data_access.get_split_names("solfunmeme-index", &character) {
@@ -223,6 +256,10 @@ impl DataConverter {
                     message: format!("Failed to create file {}: {}", char_output_path, e),
                 })?;
 
+            let mut term_ids = term_ids;
+            if let Some(rng) = rng.as_mut() {
+                term_ids.shuffle(rng);
+            }
             let sample_terms: Vec<_> = term_ids.into_iter().take(sample_per_char).collect();
             
             for term_id in sample_terms {
@@ -242,6 +279,7 @@ impl DataConverter {
                             prime_factor: term.prime_factor,
                             is_power_of_two: term.is_power_of_two,
                             numerical_address: term.numerical_address,
+                            embedding_vectors: self.prepare_embedding(&term_id, term.embedding_vectors),
                             first_seen_timestamp: term.first_seen_timestamp,
                             last_seen_timestamp: term.last_seen_timestamp,
                             character_group: character.clone(),
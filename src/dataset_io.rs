@@ -0,0 +1,326 @@
+//! # Dataset IO: helpers shared by the extractors for dataset files
+//!
+//! Building record batches with clear length errors, reading Parquet files
+//! back, typed column access for nullable values, column subsets, `pyarrow`
+//! schema snippets, and the run manifest, skipped-file report, throughput
+//! stats and provenance written next to the phase data. Used by the
+//! rust-analyzer, cargo and LLVM IR extractors and by the dataset converters,
+//! so none of them depends on another for plumbing.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow::array::StringArray;
+use arrow::record_batch::RecordBatch;
+use serde::{Deserialize, Serialize};
+
+/// Resolve a column subset against a schema, returning the column indices in the requested order
+/// 
+/// Fails on unknown or duplicate names, listing the available columns.
+pub fn select_columns(schema: &arrow::datatypes::Schema, columns: &[String]) -> Result<Vec<usize>> {
+    if columns.is_empty() {
+        return Err(anyhow::anyhow!("Column selection must not be empty"));
+    }
+
+    let mut indices = Vec::with_capacity(columns.len());
+    for column in columns {
+        let index = schema.index_of(column).map_err(|_| {
+            let available: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+            anyhow::anyhow!("Unknown column '{}'; available columns: {}", column, available.join(", "))
+        })?;
+        if indices.contains(&index) {
+            return Err(anyhow::anyhow!("Column '{}' selected more than once", column));
+        }
+        indices.push(index);
+    }
+    Ok(indices)
+}
+
+/// Build a record batch, reporting which column disagrees with the record count
+/// 
+/// `RecordBatch::try_new` only says that column lengths differ; when an offset
+/// bug shortens or lengthens one array, naming the column and the number of
+/// records being written makes the culprit obvious.
+pub fn build_record_batch(schema: Arc<arrow::datatypes::Schema>, arrays: Vec<arrow::array::ArrayRef>, num_records: usize) -> Result<RecordBatch> {
+    if arrays.len() != schema.fields().len() {
+        return Err(anyhow::anyhow!(
+            "Schema has {} columns but {} arrays were built while writing {} records",
+            schema.fields().len(), arrays.len(), num_records
+        ));
+    }
+    for (field, array) in schema.fields().iter().zip(&arrays) {
+        if array.len() != num_records {
+            return Err(anyhow::anyhow!(
+                "Column '{}' has {} values but {} records are being written",
+                field.name(), array.len(), num_records
+            ));
+        }
+    }
+    RecordBatch::try_new(schema, arrays)
+        .with_context(|| format!("Failed to build record batch of {} records", num_records))
+}
+
+/// Read every record batch of a Parquet file
+pub fn read_parquet_batches(path: &Path) -> Result<Vec<RecordBatch>> {
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    ParquetRecordBatchReaderBuilder::try_new(file)?
+        .build()?
+        .map(|batch| batch.with_context(|| format!("Failed to read {}", path.display())))
+        .collect()
+}
+
+/// `pyarrow` type expression for an Arrow data type, e.g. `pa.uint32()`
+fn pyarrow_type(data_type: &arrow::datatypes::DataType) -> Result<String> {
+    use arrow::datatypes::DataType;
+
+    Ok(match data_type {
+        DataType::Utf8 => "pa.string()".to_string(),
+        DataType::LargeUtf8 => "pa.large_string()".to_string(),
+        DataType::Boolean => "pa.bool_()".to_string(),
+        DataType::UInt32 => "pa.uint32()".to_string(),
+        DataType::UInt64 => "pa.uint64()".to_string(),
+        DataType::Int32 => "pa.int32()".to_string(),
+        DataType::Int64 => "pa.int64()".to_string(),
+        DataType::Float32 => "pa.float32()".to_string(),
+        DataType::Float64 => "pa.float64()".to_string(),
+        DataType::List(item) => format!("pa.list_({})", pyarrow_field(item)?),
+        other => return Err(anyhow::anyhow!("No pyarrow mapping for Arrow type {}", other)),
+    })
+}
+
+/// `pa.field(...)` expression with the field's name, type and nullability
+fn pyarrow_field(field: &arrow::datatypes::Field) -> Result<String> {
+    Ok(format!(
+        "pa.field({:?}, {}, nullable={})",
+        field.name(),
+        pyarrow_type(field.data_type())?,
+        if field.is_nullable() { "True" } else { "False" }
+    ))
+}
+
+/// Python snippet building the `pyarrow` schema equivalent to `schema`
+/// 
+/// Lets Python consumers pass an explicit schema to `pq.read_table` /
+/// `datasets` instead of relying on inference.
+pub fn pyarrow_schema_snippet(schema: &arrow::datatypes::Schema) -> Result<String> {
+    let mut snippet = String::from("import pyarrow as pa\n\nschema = pa.schema([\n");
+    for field in schema.fields() {
+        snippet.push_str(&format!("    {},\n", pyarrow_field(field)?));
+    }
+    snippet.push_str("])\n");
+    Ok(snippet)
+}
+
+/// Look up a column by name and downcast it to the expected Arrow array type
+pub fn typed_column<'a, T: arrow::array::Array + 'static>(batch: &'a RecordBatch, name: &str) -> Result<&'a T> {
+    batch.column_by_name(name)
+        .ok_or_else(|| anyhow::anyhow!("Missing column '{}'", name))?
+        .as_any()
+        .downcast_ref::<T>()
+        .ok_or_else(|| anyhow::anyhow!("Column '{}' has type {}", name, batch.schema().field_with_name(name).map(|f| f.data_type().to_string()).unwrap_or_default()))
+}
+
+/// Value of a nullable string column at `row`
+pub fn optional_string(array: &StringArray, row: usize) -> Option<String> {
+    use arrow::array::Array;
+    array.is_valid(row).then(|| array.value(row).to_string())
+}
+
+/// Value of a nullable primitive column at `row`
+pub fn optional_value<T: arrow::datatypes::ArrowPrimitiveType>(array: &arrow::array::PrimitiveArray<T>, row: usize) -> Option<T::Native> {
+    use arrow::array::Array;
+    array.is_valid(row).then(|| array.value(row))
+}
+
+/// Write a run manifest listing skipped files (and record counts per element type) to `output_dir/manifest.json`
+pub fn write_run_manifest(output_dir: &Path, files_found: usize, skipped: &[SkippedFile], element_type_counts: &BTreeMap<String, usize>, throughput: Option<&ThroughputStats>) -> Result<()> {
+    let manifest = serde_json::json!({
+        "files_found": files_found,
+        "files_processed": files_found.saturating_sub(skipped.iter().filter(|file| !file.truncated).count()),
+        "skipped_count": skipped.len(),
+        "skipped": skipped,
+        "element_type_counts": element_type_counts,
+        "throughput": throughput,
+    });
+    std::fs::create_dir_all(output_dir)?;
+    std::fs::write(output_dir.join("manifest.json"), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(())
+}
+
+/// A source file that could not be processed, or was only partly processed, during a run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedFile {
+    pub path: String,               // Path of the skipped file
+    pub reason: String,             // Why it was skipped (non-UTF8, permission denied, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,    // File size, for files skipped or truncated for being too large
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub truncated: bool,            // Only the first bytes were analyzed (--truncate-large-files)
+}
+
+impl SkippedFile {
+    /// Build a skipped-file entry, classifying the error into a short reason
+    pub fn from_error(path: &Path, error: &anyhow::Error) -> Self {
+        let reason = match error.downcast_ref::<std::io::Error>().map(|e| e.kind()) {
+            Some(std::io::ErrorKind::InvalidData) => "non-UTF8 content".to_string(),
+            Some(std::io::ErrorKind::PermissionDenied) => "permission denied".to_string(),
+            Some(std::io::ErrorKind::NotFound) => "file not found".to_string(),
+            Some(_) => format!("read error: {}", error),
+            None => format!("parse failure: {}", error),
+        };
+        Self {
+            path: path.to_string_lossy().to_string(),
+            reason,
+            size_bytes: None,
+            truncated: false,
+        }
+    }
+
+    /// Build a skipped-file entry for a file over the size cap
+    pub fn oversized(path: &Path, size_bytes: u64, max_file_bytes: u64) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            reason: format!("file too large ({} bytes, limit {})", size_bytes, max_file_bytes),
+            size_bytes: Some(size_bytes),
+            truncated: false,
+        }
+    }
+
+    /// Build an entry for a file over the size cap that was analyzed up to the cap
+    pub fn truncated(path: &Path, size_bytes: u64, max_file_bytes: u64) -> Self {
+        Self {
+            path: path.to_string_lossy().to_string(),
+            reason: format!("truncated ({} bytes, limit {})", size_bytes, max_file_bytes),
+            size_bytes: Some(size_bytes),
+            truncated: true,
+        }
+    }
+}
+
+/// Print a summary of skipped files at the end of a run
+pub fn print_skipped_summary(skipped: &[SkippedFile]) {
+    if skipped.is_empty() {
+        return;
+    }
+    println!("Skipped {} file(s):", skipped.len());
+    for file in skipped {
+        println!("  {} ({})", file.path, file.reason);
+    }
+}
+
+/// Volume and speed of one extraction run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThroughputStats {
+    pub total_records: usize,       // Records produced across all phases
+    pub source_bytes: u64,          // Bytes of source analyzed (each file counted once)
+    pub elapsed_secs: f64,          // Wall-clock time of the run
+    pub records_per_sec: f64,
+    pub mb_per_sec: f64,            // Source megabytes (10^6 bytes) per second
+}
+
+impl ThroughputStats {
+    pub fn new(total_records: usize, source_bytes: u64, elapsed: std::time::Duration) -> Self {
+        // Tiny runs can finish within the timer resolution; keep the rates finite
+        let elapsed_secs = elapsed.as_secs_f64().max(1e-6);
+        Self {
+            total_records,
+            source_bytes,
+            elapsed_secs,
+            records_per_sec: total_records as f64 / elapsed_secs,
+            mb_per_sec: source_bytes as f64 / 1_000_000.0 / elapsed_secs,
+        }
+    }
+
+    /// One-line summary for the end of a run
+    pub fn summary(&self) -> String {
+        format!(
+            "{} records from {:.2} MB in {:.2}s ({:.0} records/sec, {:.2} MB/sec)",
+            self.total_records,
+            self.source_bytes as f64 / 1_000_000.0,
+            self.elapsed_secs,
+            self.records_per_sec,
+            self.mb_per_sec,
+        )
+    }
+}
+
+/// Reproducibility metadata describing how a dataset was generated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub tool_name: String,          // Crate name of this tool
+    pub tool_version: String,       // Crate version of this tool
+    pub tool_git_commit: Option<String>, // Commit this tool was built from, if built from a git checkout
+    pub command_line: Vec<String>,  // Arguments the tool was invoked with
+    pub os: String,                 // Host operating system
+    pub arch: String,               // Host CPU architecture
+    pub rustc_version: String,      // `rustc --version`, or the extractor's toolchain version
+    pub generated_at: String,       // UTC generation time (RFC 3339)
+    pub phases: Vec<String>,        // Phases requested for the run
+}
+
+impl Provenance {
+    /// Capture provenance for the current process
+    ///
+    /// The generation time honours `SOURCE_DATE_EPOCH` so that reruns with a
+    /// fixed clock produce byte-identical provenance. The tool commit is
+    /// recorded by `build.rs` at compile time, so installed binaries keep it.
+    pub fn capture(phases: &[&str], fallback_rustc_version: &str) -> Self {
+        let rustc_version = std::process::Command::new("rustc")
+            .arg("--version")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .unwrap_or_else(|| fallback_rustc_version.to_string());
+        Self {
+            tool_name: env!("CARGO_PKG_NAME").to_string(),
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            tool_git_commit: option_env!("HF_VALIDATOR_GIT_COMMIT").map(str::to_string),
+            command_line: std::env::args().collect(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            rustc_version,
+            generated_at: crate::config::generation_time().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            phases: phases.iter().map(|phase| phase.to_string()).collect(),
+        }
+    }
+}
+
+/// Write `provenance.json` into the output directory
+pub fn write_provenance(output_dir: &Path, provenance: &Provenance) -> Result<()> {
+    std::fs::create_dir_all(output_dir)?;
+    std::fs::write(output_dir.join("provenance.json"), serde_json::to_string_pretty(provenance)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::UInt32Array;
+
+    #[test]
+    fn test_record_batch_length_mismatch_names_column() {
+        let schema = Arc::new(arrow::datatypes::Schema::new(vec![
+            arrow::datatypes::Field::new("id", arrow::datatypes::DataType::Utf8, false),
+            arrow::datatypes::Field::new("line", arrow::datatypes::DataType::UInt32, false),
+        ]));
+        let arrays: Vec<arrow::array::ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            Arc::new(UInt32Array::from(vec![1, 2])),
+        ];
+
+        let error = build_record_batch(schema.clone(), arrays, 3).unwrap_err().to_string();
+        assert!(error.contains("'line'"), "{}", error);
+        assert!(error.contains("has 2 values but 3 records"), "{}", error);
+
+        let arrays: Vec<arrow::array::ArrayRef> = vec![
+            Arc::new(StringArray::from(vec!["a"])),
+            Arc::new(UInt32Array::from(vec![1])),
+        ];
+        assert_eq!(build_record_batch(schema, arrays, 1).unwrap().num_rows(), 1);
+    }
+}
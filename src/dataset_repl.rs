@@ -0,0 +1,307 @@
+//! # Dataset REPL: interactive exploration of a Parquet dataset directory
+//!
+//! `repl <dataset_dir>` reads commands line by line:
+//!
+//! - `count`: total rows across all Parquet files (from footers only)
+//! - `schema`: columns and types of the first Parquet file
+//! - `head N`: the first N rows
+//! - `filter <col>=<val>`: rows whose displayed value of `col` equals `val`
+//! - `stats <col>`: row, null and distinct counts, plus min/max/mean for numeric columns
+//! - `help`, `quit`
+//!
+//! Files are opened per command and only the needed columns are decoded, so
+//! large datasets are never loaded into memory as a whole.
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use arrow::array::{Array, Float64Array};
+use arrow::datatypes::{DataType, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use arrow::util::display::array_value_to_string;
+
+use crate::partitioned_dataset::{DatasetPart, PartitionedDataset};
+
+/// Rows printed by `filter` before the remaining matches are only counted
+const MAX_FILTER_ROWS: usize = 10;
+
+/// A parsed REPL command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplCommand {
+    Count,
+    Schema,
+    Head(usize),
+    Filter { column: String, value: String },
+    Stats(String),
+    Help,
+    Quit,
+}
+
+impl ReplCommand {
+    /// Parse one input line; `Ok(None)` for blank lines
+    pub fn parse(line: &str) -> Result<Option<Self>, String> {
+        let line = line.trim();
+        let (name, argument) = match line.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (line, ""),
+        };
+        let command = match name {
+            "" => return Ok(None),
+            "count" => ReplCommand::Count,
+            "schema" => ReplCommand::Schema,
+            "head" if argument.is_empty() => ReplCommand::Head(10),
+            "head" => ReplCommand::Head(argument.parse().map_err(|_| format!("head expects a row count, got '{}'", argument))?),
+            "filter" => {
+                let (column, value) = argument.split_once('=')
+                    .ok_or_else(|| "filter expects <col>=<val>".to_string())?;
+                ReplCommand::Filter { column: column.trim().to_string(), value: value.trim().to_string() }
+            }
+            "stats" if argument.is_empty() => return Err("stats expects a column name".to_string()),
+            "stats" => ReplCommand::Stats(argument.to_string()),
+            "help" => ReplCommand::Help,
+            "quit" | "exit" => ReplCommand::Quit,
+            other => return Err(format!("Unknown command '{}' (try help)", other)),
+        };
+        Ok(Some(command))
+    }
+}
+
+/// The Parquet files of a dataset directory, explored one command at a time
+pub struct DatasetRepl {
+    dataset: PartitionedDataset,
+}
+
+impl DatasetRepl {
+    /// Find the Parquet files under `dataset_dir` (recursively, in path order)
+    pub fn open(dataset_dir: &Path) -> Result<Self> {
+        let dataset = PartitionedDataset::open(dataset_dir)?;
+        if dataset.is_empty() {
+            return Err(anyhow::anyhow!("No Parquet files found in {}", dataset_dir.display()));
+        }
+        Ok(Self { dataset })
+    }
+
+    /// Run commands from `input` until `quit` or end of input, writing results to `output`
+    pub fn run<R: BufRead, W: Write>(&self, input: R, mut output: W) -> Result<()> {
+        writeln!(output, "{} Parquet files loaded; type help for commands", self.dataset.parts().len())?;
+        for line in input.lines() {
+            let command = match ReplCommand::parse(&line?) {
+                Ok(Some(ReplCommand::Quit)) => break,
+                Ok(Some(command)) => command,
+                Ok(None) => continue,
+                Err(message) => {
+                    writeln!(output, "error: {}", message)?;
+                    continue;
+                }
+            };
+            match self.execute(&command) {
+                Ok(text) => write!(output, "{}", text)?,
+                Err(e) => writeln!(output, "error: {:#}", e)?,
+            }
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Execute one command, returning its output text
+    pub fn execute(&self, command: &ReplCommand) -> Result<String> {
+        match command {
+            ReplCommand::Count => Ok(format!("{}\n", self.dataset.total_rows()?)),
+            ReplCommand::Schema => {
+                let schema = self.dataset.parts()[0].schema()?;
+                Ok(schema.fields().iter()
+                    .map(|field| format!("{}: {}{}\n", field.name(), field.data_type(), if field.is_nullable() { " (nullable)" } else { "" }))
+                    .collect())
+            }
+            ReplCommand::Head(limit) => self.head(*limit),
+            ReplCommand::Filter { column, value } => self.filter(column, value),
+            ReplCommand::Stats(column) => self.stats(column),
+            ReplCommand::Help => Ok("commands: count, schema, head N, filter <col>=<val>, stats <col>, quit\n".to_string()),
+            ReplCommand::Quit => Ok(String::new()),
+        }
+    }
+
+    /// Batches of every file that has `column`, decoding only that column (all columns for None)
+    fn for_each_batch(&self, column: Option<&str>, mut visit: impl FnMut(&RecordBatch) -> Result<bool>) -> Result<bool> {
+        let mut found_column = column.is_none();
+        for part in self.dataset.parts() {
+            let Some(reader) = part.reader(column.as_ref().map(std::slice::from_ref))? else { continue };
+            found_column = true;
+            for batch in reader {
+                if !visit(&batch?)? {
+                    return Ok(found_column);
+                }
+            }
+        }
+        Ok(found_column)
+    }
+
+    fn format_row(batch: &RecordBatch, schema: &SchemaRef, row: usize) -> Result<String> {
+        let values = batch.columns().iter()
+            .map(|array| array_value_to_string(array, row))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(schema.fields().iter().zip(values)
+            .map(|(field, value)| format!("{}={}", field.name(), value))
+            .collect::<Vec<_>>()
+            .join(" | "))
+    }
+
+    fn head(&self, limit: usize) -> Result<String> {
+        let mut text = String::new();
+        let mut printed = 0;
+        self.for_each_batch(None, |batch| {
+            for row in 0..batch.num_rows().min(limit - printed) {
+                text.push_str(&Self::format_row(batch, &batch.schema(), row)?);
+                text.push('\n');
+                printed += 1;
+            }
+            Ok(printed < limit)
+        })?;
+        Ok(text)
+    }
+
+    /// Matches are found decoding only `column`; full rows are decoded only
+    /// for the matches that are printed
+    fn filter(&self, column: &str, value: &str) -> Result<String> {
+        let mut text = String::new();
+        let mut matches = 0;
+        for part in self.dataset.parts() {
+            let Some(reader) = part.reader(Some(std::slice::from_ref(&column)))? else { continue };
+            let mut shown_rows = Vec::new();
+            let mut offset = 0;
+            for batch in reader {
+                let batch = batch?;
+                let array = batch.column(0);
+                for row in 0..batch.num_rows() {
+                    if array.is_valid(row) && array_value_to_string(array, row)? == value {
+                        if matches < MAX_FILTER_ROWS {
+                            shown_rows.push(offset + row);
+                        }
+                        matches += 1;
+                    }
+                }
+                offset += batch.num_rows();
+            }
+            if !shown_rows.is_empty() {
+                Self::format_part_rows(part, &shown_rows, &mut text)?;
+            }
+        }
+        text.push_str(&format!("{} matching rows\n", matches));
+        Ok(text)
+    }
+
+    /// Append the rows at `rows` (ascending file row numbers) of `part`, reading no further than the last
+    fn format_part_rows(part: &DatasetPart, rows: &[usize], text: &mut String) -> Result<()> {
+        let Some(reader) = part.reader(None)? else { return Ok(()) };
+        let mut wanted = rows.iter().copied().peekable();
+        let mut offset = 0;
+        for batch in reader {
+            let batch = batch?;
+            while let Some(row) = wanted.next_if(|&row| row < offset + batch.num_rows()) {
+                text.push_str(&Self::format_row(&batch, &batch.schema(), row - offset)?);
+                text.push('\n');
+            }
+            if wanted.peek().is_none() {
+                break;
+            }
+            offset += batch.num_rows();
+        }
+        Ok(())
+    }
+
+    fn stats(&self, column: &str) -> Result<String> {
+        let mut rows = 0;
+        let mut nulls = 0;
+        let mut distinct: HashSet<String> = HashSet::new();
+        let mut data_type: Option<DataType> = None;
+        let (mut min, mut max, mut sum) = (f64::INFINITY, f64::NEG_INFINITY, 0.0);
+
+        let found = self.for_each_batch(Some(column), |batch| {
+            let array = batch.column(0);
+            rows += array.len();
+            nulls += array.null_count();
+            data_type.get_or_insert_with(|| array.data_type().clone());
+            for row in (0..array.len()).filter(|&row| array.is_valid(row)) {
+                distinct.insert(array_value_to_string(array, row)?);
+            }
+            if array.data_type().is_numeric() {
+                let values = arrow::compute::cast(array, &DataType::Float64)?;
+                let values = values.as_any().downcast_ref::<Float64Array>().context("numeric cast")?;
+                for value in values.iter().flatten() {
+                    min = min.min(value);
+                    max = max.max(value);
+                    sum += value;
+                }
+            }
+            Ok(true)
+        })?;
+        if !found {
+            return Err(anyhow::anyhow!("No column named '{}'", column));
+        }
+
+        let mut text = format!(
+            "type: {}\nrows: {}\nnulls: {}\ndistinct: {}\n",
+            data_type.map(|t| t.to_string()).unwrap_or_default(), rows, nulls, distinct.len(),
+        );
+        let non_null = rows - nulls;
+        if min.is_finite() && non_null > 0 {
+            text.push_str(&format!("min: {}\nmax: {}\nmean: {}\n", min, max, sum / non_null as f64));
+        }
+        Ok(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_analyzer_extractor::{ProcessingPhase, RustAnalyzerExtractor};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_repl_scripted_session() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("lib.rs"), "struct Point;\n\nfn main() {\n    let x = 5;\n}\n").unwrap();
+        let dataset_dir = temp_dir.path().join("dataset");
+        RustAnalyzerExtractor::new().unwrap()
+            .process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &dataset_dir)
+            .unwrap();
+
+        assert_eq!(ReplCommand::parse("  head 3 ").unwrap(), Some(ReplCommand::Head(3)));
+        assert_eq!(ReplCommand::parse("filter element_type = function").unwrap(), Some(ReplCommand::Filter {
+            column: "element_type".to_string(),
+            value: "function".to_string(),
+        }));
+        assert_eq!(ReplCommand::parse("").unwrap(), None);
+        assert!(ReplCommand::parse("head many").is_err());
+        assert!(ReplCommand::parse("drop table").is_err());
+
+        let script = "count\nschema\nhead 2\nfilter element_type=function\nstats line\nstats missing\nbogus\nquit\ncount\n";
+        let repl = DatasetRepl::open(&dataset_dir).unwrap();
+        let mut output = Vec::new();
+        repl.run(script.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "1 Parquet files loaded; type help for commands");
+        // Four non-blank source lines, one parsing record each
+        assert_eq!(lines[1], "4");
+        assert!(lines.contains(&"id: Utf8"));
+        assert!(lines.contains(&"element_name: Utf8 (nullable)"));
+        assert_eq!(lines.iter().filter(|l| l.starts_with("id=")).count(), 2 + 1);
+        assert!(lines.iter().any(|l| l.starts_with("id=") && l.contains("element_type=function") && l.contains("line=3")));
+        assert!(lines.contains(&"1 matching rows"));
+        assert!(lines.contains(&"rows: 4"));
+        assert!(lines.contains(&"min: 1"));
+        assert!(lines.contains(&"max: 5"));
+        assert!(lines.contains(&"mean: 3.25"));
+        assert!(lines.contains(&"error: No column named 'missing'"));
+        assert!(lines.contains(&"error: Unknown command 'bogus' (try help)"));
+        // Nothing runs after quit
+        assert_eq!(lines.iter().filter(|l| **l == "4").count(), 1);
+    }
+}
@@ -6,19 +6,25 @@ use std::sync::Arc;
 use arrow::array::{
     ArrayRef, BooleanArray, Int64Array, ListArray, StringArray, UInt32Array,
 };
-use arrow::buffer::OffsetBuffer;
+use arrow::buffer::{NullBuffer, OffsetBuffer};
 use arrow::datatypes::{DataType, Field, Schema};
 use arrow::record_batch::RecordBatch;
 use parquet::arrow::ArrowWriter;
 use parquet::file::properties::WriterProperties;
 
-use crate::solfunmeme_validator::SolfunmemeDataAccess;
+use crate::dataset_io::{build_record_batch, optional_string, optional_value, read_parquet_batches, typed_column};
+use crate::solfunmeme_validator::{CooccurrenceIndex, IndexTerm, SolfunmemeDataAccess};
 use crate::validator::{DataAccess, ValidationError};
 
+/// Default minimum number of examples a declared split must contain
+pub const DEFAULT_MIN_SPLIT_EXAMPLES: u64 = 1;
+
 /// Hugging Face dataset converter for solfunmeme-index
 pub struct HuggingFaceDatasetConverter {
     data_access: SolfunmemeDataAccess,
     output_dir: String,
+    min_split_examples: u64,
+    strict: bool,
 }
 
 /// Standard Hugging Face dataset configuration
@@ -68,6 +74,45 @@ pub struct SplitInfo {
     pub dataset_name: String,
 }
 
+/// Dataset card figures computed from the actual term files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardStats {
+    pub total_terms: u64,
+    pub character_groups: usize,
+    pub size_category: String,
+    /// Examples per split, in train/validation/test order
+    pub splits: Vec<(String, u64)>,
+    /// Largest character groups by term count, descending
+    pub top_groups: Vec<(String, u64)>,
+}
+
+impl CardStats {
+    /// Render a ready-to-paste YAML front matter fragment and markdown summary
+    pub fn to_card_markdown(&self) -> String {
+        let mut card = String::new();
+        card.push_str("---\n");
+        card.push_str(&format!("size_categories: {}\n", self.size_category));
+        card.push_str("dataset_info:\n  splits:\n");
+        for (name, num_examples) in &self.splits {
+            card.push_str(&format!("  - name: {}\n    num_examples: {}\n", name, num_examples));
+        }
+        card.push_str("---\n\n");
+        card.push_str(&format!(
+            "The dataset contains **{} terms** organized across **{} character groups**.\n\n",
+            format_thousands(self.total_terms), self.character_groups
+        ));
+        card.push_str("| Split | Examples |\n|-------|----------|\n");
+        for (name, num_examples) in &self.splits {
+            card.push_str(&format!("| {} | {} |\n", name, format_thousands(*num_examples)));
+        }
+        card.push_str("\n**Top character groups:**\n");
+        for (group, count) in &self.top_groups {
+            card.push_str(&format!("- `{}`: {} terms\n", group, format_thousands(*count)));
+        }
+        card
+    }
+}
+
 impl HuggingFaceDatasetConverter {
     pub fn new(base_path: &str, output_dir: &str) -> Result<Self, ValidationError> {
         let data_access = SolfunmemeDataAccess::new(base_path);
@@ -81,13 +126,27 @@ impl HuggingFaceDatasetConverter {
         Ok(Self {
             data_access,
             output_dir: output_dir.to_string(),
+            min_split_examples: DEFAULT_MIN_SPLIT_EXAMPLES,
+            strict: false,
         })
     }
 
+    /// Configure the minimum split size check
+    /// In strict mode an undersized split is an error instead of a warning
+    pub fn with_split_check(mut self, min_split_examples: u64, strict: bool) -> Self {
+        self.min_split_examples = min_split_examples;
+        self.strict = strict;
+        self
+    }
+
     /// Create the complete Hugging Face dataset structure
     pub async fn create_huggingface_dataset(&self) -> Result<(), ValidationError> {
         println!("🚀 Creating Hugging Face dataset structure...");
 
+        // Warn (or fail in strict mode) on empty or tiny splits before writing anything
+        let splits = self.split_infos()?;
+        check_split_sizes(&splits, self.min_split_examples, self.strict)?;
+
         // 1. Create dataset configuration
         self.create_dataset_config()?;
         
@@ -98,7 +157,7 @@ impl HuggingFaceDatasetConverter {
         self.convert_to_parquet().await?;
         
         // 4. Create dataset_info.json
-        self.create_dataset_info().await?;
+        self.create_dataset_info(splits).await?;
         
         // 5. Create state.json
         self.create_state_json()?;
@@ -199,6 +258,7 @@ The dataset contains **26,236 terms** organized across **103 character groups**,
 - **first_seen_timestamp**: When term was first encountered
 - **last_seen_timestamp**: When term was last encountered
 - **character_group**: First character grouping (a-z, 0-9, unicode)
+- **cooccurring_terms**: IDs of terms sharing a category or semantic name (at most 256 per term, lowest ids first)
 
 ### Splits
 
@@ -289,6 +349,9 @@ For questions or issues regarding this dataset, please open an issue in the repo
 
         // Get all character groups
         let characters = self.data_access.get_config_names("solfunmeme-index")?;
+
+        // Index co-occurrence groups once; neighbours are looked up per batch
+        let cooccurrence = self.data_access.build_cooccurrence_index()?;
         
         // Create splits based on character types
         let mut train_chars = Vec::new();
@@ -296,121 +359,137 @@ For questions or issues regarding this dataset, please open an issue in the repo
         let mut test_chars = Vec::new();
 
         for char in characters {
-            let first_char = char.chars().next().unwrap_or('a');
-            if first_char.is_ascii_alphabetic() {
-                train_chars.push(char);
-            } else if first_char.is_ascii_digit() {
-                validation_chars.push(char);
-            } else {
-                test_chars.push(char);
+            match split_for_group(&char) {
+                "train" => train_chars.push(char),
+                "validation" => validation_chars.push(char),
+                _ => test_chars.push(char),
             }
         }
 
         // Convert each split
-        self.convert_split_to_parquet("train", &train_chars).await?;
-        self.convert_split_to_parquet("validation", &validation_chars).await?;
-        self.convert_split_to_parquet("test", &test_chars).await?;
+        self.convert_split_to_parquet("train", &train_chars, &cooccurrence).await?;
+        self.convert_split_to_parquet("validation", &validation_chars, &cooccurrence).await?;
+        self.convert_split_to_parquet("test", &test_chars, &cooccurrence).await?;
 
         Ok(())
     }
 
     /// Convert a specific split to Parquet
-    async fn convert_split_to_parquet(&self, split_name: &str, characters: &[String]) -> Result<(), ValidationError> {
+    async fn convert_split_to_parquet(
+        &self,
+        split_name: &str,
+        characters: &[String],
+        cooccurrence: &CooccurrenceIndex,
+    ) -> Result<(), ValidationError> {
         println!("  Converting {} split ({} character groups)...", split_name, characters.len());
 
-        // Define Arrow schema
-        let schema = Arc::new(Schema::new(vec![
-            Field::new("id", DataType::Utf8, false),
-            Field::new("term", DataType::Utf8, false),
-            Field::new("count", DataType::UInt32, false),
-            Field::new("category", DataType::Utf8, false),
-            Field::new("significance", DataType::Utf8, false),
-            Field::new("vibe", DataType::Utf8, false),
-            Field::new("action_suggestion", DataType::Utf8, false),
-            Field::new("emoji_representation", DataType::Utf8, true),
-            Field::new("semantic_names", DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))), true),
-            Field::new("osi_layer", DataType::Utf8, true),
-            Field::new("prime_factor", DataType::Int64, true),
-            Field::new("is_power_of_two", DataType::Boolean, true),
-            Field::new("numerical_address", DataType::Utf8, true),
-            Field::new("first_seen_timestamp", DataType::Int64, true),
-            Field::new("last_seen_timestamp", DataType::Int64, true),
-            Field::new("character_group", DataType::Utf8, false),
-        ]));
-
-        // Collect all data for this split
-        let mut all_data = Vec::new();
-        
+        let schema = index_term_schema();
+
+        // Count term files up front (a directory listing, no parsing) so the
+        // number of output files is known, then stream each group's terms in
+        // batches instead of materializing the split
+        let mut listed = 0;
         for character in characters {
             match self.data_access.get_split_names("solfunmeme-index", character) {
-                Ok(term_ids) => {
-                    for term_id in term_ids {
-                        match self.data_access.load_term(&term_id) {
-                            Ok(term) => {
-                                all_data.push((term_id, term, character.clone()));
-                            }
-                            Err(e) => {
-                                eprintln!("Warning: Failed to load term {}: {}", term_id, e);
-                            }
-                        }
-                    }
-                }
+                Ok(term_ids) => listed += term_ids.len(),
                 Err(e) => {
                     eprintln!("Warning: Failed to get terms for character {}: {}", character, e);
                 }
             }
         }
 
-        if all_data.is_empty() {
+        if listed == 0 {
             println!("  No data found for {} split", split_name);
             return Ok(());
         }
 
-        // Convert to Arrow arrays
         let batch_size = 10000; // Process in batches
+        let num_files = listed.div_ceil(batch_size);
+        let mut total_records = 0;
         let mut batch_num = 0;
+        let mut seen = 0;
+        let mut batch_data = Vec::with_capacity(batch_size.min(listed));
 
-        for chunk in all_data.chunks(batch_size) {
-            let record_batch = self.create_record_batch(schema.clone(), chunk)?;
-            
-            // Write to Parquet file
-            let output_path = if chunk.len() == all_data.len() {
-                format!("{}/{}-00000-of-00001.parquet", self.output_dir, split_name)
-            } else {
-                format!("{}/{}-{:05}-of-{:05}.parquet", self.output_dir, split_name, batch_num, (all_data.len() + batch_size - 1) / batch_size)
-            };
+        for character in characters {
+            for loaded in self.data_access.iter_group_terms(character) {
+                // Failed files still take their slot so batch boundaries follow the listing
+                seen += 1;
+                match loaded {
+                    Ok((term_id, term)) => batch_data.push((term_id, term, character.clone())),
+                    Err(e) => {
+                        eprintln!("Warning: Failed to load term in group {}: {}", character, e);
+                    }
+                }
+                if seen % batch_size == 0 {
+                    total_records += batch_data.len();
+                    self.write_split_file(split_name, batch_num, num_files, &schema, &batch_data, cooccurrence)?;
+                    batch_data.clear();
+                    batch_num += 1;
+                }
+            }
+        }
+        if seen % batch_size != 0 {
+            total_records += batch_data.len();
+            self.write_split_file(split_name, batch_num, num_files, &schema, &batch_data, cooccurrence)?;
+        }
 
-            let file = File::create(&output_path).map_err(|e| ValidationError::DataAccessError {
-                message: format!("Failed to create parquet file {}: {}", output_path, e),
-            })?;
+        println!("    ✅ {} split: {} records in {} files", split_name, total_records, num_files);
+        Ok(())
+    }
 
-            let props = WriterProperties::builder().build();
-            let mut writer = ArrowWriter::try_new(file, schema.clone(), Some(props)).map_err(|e| {
-                ValidationError::DataAccessError {
-                    message: format!("Failed to create Arrow writer: {}", e),
-                }
-            })?;
+    /// Write one batch of a split as `<split>-NNNNN-of-NNNNN.parquet`
+    fn write_split_file(
+        &self,
+        split_name: &str,
+        batch_num: usize,
+        num_files: usize,
+        schema: &Arc<Schema>,
+        batch_data: &[(String, IndexTerm, String)],
+        cooccurrence: &CooccurrenceIndex,
+    ) -> Result<(), ValidationError> {
+        let neighbours: HashMap<String, Vec<String>> = batch_data.iter()
+            .map(|(id, term, _)| (id.clone(), cooccurrence.neighbours(id, term)))
+            .collect();
+        let record_batch = Self::create_record_batch(schema.clone(), batch_data, &neighbours)?;
+        if let Some(issue) = crate::parquet_validator::check_list_offsets(&record_batch, split_name).into_iter().next() {
+            return Err(ValidationError::ProcessingError(format!(
+                "Malformed list column {} in {} split: {}", issue.column, split_name, issue.message
+            )));
+        }
 
-            writer.write(&record_batch).map_err(|e| ValidationError::DataAccessError {
-                message: format!("Failed to write record batch: {}", e),
-            })?;
+        // Write to Parquet file
+        let output_path = if num_files == 1 {
+            format!("{}/{}-00000-of-00001.parquet", self.output_dir, split_name)
+        } else {
+            format!("{}/{}-{:05}-of-{:05}.parquet", self.output_dir, split_name, batch_num, num_files)
+        };
 
-            writer.close().map_err(|e| ValidationError::DataAccessError {
-                message: format!("Failed to close writer: {}", e),
-            })?;
+        let file = File::create(&output_path).map_err(|e| ValidationError::DataAccessError {
+            message: format!("Failed to create parquet file {}: {}", output_path, e),
+        })?;
 
-            batch_num += 1;
-        }
+        let props = WriterProperties::builder().build();
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), Some(props)).map_err(|e| {
+            ValidationError::DataAccessError {
+                message: format!("Failed to create Arrow writer: {}", e),
+            }
+        })?;
 
-        println!("    ✅ {} split: {} records in {} files", split_name, all_data.len(), batch_num);
+        writer.write(&record_batch).map_err(|e| ValidationError::DataAccessError {
+            message: format!("Failed to write record batch: {}", e),
+        })?;
+
+        writer.close().map_err(|e| ValidationError::DataAccessError {
+            message: format!("Failed to close writer: {}", e),
+        })?;
         Ok(())
     }
 
     /// Create Arrow RecordBatch from data
     fn create_record_batch(
-        &self,
         schema: Arc<Schema>,
-        data: &[(String, crate::solfunmeme_validator::IndexTerm, String)],
+        data: &[(String, IndexTerm, String)],
+        cooccurrence: &HashMap<String, Vec<String>>,
     ) -> Result<RecordBatch, ValidationError> {
         let _len = data.len();
 
@@ -445,13 +524,9 @@ For questions or issues regarding this dataset, please open an issue in the repo
             offsets
         };
         
-        let semantic_names_string_array = StringArray::from(semantic_names_values);
-        let semantic_names = ListArray::new(
-            Arc::new(Field::new("item", DataType::Utf8, true)),
-            OffsetBuffer::new(semantic_names_offsets.into()),
-            Arc::new(semantic_names_string_array),
-            None,
-        );
+        // `None` is a null list, distinct from `Some` of an empty list
+        let semantic_names_validity: Vec<bool> = data.iter().map(|(_, term, _)| term.semantic_names.is_some()).collect();
+        let semantic_names = string_list_array("semantic_names", semantic_names_offsets, semantic_names_values, Some(semantic_names_validity))?;
 
         let osi_layers: StringArray = data.iter().map(|(_, term, _)| {
             term.osi_layer.as_ref().map(|s| s.as_str())
@@ -479,6 +554,26 @@ For questions or issues regarding this dataset, please open an issue in the repo
 
         let character_groups: StringArray = data.iter().map(|(_, _, char_group)| Some(char_group.as_str())).collect();
 
+        // Handle cooccurring_terms as List<String>, same layout as semantic_names
+        let empty_neighbours = Vec::new();
+        let neighbours_for = |id: &String| cooccurrence.get(id).unwrap_or(&empty_neighbours);
+
+        let cooccurring_values: Vec<Option<String>> = data.iter().flat_map(|(id, _, _)| {
+            neighbours_for(id).iter().map(|other| Some(other.clone())).collect::<Vec<_>>()
+        }).collect();
+
+        let cooccurring_offsets: Vec<i32> = {
+            let mut offsets = vec![0i32];
+            let mut current_offset = 0i32;
+            for (id, _, _) in data {
+                current_offset += neighbours_for(id).len() as i32;
+                offsets.push(current_offset);
+            }
+            offsets
+        };
+
+        let cooccurring_terms = string_list_array("cooccurring_terms", cooccurring_offsets, cooccurring_values, None)?;
+
         // Create arrays vector
         let arrays: Vec<ArrayRef> = vec![
             Arc::new(ids),
@@ -497,20 +592,17 @@ For questions or issues regarding this dataset, please open an issue in the repo
             Arc::new(first_seen_timestamps),
             Arc::new(last_seen_timestamps),
             Arc::new(character_groups),
+            Arc::new(cooccurring_terms),
         ];
 
-        RecordBatch::try_new(schema, arrays).map_err(|e| ValidationError::DataAccessError {
-            message: format!("Failed to create record batch: {}", e),
+        build_record_batch(schema, arrays, data.len()).map_err(|e| ValidationError::DataAccessError {
+            message: format!("Failed to create record batch: {:#}", e),
         })
     }
 
-    /// Create dataset_info.json
-    async fn create_dataset_info(&self) -> Result<(), ValidationError> {
-        println!("📋 Creating dataset info...");
-
-        // Calculate dataset statistics
+    /// Example counts of the train, validation and test splits, from the term ids of each character group
+    fn split_infos(&self) -> Result<HashMap<String, SplitInfo>, ValidationError> {
         let characters = self.data_access.get_config_names("solfunmeme-index")?;
-        let mut total_examples = 0;
         let mut splits = HashMap::new();
 
         // Calculate split sizes
@@ -521,15 +613,11 @@ For questions or issues regarding this dataset, please open an issue in the repo
         for character in &characters {
             if let Ok(term_ids) = self.data_access.get_split_names("solfunmeme-index", character) {
                 let count = term_ids.len() as u64;
-                total_examples += count;
-
-                let first_char = character.chars().next().unwrap_or('a');
-                if first_char.is_ascii_alphabetic() {
-                    train_size += count;
-                } else if first_char.is_ascii_digit() {
-                    validation_size += count;
-                } else {
-                    test_size += count;
+
+                match split_for_group(character) {
+                    "train" => train_size += count,
+                    "validation" => validation_size += count,
+                    _ => test_size += count,
                 }
             }
         }
@@ -555,6 +643,17 @@ For questions or issues regarding this dataset, please open an issue in the repo
             dataset_name: "solfunmeme-index".to_string(),
         });
 
+        Ok(splits)
+    }
+
+    /// Create dataset_info.json from the split sizes computed by `split_infos`
+    async fn create_dataset_info(&self, splits: HashMap<String, SplitInfo>) -> Result<(), ValidationError> {
+        println!("📋 Creating dataset info...");
+
+        let split_size = |name: &str| splits.get(name).map_or(0, |split| split.num_examples);
+        let (train_size, validation_size, test_size) = (split_size("train"), split_size("validation"), split_size("test"));
+        let total_examples = train_size + validation_size + test_size;
+
         // Define features
         let mut features = HashMap::new();
         features.insert("id".to_string(), FeatureInfo {
@@ -582,6 +681,11 @@ For questions or issues regarding this dataset, please open an issue in the repo
             description: "First character grouping (a-z, 0-9, unicode)".to_string(),
             class_label: None,
         });
+        features.insert("cooccurring_terms".to_string(), FeatureInfo {
+            dtype: "list<string>".to_string(),
+            description: "IDs of terms sharing a category or semantic name".to_string(),
+            class_label: None,
+        });
 
         let dataset_info = DatasetInfo {
             description: "A comprehensive semantic analysis dataset containing terms extracted from the ragit codebase".to_string(),
@@ -637,18 +741,335 @@ For questions or issues regarding this dataset, please open an issue in the repo
     }
 }
 
+/// Arrow schema of the index-term Parquet splits
+///
+/// `embedding_vectors` and `versions` are not exported.
+pub fn index_term_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("term", DataType::Utf8, false),
+        Field::new("count", DataType::UInt32, false),
+        Field::new("category", DataType::Utf8, false),
+        Field::new("significance", DataType::Utf8, false),
+        Field::new("vibe", DataType::Utf8, false),
+        Field::new("action_suggestion", DataType::Utf8, false),
+        Field::new("emoji_representation", DataType::Utf8, true),
+        Field::new("semantic_names", DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))), true),
+        Field::new("osi_layer", DataType::Utf8, true),
+        Field::new("prime_factor", DataType::Int64, true),
+        Field::new("is_power_of_two", DataType::Boolean, true),
+        Field::new("numerical_address", DataType::Utf8, true),
+        Field::new("first_seen_timestamp", DataType::Int64, true),
+        Field::new("last_seen_timestamp", DataType::Int64, true),
+        Field::new("character_group", DataType::Utf8, false),
+        Field::new("cooccurring_terms", DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))), true),
+    ]))
+}
+
+/// Read `(id, term, character_group, cooccurring_terms)` rows back from a split Parquet file
+///
+/// `embedding_vectors` and `versions` are not exported and come back empty.
+pub fn read_index_terms_from_parquet(
+    path: &std::path::Path,
+) -> Result<Vec<(String, IndexTerm, String, Vec<String>)>, ValidationError> {
+    use arrow::array::Array;
+
+    let read_error = |e: anyhow::Error| ValidationError::DataAccessError {
+        message: format!("Failed to read {}: {:#}", path.display(), e),
+    };
+    let list_values = |list: &ListArray, row: usize| -> Option<Vec<String>> {
+        if list.is_null(row) {
+            return None;
+        }
+        let items = list.value(row);
+        let items = items.as_any().downcast_ref::<StringArray>()?;
+        Some((0..items.len()).map(|i| items.value(i).to_string()).collect())
+    };
+
+    let mut rows = Vec::new();
+    for batch in read_parquet_batches(path).map_err(read_error)? {
+        let string_column = |name: &str| typed_column::<StringArray>(&batch, name).map_err(read_error);
+        let ids = string_column("id")?;
+        let terms = string_column("term")?;
+        let counts = typed_column::<UInt32Array>(&batch, "count").map_err(read_error)?;
+        let categories = string_column("category")?;
+        let significances = string_column("significance")?;
+        let vibes = string_column("vibe")?;
+        let action_suggestions = string_column("action_suggestion")?;
+        let emoji_representations = string_column("emoji_representation")?;
+        let semantic_names = typed_column::<ListArray>(&batch, "semantic_names").map_err(read_error)?;
+        let osi_layers = string_column("osi_layer")?;
+        let prime_factors = typed_column::<Int64Array>(&batch, "prime_factor").map_err(read_error)?;
+        let is_power_of_twos = typed_column::<BooleanArray>(&batch, "is_power_of_two").map_err(read_error)?;
+        let numerical_addresses = string_column("numerical_address")?;
+        let first_seen = typed_column::<Int64Array>(&batch, "first_seen_timestamp").map_err(read_error)?;
+        let last_seen = typed_column::<Int64Array>(&batch, "last_seen_timestamp").map_err(read_error)?;
+        let character_groups = string_column("character_group")?;
+        let cooccurring_terms = typed_column::<ListArray>(&batch, "cooccurring_terms").map_err(read_error)?;
+
+        for row in 0..batch.num_rows() {
+            let term = IndexTerm {
+                term: terms.value(row).to_string(),
+                count: counts.value(row),
+                category: categories.value(row).to_string(),
+                significance: significances.value(row).to_string(),
+                vibe: vibes.value(row).to_string(),
+                action_suggestion: action_suggestions.value(row).to_string(),
+                emoji_representation: optional_string(emoji_representations, row),
+                semantic_names: list_values(semantic_names, row),
+                osi_layer: optional_string(osi_layers, row),
+                // Written as Int64; the cast back restores the original bits
+                prime_factor: optional_value(prime_factors, row).map(|pf| pf as u64),
+                is_power_of_two: is_power_of_twos.is_valid(row).then(|| is_power_of_twos.value(row)),
+                numerical_address: optional_string(numerical_addresses, row),
+                embedding_vectors: None,
+                versions: Vec::new(),
+                first_seen_timestamp: optional_value(first_seen, row).map(|ts| ts as u64),
+                last_seen_timestamp: optional_value(last_seen, row).map(|ts| ts as u64),
+            };
+            rows.push((
+                ids.value(row).to_string(),
+                term,
+                character_groups.value(row).to_string(),
+                list_values(cooccurring_terms, row).unwrap_or_default(),
+            ));
+        }
+    }
+    Ok(rows)
+}
+
+/// A row of an exported split that breaks an export invariant
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportViolation {
+    /// Split file, relative to the export directory
+    pub file: String,
+    /// Row index within the file
+    pub row: usize,
+    pub message: String,
+}
+
+/// Character group a term belongs in: its first character, lowercased
+fn expected_character_group(term: &str) -> Option<String> {
+    term.chars().next().map(|first| first.to_lowercase().collect())
+}
+
+/// Check the split Parquet files in `export_dir` for export invariants
+///
+/// - `id` is unique across all splits
+/// - `character_group` matches the first character of `term`
+///
+/// Violations are returned in file and row order.
+pub fn find_export_violations(export_dir: &std::path::Path) -> Result<Vec<ExportViolation>, ValidationError> {
+    let mut split_files: Vec<_> = fs::read_dir(export_dir)
+        .map_err(|e| ValidationError::DataAccessError {
+            message: format!("Failed to read export directory {}: {}", export_dir.display(), e),
+        })?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("parquet"))
+        .collect();
+    split_files.sort();
+
+    let mut first_seen: HashMap<String, (String, usize)> = HashMap::new();
+    let mut violations = Vec::new();
+    for path in &split_files {
+        let file = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        for (row, (id, term, character_group, _)) in read_index_terms_from_parquet(path)?.into_iter().enumerate() {
+            if let Some((first_file, first_row)) = first_seen.get(&id) {
+                violations.push(ExportViolation {
+                    file: file.clone(),
+                    row,
+                    message: format!("duplicate id {} (first seen in {} row {})", id, first_file, first_row),
+                });
+            } else {
+                first_seen.insert(id.clone(), (file.clone(), row));
+            }
+
+            let expected = expected_character_group(&term.term);
+            if expected.as_deref() != Some(character_group.as_str()) {
+                violations.push(ExportViolation {
+                    file: file.clone(),
+                    row,
+                    message: format!(
+                        "id {}: character_group '{}' does not match term '{}' (expected '{}')",
+                        id, character_group, term.term, expected.unwrap_or_default()
+                    ),
+                });
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// CLI function to check an exported solfunmeme dataset for id and character group errors
+pub fn validate_solfunmeme_export(export_dir: &str) -> Result<(), ValidationError> {
+    let violations = find_export_violations(std::path::Path::new(export_dir))?;
+    if violations.is_empty() {
+        println!("✅ Ids are unique and character groups match their terms in {}", export_dir);
+        return Ok(());
+    }
+
+    for violation in &violations {
+        println!("  ❌ {} row {}: {}", violation.file, violation.row, violation.message);
+    }
+    Err(ValidationError::ProcessingError(format!("{} export violations found", violations.len())))
+}
+
+/// Build a `List<Utf8>` column from flattened values and per-row offsets
+///
+/// Uses the fallible constructor so a bad offset buffer (non-monotonic, or
+/// pointing past the values) is reported against the column instead of panicking.
+/// `validity` marks which rows are non-null; `None` means every row is valid.
+fn string_list_array(
+    column: &str,
+    offsets: Vec<i32>,
+    values: Vec<Option<String>>,
+    validity: Option<Vec<bool>>,
+) -> Result<ListArray, ValidationError> {
+    let num_values = values.len();
+    let invalid = |reason: String| ValidationError::DataAccessError {
+        message: format!(
+            "Invalid offsets for list column '{}' ({} rows, {} values): {}",
+            column, offsets.len().saturating_sub(1), num_values, reason
+        ),
+    };
+
+    if offsets.first() != Some(&0) || offsets.windows(2).any(|w| w[0] > w[1]) {
+        return Err(invalid("offsets must start at 0 and be non-decreasing".to_string()));
+    }
+    ListArray::try_new(
+        Arc::new(Field::new("item", DataType::Utf8, true)),
+        OffsetBuffer::new(offsets.clone().into()),
+        Arc::new(StringArray::from(values)),
+        validity.map(NullBuffer::from),
+    )
+    .map_err(|e| invalid(e.to_string()))
+}
+
+/// Check that every declared split has at least `min_examples` examples
+///
+/// Returns the warnings that were printed. Empty or tiny splits break HF
+/// evaluation loaders, so in strict mode the first undersized split is an error.
+pub fn check_split_sizes(
+    splits: &HashMap<String, SplitInfo>,
+    min_examples: u64,
+    strict: bool,
+) -> Result<Vec<String>, ValidationError> {
+    let mut split_names: Vec<&String> = splits.keys().collect();
+    split_names.sort();
+
+    let mut warnings = Vec::new();
+    for name in split_names {
+        let split = &splits[name];
+        if split.num_examples < min_examples {
+            let message = format!(
+                "Split '{}' has {} examples (minimum {}); consider merging it into another split or dropping it",
+                name, split.num_examples, min_examples
+            );
+            if strict {
+                return Err(ValidationError::InvalidInput(message));
+            }
+            println!("    ⚠️  {}", message);
+            warnings.push(message);
+        }
+    }
+
+    Ok(warnings)
+}
+
+/// Split a character group is assigned to: letters train, digits validate, the rest test
+pub fn split_for_group(character: &str) -> &'static str {
+    let first_char = character.chars().next().unwrap_or('a');
+    if first_char.is_ascii_alphabetic() {
+        "train"
+    } else if first_char.is_ascii_digit() {
+        "validation"
+    } else {
+        "test"
+    }
+}
+
+/// Hugging Face `size_categories` bucket for a number of examples
+pub fn size_category(num_examples: u64) -> &'static str {
+    match num_examples {
+        0..=999 => "n<1K",
+        1_000..=9_999 => "1K<n<10K",
+        10_000..=99_999 => "10K<n<100K",
+        100_000..=999_999 => "100K<n<1M",
+        1_000_000..=9_999_999 => "1M<n<10M",
+        10_000_000..=99_999_999 => "10M<n<100M",
+        100_000_000..=999_999_999 => "100M<n<1B",
+        _ => "n>1B",
+    }
+}
+
+/// Format a count with thousands separators (26236 -> "26,236")
+fn format_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3);
+    for (idx, ch) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % 3 == 0 {
+            formatted.push(',');
+        }
+        formatted.push(ch);
+    }
+    formatted
+}
+
+/// Compute dataset card statistics from the term files under `base_path`
+pub fn compute_card_stats(base_path: &str, top_n: usize) -> Result<CardStats, ValidationError> {
+    let data_access = SolfunmemeDataAccess::new(base_path);
+    data_access.health_check()?;
+
+    let characters = data_access.get_config_names("solfunmeme-index")?;
+    let mut group_counts = Vec::new();
+    let mut split_counts: HashMap<&str, u64> = HashMap::new();
+    for character in &characters {
+        let count = data_access.get_split_names("solfunmeme-index", character)?.len() as u64;
+        *split_counts.entry(split_for_group(character)).or_insert(0) += count;
+        group_counts.push((character.clone(), count));
+    }
+
+    let total_terms = group_counts.iter().map(|(_, count)| count).sum();
+    // Largest first; ties broken by group name so the output is stable
+    group_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    group_counts.truncate(top_n);
+
+    Ok(CardStats {
+        total_terms,
+        character_groups: characters.len(),
+        size_category: size_category(total_terms).to_string(),
+        splits: ["train", "validation", "test"].iter()
+            .map(|split| (split.to_string(), split_counts.get(split).copied().unwrap_or(0)))
+            .collect(),
+        top_groups: group_counts,
+    })
+}
+
+/// CLI function to print dataset card statistics
+pub fn print_card_stats(base_path: &str, top_n: usize) -> Result<(), ValidationError> {
+    let stats = compute_card_stats(base_path, top_n)?;
+    println!("{}", stats.to_card_markdown());
+    Ok(())
+}
+
 /// CLI function to create Hugging Face dataset
 pub async fn create_huggingface_dataset(
     base_path: &str,
     output_dir: &str,
+    min_split_examples: u64,
+    strict: bool,
 ) -> Result<(), ValidationError> {
-    let converter = HuggingFaceDatasetConverter::new(base_path, output_dir)?;
+    let converter = HuggingFaceDatasetConverter::new(base_path, output_dir)?
+        .with_split_check(min_split_examples, strict);
     converter.create_huggingface_dataset().await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::write_parquet;
+    use proptest::prelude::*;
     use std::path::Path;
 
     #[tokio::test]
@@ -671,4 +1092,225 @@ mod tests {
         // Clean up
         let _ = fs::remove_dir_all(output_dir);
     }
+
+    #[tokio::test]
+    async fn test_generated_fixture_converts_to_parquet() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("solfunmeme-index");
+        let term_count = crate::solfunmeme_validator::generate_fixture(&base_path).unwrap();
+        assert!(SolfunmemeDataAccess::new(base_path.to_str().unwrap()).health_check().is_ok());
+
+        let output_dir = temp_dir.path().join("hf");
+        let converter = HuggingFaceDatasetConverter::new(base_path.to_str().unwrap(), output_dir.to_str().unwrap()).unwrap();
+        converter.convert_to_parquet().await.unwrap();
+
+        let mut rows = Vec::new();
+        for split in ["train", "validation", "test"] {
+            rows.extend(read_index_terms_from_parquet(&output_dir.join(format!("{}-00000-of-00001.parquet", split))).unwrap());
+        }
+        assert_eq!(rows.len(), term_count);
+
+        let (_, abilities, group, cooccurring) = rows.iter().find(|(id, ..)| id == "1001").unwrap();
+        assert_eq!(abilities.semantic_names, Some(vec!["skill".to_string(), "capacity".to_string()]));
+        assert_eq!(group, "a");
+        assert!(cooccurring.contains(&"1004".to_string()));
+        let (_, unicode, group, _) = rows.iter().find(|(id, ..)| id == "1006").unwrap();
+        assert_eq!(unicode.term, "λ-calculus");
+        assert_eq!(group, "λ");
+    }
+
+    #[test]
+    fn test_export_violations_flag_mismatched_group_and_duplicate_id() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let term = |text: &str| IndexTerm {
+            term: text.to_string(),
+            count: 1,
+            category: String::new(),
+            significance: String::new(),
+            vibe: String::new(),
+            action_suggestion: String::new(),
+            emoji_representation: None,
+            semantic_names: None,
+            osi_layer: None,
+            prime_factor: None,
+            is_power_of_two: None,
+            numerical_address: None,
+            embedding_vectors: None,
+            versions: Vec::new(),
+            first_seen_timestamp: None,
+            last_seen_timestamp: None,
+        };
+        let write_split = |name: &str, rows: Vec<(String, IndexTerm, String)>| {
+            let schema = index_term_schema();
+            let batch = HuggingFaceDatasetConverter::create_record_batch(schema, &rows, &HashMap::new()).unwrap();
+            write_parquet(&temp_dir.path().join(name), &batch);
+        };
+        write_split("test-00000-of-00001.parquet", vec![
+            ("1006".to_string(), term("λ-calculus"), "λ".to_string()),
+        ]);
+        write_split("train-00000-of-00001.parquet", vec![
+            ("1001".to_string(), term("Abilities"), "a".to_string()),
+            ("1002".to_string(), term("async"), "s".to_string()),
+            ("1006".to_string(), term("serde"), "s".to_string()),
+        ]);
+
+        let violations = find_export_violations(temp_dir.path()).unwrap();
+        assert_eq!(violations.len(), 2);
+        assert_eq!(violations[0].file, "train-00000-of-00001.parquet");
+        assert_eq!(violations[0].row, 1);
+        assert!(violations[0].message.contains("character_group 's'"), "{}", violations[0].message);
+        assert_eq!(violations[1].row, 2);
+        assert!(violations[1].message.contains("duplicate id 1006"), "{}", violations[1].message);
+        assert!(validate_solfunmeme_export(temp_dir.path().to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_check_split_sizes_strict() {
+        let split = |name: &str, num_examples: u64| SplitInfo {
+            name: name.to_string(),
+            num_bytes: num_examples * 200,
+            num_examples,
+            dataset_name: "solfunmeme-index".to_string(),
+        };
+
+        let mut splits = HashMap::new();
+        splits.insert("train".to_string(), split("train", 500));
+        splits.insert("test".to_string(), split("test", 2));
+
+        let warnings = check_split_sizes(&splits, 10, false).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'test'"));
+
+        assert!(check_split_sizes(&splits, 10, true).is_err());
+        assert!(check_split_sizes(&splits, 2, true).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_strict_split_check_fails_before_writing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let base_path = temp_dir.path().join("solfunmeme-index");
+        let term_count = crate::solfunmeme_validator::generate_fixture(&base_path).unwrap();
+
+        let output_dir = temp_dir.path().join("hf");
+        let converter = HuggingFaceDatasetConverter::new(base_path.to_str().unwrap(), output_dir.to_str().unwrap())
+            .unwrap()
+            .with_split_check(term_count as u64 + 1, true);
+        assert!(converter.create_huggingface_dataset().await.is_err());
+        assert_eq!(fs::read_dir(&output_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_bad_list_offsets_name_the_column() {
+        let values = vec![Some("a".to_string()), Some("b".to_string())];
+
+        // Last offset points past the two values
+        let error = string_list_array("semantic_names", vec![0, 1, 5], values.clone(), None).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("'semantic_names'"), "{}", message);
+        assert!(message.contains("2 rows, 2 values"), "{}", message);
+
+        assert!(string_list_array("semantic_names", vec![0, 2, 1], values.clone(), None).is_err());
+        assert_eq!(string_list_array("semantic_names", vec![0, 1, 2], values, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_card_stats() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let write_terms = |char_dir: &str, count: usize| {
+            let dir = temp_dir.path().join("terms").join(char_dir);
+            fs::create_dir_all(&dir).unwrap();
+            for i in 0..count {
+                fs::write(dir.join(format!("{}{}.json", char_dir, i)), "{}").unwrap();
+            }
+        };
+        write_terms("a", 2);
+        write_terms("s", 5);
+        write_terms("7", 3);
+        write_terms("λ", 1);
+
+        let stats = compute_card_stats(temp_dir.path().to_str().unwrap(), 2).unwrap();
+        assert_eq!(stats.total_terms, 11);
+        assert_eq!(stats.character_groups, 4);
+        assert_eq!(stats.size_category, "n<1K");
+        assert_eq!(stats.top_groups[0], ("s".to_string(), 5));
+        assert_eq!(stats.top_groups.len(), 2);
+        assert_eq!(stats.splits, vec![
+            ("train".to_string(), 7),
+            ("validation".to_string(), 3),
+            ("test".to_string(), 1),
+        ]);
+
+        let card = stats.to_card_markdown();
+        assert!(card.contains("size_categories: n<1K"));
+        assert!(card.contains("**11 terms**"));
+    }
+
+    /// Terms as the Parquet splits can represent them: embeddings and versions are not exported
+    fn arb_index_term() -> impl Strategy<Value = IndexTerm> {
+        let text = || ".{0,30}";
+        let optional_text = || proptest::option::of(".{0,30}");
+        (
+            (text(), any::<u32>(), text(), text(), text(), text(), optional_text()),
+            (
+                proptest::option::of(proptest::collection::vec(".{0,20}", 0..4)),
+                optional_text(),
+                proptest::option::of(any::<u64>()),
+                proptest::option::of(any::<bool>()),
+                optional_text(),
+                proptest::option::of(any::<u64>()),
+                proptest::option::of(any::<u64>()),
+            ),
+        )
+            .prop_map(|(
+                (term, count, category, significance, vibe, action_suggestion, emoji_representation),
+                (semantic_names, osi_layer, prime_factor, is_power_of_two, numerical_address, first_seen_timestamp, last_seen_timestamp),
+            )| IndexTerm {
+                term,
+                count,
+                category,
+                significance,
+                vibe,
+                action_suggestion,
+                emoji_representation,
+                semantic_names,
+                osi_layer,
+                prime_factor,
+                is_power_of_two,
+                numerical_address,
+                embedding_vectors: None,
+                versions: Vec::new(),
+                first_seen_timestamp,
+                last_seen_timestamp,
+            })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_index_terms_roundtrip_through_parquet(
+            rows in proptest::collection::vec(("[a-z0-9_]{1,12}", arb_index_term(), "[a-z]"), 0..20),
+            neighbours in proptest::collection::vec(proptest::collection::vec("[a-z]{1,8}", 1..4), 0..20),
+        ) {
+            let cooccurrence: HashMap<String, Vec<String>> = rows.iter()
+                .map(|(id, _, _)| id.clone())
+                .zip(neighbours)
+                .collect();
+
+            let temp_dir = tempfile::TempDir::new().unwrap();
+            let output_file = temp_dir.path().join("train-00000-of-00001.parquet");
+            let schema = index_term_schema();
+            let batch = HuggingFaceDatasetConverter::create_record_batch(schema, &rows, &cooccurrence).unwrap();
+            write_parquet(&output_file, &batch);
+
+            let read_back = read_index_terms_from_parquet(&output_file).unwrap();
+            prop_assert_eq!(read_back.len(), rows.len());
+            for ((id, term, group, neighbours), (expected_id, expected_term, expected_group)) in read_back.iter().zip(&rows) {
+                prop_assert_eq!(id, expected_id);
+                prop_assert_eq!(term, expected_term);
+                prop_assert_eq!(group, expected_group);
+                prop_assert_eq!(neighbours, &cooccurrence.get(id).cloned().unwrap_or_default());
+            }
+        }
+    }
 }
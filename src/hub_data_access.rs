@@ -0,0 +1,161 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::validator::{
+    CachedResponse, DataAccess, EntityIdentifier, ParquetMetadata, ValidationError, ValidationResult,
+};
+
+/// Public endpoint of the Hugging Face dataset viewer API
+pub const DEFAULT_DATASETS_SERVER_URL: &str = "https://datasets-server.huggingface.co";
+
+/// `DataAccess` backed by the Hugging Face dataset viewer HTTP API
+///
+/// Uses a blocking client because `DataAccess` is synchronous; call it from
+/// `tokio::task::spawn_blocking` when running inside the async runtime.
+pub struct HubDataAccess {
+    base_url: String,
+    client: reqwest::blocking::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitsResponse {
+    splits: Vec<SplitEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SplitEntry {
+    config: String,
+    split: String,
+}
+
+impl HubDataAccess {
+    pub fn new(base_url: &str) -> Result<Self, ValidationError> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!("hf-dataset-validator/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| ValidationError::DataAccessError {
+                message: format!("Failed to build HTTP client: {}", e),
+            })?;
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+        })
+    }
+
+    /// GET `{base_url}/{endpoint}` with the given query, returning the HTTP status and JSON body
+    fn get_json(&self, endpoint: &str, query: &[(&str, &str)]) -> Result<(u16, serde_json::Value), ValidationError> {
+        let url = format!("{}/{}", self.base_url, endpoint);
+        let response = self.client.get(&url).query(query).send()
+            .map_err(|e| ValidationError::DataAccessError {
+                message: format!("Request to {} failed: {}", url, e),
+            })?;
+        let status = response.status().as_u16();
+        let body = response.json::<serde_json::Value>().unwrap_or(serde_json::Value::Null);
+        Ok((status, body))
+    }
+
+    /// Query `/is-valid` for an entity at whatever level it identifies
+    fn is_valid(&self, entity: &EntityIdentifier) -> Result<(u16, ValidationResult), ValidationError> {
+        let mut query = vec![("dataset", entity.dataset.as_str())];
+        if let Some(config) = &entity.config {
+            query.push(("config", config.as_str()));
+        }
+        if let Some(split) = &entity.split {
+            query.push(("split", split.as_str()));
+        }
+
+        let (status, body) = self.get_json("is-valid", &query)?;
+        let flag = |name: &str| body.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+        Ok((status, ValidationResult {
+            viewer: flag("viewer"),
+            preview: flag("preview"),
+            search: flag("search"),
+            filter: flag("filter"),
+            statistics: flag("statistics"),
+            schema_fingerprint: None,
+        }))
+    }
+
+    fn list_splits(&self, dataset: &str) -> Result<Vec<SplitEntry>, ValidationError> {
+        let (status, body) = self.get_json("splits", &[("dataset", dataset)])?;
+        if status != 200 {
+            return Err(ValidationError::DataAccessError {
+                message: format!("Listing splits for {} returned HTTP {}", dataset, status),
+            });
+        }
+        Ok(serde_json::from_value::<SplitsResponse>(body)?.splits)
+    }
+}
+
+impl DataAccess for HubDataAccess {
+    fn check_successful_response(&self, kind: &str, entity: &EntityIdentifier) -> Result<bool, ValidationError> {
+        let (status, result) = self.is_valid(entity)?;
+        if status != 200 {
+            return Ok(false);
+        }
+        Ok(match kind {
+            "config-has-viewer" => result.viewer,
+            "split-has-preview" => result.preview,
+            "split-has-statistics" => result.statistics,
+            _ => false,
+        })
+    }
+
+    fn get_parquet_metadata(&self, dataset: &str, config: &str) -> Result<ParquetMetadata, ValidationError> {
+        let (status, body) = self.get_json("info", &[("dataset", dataset), ("config", config)])?;
+        if status != 200 {
+            return Err(ValidationError::MetadataNotFound {
+                entity: format!("{}:{}", dataset, config),
+            });
+        }
+
+        let info = &body["dataset_info"];
+        let features: HashMap<String, String> = info["features"].as_object()
+            .map(|features| features.iter().map(|(name, feature)| {
+                // Plain columns carry a dtype; nested features fall back to their _type
+                let dtype = feature.get("dtype")
+                    .or_else(|| feature.get("_type"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown");
+                (name.clone(), dtype.to_string())
+            }).collect())
+            .unwrap_or_default();
+
+        let metadata = ParquetMetadata::new(features);
+        let num_rows = info["splits"].as_object()
+            .map(|splits| splits.values().filter_map(|s| s["num_examples"].as_u64()).sum::<u64>());
+        Ok(match num_rows {
+            Some(num_rows) => metadata.with_rows(num_rows),
+            None => metadata,
+        })
+    }
+
+    fn get_split_names(&self, dataset: &str, config: &str) -> Result<Vec<String>, ValidationError> {
+        Ok(self.list_splits(dataset)?
+            .into_iter()
+            .filter(|entry| entry.config == config)
+            .map(|entry| entry.split)
+            .collect())
+    }
+
+    fn get_config_names(&self, dataset: &str) -> Result<Vec<String>, ValidationError> {
+        let mut configs: Vec<String> = Vec::new();
+        for entry in self.list_splits(dataset)? {
+            if !configs.contains(&entry.config) {
+                configs.push(entry.config);
+            }
+        }
+        Ok(configs)
+    }
+
+    fn get_cached_validation(&self, _kind: &str, entity: &EntityIdentifier) -> Result<CachedResponse, ValidationError> {
+        let (status, result) = self.is_valid(entity)?;
+        Ok(CachedResponse::new(status, result, 1.0))
+    }
+
+    fn has_indexable_columns(&self, features: &HashMap<String, String>) -> bool {
+        features.values().any(|v| v.contains("string"))
+    }
+}
@@ -0,0 +1,226 @@
+//! # JSON Schema Validation: check JSON/JSONL records against a user schema
+//!
+//! The built-in validators know the record types this crate writes. Datasets
+//! with their own layout can instead be checked against a JSON Schema supplied
+//! on the command line (`validate-json <dir> --schema schema.json`).
+//!
+//! ## Records
+//!
+//! - **`.jsonl`**: every non-empty line is a record, numbered by line (1-based)
+//! - **`.json`**: a top-level array holds one record per element (0-based);
+//!   any other document is a single record 0
+//!
+//! Each violation is reported with the file, the record and the JSON pointer
+//! of the offending value inside the record (empty for the record itself).
+//!
+//! The metadata files this tool writes next to the data (`METADATA_FILES`)
+//! and the schema file itself are not records, so they are never checked.
+
+use std::fs;
+use std::path::Path;
+
+use crate::rust_analyzer_extractor::CHECKPOINT_FILE;
+use crate::validator::ValidationError;
+
+/// File names of the run metadata written alongside dataset records
+pub const METADATA_FILES: &[&str] = &[
+    "manifest.json",
+    "provenance.json",
+    CHECKPOINT_FILE,
+    "line_coverage.json",
+    "validation_report.json",
+];
+
+/// One record failing one schema constraint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// File path relative to the dataset directory
+    pub file: String,
+    /// Line number (`.jsonl`) or array index (`.json`) of the record
+    pub record: usize,
+    /// JSON pointer to the failing value within the record, e.g. `/stats/count`
+    pub instance_path: String,
+    pub message: String,
+}
+
+/// Outcome of checking a dataset against a schema
+#[derive(Debug, Default)]
+pub struct SchemaReport {
+    pub files_checked: usize,
+    pub records_checked: usize,
+    pub violations: Vec<SchemaViolation>,
+}
+
+/// Compile a JSON Schema document, rejecting invalid schemas up front
+fn compile_schema(schema: &serde_json::Value) -> Result<jsonschema::Validator, ValidationError> {
+    jsonschema::validator_for(schema)
+        .map_err(|e| ValidationError::InvalidInput(format!("Invalid JSON Schema: {}", e)))
+}
+
+/// Split a file into (record number, record) pairs according to its extension
+fn read_records(path: &Path) -> Result<Vec<(usize, serde_json::Value)>, ValidationError> {
+    let content = fs::read_to_string(path).map_err(|e| ValidationError::DataAccessError {
+        message: format!("Failed to read {}: {}", path.display(), e),
+    })?;
+    let parse_error = |e: serde_json::Error, location: String| ValidationError::DataAccessError {
+        message: format!("Invalid JSON in {}{}: {}", path.display(), location, e),
+    };
+
+    if path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
+        return content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .map(|record| (index + 1, record))
+                    .map_err(|e| parse_error(e, format!(" line {}", index + 1)))
+            })
+            .collect();
+    }
+
+    match serde_json::from_str(&content).map_err(|e| parse_error(e, String::new()))? {
+        serde_json::Value::Array(records) => Ok(records.into_iter().enumerate().collect()),
+        record => Ok(vec![(0, record)]),
+    }
+}
+
+/// Check every `.json` and `.jsonl` file under `dataset_dir` against `schema`
+///
+/// Files are visited in path order. `dataset_dir` may also be a single file.
+/// Metadata files and `schema_path`, when it lies inside the dataset, are skipped.
+pub fn find_schema_violations(dataset_dir: &Path, schema: &serde_json::Value, schema_path: Option<&Path>) -> Result<SchemaReport, ValidationError> {
+    let validator = compile_schema(schema)?;
+    let schema_path = schema_path.and_then(|path| path.canonicalize().ok());
+    let is_skipped = |path: &Path| {
+        path.file_name().and_then(|name| name.to_str()).is_some_and(|name| METADATA_FILES.contains(&name))
+            || (schema_path.is_some() && path.canonicalize().ok() == schema_path)
+    };
+
+    let mut json_files: Vec<_> = walkdir::WalkDir::new(dataset_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file() && matches!(path.extension().and_then(|e| e.to_str()), Some("json" | "jsonl")))
+        .filter(|path| !is_skipped(path))
+        .collect();
+    json_files.sort();
+
+    let mut report = SchemaReport::default();
+    for path in &json_files {
+        let display_path = path.strip_prefix(dataset_dir).ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .unwrap_or(path)
+            .display()
+            .to_string();
+        for (record, value) in read_records(path)? {
+            report.violations.extend(validator.iter_errors(&value).map(|error| SchemaViolation {
+                file: display_path.clone(),
+                record,
+                instance_path: error.instance_path.to_string(),
+                message: error.to_string(),
+            }));
+            report.records_checked += 1;
+        }
+        report.files_checked += 1;
+    }
+    Ok(report)
+}
+
+/// CLI function to validate JSON/JSONL records against a JSON Schema file
+pub fn validate_json_against_schema(dataset_dir: &str, schema_path: &str) -> Result<(), ValidationError> {
+    if !Path::new(dataset_dir).exists() {
+        return Err(ValidationError::DataAccessError {
+            message: format!("Dataset directory does not exist: {}", dataset_dir),
+        });
+    }
+    let schema_content = fs::read_to_string(schema_path).map_err(|e| ValidationError::InvalidInput(
+        format!("Failed to read schema {}: {}", schema_path, e),
+    ))?;
+    let schema: serde_json::Value = serde_json::from_str(&schema_content).map_err(|e| ValidationError::InvalidInput(
+        format!("Schema {} is not valid JSON: {}", schema_path, e),
+    ))?;
+
+    let report = find_schema_violations(Path::new(dataset_dir), &schema, Some(Path::new(schema_path)))?;
+    if report.files_checked == 0 {
+        println!("⚠️  No .json or .jsonl files found in {}", dataset_dir);
+        return Ok(());
+    }
+    if report.violations.is_empty() {
+        println!("✅ {} records in {} files match {}", report.records_checked, report.files_checked, schema_path);
+        return Ok(());
+    }
+
+    for violation in &report.violations {
+        let at = if violation.instance_path.is_empty() { "/" } else { violation.instance_path.as_str() };
+        println!("  ❌ {} record {} at {}: {}", violation.file, violation.record, at, violation.message);
+    }
+    Err(ValidationError::ProcessingError(format!(
+        "{} schema violations in {} records checked",
+        report.violations.len(),
+        report.records_checked
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_schema_violation_reported_with_path() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("records.jsonl"), "\
+{\"id\": \"a\", \"stats\": {\"count\": 3}}
+
+{\"id\": \"b\", \"stats\": {\"count\": \"three\"}}
+").unwrap();
+        fs::write(temp_dir.path().join("extra.json"), r#"[{"id": "c", "stats": {"count": 1}}]"#).unwrap();
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["id", "stats"],
+            "properties": {
+                "stats": {
+                    "type": "object",
+                    "properties": { "count": { "type": "integer" } },
+                    "required": ["count"]
+                }
+            }
+        });
+
+        let report = find_schema_violations(temp_dir.path(), &schema, None).unwrap();
+        assert_eq!(report.files_checked, 2);
+        assert_eq!(report.records_checked, 3);
+        assert_eq!(report.violations.len(), 1);
+        let violation = &report.violations[0];
+        assert_eq!(violation.file, "records.jsonl");
+        assert_eq!(violation.record, 3);
+        assert_eq!(violation.instance_path, "/stats/count");
+        assert!(violation.message.contains("integer"), "{}", violation.message);
+    }
+
+    #[test]
+    fn test_metadata_and_schema_files_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("records.jsonl"), "{\"id\": \"a\"}\n").unwrap();
+        fs::write(temp_dir.path().join("manifest.json"), r#"{"files_found": 1, "skipped": []}"#).unwrap();
+        let schema = serde_json::json!({ "type": "object", "required": ["id"] });
+        let schema_path = temp_dir.path().join("schema.json");
+        fs::write(&schema_path, schema.to_string()).unwrap();
+
+        let report = find_schema_violations(temp_dir.path(), &schema, Some(&schema_path)).unwrap();
+        assert_eq!(report.files_checked, 1);
+        assert_eq!(report.records_checked, 1);
+        assert!(report.violations.is_empty(), "{:?}", report.violations);
+    }
+
+    #[test]
+    fn test_invalid_schema_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let schema = serde_json::json!({ "type": "not-a-type" });
+        assert!(matches!(
+            find_schema_violations(temp_dir.path(), &schema, None),
+            Err(ValidationError::InvalidInput(_))
+        ));
+    }
+}
@@ -45,22 +45,21 @@
 //! - **Performance tools**: Correlate with actual execution performance
 
 use anyhow::{
-    //Context,
+    Context,
     Result};
 use serde::{Deserialize, Serialize};
 //use std::collections::HashMap;
 use std::path::{Path
 		//,		PathBuf
 };
-use std::time::{SystemTime, UNIX_EPOCH};
 
 use arrow::array::{StringArray, UInt32Array,
 		   //UInt64Array,
-		   //Float32Array,
+		   Float32Array,
 		   //BooleanArray
 };
 use arrow::datatypes::{DataType, Field, Schema};
-use arrow::record_batch::RecordBatch;
+use crate::dataset_io::{build_record_batch, write_provenance, Provenance};
 use parquet::arrow::ArrowWriter;
 use parquet::file::properties::WriterProperties;
 use std::sync::Arc;
@@ -337,6 +336,9 @@ impl LLVMIRExtractor {
             }
         }
         
+        let phase_names: Vec<&str> = phases.iter().map(LLVMAnalysisPhase::as_str).collect();
+        write_provenance(output_dir, &Provenance::capture(&phase_names, &self.rustc_version))?;
+        
         Ok(())
     }
     
@@ -439,7 +441,7 @@ impl LLVMIRExtractor {
             
             // Metadata
             processing_time_ms: 1,
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            timestamp: crate::config::generation_timestamp(),
             extractor_version: self.extractor_version.clone(),
             llvm_version: self.llvm_version.clone(),
             rustc_version: self.rustc_version.clone(),
@@ -501,10 +503,9 @@ impl LLVMIRExtractor {
         Ok(())
     }
     
-    /// Write records to a single Parquet file
-    fn write_records_to_parquet(&self, records: &[LLVMIRRecord], output_file: &Path) -> Result<()> {
-        // Define Arrow schema for LLVM IR records (simplified for now)
-        let schema = Arc::new(Schema::new(vec![
+    /// Arrow schema for LLVM IR records (simplified for now)
+    pub fn parquet_schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
             Field::new("id", DataType::Utf8, false),
             Field::new("source_file", DataType::Utf8, false),
             Field::new("construct_name", DataType::Utf8, false),
@@ -515,7 +516,12 @@ impl LLVMIRExtractor {
             Field::new("optimization_level", DataType::Utf8, false),
             Field::new("target_architecture", DataType::Utf8, false),
             Field::new("extractor_version", DataType::Utf8, false),
-        ]));
+        ]))
+    }
+
+    /// Write records to a single Parquet file
+    fn write_records_to_parquet(&self, records: &[LLVMIRRecord], output_file: &Path) -> Result<()> {
+        let schema = Self::parquet_schema();
         
         // Convert records to Arrow arrays (simplified)
         let ids: Vec<String> = records.iter().map(|r| r.id.clone()).collect();
@@ -542,7 +548,7 @@ impl LLVMIRExtractor {
         let extractor_version_array = Arc::new(StringArray::from(extractor_versions));
         
         // Create record batch
-        let batch = RecordBatch::try_new(
+        let batch = build_record_batch(
             schema.clone(),
             vec![
                 id_array,
@@ -556,6 +562,7 @@ impl LLVMIRExtractor {
                 target_arch_array,
                 extractor_version_array,
             ],
+            records.len(),
         )?;
         
         // Write to Parquet file
@@ -572,6 +579,169 @@ impl LLVMIRExtractor {
     }
 }
 
+/// Per-function instruction counts at O0 and O2, joined by `construct_name`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IrComparisonRecord {
+    /// Function path within the crate (`helpers::sum`), crate name and hash stripped
+    pub construct_name: String,
+    /// Instructions in the O0 definition
+    pub o0_instructions: u32,
+    /// Instructions in the O2 definition
+    pub o2_instructions: u32,
+    /// Fraction of O0 instructions removed at O2 (`1 - o2 / o0`)
+    pub reduction_ratio: f32,
+}
+
+/// Crate name passed to rustc so symbol paths are predictable
+const COMPARE_CRATE_NAME: &str = "ir_compare";
+
+/// Compile a single Rust file as a library and return the emitted LLVM IR
+/// 
+/// `link-dead-code` keeps small functions that rustc would otherwise leave to
+/// cross-crate inlining (and omit from the library's IR) at higher opt levels.
+pub fn compile_to_llvm_ir(source_path: &Path, opt_level: &str) -> Result<String> {
+    let level = opt_level.trim_start_matches(['O', 'o']);
+    let temp_dir = tempfile::TempDir::new()?;
+    let ir_file = temp_dir.path().join("output.ll");
+
+    let output = std::process::Command::new("rustc")
+        .args(["--crate-type=lib", "--emit=llvm-ir", "--crate-name", COMPARE_CRATE_NAME])
+        .args(["-C", &format!("opt-level={}", level), "-C", "codegen-units=1", "-C", "link-dead-code"])
+        .arg("-o").arg(&ir_file)
+        .arg(source_path)
+        .output()
+        .context("Failed to run rustc")?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("rustc failed at {}: {}", opt_level, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    std::fs::read_to_string(&ir_file).with_context(|| format!("Failed to read {}", ir_file.display()))
+}
+
+/// Count the instructions of each function defined in LLVM IR text, keyed by demangled name
+/// 
+/// Labels, comments, and the braces around the body are not counted.
+pub fn count_function_instructions(llvm_ir: &str) -> Vec<(String, u32)> {
+    let mut functions = Vec::new();
+    let mut current: Option<(String, u32)> = None;
+
+    for line in llvm_ir.lines() {
+        let trimmed = line.trim();
+        if let Some((name, count)) = current.as_mut() {
+            if trimmed == "}" {
+                functions.push((std::mem::take(name), *count));
+                current = None;
+            } else if !trimmed.is_empty() && !trimmed.starts_with(';') && !is_ir_label(trimmed) {
+                *count += 1;
+            }
+        } else if trimmed.starts_with("define ") {
+            let symbol = trimmed.split_once('@')
+                .and_then(|(_, rest)| rest.split_once('('))
+                .map(|(symbol, _)| symbol.trim_matches('"'))
+                .unwrap_or_default();
+            current = Some((demangle_symbol(symbol), 0));
+        }
+    }
+    functions
+}
+
+/// `start:` or `bb3:    ; preds = %bb2` — a basic block label, not an instruction
+fn is_ir_label(line: &str) -> bool {
+    line.split(';').next()
+        .map(|code| code.trim())
+        .map_or(false, |code| code.ends_with(':') && !code.contains(' '))
+}
+
+/// Demangle a legacy Rust symbol (`_ZN10ir_compare3add17h0123456789abcdefE` → `add`)
+/// 
+/// The crate name and trailing hash are dropped so names join across opt levels;
+/// symbols that are not legacy-mangled are returned unchanged.
+fn demangle_symbol(symbol: &str) -> String {
+    let Some(mut rest) = symbol.strip_prefix("_ZN") else {
+        return symbol.to_string();
+    };
+    let mut segments = Vec::new();
+    while !rest.starts_with('E') {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let Ok(len) = rest[..digits].parse::<usize>() else {
+            return symbol.to_string();
+        };
+        let Some(segment) = rest.get(digits..digits + len) else {
+            return symbol.to_string();
+        };
+        segments.push(segment);
+        rest = &rest[digits + len..];
+    }
+
+    let is_hash = |s: &str| s.len() == 17 && s.starts_with('h') && s[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if segments.last().map_or(false, |s| is_hash(s)) {
+        segments.pop();
+    }
+    if segments.first() == Some(&COMPARE_CRATE_NAME) {
+        segments.remove(0);
+    }
+    segments.join("::")
+}
+
+/// Join O0 and O2 instruction counts by function name (functions present at both levels)
+pub fn join_opt_level_counts(o0: &[(String, u32)], o2: &[(String, u32)]) -> Vec<IrComparisonRecord> {
+    let o2_counts: std::collections::HashMap<&str, u32> = o2.iter().map(|(name, count)| (name.as_str(), *count)).collect();
+    let mut records: Vec<IrComparisonRecord> = o0.iter()
+        .filter_map(|(name, o0_instructions)| {
+            let o2_instructions = *o2_counts.get(name.as_str())?;
+            let reduction_ratio = if *o0_instructions == 0 {
+                0.0
+            } else {
+                1.0 - o2_instructions as f32 / *o0_instructions as f32
+            };
+            Some(IrComparisonRecord {
+                construct_name: name.clone(),
+                o0_instructions: *o0_instructions,
+                o2_instructions,
+                reduction_ratio,
+            })
+        })
+        .collect();
+    records.sort_by(|a, b| a.construct_name.cmp(&b.construct_name));
+    records
+}
+
+/// Compile `source_path` at O0 and O2 and join the per-function instruction counts
+pub fn compare_opt_levels(source_path: &Path) -> Result<Vec<IrComparisonRecord>> {
+    let o0 = count_function_instructions(&compile_to_llvm_ir(source_path, "O0")?);
+    let o2 = count_function_instructions(&compile_to_llvm_ir(source_path, "O2")?);
+    Ok(join_opt_level_counts(&o0, &o2))
+}
+
+/// Write the comparison dataset to `output_dir/data.parquet`
+pub fn write_comparison_to_parquet(records: &[IrComparisonRecord], output_dir: &Path) -> Result<()> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("construct_name", DataType::Utf8, false),
+        Field::new("o0_instructions", DataType::UInt32, false),
+        Field::new("o2_instructions", DataType::UInt32, false),
+        Field::new("reduction_ratio", DataType::Float32, false),
+    ]));
+    let batch = build_record_batch(
+        schema.clone(),
+        vec![
+            Arc::new(StringArray::from_iter_values(records.iter().map(|r| r.construct_name.as_str()))),
+            Arc::new(UInt32Array::from_iter_values(records.iter().map(|r| r.o0_instructions))),
+            Arc::new(UInt32Array::from_iter_values(records.iter().map(|r| r.o2_instructions))),
+            Arc::new(Float32Array::from_iter_values(records.iter().map(|r| r.reduction_ratio))),
+        ],
+        records.len(),
+    )?;
+
+    std::fs::create_dir_all(output_dir)?;
+    let file = std::fs::File::create(output_dir.join("data.parquet"))?;
+    let props = WriterProperties::builder()
+        .set_compression(parquet::basic::Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -603,4 +773,44 @@ fn main() {
         assert_eq!(records[0].target_architecture, "x86_64");
         assert!(records[0].llvm_ir.contains("define void @main"));
     }
+
+    #[test]
+    fn test_compare_opt_levels() {
+        // Needs a working rustc on PATH
+        if std::process::Command::new("rustc").arg("--version").output().is_err() {
+            return;
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let source_file = temp_dir.path().join("inline.rs");
+        fs::write(&source_file, r#"
+fn double(x: u64) -> u64 {
+    x.wrapping_mul(2)
+}
+
+pub fn quadruple(x: u64) -> u64 {
+    double(double(x))
+}
+"#).unwrap();
+
+        let records = compare_opt_levels(&source_file).unwrap();
+        let quadruple = records.iter().find(|r| r.construct_name == "quadruple").unwrap();
+        assert!(quadruple.o2_instructions < quadruple.o0_instructions);
+        let expected_ratio = 1.0 - quadruple.o2_instructions as f32 / quadruple.o0_instructions as f32;
+        assert!((quadruple.reduction_ratio - expected_ratio).abs() < f32::EPSILON);
+        assert!(quadruple.reduction_ratio > 0.0);
+        // std helpers only instantiated at O0 have no O2 counterpart to join with
+        assert!(records.iter().all(|r| !r.construct_name.contains("wrapping_mul")));
+
+        let output_dir = temp_dir.path().join("compare");
+        write_comparison_to_parquet(&records, &output_dir).unwrap();
+        assert!(output_dir.join("data.parquet").exists());
+    }
+
+    #[test]
+    fn test_demangle_symbol() {
+        assert_eq!(demangle_symbol("_ZN10ir_compare3add17h0123456789abcdefE"), "add");
+        assert_eq!(demangle_symbol("_ZN10ir_compare7helpers3sum17hfedcba9876543210E"), "helpers::sum");
+        assert_eq!(demangle_symbol("main"), "main");
+    }
 }
@@ -7,22 +7,56 @@ mod dataset_loader_example;
 mod rust_analyzer_extractor;
 mod cargo2hf_extractor;
 mod llvm_ir_extractor;
+mod hub_data_access;
+mod training_pairs;
+mod object_store_access;
+mod config;
+mod dataset_repl;
+mod json_schema_validator;
+mod partitioned_dataset;
+mod dataset_io;
+mod source_scan;
+#[cfg(test)]
+mod test_support;
 
 use validator::{
     DatasetValidator, MockDataAccess, EntityIdentifier, ValidationLevel,
-    validate_split, validate_config, validate_dataset, ValidationError
+    validate_split, validate_config, validate_dataset, ValidationError,
+    check_output_dir_safety, compare_access
 };
-use rust_analyzer_extractor::{RustAnalyzerExtractor, ProcessingPhase};
+use rust_analyzer_extractor::{OversizeAction, ParquetCodec, ParquetWriteOptions, PartitionBy, RecordIdTemplate, RustAnalyzerExtractor, ProcessingPhase};
+use partitioned_dataset::PartitionedDataset;
 use std::env;
 use std::path::Path;
 
-#[tokio::main]
-async fn main() -> Result<(), ValidationError> {
-    println!("🚀 Hugging Face Dataset Validator - Rust Implementation");
-    println!("======================================================\n");
-
+fn main() -> Result<(), ValidationError> {
     let args: Vec<String> = env::args().collect();
-    
+    let jobs = parse_jobs(&args)?;
+    let seed = parse_seed(&args)?;
+    tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(jobs)
+        .enable_all()
+        .build()
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to start async runtime: {}", e)))?
+        .block_on(run(args, jobs, seed))
+}
+
+/// Commands whose stdout is meant to be redirected to a file, so no banner is printed
+const MACHINE_READABLE_COMMANDS: &[&str] = &["emit-pyarrow-schema"];
+
+/// Print the startup banner, unless `command` writes machine-readable output
+fn print_banner(out: &mut impl std::io::Write, command: Option<&str>) -> std::io::Result<()> {
+    if command.is_some_and(|command| MACHINE_READABLE_COMMANDS.contains(&command)) {
+        return Ok(());
+    }
+    writeln!(out, "🚀 Hugging Face Dataset Validator - Rust Implementation")?;
+    writeln!(out, "======================================================\n")
+}
+
+async fn run(args: Vec<String>, jobs: usize, seed: Option<u64>) -> Result<(), ValidationError> {
+    print_banner(&mut std::io::stdout(), args.get(1).map(|s| s.as_str()))
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to write to stdout: {}", e)))?;
+
     match args.get(1).map(|s| s.as_str()) {
         Some("test-mock") => {
             println!("Running mock dataset tests...\n");
@@ -39,8 +73,32 @@ async fn main() -> Result<(), ValidationError> {
         Some("export-all") => {
             println!("Exporting solfunmeme dataset to JSONL...\n");
             let base_path = "/home/mdupont/2025/08/07/solfunmeme-index";
-            let output_path = args.get(2).map(|s| s.as_str()).unwrap_or("solfunmeme_export.jsonl");
-            data_converter::run_data_conversion(base_path, "export-all", output_path)?;
+            let output_path = args.get(2).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("solfunmeme_export.jsonl");
+            if has_flag(&args, "--normalize-embeddings") {
+                let converter = data_converter::DataConverter::new(base_path)?.with_normalized_embeddings(true);
+                let count = converter.export_all_to_jsonl(output_path)?;
+                println!("✅ Exported {} terms (normalized embeddings) to {}", count, output_path);
+            } else {
+                data_converter::run_data_conversion(base_path, "export-all", output_path)?;
+            }
+        }
+        Some("validate-embeddings") => {
+            println!("Validating solfunmeme embedding vectors...\n");
+            let base_path = "/home/mdupont/2025/08/07/solfunmeme-index";
+            let require_normalized = has_flag(&args, "--require-normalized");
+            let tolerance = parse_flag_value(&args, "--tolerance")?
+                .unwrap_or(solfunmeme_validator::DEFAULT_NORM_TOLERANCE);
+            let data_access = solfunmeme_validator::SolfunmemeDataAccess::new(base_path);
+            data_access.health_check()?;
+            let issues = data_access.validate_embeddings(require_normalized, tolerance)?;
+            if issues.is_empty() {
+                println!("✅ All embeddings are valid");
+            } else {
+                for issue in &issues {
+                    println!("  ❌ {}: {}", issue.term_id, issue.reason);
+                }
+                return Err(ValidationError::ProcessingError(format!("{} terms have invalid embeddings", issues.len())));
+            }
         }
         Some("export-stats") => {
             println!("Exporting solfunmeme dataset statistics...\n");
@@ -51,19 +109,136 @@ async fn main() -> Result<(), ValidationError> {
         Some("create-sample") => {
             println!("Creating sample dataset...\n");
             let base_path = "/home/mdupont/2025/08/07/solfunmeme-index";
-            let output_path = args.get(2).map(|s| s.as_str()).unwrap_or("solfunmeme_sample");
-            data_converter::run_data_conversion(base_path, "create-sample", output_path)?;
+            let output_path = args.get(2).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("solfunmeme_sample");
+            if let Some(seed) = seed {
+                let converter = data_converter::DataConverter::new(base_path)?.with_seed(seed);
+                let count = converter.create_sample_dataset(output_path, 1000)?;
+                println!("✅ Created sample dataset with {} terms (seed {}) in {}", count, seed, output_path);
+            } else {
+                data_converter::run_data_conversion(base_path, "create-sample", output_path)?;
+            }
         }
         Some("create-hf-dataset") => {
             println!("Creating Hugging Face dataset...\n");
             let base_path = "/home/mdupont/2025/08/07/solfunmeme-index";
-            let output_path = args.get(2).map(|s| s.as_str()).unwrap_or("solfunmeme-hf-dataset");
-            hf_dataset_converter::create_huggingface_dataset(base_path, output_path).await?;
+            let output_path = args.get(2).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("solfunmeme-hf-dataset");
+            let min_split = parse_flag_value(&args, "--min-split")?
+                .unwrap_or(hf_dataset_converter::DEFAULT_MIN_SPLIT_EXAMPLES);
+            let strict = has_flag(&args, "--strict");
+            hf_dataset_converter::create_huggingface_dataset(base_path, output_path, min_split, strict).await?;
+        }
+        Some("validate-url") => {
+            println!("Validating Hugging Face dataset from URL...\n");
+            let url = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset URL required".to_string()))?;
+            validate_dataset_url(url).await?;
+        }
+        Some("compare-access") => {
+            println!("Comparing mock and Hugging Face validation results...\n");
+            let entities = args[2..].iter()
+                .map(|url| EntityIdentifier::from_url(url))
+                .collect::<Result<Vec<_>, _>>()?;
+            if entities.is_empty() {
+                return Err(ValidationError::InvalidInput("At least one dataset URL required".to_string()));
+            }
+            let report = tokio::task::spawn_blocking(move || -> Result<_, ValidationError> {
+                let hub = hub_data_access::HubDataAccess::new(hub_data_access::DEFAULT_DATASETS_SERVER_URL)?;
+                Ok(compare_access(MockDataAccess::default(), hub, &entities))
+            })
+            .await
+            .map_err(|e| ValidationError::ProcessingError(format!("Comparison task failed: {}", e)))??;
+            if report.is_empty() {
+                println!("✅ Mock and Hugging Face results agree");
+            }
+            for difference in &report {
+                println!("  {}: {}", difference.entity, difference.differences.join(" "));
+            }
+        }
+        Some("card-stats") => {
+            println!("Computing dataset card statistics...\n");
+            let base_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset base path required".to_string()))?;
+            let top_n = parse_flag_value(&args, "--top")?.unwrap_or(10);
+            hf_dataset_converter::print_card_stats(base_path, top_n)?;
+        }
+        Some("emit-pyarrow-schema") => {
+            let record_type = args.get(2).ok_or_else(|| ValidationError::InvalidInput(format!(
+                "Record type required ({})", RECORD_TYPES.join(", ")
+            )))?;
+            print!("{}", pyarrow_schema_for(record_type)?);
+        }
+        Some("validate-solfunmeme-export") => {
+            println!("Checking solfunmeme export for unique ids and consistent character groups...\n");
+            let export_dir = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Export directory required".to_string()))?;
+            hf_dataset_converter::validate_solfunmeme_export(export_dir)?;
         }
         Some("validate-parquet") => {
             println!("Validating Parquet dataset...\n");
-            let dataset_path = args.get(2).map(|s| s.as_str()).unwrap_or("solfunmeme-hf-dataset");
-            parquet_validator::validate_parquet_dataset(dataset_path)?;
+            let dataset_path = args.get(2).cloned().unwrap_or_else(|| "solfunmeme-hf-dataset".to_string());
+            // Object store access drives its own runtime, so keep it off the async executor
+            tokio::task::spawn_blocking(move || parquet_validator::validate_parquet_dataset(&dataset_path, jobs))
+                .await
+                .map_err(|e| ValidationError::ProcessingError(format!("Parquet validation task failed: {}", e)))??;
+        }
+        Some("repl") => {
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset directory required".to_string()))?;
+            let repl = dataset_repl::DatasetRepl::open(Path::new(dataset_path))
+                .map_err(|e| ValidationError::InvalidInput(format!("{:#}", e)))?;
+            repl.run(std::io::stdin().lock(), std::io::stdout())
+                .map_err(|e| ValidationError::ProcessingError(format!("REPL failed: {}", e)))?;
+        }
+        Some("repair") => {
+            println!("Repairing dataset repository files...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset directory required".to_string()))?;
+            let repaired = repair_repository_files(Path::new(dataset_path))?;
+            if repaired.is_empty() {
+                println!("✅ Nothing to repair in {}", dataset_path);
+            } else {
+                println!("✅ Recreated {} in {}", repaired.join(", "), dataset_path);
+            }
+        }
+        Some("validate-hf-ready") => {
+            println!("Checking dataset is ready for the Hugging Face Hub...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset directory required".to_string()))?;
+            parquet_validator::validate_hf_ready(dataset_path)?;
+        }
+        Some("check-ids") => {
+            println!("Checking dataset for duplicate ids...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset directory required".to_string()))?;
+            parquet_validator::check_dataset_ids(dataset_path)?;
+        }
+        Some("check-processing-order") => {
+            println!("Checking processing_order within each phase...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset directory required".to_string()))?;
+            parquet_validator::check_processing_order(dataset_path)?;
+        }
+        Some("dataset-stats") => {
+            println!("Summarizing dataset files...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset directory required".to_string()))?;
+            partitioned_dataset::print_dataset_stats(dataset_path)?;
+        }
+        Some("check-timestamps") => {
+            println!("Checking record timestamps...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset directory required".to_string()))?;
+            let future_margin = parse_flag_value(&args, "--future-margin")?.unwrap_or(parquet_validator::DEFAULT_TIMESTAMP_FUTURE_MARGIN_SECS);
+            parquet_validator::check_timestamps(dataset_path, future_margin)?;
+        }
+        Some("check-list-offsets") => {
+            println!("Checking list column offsets...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset directory required".to_string()))?;
+            parquet_validator::check_dataset_list_offsets(dataset_path)?;
+        }
+        Some("validate-json") => {
+            println!("Validating JSON records against a schema...\n");
+            let dataset_path = args.get(2).filter(|arg| !arg.starts_with("--"))
+                .ok_or_else(|| ValidationError::InvalidInput("Dataset directory required".to_string()))?;
+            let schema_path = parse_flag_value::<String>(&args, "--schema")?
+                .ok_or_else(|| ValidationError::InvalidInput("--schema <schema.json> required".to_string()))?;
+            json_schema_validator::validate_json_against_schema(dataset_path, &schema_path)?;
+        }
+        Some("gen-fixture") => {
+            println!("Generating synthetic solfunmeme fixture...\n");
+            let output_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Output directory required".to_string()))?;
+            let count = solfunmeme_validator::generate_fixture(Path::new(output_path))?;
+            println!("✅ Wrote {} fixture terms to {}/terms", count, output_path);
         }
         Some("demo-dataset") => {
             println!("Demonstrating dataset loading...\n");
@@ -73,15 +248,21 @@ async fn main() -> Result<(), ValidationError> {
         Some("analyze-rust-project") => {
             println!("Analyzing Rust project with rust-analyzer...\n");
             let project_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Project path required".to_string()))?;
-            let output_path = args.get(3).map(|s| s.as_str()).unwrap_or("rust-analyzer-datasets");
-            analyze_rust_project(project_path, output_path)?;
+            let output_path = args.get(3).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("rust-analyzer-datasets");
+            analyze_rust_project(project_path, output_path, has_flag(&args, "--force"), has_flag(&args, "--fail-fast"))?;
         }
         Some("analyze-rust-phases") => {
             println!("Analyzing specific Rust processing phases...\n");
             let project_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Project path required".to_string()))?;
             let phases_str = args.get(3).map(|s| s.as_str()).unwrap_or("parsing,name_resolution,type_inference");
-            let output_path = args.get(4).map(|s| s.as_str()).unwrap_or("rust-analyzer-phase-datasets");
-            analyze_rust_phases(project_path, phases_str, output_path)?;
+            let output_path = args.get(4).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("rust-analyzer-phase-datasets");
+            analyze_rust_phases(project_path, phases_str, output_path, has_flag(&args, "--force"), has_flag(&args, "--fail-fast"))?;
+        }
+        Some("estimate") => {
+            println!("Estimating dataset size from a dry scan...\n");
+            let project_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Project path required".to_string()))?;
+            let phases_str = args.get(3).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("parsing,name_resolution,type_inference");
+            estimate_dataset_size(project_path, phases_str)?;
         }
         Some("validate-rust-analyzer-datasets") => {
             println!("Validating rust-analyzer generated datasets...\n");
@@ -91,21 +272,69 @@ async fn main() -> Result<(), ValidationError> {
         Some("generate-hf-dataset") => {
             println!("Generating HuggingFace dataset with Parquet files...\n");
             let project_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Project path required".to_string()))?;
-            let output_path = args.get(3).map(|s| s.as_str()).unwrap_or("rust-analyzer-hf-dataset");
-            generate_hf_dataset(project_path, output_path)?;
+            let output_path = args.get(3).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("rust-analyzer-hf-dataset");
+            let options = RustExtractionOptions::from_args(&args)?;
+            generate_hf_dataset(project_path, output_path, has_flag(&args, "--force"), &options)?;
+        }
+        Some("emit-pairs") => {
+            println!("Emitting training text pairs...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset path required".to_string()))?;
+            let mode_name = args.get(3).ok_or_else(|| ValidationError::InvalidInput("Pair mode required (snippet-type or signature-doc)".to_string()))?;
+            let output_path = args.get(4).ok_or_else(|| ValidationError::InvalidInput("Output path required".to_string()))?;
+            let mode = training_pairs::PairMode::from_name(mode_name)
+                .ok_or_else(|| ValidationError::InvalidInput(format!("Unknown pair mode: {} (expected snippet-type or signature-doc)", mode_name)))?;
+            let shuffle_seed = has_flag(&args, "--shuffle").then(|| seed.unwrap_or(config::DEFAULT_SEED));
+            let count = training_pairs::emit_pairs(Path::new(dataset_path), mode, Path::new(output_path), shuffle_seed)
+                .map_err(|e| ValidationError::ProcessingError(format!("Failed to emit pairs: {}", e)))?;
+            println!("✅ Wrote {} {} pairs to {}", count, mode_name, output_path);
+        }
+        Some("api-changes") => {
+            println!("Comparing public API between revisions...\n");
+            let repo_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Repository path required".to_string()))?;
+            let old_revision = args.get(3).ok_or_else(|| ValidationError::InvalidInput("Old revision required".to_string()))?;
+            let new_revision = args.get(4).ok_or_else(|| ValidationError::InvalidInput("New revision required".to_string()))?;
+            let output_path = args.get(5).map(|s| s.as_str()).unwrap_or("api_changes.json");
+            let changes = rust_analyzer_extractor::compare_api_at_revisions(Path::new(repo_path), old_revision, new_revision)
+                .map_err(|e| ValidationError::ProcessingError(format!("Failed to compare API: {:#}", e)))?;
+            std::fs::write(output_path, serde_json::to_string_pretty(&changes)?)
+                .map_err(|e| ValidationError::ProcessingError(format!("Failed to write {}: {}", output_path, e)))?;
+            println!("✅ {} added, {} removed public items; wrote {}", changes.added.len(), changes.removed.len(), output_path);
+        }
+        Some("json-to-parquet") => {
+            println!("Converting JSON phase datasets to Parquet...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset path required".to_string()))?;
+            convert_json_dataset_to_parquet(dataset_path)?;
+        }
+        Some("merge-phases-jsonl") => {
+            println!("Merging JSON phase datasets into one NDJSON file...\n");
+            let dataset_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Dataset path required".to_string()))?;
+            let output_path = args.get(3).ok_or_else(|| ValidationError::InvalidInput("Output .jsonl path required".to_string()))?;
+            merge_json_dataset_to_jsonl(dataset_path, output_path)?;
         }
         Some("analyze-cargo-project") => {
             println!("Analyzing Cargo project with cargo2hf...\n");
             let project_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Cargo project path required".to_string()))?;
-            let output_path = args.get(3).map(|s| s.as_str()).unwrap_or("cargo2hf-dataset");
+            let output_path = args.get(3).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("cargo2hf-dataset");
             let include_deps = args.get(4).map(|s| s == "true").unwrap_or(false);
-            analyze_cargo_project(project_path, output_path, include_deps).await?;
+            analyze_cargo_project(project_path, output_path, include_deps, has_flag(&args, "--force"), cargo_http_config(&args)?, &CargoExtractionOptions::from_args(&args)?).await?;
         }
         Some("analyze-cargo-ecosystem") => {
             println!("Analyzing Cargo ecosystem (project + dependencies)...\n");
             let project_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Cargo project path required".to_string()))?;
-            let output_path = args.get(3).map(|s| s.as_str()).unwrap_or("cargo-ecosystem-dataset");
-            analyze_cargo_project(project_path, output_path, true).await?; // Include dependencies
+            let output_path = args.get(3).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("cargo-ecosystem-dataset");
+            analyze_cargo_project(project_path, output_path, true, has_flag(&args, "--force"), cargo_http_config(&args)?, &CargoExtractionOptions::from_args(&args)?).await?; // Include dependencies
+        }
+        Some("ecosystem-summary") => {
+            println!("Aggregating cargo2hf datasets into ecosystem statistics...\n");
+            let root = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Root directory of cargo2hf datasets required".to_string()))?;
+            let top_n = parse_flag_value(&args, "--top")?.unwrap_or(20);
+            let summary = cargo2hf_extractor::summarize_ecosystem(Path::new(root))
+                .map_err(|e| ValidationError::ProcessingError(format!("Failed to aggregate datasets: {:#}", e)))?;
+            cargo2hf_extractor::print_ecosystem_summary(&summary, top_n);
+            let output_file = Path::new(root).join("ecosystem_summary.parquet");
+            cargo2hf_extractor::write_ecosystem_summary(&summary, &output_file)
+                .map_err(|e| ValidationError::ProcessingError(format!("Failed to write ecosystem summary: {}", e)))?;
+            println!("✅ Wrote {}", output_file.display());
         }
         Some("validate-cargo-dataset") => {
             println!("Validating cargo2hf generated dataset...\n");
@@ -115,15 +344,29 @@ async fn main() -> Result<(), ValidationError> {
         Some("analyze-llvm-ir") => {
             println!("Analyzing LLVM IR generation from Rust source...\n");
             let source_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Source path required".to_string()))?;
-            let output_path = args.get(3).map(|s| s.as_str()).unwrap_or("llvm-ir-dataset");
-            let opt_levels = args.get(4).map(|s| s.as_str()).unwrap_or("O0,O1,O2,O3");
-            analyze_llvm_ir(source_path, output_path, opt_levels)?;
+            let output_path = args.get(3).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("llvm-ir-dataset");
+            let opt_levels = args.get(4).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("O0,O1,O2,O3");
+            analyze_llvm_ir(source_path, output_path, opt_levels, has_flag(&args, "--force"))?;
+        }
+        Some("extract-llvm-compare") => {
+            println!("Comparing LLVM IR across O0 and O2...\n");
+            let source_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Source file required".to_string()))?;
+            let output_path = args.get(3).ok_or_else(|| ValidationError::InvalidInput("Output directory required".to_string()))?;
+            let records = llvm_ir_extractor::compare_opt_levels(Path::new(source_path))
+                .map_err(|e| ValidationError::ProcessingError(format!("Failed to compare opt levels: {}", e)))?;
+            llvm_ir_extractor::write_comparison_to_parquet(&records, Path::new(output_path))
+                .map_err(|e| ValidationError::ProcessingError(format!("Failed to write comparison dataset: {}", e)))?;
+            for record in &records {
+                println!("  {}: {} → {} instructions ({:.0}% reduction)",
+                    record.construct_name, record.o0_instructions, record.o2_instructions, record.reduction_ratio * 100.0);
+            }
+            println!("✅ Wrote {} joined functions to {}/data.parquet", records.len(), output_path);
         }
         Some("analyze-rust-to-ir") => {
             println!("Comprehensive Rust → LLVM IR analysis...\n");
             let source_path = args.get(2).ok_or_else(|| ValidationError::InvalidInput("Source path required".to_string()))?;
-            let output_path = args.get(3).map(|s| s.as_str()).unwrap_or("rust-to-ir-dataset");
-            analyze_rust_to_ir_pipeline(source_path, output_path).await?;
+            let output_path = args.get(3).map(|s| s.as_str()).filter(|s| !s.starts_with("--")).unwrap_or("rust-to-ir-dataset");
+            analyze_rust_to_ir_pipeline(source_path, output_path, has_flag(&args, "--force")).await?;
         }
         Some("validate-llvm-dataset") => {
             println!("Validating LLVM IR analysis dataset...\n");
@@ -151,19 +394,67 @@ async fn main() -> Result<(), ValidationError> {
             println!("📋 CORE ANALYSIS COMMANDS:");
             println!("  analyze-rust-to-ir <source> [output]           - Complete pipeline analysis (semantic + project + LLVM IR)");
             println!("  generate-hf-dataset <source> [output]          - Rust semantic analysis (parsing, name resolution, type inference)");
-            println!("  analyze-cargo-project <source> [output] [deps] - Project structure analysis (Cargo metadata and dependencies)");
+            println!("  analyze-cargo-project <source> [output] [deps] - Project structure analysis (Cargo metadata and dependencies; <source> may be a single-file .rs package)");
             println!("  analyze-llvm-ir <source> [output] [opt_levels]  - LLVM IR generation analysis (across O0, O1, O2, O3)");
+            println!("  extract-llvm-compare <file> <output>           - Per-function O0 vs O2 instruction counts (needs rustc)");
+            println!("  (add --force to allow an output directory inside the source project,");
+            println!("   --columns a,b,c to generate-hf-dataset/analyze-cargo-project to write only those columns,");
+            println!("   --offline to analyze-cargo-project to skip crates.io/GitHub lookups,");
+            println!("   --ecosystem-cache <dir> to analyze-cargo-project to cache fetched metrics per package and resume interrupted runs,");
+            println!("   --request-interval-ms N to analyze-cargo-project to wait N ms between crates.io/GitHub requests (default 1000),");
+            println!("   --overview to analyze-cargo-project to add a one-row overview-phase (LOC, deps, license, stars, commits, health score),");
+            println!("   --phase-schemas to generate-hf-dataset to write only the columns each phase fills,");
+            println!("   --diff-against <prev_dir> to generate-hf-dataset to also write changed records to delta/,");
+            println!("   --max-file-bytes N [--truncate-large-files] to generate-hf-dataset to skip (or truncate) huge files,");
+            println!("   --element-types a,b / --exclude-element-types a,b to generate-hf-dataset to keep/drop records by element_type,");
+            println!("   --include 'src/**/*.rs' / --exclude '**/tests/*.rs' to generate-hf-dataset to select files by comma-separated globs (relative to <source>),");
+            println!("   --compression snappy|zstd|none [--compression-level N] to generate-hf-dataset to pick the Parquet codec (level 1-22 for zstd),");
+            println!("   --dictionary-columns <col,...>|none to generate-hf-dataset to restrict dictionary encoding to these columns (default: all),");
+            println!("   --id-template '{{path}}:{{line}}:{{col}}:{{phase}}:{{order}}' to generate-hf-dataset to change the record id format,");
+            println!("   --concurrent-phase-writes <MB> to generate-hf-dataset to write phases on background threads (MB of records held at most),");
+            println!("   --partition-by phase|source-dir|none to generate-hf-dataset to lay out files per phase, src_dir={{dir}}/{{phase}}-phase/ or flat,");
+            println!("   --emit-coverage to generate-hf-dataset to write line_coverage.json (source line -> record ids),");
+            println!("   --with-blame to generate-hf-dataset to add the git blame author/timestamp of each record's line (slow),");
+            println!("   --max-duration <secs> / --max-memory <MB> to generate-hf-dataset to stop with checkpoint.json when exceeded,");
+            println!("   --min-records N to generate-hf-dataset to fail when fewer records are produced across all phases,");
+            println!("   --git-rev <rev> to generate-hf-dataset to analyze a commit of the source repository without checking it out,");
+            println!("   --fail-fast to abort on the first unreadable or unparseable file instead of skipping it)");
             println!();
             println!("🔍 VALIDATION COMMANDS:");
             println!("  validate-hf-dataset [dataset_dir]              - Validate semantic analysis dataset");
             println!("  validate-cargo-dataset [dataset_dir]           - Validate cargo analysis dataset");
+            println!("  ecosystem-summary <root> [--top N]             - Aggregate per-crate cargo2hf datasets into ecosystem_summary.parquet");
             println!("  validate-llvm-dataset [dataset_dir]            - Validate LLVM IR analysis dataset");
+            println!("  json-to-parquet <dataset_dir>                  - Convert {{phase}}-phase/data.json datasets to Parquet");
+            println!("  merge-phases-jsonl <dataset_dir> <out.jsonl>   - Merge {{phase}}-phase/data.json files into one NDJSON stream");
+            println!("  api-changes <repo> <old-rev> <new-rev> [out]   - Public items added/removed between revisions (default api_changes.json)");
+            println!("  estimate <project> [phases]                    - Project per-phase and total Parquet size from a dry scan");
+            println!("  emit-pairs <dataset_dir> <mode> <out>          - Training pairs (snippet-type, signature-doc) as JSONL or .parquet");
+            println!("  (add --shuffle [--seed N] to emit-pairs, or --seed N to create-sample, for reproducible random order/sampling)");
+            println!("  (add --jobs N to any command to cap parallel work, default: available CPUs; --jobs 1 runs serially)");
             println!();
             println!("🛠️ UTILITY COMMANDS:");
             println!("  test-mock                                       - Test with mock data");
             println!("  benchmark                                       - Run performance benchmarks");
-            println!("  create-hf-dataset [dir]                        - Create Hugging Face dataset with Parquet files");
+            println!("  create-hf-dataset [dir] [--strict] [--min-split N] - Create Hugging Face dataset with Parquet files");
             println!("  validate-parquet [dir]                         - Validate Hugging Face Parquet dataset");
+            println!("  validate-parquet s3://bucket/prefix            - Validate Parquet dataset in S3/GCS (reads footers only)");
+            println!("  gen-fixture <dir>                              - Write a small deterministic solfunmeme-style dataset for tests");
+            println!("  repl <dir>                                     - Explore a Parquet dataset (count, schema, head N, filter c=v, stats c)");
+            println!("  repair <dir>                                   - Recreate missing README.md/.gitattributes/.gitignore of a dataset");
+            println!("  validate-hf-ready <dir>                        - Warn about Parquet files that Git LFS would not track");
+            println!("  check-ids <dir>                                - Report duplicate ids across all Parquet files of a dataset");
+            println!("  check-list-offsets <dir>                       - Report list columns whose offsets are decreasing or miss values");
+            println!("  validate-json <dir> --schema <schema.json>     - Validate every .json/.jsonl record against a JSON Schema");
+            println!("  check-processing-order <dir>                   - Report duplicate or decreasing processing_order values per phase");
+            println!("  dataset-stats <dir>                            - Rows per phase, split and partition, plus schema drift between files");
+            println!("  check-timestamps <dir> [--future-margin <secs>] - Report zero or future timestamp values (default margin: 1 day)");
+            println!("  emit-pyarrow-schema <record-type>              - Print a pyarrow.schema([...]) snippet (rust-analyzer, cargo, llvm-ir, index-term)");
+            println!("  validate-solfunmeme-export <dir>               - Check an exported solfunmeme dataset for duplicate ids and mismatched character groups");
+            println!("  validate-url <url>                             - Validate a huggingface.co/datasets/... or hf://datasets/... link");
+            println!("  compare-access <url>...                        - Diff mock vs Hugging Face validation results (+cap = only on the Hub)");
+            println!("  card-stats <base_path> [--top N]               - Print dataset card statistics (size category, splits, top groups)");
+            println!("  validate-embeddings [--require-normalized] [--tolerance X] - Check embeddings are finite (and unit-normalized)");
             println!();
             println!("💡 EXAMPLES:");
             println!("  # Analyze rust-analyzer (533K records)");
@@ -207,6 +498,101 @@ async fn main() -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Check whether a `--flag` style switch was passed on the command line
+fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|arg| arg == flag)
+}
+
+/// Ecosystem fetch settings from `--offline`, `--ecosystem-cache <dir>` and `--request-interval-ms <ms>`
+fn cargo_http_config(args: &[String]) -> Result<cargo2hf_extractor::HttpClientConfig, ValidationError> {
+    let defaults = cargo2hf_extractor::HttpClientConfig::default();
+    Ok(cargo2hf_extractor::HttpClientConfig {
+        offline: has_flag(args, "--offline"),
+        cache_dir: parse_flag_value::<String>(args, "--ecosystem-cache")?.map(std::path::PathBuf::from),
+        min_request_interval: parse_flag_value::<u64>(args, "--request-interval-ms")?
+            .map_or(defaults.min_request_interval, std::time::Duration::from_millis),
+        ..defaults
+    })
+}
+
+/// `--jobs <n>`, defaulting to the available parallelism
+fn parse_jobs(args: &[String]) -> Result<usize, ValidationError> {
+    match parse_flag_value::<usize>(args, "--jobs")? {
+        Some(0) => Err(ValidationError::InvalidInput("--jobs must be at least 1".to_string())),
+        Some(jobs) => Ok(jobs),
+        None => Ok(config::default_jobs()),
+    }
+}
+
+/// `--seed <u64>`, shared by every randomized step of a run
+fn parse_seed(args: &[String]) -> Result<Option<u64>, ValidationError> {
+    parse_flag_value::<u64>(args, "--seed")
+}
+
+/// `--max-duration <secs>` and `--max-memory <MB>` bounds for a whole run
+fn parse_run_limits(args: &[String]) -> Result<config::RunLimits, ValidationError> {
+    let max_duration = match parse_flag_value::<f64>(args, "--max-duration")? {
+        Some(seconds) if !(seconds.is_finite() && seconds > 0.0) => {
+            return Err(ValidationError::InvalidInput("--max-duration must be a positive number of seconds".to_string()));
+        }
+        seconds => seconds.map(std::time::Duration::from_secs_f64),
+    };
+    let max_memory_bytes = parse_flag_value::<u64>(args, "--max-memory")?.map(|mb| mb.saturating_mul(1024 * 1024));
+    Ok(config::RunLimits { max_duration, max_memory_bytes })
+}
+
+/// Parse the value following a `--flag value` style option
+fn parse_flag_value<T: std::str::FromStr>(args: &[String], flag: &str) -> Result<Option<T>, ValidationError> {
+    match args.iter().position(|arg| arg == flag) {
+        Some(pos) => {
+            let raw = args.get(pos + 1)
+                .ok_or_else(|| ValidationError::InvalidInput(format!("{} requires a value", flag)))?;
+            raw.parse::<T>()
+                .map(Some)
+                .map_err(|_| ValidationError::InvalidInput(format!("Invalid value for {}: {}", flag, raw)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Parse a `--columns a,b,c` option into a list of column names
+fn parse_column_list(args: &[String]) -> Result<Option<Vec<String>>, ValidationError> {
+    parse_list_flag(args, "--columns")
+}
+
+/// Parse a comma-separated list given as `<flag> a,b,c`
+fn parse_list_flag(args: &[String], flag: &str) -> Result<Option<Vec<String>>, ValidationError> {
+    Ok(parse_flag_value::<String>(args, flag)?.map(|list| {
+        list.split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect()
+    }))
+}
+
+/// Record types whose Arrow schema `emit-pyarrow-schema` can print
+const RECORD_TYPES: [&str; 4] = ["rust-analyzer", "cargo", "llvm-ir", "index-term"];
+
+/// Arrow schema written for a record type (see `RECORD_TYPES`)
+fn record_type_schema(record_type: &str) -> Option<std::sync::Arc<arrow::datatypes::Schema>> {
+    match record_type {
+        "rust-analyzer" => Some(RustAnalyzerExtractor::parquet_schema()),
+        "cargo" => Some(cargo2hf_extractor::Cargo2HfExtractor::parquet_schema()),
+        "llvm-ir" => Some(llvm_ir_extractor::LLVMIRExtractor::parquet_schema()),
+        "index-term" => Some(hf_dataset_converter::index_term_schema()),
+        _ => None,
+    }
+}
+
+/// `pyarrow.schema([...])` snippet for a record type
+fn pyarrow_schema_for(record_type: &str) -> Result<String, ValidationError> {
+    let schema = record_type_schema(record_type).ok_or_else(|| ValidationError::InvalidInput(format!(
+        "Unknown record type: {} (expected one of {})", record_type, RECORD_TYPES.join(", ")
+    )))?;
+    dataset_io::pyarrow_schema_snippet(&schema)
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to build pyarrow schema: {}", e)))
+}
+
 fn test_mock_dataset() -> Result<(), ValidationError> {
     println!("=== Mock Dataset Validation Tests ===\n");
     
@@ -357,6 +743,29 @@ fn run_benchmarks() -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Validate a dataset (or config/split) given as a Hugging Face URL via the dataset viewer API
+async fn validate_dataset_url(url: &str) -> Result<(), ValidationError> {
+    let entity = EntityIdentifier::from_url(url)?;
+    let level = entity.infer_level();
+    println!("🔗 {} → {} ({:?} level)", url, entity, level);
+
+    // HubDataAccess is blocking, so keep it off the async runtime threads
+    let (result, progress) = tokio::task::spawn_blocking(move || {
+        let data_access = hub_data_access::HubDataAccess::new(hub_data_access::DEFAULT_DATASETS_SERVER_URL)?;
+        DatasetValidator::new(data_access).validate(&entity, level)
+    })
+    .await
+    .map_err(|e| ValidationError::ProcessingError(format!("Validation task failed: {}", e)))??;
+
+    println!("  Viewer:     {}", result.viewer);
+    println!("  Preview:    {}", result.preview);
+    println!("  Search:     {}", result.search);
+    println!("  Filter:     {}", result.filter);
+    println!("  Statistics: {}", result.statistics);
+    println!("  Progress:   {:.1}%", progress * 100.0);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -384,6 +793,65 @@ mod tests {
         assert_eq!(entity.to_string(), "test/config/split");
     }
 
+    #[test]
+    fn test_entity_from_url() {
+        let entity = EntityIdentifier::from_url("https://huggingface.co/datasets/introspector/rust").unwrap();
+        assert_eq!(entity, EntityIdentifier::new_dataset("introspector/rust".to_string()));
+        assert_eq!(entity.infer_level(), ValidationLevel::Dataset);
+
+        let entity = EntityIdentifier::from_url("https://huggingface.co/datasets/org/name/viewer/default/train?row=10").unwrap();
+        assert_eq!(entity, EntityIdentifier::new_split("org/name".to_string(), "default".to_string(), "train".to_string()));
+        assert_eq!(entity.infer_level(), ValidationLevel::Split);
+
+        let entity = EntityIdentifier::from_url("hf://datasets/org/name@main/viewer/extra").unwrap();
+        assert_eq!(entity, EntityIdentifier::new_config("org/name".to_string(), "extra".to_string()));
+
+        assert!(EntityIdentifier::from_url("https://huggingface.co/models/org/name").is_err());
+        assert!(EntityIdentifier::from_url("hf://datasets/").is_err());
+    }
+
+    #[test]
+    fn test_compare_access() {
+        use std::collections::HashMap;
+        use validator::{CachedResponse, DataAccess, ParquetMetadata};
+
+        // Same as the mock, except no column is considered searchable
+        struct NoSearchAccess(MockDataAccess);
+        impl DataAccess for NoSearchAccess {
+            fn check_successful_response(&self, kind: &str, entity: &EntityIdentifier) -> Result<bool, ValidationError> {
+                self.0.check_successful_response(kind, entity)
+            }
+            fn get_parquet_metadata(&self, dataset: &str, config: &str) -> Result<ParquetMetadata, ValidationError> {
+                self.0.get_parquet_metadata(dataset, config)
+            }
+            fn get_split_names(&self, dataset: &str, config: &str) -> Result<Vec<String>, ValidationError> {
+                self.0.get_split_names(dataset, config)
+            }
+            fn get_config_names(&self, dataset: &str) -> Result<Vec<String>, ValidationError> {
+                self.0.get_config_names(dataset)
+            }
+            fn get_cached_validation(&self, kind: &str, entity: &EntityIdentifier) -> Result<CachedResponse, ValidationError> {
+                self.0.get_cached_validation(kind, entity)
+            }
+            fn has_indexable_columns(&self, _features: &HashMap<String, String>) -> bool {
+                false
+            }
+        }
+
+        let entities = vec![
+            EntityIdentifier::new_split("mock/dataset".to_string(), "default".to_string(), "train".to_string()),
+            EntityIdentifier::new_config("mock/dataset".to_string(), "default".to_string()),
+        ];
+
+        let report = compare_access(MockDataAccess::default(), NoSearchAccess(MockDataAccess::default()), &entities);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].entity, entities[0]);
+        assert_eq!(report[0].differences, vec!["-search".to_string()]);
+
+        let report = compare_access(NoSearchAccess(MockDataAccess::default()), MockDataAccess::default(), &entities);
+        assert_eq!(report[0].differences, vec!["+search".to_string()]);
+    }
+
     #[test]
     fn test_validation_result() {
         let mut result1 = validator::ValidationResult {
@@ -392,6 +860,7 @@ mod tests {
             search: true,
             filter: false,
             statistics: true,
+            schema_fingerprint: None,
         };
         
         let result2 = validator::ValidationResult {
@@ -400,6 +869,7 @@ mod tests {
             search: false,
             filter: true,
             statistics: false,
+            schema_fingerprint: Some("abc".to_string()),
         };
         
         result1.merge(&result2);
@@ -410,11 +880,188 @@ mod tests {
         assert!(result1.filter);
         assert!(result1.statistics);
         assert_eq!(result1.capability_count(), 5);
+        assert_eq!(result1.schema_fingerprint.as_deref(), Some("abc"));
+    }
+
+    #[test]
+    fn test_emit_pyarrow_schema_stdout_is_only_the_snippet() {
+        use std::io::Write;
+
+        let mut stdout = Vec::new();
+        print_banner(&mut stdout, Some("emit-pyarrow-schema")).unwrap();
+        write!(stdout, "{}", pyarrow_schema_for("cargo").unwrap()).unwrap();
+        let stdout = String::from_utf8(stdout).unwrap();
+        assert!(stdout.starts_with("import pyarrow as pa\n"), "{}", stdout);
+        assert!(stdout.contains("\nschema = pa.schema(["), "{}", stdout);
+
+        // Other commands keep the banner
+        let mut stdout = Vec::new();
+        print_banner(&mut stdout, Some("validate-parquet")).unwrap();
+        assert!(String::from_utf8(stdout).unwrap().starts_with("🚀"));
+    }
+
+    #[test]
+    fn test_pyarrow_schema_snippet() {
+        let snippet = pyarrow_schema_for("rust-analyzer").unwrap();
+        assert!(snippet.starts_with("import pyarrow as pa"));
+        assert!(snippet.contains(r#"pa.field("line", pa.uint32(), nullable=False)"#), "{}", snippet);
+        assert!(snippet.contains(r#"pa.field("id", pa.string(), nullable=False)"#), "{}", snippet);
+        assert!(snippet.contains(r#"pa.field("element_name", pa.string(), nullable=True)"#), "{}", snippet);
+
+        let index_terms = pyarrow_schema_for("index-term").unwrap();
+        assert!(index_terms.contains(r#"pa.field("semantic_names", pa.list_(pa.field("item", pa.string(), nullable=True)), nullable=True)"#), "{}", index_terms);
+
+        for record_type in RECORD_TYPES {
+            assert!(pyarrow_schema_for(record_type).is_ok(), "{}", record_type);
+        }
+        assert!(pyarrow_schema_for("unknown").is_err());
+    }
+
+    #[test]
+    fn test_schema_fingerprint() {
+        use validator::DataAccess;
+
+        let metadata = |features: &[(&str, &str)]| validator::ParquetMetadata::new(
+            features.iter().map(|(name, data_type)| (name.to_string(), data_type.to_string())).collect()
+        );
+        let original = metadata(&[("id", "string"), ("count", "int32"), ("term", "string")]);
+        let reordered = metadata(&[("term", "string"), ("id", "string"), ("count", "int32")]);
+        let retyped = metadata(&[("id", "string"), ("count", "int64"), ("term", "string")]);
+
+        assert_eq!(original.schema_fingerprint(), reordered.schema_fingerprint());
+        assert_ne!(original.schema_fingerprint(), retyped.schema_fingerprint());
+        assert_eq!(original.schema_fingerprint().len(), 16);
+
+        // Surfaced in split validation results
+        let (result, _) = validate_split("mock/dataset", "default", "train", MockDataAccess::default()).unwrap();
+        let service = MockDataAccess::default();
+        let expected = service.get_parquet_metadata("mock/dataset", "default").unwrap().schema_fingerprint();
+        assert_eq!(result.schema_fingerprint, Some(expected));
+    }
+
+    #[test]
+    fn test_fingerprint_strings_is_stable() {
+        let strings = |entries: &[&str]| entries.iter().map(|entry| entry.to_string()).collect::<Vec<_>>();
+
+        // Pinned so cache files stay valid across builds and Rust versions
+        assert_eq!(validator::fingerprint_strings(&strings(&["a", "b"])), "41c80da72d0aec94");
+        assert_ne!(validator::fingerprint_strings(&strings(&["a", "b"])), validator::fingerprint_strings(&strings(&["ab"])));
+    }
+
+    #[test]
+    fn test_validation_cache() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let cache_path = temp_dir.path().join("validation-cache.json");
+        let service = MockDataAccess::default();
+        let entity = EntityIdentifier::new_config("mock/dataset".to_string(), "default".to_string());
+
+        let validator = DatasetValidator::new(service.clone()).with_cache(&cache_path);
+        let first = validator.validate(&entity, ValidationLevel::Config).unwrap();
+        let calls_after_first = service.call_count();
+        assert!(calls_after_first > 0);
+        assert!(cache_path.exists());
+
+        // Unchanged data: a fresh validator answers from the cache file
+        let validator = DatasetValidator::new(service.clone()).with_cache(&cache_path);
+        let second = validator.validate(&entity, ValidationLevel::Config).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(service.call_count(), calls_after_first);
+
+        // Different data invalidates the cache
+        let validator = DatasetValidator::new(MockDataAccess::new()).with_cache(&cache_path);
+        assert!(validator.validate(&entity, ValidationLevel::Config).is_err());
+    }
+
+    #[test]
+    fn test_repair_repository_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src/main.rs"), "fn main() {}").unwrap();
+        std::fs::write(project.join("Cargo.toml"), "[package]\nname = \"project\"\nversion = \"0.1.0\"\n").unwrap();
+
+        let dataset = temp_dir.path().join("dataset");
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        extractor.process_codebase_to_parquet(&project.join("src"), &[ProcessingPhase::Parsing], &dataset).unwrap();
+        let parquet_file = dataset.join("parsing-phase/data.parquet");
+        let parquet_before = std::fs::read(&parquet_file).unwrap();
+
+        let repaired = repair_repository_files(&dataset).unwrap();
+        assert_eq!(repaired, vec!["README.md", ".gitattributes", ".gitignore"]);
+        let gitattributes = std::fs::read_to_string(dataset.join(".gitattributes")).unwrap();
+        assert!(gitattributes.lines().any(|line| line == "*.parquet filter=lfs diff=lfs merge=lfs -text"));
+        let readme = std::fs::read_to_string(dataset.join("README.md")).unwrap();
+        // Every record is under src/, but the source project is the package root
+        assert!(readme.contains(&format!("**Source Project**: {}", project.display())));
+        assert_eq!(std::fs::read(&parquet_file).unwrap(), parquet_before);
+
+        // A custom README is kept; an .gitattributes without LFS rules is replaced
+        std::fs::write(dataset.join("README.md"), "custom").unwrap();
+        std::fs::write(dataset.join(".gitattributes"), "*.txt text\n").unwrap();
+        assert_eq!(repair_repository_files(&dataset).unwrap(), vec![".gitattributes"]);
+        assert_eq!(std::fs::read_to_string(dataset.join("README.md")).unwrap(), "custom");
+        assert!(repair_repository_files(&dataset).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate_partitioned_rust_analyzer_datasets() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src/main.rs"), "fn main() {}").unwrap();
+
+        for partition_by in [PartitionBy::SourceDir, PartitionBy::None] {
+            let dataset = temp_dir.path().join(format!("dataset-{:?}", partition_by));
+            RustAnalyzerExtractor::new().unwrap()
+                .with_partition_by(partition_by)
+                .process_codebase_to_parquet(&project, &[ProcessingPhase::Parsing], &dataset)
+                .unwrap();
+            validate_rust_analyzer_datasets(dataset.to_str().unwrap()).unwrap();
+        }
+        assert!(validate_rust_analyzer_datasets(temp_dir.path().join("project/src").to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_output_inside_project_requires_force() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let project = temp_dir.path().join("project");
+        std::fs::create_dir_all(project.join("src")).unwrap();
+        std::fs::write(project.join("src/main.rs"), "fn main() {}").unwrap();
+        let output = project.join("dataset");
+
+        let result = generate_hf_dataset(project.to_str().unwrap(), output.to_str().unwrap(), false, &RustExtractionOptions::default());
+        assert!(matches!(result, Err(ValidationError::InvalidInput(_))));
+        assert!(!output.exists());
+
+        // The reverse nesting is refused too, and --force skips the check
+        assert!(check_output_dir_safety(&project, temp_dir.path(), false).is_err());
+        assert!(check_output_dir_safety(&project, &output, true).is_ok());
+        assert!(check_output_dir_safety(&project, &temp_dir.path().join("elsewhere"), false).is_ok());
+    }
+
+    #[test]
+    fn test_include_exclude_flags_filter_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("src/handlers")).unwrap();
+        std::fs::write(temp_dir.path().join("src/lib.rs"), "pub fn lib() {}\n").unwrap();
+        std::fs::write(temp_dir.path().join("src/handlers/user.rs"), "pub fn user() {}\n").unwrap();
+        std::fs::write(temp_dir.path().join("src/handlers/user_test.rs"), "fn user_test() {}\n").unwrap();
+
+        let args: Vec<String> = ["hf-validator", "generate-hf-dataset", "src", "--include", "src/**/*.rs", "--exclude", "**/*_test.rs,src/lib.rs"]
+            .iter().map(|arg| arg.to_string()).collect();
+        let mut extractor = RustExtractionOptions::from_args(&args).unwrap().build_extractor().unwrap();
+        let records = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing]).unwrap();
+        assert!(!records.is_empty());
+        assert!(records.iter().all(|r| r.file_path.ends_with("handlers/user.rs")), "{:?}", records.iter().map(|r| &r.file_path).collect::<Vec<_>>());
+
+        let args: Vec<String> = ["hf-validator", "generate-hf-dataset", "src", "--include", "src/[.rs"]
+            .iter().map(|arg| arg.to_string()).collect();
+        assert!(matches!(RustExtractionOptions::from_args(&args).unwrap().build_extractor(), Err(ValidationError::InvalidInput(_))));
     }
 }
 
 /// Analyze a Rust project with all processing phases
-fn analyze_rust_project(project_path: &str, output_path: &str) -> Result<(), ValidationError> {
+fn analyze_rust_project(project_path: &str, output_path: &str, force: bool, fail_fast: bool) -> Result<(), ValidationError> {
     println!("🔍 Analyzing Rust project: {}", project_path);
     println!("📁 Output directory: {}", output_path);
     
@@ -422,10 +1069,12 @@ fn analyze_rust_project(project_path: &str, output_path: &str) -> Result<(), Val
     if !project_path.exists() {
         return Err(ValidationError::InvalidInput(format!("Project path does not exist: {}", project_path.display())));
     }
+    check_output_dir_safety(project_path, Path::new(output_path), force)?;
 
     // Create rust-analyzer extractor
     let mut extractor = RustAnalyzerExtractor::new()
-        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create rust-analyzer extractor: {}", e)))?;
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create rust-analyzer extractor: {}", e)))?
+        .with_fail_fast(fail_fast);
 
     // Define all phases to analyze
     let phases = vec![
@@ -446,13 +1095,37 @@ fn analyze_rust_project(project_path: &str, output_path: &str) -> Result<(), Val
 
     // Convert to HF dataset format
     create_rust_analyzer_hf_dataset(records, output_path)?;
+    extractor.write_manifest(Path::new(output_path))
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to write manifest: {}", e)))?;
 
     println!("🎉 Successfully created rust-analyzer datasets in: {}", output_path);
     Ok(())
 }
 
+/// Print the projected Parquet size of each phase and the total, without writing anything
+fn estimate_dataset_size(project_path: &str, phases_str: &str) -> Result<(), ValidationError> {
+    let project_path = Path::new(project_path);
+    if !project_path.exists() {
+        return Err(ValidationError::InvalidInput(format!("Project path does not exist: {}", project_path.display())));
+    }
+    let phases = parse_phases_string(phases_str)?;
+
+    let mut extractor = RustAnalyzerExtractor::new()
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create rust-analyzer extractor: {}", e)))?;
+    let estimate = extractor.estimate_dataset_size(project_path, &phases)
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to estimate dataset size: {}", e)))?;
+
+    let megabytes = |bytes: u64| bytes as f64 / (1024.0 * 1024.0);
+    println!("📊 {} Rust files", estimate.files);
+    for phase in &estimate.phases {
+        println!("  {:<16} {:>9} records × {:>6.0} B ≈ {:>8.2} MB", phase.phase, phase.candidate_records, phase.bytes_per_record, megabytes(phase.estimated_bytes));
+    }
+    println!("✅ Estimated total: {:.2} MB", megabytes(estimate.total_bytes));
+    Ok(())
+}
+
 /// Analyze specific Rust processing phases
-fn analyze_rust_phases(project_path: &str, phases_str: &str, output_path: &str) -> Result<(), ValidationError> {
+fn analyze_rust_phases(project_path: &str, phases_str: &str, output_path: &str, force: bool, fail_fast: bool) -> Result<(), ValidationError> {
     println!("🔍 Analyzing Rust project phases: {}", phases_str);
     println!("📁 Project path: {}", project_path);
     println!("📁 Output directory: {}", output_path);
@@ -461,6 +1134,7 @@ fn analyze_rust_phases(project_path: &str, phases_str: &str, output_path: &str)
     if !project_path.exists() {
         return Err(ValidationError::InvalidInput(format!("Project path does not exist: {}", project_path.display())));
     }
+    check_output_dir_safety(project_path, Path::new(output_path), force)?;
 
     // Parse phases from string
     let phases = parse_phases_string(phases_str)?;
@@ -468,7 +1142,8 @@ fn analyze_rust_phases(project_path: &str, phases_str: &str, output_path: &str)
 
     // Create rust-analyzer extractor
     let mut extractor = RustAnalyzerExtractor::new()
-        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create rust-analyzer extractor: {}", e)))?;
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create rust-analyzer extractor: {}", e)))?
+        .with_fail_fast(fail_fast);
 
     // Extract data from selected phases
     let records = extractor.process_codebase(project_path, &phases)
@@ -478,6 +1153,8 @@ fn analyze_rust_phases(project_path: &str, phases_str: &str, output_path: &str)
 
     // Convert to HF dataset format
     create_rust_analyzer_hf_dataset(records, output_path)?;
+    extractor.write_manifest(Path::new(output_path))
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to write manifest: {}", e)))?;
 
     println!("🎉 Successfully created phase-specific datasets in: {}", output_path);
     Ok(())
@@ -569,6 +1246,47 @@ fn create_rust_analyzer_hf_dataset(records: Vec<rust_analyzer_extractor::RustAna
     Ok(())
 }
 
+/// Merge a JSON phase dataset (as written by `create_rust_analyzer_hf_dataset`) into one NDJSON file
+fn merge_json_dataset_to_jsonl(dataset_path: &str, output_path: &str) -> Result<(), ValidationError> {
+    let dataset_dir = Path::new(dataset_path);
+    if !dataset_dir.exists() {
+        return Err(ValidationError::InvalidInput(format!("Dataset directory does not exist: {}", dataset_path)));
+    }
+
+    let merged = rust_analyzer_extractor::merge_json_phases_to_jsonl(dataset_dir, Path::new(output_path))
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to merge JSON datasets: {}", e)))?;
+
+    if merged == 0 {
+        println!("⚠️  No records found in {{phase}}-phase/data.json files");
+    }
+
+    println!("🎉 Merged {} records into: {}", merged, output_path);
+    Ok(())
+}
+
+/// Convert a JSON phase dataset (as written by `create_rust_analyzer_hf_dataset`) to Parquet
+fn convert_json_dataset_to_parquet(dataset_path: &str) -> Result<(), ValidationError> {
+    println!("🔄 Converting JSON phase datasets in: {}", dataset_path);
+
+    let dataset_dir = Path::new(dataset_path);
+    if !dataset_dir.exists() {
+        return Err(ValidationError::InvalidInput(format!("Dataset directory does not exist: {}", dataset_path)));
+    }
+
+    let extractor = RustAnalyzerExtractor::new()
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create rust-analyzer extractor: {}", e)))?;
+
+    let converted = extractor.convert_json_phases_to_parquet(dataset_dir)
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to convert JSON datasets: {}", e)))?;
+
+    if converted == 0 {
+        println!("⚠️  No records found in {{phase}}-phase/data.json files");
+    }
+
+    println!("🎉 Converted {} records to Parquet in: {}", converted, dataset_path);
+    Ok(())
+}
+
 /// Validate rust-analyzer generated datasets
 fn validate_rust_analyzer_datasets(dataset_path: &str) -> Result<(), ValidationError> {
     println!("🔍 Validating rust-analyzer datasets in: {}", dataset_path);
@@ -578,39 +1296,47 @@ fn validate_rust_analyzer_datasets(dataset_path: &str) -> Result<(), ValidationE
         return Err(ValidationError::InvalidInput(format!("Dataset directory does not exist: {}", dataset_path)));
     }
 
-    // Find all phase directories
-    let mut phase_dirs = Vec::new();
+    // Parquet parts in any layout: phase directories, src_dir= partitions or flat files
+    let dataset = PartitionedDataset::open(dataset_dir)?;
+    // JSON datasets keep one `{phase}-phase/data.json` per phase directory
+    let mut json_phase_dirs = Vec::new();
     for entry in std::fs::read_dir(dataset_dir)
         .map_err(|e| ValidationError::ProcessingError(format!("Failed to read dataset directory: {}", e)))? 
     {
         let entry = entry.map_err(|e| ValidationError::ProcessingError(format!("Failed to read directory entry: {}", e)))?;
         let path = entry.path();
         
-        if path.is_dir() && path.file_name().and_then(|n| n.to_str()).map_or(false, |n| n.ends_with("-phase")) {
-            phase_dirs.push(path);
+        if path.join("data.json").is_file() && path.file_name().and_then(|n| n.to_str()).map_or(false, |n| n.ends_with("-phase")) {
+            json_phase_dirs.push(path);
         }
     }
 
-    if phase_dirs.is_empty() {
-        return Err(ValidationError::InvalidInput("No phase directories found".to_string()));
+    if dataset.is_empty() && json_phase_dirs.is_empty() {
+        return Err(ValidationError::InvalidInput("No Parquet files or phase directories found".to_string()));
     }
 
-    println!("📊 Found {} phase directories to validate", phase_dirs.len());
+    // Parquet phases may use the full schema or the phase-specialized one
+    println!("📊 Found {} Parquet files across {} phases", dataset.parts().len(), dataset.phases().len());
+    for part in dataset.parts() {
+        match (part.schema(), part.phase.as_deref().and_then(ProcessingPhase::from_name)) {
+            (Ok(schema), Some(phase)) => match RustAnalyzerExtractor::check_phase_schema(&phase, &schema) {
+                Ok(()) => println!("    ✅ Schema OK: {}", part.relative_path),
+                Err(e) => println!("    ❌ {}: {}", part.relative_path, e),
+            },
+            (Ok(_), None) => println!("    ⚠️  Unknown phase, schema not checked: {}", part.relative_path),
+            (Err(e), _) => println!("    ❌ {}", e),
+        }
+    }
 
-    // Validate each phase directory
-    for phase_dir in phase_dirs {
+    // Validate each JSON phase directory
+    for phase_dir in json_phase_dirs {
         let phase_name = phase_dir.file_name().and_then(|n| n.to_str()).unwrap_or("unknown");
         println!("  🔍 Validating phase: {}", phase_name);
-        
+
         // Check for required files
         let data_file = phase_dir.join("data.json");
         let readme_file = phase_dir.join("README.md");
         
-        if !data_file.exists() {
-            println!("    ❌ Missing data.json file");
-            continue;
-        }
-        
         if !readme_file.exists() {
             println!("    ⚠️  Missing README.md file");
         }
@@ -648,8 +1374,163 @@ fn validate_rust_analyzer_datasets(dataset_path: &str) -> Result<(), ValidationE
     Ok(())
 }
 
+/// Extractor settings for `analyze-cargo-project`, parsed from command-line flags
+#[derive(Debug, Default)]
+struct CargoExtractionOptions {
+    fail_fast: bool,
+    columns: Option<Vec<String>>,
+    overview: bool,
+}
+
+impl CargoExtractionOptions {
+    fn from_args(args: &[String]) -> Result<Self, ValidationError> {
+        Ok(Self {
+            fail_fast: has_flag(args, "--fail-fast"),
+            columns: parse_column_list(args)?,
+            overview: has_flag(args, "--overview"),
+        })
+    }
+}
+
+/// Extractor settings for `generate-hf-dataset`, parsed from command-line flags
+#[derive(Debug, Default)]
+struct RustExtractionOptions {
+    fail_fast: bool,
+    phase_schemas: bool,
+    columns: Option<Vec<String>>,
+    diff_against: Option<String>,
+    max_file_bytes: Option<u64>,
+    truncate_large_files: bool,
+    git_rev: Option<String>,
+    min_records: Option<usize>,
+    element_types: Option<Vec<String>>,
+    excluded_element_types: Option<Vec<String>>,
+    include_globs: Option<Vec<String>>,
+    exclude_globs: Option<Vec<String>>,
+    compression: Option<String>,
+    compression_level: Option<i32>,
+    dictionary_columns: Option<Vec<String>>,
+    emit_coverage: bool,
+    with_blame: bool,
+    concurrent_phase_writes_mb: Option<u64>,
+    id_template: Option<String>,
+    jobs: usize,
+    run_limits: config::RunLimits,
+    partition_by: Option<String>,
+}
+
+impl RustExtractionOptions {
+    fn from_args(args: &[String]) -> Result<Self, ValidationError> {
+        Ok(Self {
+            fail_fast: has_flag(args, "--fail-fast"),
+            phase_schemas: has_flag(args, "--phase-schemas"),
+            columns: parse_column_list(args)?,
+            diff_against: parse_flag_value(args, "--diff-against")?,
+            max_file_bytes: parse_flag_value(args, "--max-file-bytes")?,
+            truncate_large_files: has_flag(args, "--truncate-large-files"),
+            git_rev: parse_flag_value(args, "--git-rev")?,
+            min_records: parse_flag_value(args, "--min-records")?,
+            element_types: parse_list_flag(args, "--element-types")?,
+            excluded_element_types: parse_list_flag(args, "--exclude-element-types")?,
+            include_globs: parse_list_flag(args, "--include")?,
+            exclude_globs: parse_list_flag(args, "--exclude")?,
+            compression: parse_flag_value(args, "--compression")?,
+            compression_level: parse_flag_value(args, "--compression-level")?,
+            dictionary_columns: parse_list_flag(args, "--dictionary-columns")?,
+            emit_coverage: has_flag(args, "--emit-coverage"),
+            with_blame: has_flag(args, "--with-blame"),
+            concurrent_phase_writes_mb: parse_flag_value(args, "--concurrent-phase-writes")?,
+            id_template: parse_flag_value(args, "--id-template")?,
+            jobs: parse_jobs(args)?,
+            run_limits: parse_run_limits(args)?,
+            partition_by: parse_flag_value(args, "--partition-by")?,
+        })
+    }
+
+    /// Create a rust-analyzer extractor configured with these options
+    fn build_extractor(&self) -> Result<RustAnalyzerExtractor, ValidationError> {
+        let mut extractor = RustAnalyzerExtractor::new()
+            .map_err(|e| ValidationError::ProcessingError(format!("Failed to create rust-analyzer extractor: {}", e)))?
+            .with_fail_fast(self.fail_fast)
+            .with_phase_schemas(self.phase_schemas)
+            .with_coverage(self.emit_coverage)
+            .with_blame(self.with_blame)
+            .with_jobs(self.jobs)
+            .with_run_limits(self.run_limits);
+        if let Some(columns) = &self.columns {
+            extractor = extractor.with_columns(columns.clone())
+                .map_err(|e| ValidationError::InvalidInput(format!("Invalid --columns: {}", e)))?;
+        }
+        if let Some(name) = &self.partition_by {
+            let partition_by = PartitionBy::from_name(name)
+                .ok_or_else(|| ValidationError::InvalidInput(format!("Unknown --partition-by: {} (expected phase, source-dir or none)", name)))?;
+            if partition_by == PartitionBy::None && self.diff_against.is_some() {
+                return Err(ValidationError::InvalidInput("--diff-against needs per-phase directories; use --partition-by phase or source-dir".to_string()));
+            }
+            extractor = extractor.with_partition_by(partition_by);
+        }
+        if let Some(previous_dir) = &self.diff_against {
+            if !Path::new(previous_dir).is_dir() {
+                return Err(ValidationError::InvalidInput(format!("--diff-against directory does not exist: {}", previous_dir)));
+            }
+            extractor = extractor.with_diff_against(previous_dir);
+        }
+        if let Some(max_file_bytes) = self.max_file_bytes {
+            let action = if self.truncate_large_files { OversizeAction::Truncate } else { OversizeAction::Skip };
+            extractor = extractor.with_max_file_bytes(max_file_bytes, action);
+        }
+        if let Some(min_records) = self.min_records {
+            extractor = extractor.with_min_records(min_records);
+        }
+        if let Some(memory_budget_mb) = self.concurrent_phase_writes_mb {
+            extractor = extractor.with_concurrent_phase_writes(memory_budget_mb.saturating_mul(1024 * 1024));
+        }
+        if let Some(id_template) = &self.id_template {
+            let id_template = RecordIdTemplate::parse(id_template)
+                .map_err(|e| ValidationError::InvalidInput(format!("Invalid --id-template: {}", e)))?;
+            extractor = extractor.with_id_template(id_template);
+        }
+        if let Some(element_types) = &self.element_types {
+            extractor = extractor.with_element_types(element_types.clone());
+        }
+        if let Some(element_types) = &self.excluded_element_types {
+            extractor = extractor.with_excluded_element_types(element_types.clone());
+        }
+        if self.include_globs.is_some() || self.exclude_globs.is_some() {
+            extractor = extractor.with_file_filter(
+                self.include_globs.as_deref().unwrap_or_default(),
+                self.exclude_globs.as_deref().unwrap_or_default(),
+            ).map_err(|e| ValidationError::InvalidInput(format!("Invalid --include/--exclude: {:#}", e)))?;
+        }
+        if self.compression.is_some() || self.compression_level.is_some() || self.dictionary_columns.is_some() {
+            let compression = match &self.compression {
+                Some(name) => ParquetCodec::from_name(name)
+                    .ok_or_else(|| ValidationError::InvalidInput(format!("Unknown --compression: {} (expected snappy, zstd or none)", name)))?,
+                None if self.compression_level.is_some() => ParquetCodec::Zstd,
+                None => ParquetCodec::default(),
+            };
+            let mut write_options = ParquetWriteOptions { compression, compression_level: self.compression_level, ..Default::default() };
+            if let Some(columns) = &self.dictionary_columns {
+                let schema = RustAnalyzerExtractor::parquet_schema();
+                let mut dictionary_columns = Vec::new();
+                for column in columns.iter().filter(|c| c.as_str() != "none") {
+                    if schema.field_with_name(column).is_err() {
+                        return Err(ValidationError::InvalidInput(format!("Unknown column in --dictionary-columns: {}", column)));
+                    }
+                    dictionary_columns.push(column.clone());
+                }
+                write_options.dictionary_columns = Some(dictionary_columns);
+            }
+            write_options.writer_properties()
+                .map_err(|e| ValidationError::InvalidInput(format!("Invalid --compression-level: {:#}", e)))?;
+            extractor = extractor.with_write_options(write_options);
+        }
+        Ok(extractor)
+    }
+}
+
 /// Generate HuggingFace dataset with Parquet files ready for Git LFS
-fn generate_hf_dataset(project_path: &str, output_path: &str) -> Result<(), ValidationError> {
+fn generate_hf_dataset(project_path: &str, output_path: &str, force: bool, options: &RustExtractionOptions) -> Result<(), ValidationError> {
     println!("🔍 Generating HuggingFace dataset from Rust project: {}", project_path);
     println!("📁 Output directory: {}", output_path);
     
@@ -657,10 +1538,14 @@ fn generate_hf_dataset(project_path: &str, output_path: &str) -> Result<(), Vali
     if !project_path.exists() {
         return Err(ValidationError::InvalidInput(format!("Project path does not exist: {}", project_path.display())));
     }
+    check_output_dir_safety(project_path, Path::new(output_path), force)?;
 
     // Create rust-analyzer extractor
-    let mut extractor = RustAnalyzerExtractor::new()
-        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create rust-analyzer extractor: {}", e)))?;
+    let mut extractor = options.build_extractor()?;
+    if let Some(revision) = &options.git_rev {
+        extractor = extractor.with_git_revision(project_path, revision)
+            .map_err(|e| ValidationError::InvalidInput(format!("Invalid --git-rev: {:#}", e)))?;
+    }
 
     // Define phases to analyze
     let phases = vec![
@@ -745,6 +1630,7 @@ Each record contains:
 - `type_data`: JSON-serialized type inference information
 - `source_snippet`: The actual source code being analyzed
 - `context_before`/`context_after`: Surrounding code context
+- `blame_author`, `blame_timestamp`: Last commit to touch the line (only with `--with-blame`)
 - `processing_time_ms`: Time taken for analysis
 - `rust_version`, `analyzer_version`: Tool versions used
 
@@ -866,12 +1752,77 @@ target/
     Ok(())
 }
 
+/// Source project of an existing dataset: the nearest directory with a
+/// `Cargo.toml` at or above the deepest directory containing every `file_path`
+/// of its first Parquet file (that common directory itself when none has one)
+fn infer_source_project(dataset_dir: &Path) -> Option<std::path::PathBuf> {
+    let first_parquet = walkdir::WalkDir::new(dataset_dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .find(|path| path.extension().and_then(|e| e.to_str()) == Some("parquet"))?;
+
+    let mut common: Option<std::path::PathBuf> = None;
+    for batch in dataset_io::read_parquet_batches(&first_parquet).ok()? {
+        let file_paths = dataset_io::typed_column::<arrow::array::StringArray>(&batch, "file_path").ok()?;
+        for file_path in file_paths.iter().flatten() {
+            let parent = Path::new(file_path).parent()?.to_path_buf();
+            common = Some(match common {
+                None => parent,
+                Some(current) => current.ancestors().find(|a| parent.starts_with(a))?.to_path_buf(),
+            });
+        }
+    }
+    let common = common?;
+    // Records usually all sit under `src/`; the project is the package around it
+    let project = common.ancestors().find(|dir| dir.join("Cargo.toml").is_file()).unwrap_or(&common);
+    Some(project.to_path_buf())
+}
+
+/// Recreate missing repository files (README.md, .gitattributes, .gitignore) of an existing dataset
+///
+/// Files are generated by `create_repository_files` in a scratch directory and
+/// only the missing ones are copied over; existing files and Parquet data are
+/// left untouched. A `.gitattributes` that does not LFS-track `*.parquet` counts
+/// as missing. Returns the names of the files written.
+fn repair_repository_files(dataset_dir: &Path) -> Result<Vec<String>, ValidationError> {
+    if !dataset_dir.is_dir() {
+        return Err(ValidationError::InvalidInput(format!("Dataset directory does not exist: {}", dataset_dir.display())));
+    }
+
+    let needs_repair = |name: &str| {
+        let path = dataset_dir.join(name);
+        match name {
+            ".gitattributes" => std::fs::read_to_string(&path)
+                .map(|content| !content.lines().any(|line| line.starts_with("*.parquet") && line.contains("filter=lfs")))
+                .unwrap_or(true),
+            _ => !path.exists(),
+        }
+    };
+    let missing: Vec<&str> = ["README.md", ".gitattributes", ".gitignore"].into_iter().filter(|name| needs_repair(name)).collect();
+    if missing.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let source_project = infer_source_project(dataset_dir).unwrap_or_else(|| dataset_dir.to_path_buf());
+    let scratch_dir = tempfile::TempDir::new()
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create scratch directory: {}", e)))?;
+    create_repository_files(scratch_dir.path(), &source_project)?;
+
+    for name in &missing {
+        std::fs::copy(scratch_dir.path().join(name), dataset_dir.join(name))
+            .map_err(|e| ValidationError::ProcessingError(format!("Failed to write {}: {}", name, e)))?;
+    }
+    Ok(missing.into_iter().map(String::from).collect())
+}
+
 /// Analyze a Cargo project and generate HuggingFace dataset
 /// 
 /// This function uses the cargo2hf extractor to analyze a Cargo project
 /// and generate comprehensive datasets including project metadata,
 /// dependency analysis, source code metrics, and ecosystem information.
-async fn analyze_cargo_project(project_path: &str, output_path: &str, include_dependencies: bool) -> Result<(), ValidationError> {
+async fn analyze_cargo_project(project_path: &str, output_path: &str, include_dependencies: bool, force: bool, http_config: cargo2hf_extractor::HttpClientConfig, options: &CargoExtractionOptions) -> Result<(), ValidationError> {
     use cargo2hf_extractor::{Cargo2HfExtractor, CargoExtractionPhase};
     
     let project_path = Path::new(project_path);
@@ -882,10 +1833,12 @@ async fn analyze_cargo_project(project_path: &str, output_path: &str, include_de
         return Err(ValidationError::InvalidInput(format!("Project path does not exist: {}", project_path.display())));
     }
     
+    // A single-file package (cargo-script) carries its manifest in the `.rs` file
     let cargo_toml = project_path.join("Cargo.toml");
-    if !cargo_toml.exists() {
+    if !cargo_toml.exists() && !Cargo2HfExtractor::is_single_file_package(project_path) {
         return Err(ValidationError::InvalidInput(format!("No Cargo.toml found in: {}", project_path.display())));
     }
+    check_output_dir_safety(project_path, output_path, force)?;
     
     println!("🔍 Analyzing Cargo project: {}", project_path.display());
     println!("📊 Output directory: {}", output_path.display());
@@ -893,10 +1846,17 @@ async fn analyze_cargo_project(project_path: &str, output_path: &str, include_de
     
     // Create extractor
     let mut extractor = Cargo2HfExtractor::new()
-        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create extractor: {}", e)))?;
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to create extractor: {}", e)))?
+        .with_fail_fast(options.fail_fast)
+        .with_http_config(http_config)
+        .map_err(|e| ValidationError::ProcessingError(format!("Failed to configure HTTP client: {}", e)))?;
+    if let Some(columns) = options.columns.clone() {
+        extractor = extractor.with_columns(columns)
+            .map_err(|e| ValidationError::InvalidInput(format!("Invalid --columns: {}", e)))?;
+    }
     
     // Define extraction phases
-    let phases = vec![
+    let mut phases = vec![
         CargoExtractionPhase::ProjectMetadata,
         CargoExtractionPhase::DependencyAnalysis,
         CargoExtractionPhase::SourceCodeAnalysis,
@@ -904,6 +1864,9 @@ async fn analyze_cargo_project(project_path: &str, output_path: &str, include_de
         CargoExtractionPhase::EcosystemAnalysis,
         CargoExtractionPhase::VersionHistory,
     ];
+    if options.overview {
+        phases.push(CargoExtractionPhase::Overview);
+    }
     
     // Extract project data
     extractor.extract_project_to_parquet(project_path, &phases, output_path, include_dependencies)
@@ -941,45 +1904,38 @@ fn validate_cargo_dataset(dataset_path: &str) -> Result<(), ValidationError> {
         "version_history-phase",
     ];
     
+    let dataset = PartitionedDataset::open(dataset_path)?;
     let mut found_phases = 0;
     let mut total_records = 0;
     let mut total_size_mb = 0.0;
     
     for phase in &expected_phases {
-        let phase_dir = dataset_path.join(phase);
-        if phase_dir.exists() {
-            found_phases += 1;
-            println!("✅ Found phase: {}", phase);
-            
-            // Count Parquet files and estimate records
-            for entry in std::fs::read_dir(&phase_dir)
-                .map_err(|e| ValidationError::ProcessingError(format!("Failed to read phase directory: {}", e)))? 
-            {
-                let entry = entry.map_err(|e| ValidationError::ProcessingError(format!("Failed to read directory entry: {}", e)))?;
-                let path = entry.path();
-                
-                if path.extension().and_then(|s| s.to_str()) == Some("parquet") {
-                    let metadata = std::fs::metadata(&path)
-                        .map_err(|e| ValidationError::ProcessingError(format!("Failed to read file metadata: {}", e)))?;
-                    let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
-                    total_size_mb += size_mb;
-                    
-                    // Estimate records (rough approximation)
-                    let estimated_records = (size_mb * 1000.0) as u32; // Very rough estimate
-                    total_records += estimated_records;
-                    
-                    println!("  📄 {}: {:.2} MB (~{} records)", path.file_name().unwrap().to_string_lossy(), size_mb, estimated_records);
-                }
-            }
-        } else {
+        let parts: Vec<_> = dataset.parts().iter()
+            .filter(|part| part.phase.as_deref() == phase.strip_suffix("-phase"))
+            .collect();
+        if parts.is_empty() {
             println!("⚠️  Missing phase: {}", phase);
+            continue;
+        }
+        found_phases += 1;
+        println!("✅ Found phase: {}", phase);
+        
+        for part in parts {
+            let metadata = std::fs::metadata(&part.path)
+                .map_err(|e| ValidationError::ProcessingError(format!("Failed to read file metadata: {}", e)))?;
+            let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
+            total_size_mb += size_mb;
+            let records = part.num_rows()?;
+            total_records += records;
+            
+            println!("  📄 {}: {:.2} MB ({} records)", part.relative_path, size_mb, records);
         }
     }
     
     println!("\n📊 Dataset Summary:");
     println!("  Phases found: {}/{}", found_phases, expected_phases.len());
     println!("  Total size: {:.2} MB", total_size_mb);
-    println!("  Estimated records: {}", total_records);
+    println!("  Records: {}", total_records);
     
     // Check for required files
     let readme_path = dataset_path.join("README.md");
@@ -1131,7 +2087,7 @@ The extraction tool and dataset format are licensed under AGPL-3.0.
 /// 
 /// This function uses the LLVM IR extractor to analyze how Rust source code
 /// is compiled to LLVM IR, capturing optimization passes and code generation.
-fn analyze_llvm_ir(source_path: &str, output_path: &str, opt_levels_str: &str) -> Result<(), ValidationError> {
+fn analyze_llvm_ir(source_path: &str, output_path: &str, opt_levels_str: &str, force: bool) -> Result<(), ValidationError> {
     use llvm_ir_extractor::{LLVMIRExtractor, LLVMAnalysisPhase};
     
     let source_path = Path::new(source_path);
@@ -1141,6 +2097,7 @@ fn analyze_llvm_ir(source_path: &str, output_path: &str, opt_levels_str: &str) -
     if !source_path.exists() {
         return Err(ValidationError::InvalidInput(format!("Source path does not exist: {}", source_path.display())));
     }
+    check_output_dir_safety(source_path, output_path, force)?;
     
     // Parse optimization levels
     let opt_levels: Vec<&str> = opt_levels_str.split(',').collect();
@@ -1180,9 +2137,10 @@ fn analyze_llvm_ir(source_path: &str, output_path: &str, opt_levels_str: &str) -
 /// 
 /// This function performs a complete analysis of the Rust compilation pipeline,
 /// combining semantic analysis, project analysis, and LLVM IR generation.
-async fn analyze_rust_to_ir_pipeline(source_path: &str, output_path: &str) -> Result<(), ValidationError> {
+async fn analyze_rust_to_ir_pipeline(source_path: &str, output_path: &str, force: bool) -> Result<(), ValidationError> {
     let source_path = Path::new(source_path);
     let output_path = Path::new(output_path);
+    check_output_dir_safety(source_path, output_path, force)?;
     
     println!("🚀 COMPREHENSIVE RUST → LLVM IR PIPELINE ANALYSIS");
     println!("==================================================");
@@ -1192,17 +2150,17 @@ async fn analyze_rust_to_ir_pipeline(source_path: &str, output_path: &str) -> Re
     // Phase 1: Rust semantic analysis
     println!("\n🔍 Phase 1: Rust Semantic Analysis");
     let semantic_output = output_path.join("semantic");
-    generate_hf_dataset(source_path.to_str().unwrap(), semantic_output.to_str().unwrap())?;
+    generate_hf_dataset(source_path.to_str().unwrap(), semantic_output.to_str().unwrap(), force, &RustExtractionOptions::default())?;
     
     // Phase 2: Cargo project analysis
     println!("\n🏗️ Phase 2: Cargo Project Analysis");
     let cargo_output = output_path.join("cargo");
-    analyze_cargo_project(source_path.to_str().unwrap(), cargo_output.to_str().unwrap(), false).await?;
+    analyze_cargo_project(source_path.to_str().unwrap(), cargo_output.to_str().unwrap(), false, force, cargo2hf_extractor::HttpClientConfig::default(), &CargoExtractionOptions::default()).await?;
     
     // Phase 3: LLVM IR analysis
     println!("\n⚡ Phase 3: LLVM IR Analysis");
     let llvm_output = output_path.join("llvm-ir");
-    analyze_llvm_ir(source_path.to_str().unwrap(), llvm_output.to_str().unwrap(), "O0,O1,O2,O3")?;
+    analyze_llvm_ir(source_path.to_str().unwrap(), llvm_output.to_str().unwrap(), "O0,O1,O2,O3", force)?;
     
     println!("\n🎉 COMPLETE PIPELINE ANALYSIS FINISHED!");
     println!("📊 Generated comprehensive dataset covering:");
@@ -1242,37 +2200,32 @@ fn validate_llvm_dataset(dataset_path: &str) -> Result<(), ValidationError> {
     
     let opt_levels = vec!["O0", "O1", "O2", "O3"];
     
+    let dataset = PartitionedDataset::open(dataset_path)?;
     let mut found_phases = 0;
     let mut total_records = 0;
     let mut total_size_mb = 0.0;
     
     for phase in &expected_phases {
         for opt_level in &opt_levels {
-            let phase_dir = dataset_path.join(format!("{}-{}-phase", phase, opt_level));
-            if phase_dir.exists() {
-                found_phases += 1;
-                println!("✅ Found phase: {}-{}", phase, opt_level);
+            let phase_name = format!("{}-{}", phase, opt_level);
+            let parts: Vec<_> = dataset.parts().iter()
+                .filter(|part| part.phase.as_deref() == Some(phase_name.as_str()))
+                .collect();
+            if parts.is_empty() {
+                continue;
+            }
+            found_phases += 1;
+            println!("✅ Found phase: {}", phase_name);
+            
+            for part in parts {
+                let metadata = std::fs::metadata(&part.path)
+                    .map_err(|e| ValidationError::ProcessingError(format!("Failed to read file metadata: {}", e)))?;
+                let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
+                total_size_mb += size_mb;
+                let records = part.num_rows()?;
+                total_records += records;
                 
-                // Count Parquet files
-                for entry in std::fs::read_dir(&phase_dir)
-                    .map_err(|e| ValidationError::ProcessingError(format!("Failed to read phase directory: {}", e)))? 
-                {
-                    let entry = entry.map_err(|e| ValidationError::ProcessingError(format!("Failed to read directory entry: {}", e)))?;
-                    let path = entry.path();
-                    
-                    if path.extension().and_then(|s| s.to_str()) == Some("parquet") {
-                        let metadata = std::fs::metadata(&path)
-                            .map_err(|e| ValidationError::ProcessingError(format!("Failed to read file metadata: {}", e)))?;
-                        let size_mb = metadata.len() as f64 / (1024.0 * 1024.0);
-                        total_size_mb += size_mb;
-                        
-                        // Estimate records
-                        let estimated_records = (size_mb * 1000.0) as u32;
-                        total_records += estimated_records;
-                        
-                        println!("  📄 {}: {:.2} MB (~{} records)", path.file_name().unwrap().to_string_lossy(), size_mb, estimated_records);
-                    }
-                }
+                println!("  📄 {}: {:.2} MB ({} records)", part.relative_path, size_mb, records);
             }
         }
     }
@@ -1280,7 +2233,7 @@ fn validate_llvm_dataset(dataset_path: &str) -> Result<(), ValidationError> {
     println!("\n📊 Dataset Summary:");
     println!("  Phase-optimization combinations found: {}/{}", found_phases, expected_phases.len() * opt_levels.len());
     println!("  Total size: {:.2} MB", total_size_mb);
-    println!("  Estimated records: {}", total_records);
+    println!("  Records: {}", total_records);
     
     if found_phases == 0 {
         return Err(ValidationError::ProcessingError("No valid phases found in dataset".to_string()));
@@ -1425,7 +2378,7 @@ for batch_result in reader {{
         source_path.display(),
         source_path.display(),
         opt_levels,
-        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        config::generation_time().format("%Y-%m-%d %H:%M:%S UTC"),
         source_path.display(),
         opt_levels,
         opt_levels.len()
@@ -1564,7 +2517,7 @@ The extraction tools and dataset format are licensed under AGPL-3.0.
 "#, 
         source_name,
         source_path.display(),
-        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        config::generation_time().format("%Y-%m-%d %H:%M:%S UTC"),
         source_path.display()
     );
 
@@ -0,0 +1,383 @@
+use futures::TryStreamExt;
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use parquet::file::metadata::{ParquetMetaData, ParquetMetaDataReader};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::parquet_validator::{DatasetValidationReport, ParquetFileInfo, SplitValidationInfo};
+use crate::validator::{
+    CachedResponse, DataAccess, EntityIdentifier, ParquetMetadata, ValidationError, ValidationResult,
+};
+
+/// Config name used for part files stored directly under the prefix
+pub const DEFAULT_CONFIG: &str = "default";
+
+/// Size of the Parquet footer: 4-byte metadata length + `PAR1` magic
+const FOOTER_SIZE: u64 = 8;
+
+/// A Parquet part file discovered under the dataset prefix
+#[derive(Debug, Clone)]
+pub struct PartFile {
+    pub config: String,
+    pub split: String,
+    pub location: ObjectPath,
+    pub size: u64,
+}
+
+/// `DataAccess` over Parquet datasets in object storage (S3, GCS, or any `ObjectStore`)
+///
+/// Configs and splits are discovered from object keys below the prefix:
+/// `{split}-00000-of-00001.parquet` (config `default`), `{config}/{split}-*.parquet`
+/// or `{config}/{split}/*.parquet`. Schemas and row counts come from the Parquet
+/// footers, fetched with ranged reads, so row data is never downloaded.
+///
+/// `DataAccess` is synchronous, so requests run on a private runtime; call it
+/// from `tokio::task::spawn_blocking` when already inside an async context.
+pub struct ObjectStoreDataAccess {
+    store: Arc<dyn ObjectStore>,
+    runtime: tokio::runtime::Runtime,
+    parts: Vec<PartFile>,
+}
+
+impl ObjectStoreDataAccess {
+    /// Open a dataset from an `s3://bucket/prefix` or `gs://bucket/prefix` URL
+    ///
+    /// Credentials and region are read from the environment (`AWS_*` / `GOOGLE_*`).
+    pub fn from_url(url: &str) -> Result<Self, ValidationError> {
+        let invalid = |message: String| ValidationError::InvalidInput(message);
+        let (scheme, rest) = url.split_once("://")
+            .ok_or_else(|| invalid(format!("Not an object store URL: {}", url)))?;
+        let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+        if bucket.is_empty() {
+            return Err(invalid(format!("Missing bucket in {}", url)));
+        }
+
+        let store: Arc<dyn ObjectStore> = match scheme {
+            "s3" => Arc::new(object_store::aws::AmazonS3Builder::from_env()
+                .with_bucket_name(bucket)
+                .build()
+                .map_err(|e| invalid(format!("Failed to configure S3 for {}: {}", url, e)))?),
+            "gs" | "gcs" => Arc::new(object_store::gcp::GoogleCloudStorageBuilder::from_env()
+                .with_bucket_name(bucket)
+                .build()
+                .map_err(|e| invalid(format!("Failed to configure GCS for {}: {}", url, e)))?),
+            _ => return Err(invalid(format!("Unsupported object store scheme '{}' (expected s3 or gs)", scheme))),
+        };
+        Self::new(store, prefix)
+    }
+
+    /// Discover the Parquet part files under `prefix` in `store`
+    pub fn new(store: Arc<dyn ObjectStore>, prefix: &str) -> Result<Self, ValidationError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| ValidationError::ProcessingError(format!("Failed to start runtime: {}", e)))?;
+
+        let prefix = ObjectPath::from(prefix.trim_matches('/'));
+        let objects: Vec<object_store::ObjectMeta> = runtime
+            .block_on(store.list(Some(&prefix)).try_collect())
+            .map_err(|e| ValidationError::DataAccessError {
+                message: format!("Failed to list objects under {}: {}", prefix, e),
+            })?;
+
+        let mut parts: Vec<PartFile> = objects.into_iter()
+            .filter_map(|object| {
+                let relative: Vec<String> = object.location.prefix_match(&prefix)?
+                    .map(|part| part.as_ref().to_string())
+                    .collect();
+                let (config, split) = classify_part_key(&relative)?;
+                Some(PartFile { config, split, location: object.location, size: object.size })
+            })
+            .collect();
+        parts.sort_by(|a, b| a.location.as_ref().cmp(b.location.as_ref()));
+
+        Ok(Self { store, runtime, parts })
+    }
+
+    /// All discovered part files, sorted by key
+    pub fn parts(&self) -> &[PartFile] {
+        &self.parts
+    }
+
+    fn parts_for(&self, config: &str, split: Option<&str>) -> impl Iterator<Item = &PartFile> {
+        let config = config.to_string();
+        let split = split.map(|s| s.to_string());
+        self.parts.iter().filter(move |part| {
+            part.config == config && split.as_ref().map_or(true, |s| &part.split == s)
+        })
+    }
+
+    /// Fetch and decode a part file's footer with two ranged reads
+    fn read_footer(&self, part: &PartFile) -> Result<ParquetMetaData, ValidationError> {
+        let error = |message: String| ValidationError::DataAccessError {
+            message: format!("{}: {}", part.location, message),
+        };
+        if part.size < FOOTER_SIZE {
+            return Err(error("file too small to be Parquet".to_string()));
+        }
+
+        self.runtime.block_on(async {
+            let footer = self.store.get_range(&part.location, part.size - FOOTER_SIZE..part.size).await
+                .map_err(|e| error(e.to_string()))?;
+            if &footer[4..] != b"PAR1" {
+                return Err(error("missing Parquet magic bytes".to_string()));
+            }
+            let metadata_len = u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]) as u64;
+            if metadata_len + FOOTER_SIZE > part.size {
+                return Err(error(format!("metadata length {} exceeds file size", metadata_len)));
+            }
+
+            let metadata_start = part.size - FOOTER_SIZE - metadata_len;
+            let metadata = self.store.get_range(&part.location, metadata_start..part.size - FOOTER_SIZE).await
+                .map_err(|e| error(e.to_string()))?;
+            ParquetMetaDataReader::decode_metadata(&metadata).map_err(|e| error(e.to_string()))
+        })
+    }
+
+    fn arrow_schema(metadata: &ParquetMetaData) -> Result<arrow::datatypes::Schema, ValidationError> {
+        let file_metadata = metadata.file_metadata();
+        parquet::arrow::parquet_to_arrow_schema(file_metadata.schema_descr(), file_metadata.key_value_metadata())
+            .map_err(|e| ValidationError::DataAccessError {
+                message: format!("Failed to convert Parquet schema: {}", e),
+            })
+    }
+
+    /// Row count of a config (or one of its splits), summed over part footers
+    fn row_count(&self, config: &str, split: Option<&str>) -> Result<u64, ValidationError> {
+        let mut rows = 0;
+        for part in self.parts_for(config, split) {
+            rows += self.read_footer(part)?.file_metadata().num_rows() as u64;
+        }
+        Ok(rows)
+    }
+
+    /// Assess a config or split from its part files
+    fn assess(&self, config: &str, split: Option<&str>) -> Result<ValidationResult, ValidationError> {
+        let mut result = ValidationResult::new();
+        result.viewer = self.parts_for(config, split).next().is_some();
+        if !result.viewer {
+            return Ok(result);
+        }
+
+        let rows = self.row_count(config, split)?;
+        result.preview = rows > 0;
+        result.statistics = rows > 0;
+        if let Ok(metadata) = self.get_parquet_metadata("", config) {
+            result.filter = true;
+            result.search = self.has_indexable_columns(&metadata.features);
+        }
+        Ok(result)
+    }
+
+    /// Build a `parquet_validator` report for the whole dataset from part footers
+    pub fn validation_report(&self, dataset_name: &str) -> Result<DatasetValidationReport, ValidationError> {
+        let mut splits: HashMap<String, SplitValidationInfo> = HashMap::new();
+        let mut schemas = Vec::new();
+        let mut total_rows = 0;
+        let mut total_size = 0;
+
+        for part in &self.parts {
+            let metadata = self.read_footer(part)?;
+            let schema = Self::arrow_schema(&metadata)?;
+            let num_rows = metadata.file_metadata().num_rows() as usize;
+            let split_name = format!("{}/{}", part.config, part.split);
+
+            let file_info = ParquetFileInfo {
+                filename: part.location.to_string(),
+                num_rows,
+                num_columns: schema.fields().len(),
+                columns: schema.fields().iter().map(|f| f.name().clone()).collect(),
+                file_size_bytes: part.size,
+                split_name: split_name.clone(),
+            };
+            let split_info = splits.entry(split_name.clone()).or_insert_with(|| SplitValidationInfo {
+                split_name,
+                num_files: 0,
+                num_rows: 0,
+                size_bytes: 0,
+                files: Vec::new(),
+            });
+            split_info.num_files += 1;
+            split_info.num_rows += num_rows;
+            split_info.size_bytes += part.size;
+            split_info.files.push(file_info);
+
+            total_rows += num_rows;
+            total_size += part.size;
+            schemas.push(schema);
+        }
+
+        let mut validation_result = ValidationResult::new();
+        for config in self.get_config_names(dataset_name)? {
+            validation_result.merge(&self.assess(&config, None)?);
+        }
+
+        Ok(DatasetValidationReport {
+            dataset_name: dataset_name.to_string(),
+            total_files: self.parts.len(),
+            total_rows,
+            total_size_bytes: total_size,
+            splits,
+            schema_consistency: schemas.windows(2).all(|w| w[0].fields() == w[1].fields()),
+            validation_result,
+            sample_records: Vec::new(), // Reading rows would mean downloading data
+        })
+    }
+}
+
+/// Derive (config, split) from a part file's key relative to the dataset prefix
+fn classify_part_key(relative: &[String]) -> Option<(String, String)> {
+    let file_name = relative.last()?;
+    let stem = file_name.strip_suffix(".parquet")?;
+    match relative.len() {
+        1 => Some((DEFAULT_CONFIG.to_string(), split_from_stem(stem))),
+        2 => Some((relative[0].clone(), split_from_stem(stem))),
+        _ => Some((relative[0].clone(), relative[1].clone())),
+    }
+}
+
+/// `train-00000-of-00003` → `train`; other names are used as-is
+fn split_from_stem(stem: &str) -> String {
+    let parts: Vec<&str> = stem.rsplitn(4, '-').collect();
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    match parts.as_slice() {
+        [total, "of", index, split] if is_number(total) && is_number(index) => split.to_string(),
+        _ => stem.to_string(),
+    }
+}
+
+impl DataAccess for ObjectStoreDataAccess {
+    fn check_successful_response(&self, kind: &str, entity: &EntityIdentifier) -> Result<bool, ValidationError> {
+        let config = match &entity.config {
+            Some(config) => config,
+            None => return Ok(false),
+        };
+        match kind {
+            "config-has-viewer" => Ok(self.parts_for(config, None).next().is_some()),
+            "split-has-preview" | "split-has-statistics" => {
+                Ok(self.row_count(config, entity.split.as_deref())? > 0)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn get_parquet_metadata(&self, _dataset: &str, config: &str) -> Result<ParquetMetadata, ValidationError> {
+        let first_part = self.parts_for(config, None).next()
+            .ok_or_else(|| ValidationError::MetadataNotFound { entity: config.to_string() })?;
+        let schema = Self::arrow_schema(&self.read_footer(first_part)?)?;
+
+        let features = schema.fields().iter()
+            .map(|field| {
+                let dtype = match field.data_type() {
+                    arrow::datatypes::DataType::Utf8 | arrow::datatypes::DataType::LargeUtf8 => "string".to_string(),
+                    other => other.to_string().to_lowercase(),
+                };
+                (field.name().clone(), dtype)
+            })
+            .collect();
+        Ok(ParquetMetadata::new(features).with_rows(self.row_count(config, None)?))
+    }
+
+    fn get_split_names(&self, _dataset: &str, config: &str) -> Result<Vec<String>, ValidationError> {
+        let mut splits: Vec<String> = self.parts_for(config, None).map(|part| part.split.clone()).collect();
+        splits.sort();
+        splits.dedup();
+        Ok(splits)
+    }
+
+    fn get_config_names(&self, _dataset: &str) -> Result<Vec<String>, ValidationError> {
+        let mut configs: Vec<String> = self.parts.iter().map(|part| part.config.clone()).collect();
+        configs.sort();
+        configs.dedup();
+        Ok(configs)
+    }
+
+    fn get_cached_validation(&self, _kind: &str, entity: &EntityIdentifier) -> Result<CachedResponse, ValidationError> {
+        let config = entity.config.as_ref().ok_or_else(|| ValidationError::InvalidEntityIdentifier {
+            message: "Config required for object store validation".to_string(),
+        })?;
+        let result = self.assess(config, entity.split.as_deref())?;
+        let status = if result.viewer { 200 } else { 404 };
+        Ok(CachedResponse::new(status, result, 1.0))
+    }
+
+    fn has_indexable_columns(&self, features: &HashMap<String, String>) -> bool {
+        features.values().any(|v| v.contains("string"))
+    }
+}
+
+/// CLI function to validate a Parquet dataset in object storage
+pub fn validate_object_store_dataset(url: &str) -> Result<DatasetValidationReport, ValidationError> {
+    let access = ObjectStoreDataAccess::from_url(url)?;
+    if access.parts().is_empty() {
+        return Err(ValidationError::DataAccessError {
+            message: format!("No Parquet files found under {}", url),
+        });
+    }
+    println!("📄 Found {} Parquet files", access.parts().len());
+    access.validation_report(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{StringArray, UInt32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use object_store::memory::InMemory;
+    use object_store::PutPayload;
+    use parquet::arrow::ArrowWriter;
+
+    fn parquet_bytes(num_rows: u32) -> Vec<u8> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("text", DataType::Utf8, false),
+            Field::new("label", DataType::UInt32, false),
+        ]));
+        let batch = RecordBatch::try_new(schema.clone(), vec![
+            Arc::new(StringArray::from_iter_values((0..num_rows).map(|i| format!("row {}", i)))),
+            Arc::new(UInt32Array::from_iter_values(0..num_rows)),
+        ]).unwrap();
+
+        let mut buffer = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buffer, schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_object_store_discovery() {
+        let store = Arc::new(InMemory::new());
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        for (key, rows) in [
+            ("datasets/demo/train-00000-of-00002.parquet", 3),
+            ("datasets/demo/train-00001-of-00002.parquet", 2),
+            ("datasets/demo/test-00000-of-00001.parquet", 1),
+            ("datasets/demo/extra/validation/0000.parquet", 4),
+            ("datasets/other/train-00000-of-00001.parquet", 9),
+        ] {
+            runtime.block_on(store.put(&ObjectPath::from(key), PutPayload::from(parquet_bytes(rows)))).unwrap();
+        }
+        runtime.block_on(store.put(&ObjectPath::from("datasets/demo/README.md"), PutPayload::from("# demo"))).unwrap();
+
+        let access = ObjectStoreDataAccess::new(store, "datasets/demo/").unwrap();
+        assert_eq!(access.parts().len(), 4);
+        assert_eq!(access.get_config_names("demo").unwrap(), vec!["default", "extra"]);
+        assert_eq!(access.get_split_names("demo", "default").unwrap(), vec!["test", "train"]);
+        assert_eq!(access.get_split_names("demo", "extra").unwrap(), vec!["validation"]);
+
+        let metadata = access.get_parquet_metadata("demo", "default").unwrap();
+        assert_eq!(metadata.num_rows, Some(6));
+        assert_eq!(metadata.features["text"], "string");
+
+        let train = EntityIdentifier::new_split("demo".to_string(), "default".to_string(), "train".to_string());
+        let cached = access.get_cached_validation("split-is-valid", &train).unwrap();
+        assert!(cached.is_success());
+        assert!(cached.content.preview && cached.content.search);
+
+        let report = access.validation_report("demo").unwrap();
+        assert_eq!(report.total_rows, 10);
+        assert_eq!(report.splits["default/train"].num_files, 2);
+    }
+}
@@ -7,11 +7,13 @@ use arrow::record_batch::RecordBatch;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use serde::{Deserialize, Serialize};
 
+use crate::partitioned_dataset::{PartBatch, PartitionedDataset};
 use crate::validator::{ValidationError, ValidationResult};
 
 /// Parquet file validator for Hugging Face datasets
 pub struct ParquetValidator {
     dataset_dir: String,
+    jobs: usize, // Files validated at the same time
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,9 +57,16 @@ impl ParquetValidator {
 
         Ok(Self {
             dataset_dir: dataset_dir.to_string(),
+            jobs: crate::config::default_jobs(),
         })
     }
 
+    /// Validate at most `jobs` files at the same time (1 = serially)
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
     /// Validate the entire Hugging Face dataset
     pub fn validate_dataset(&self) -> Result<DatasetValidationReport, ValidationError> {
         println!("🔍 Validating Hugging Face dataset at: {}", self.dataset_dir);
@@ -79,13 +88,15 @@ impl ParquetValidator {
         let mut total_size = 0;
         let mut schemas = Vec::new();
 
-        for file_path in &parquet_files {
-            let file_info = self.validate_parquet_file(file_path)?;
+        let file_results = crate::config::run_jobs(self.jobs, parquet_files.iter().collect(), |file_path| {
+            Ok::<_, ValidationError>((self.validate_parquet_file(file_path)?, self.get_file_schema(file_path)?))
+        });
+        for file_result in file_results {
+            let (file_info, schema) = file_result?;
             total_rows += file_info.num_rows;
             total_size += file_info.file_size_bytes;
             
             // Collect schema for consistency check
-            let schema = self.get_file_schema(file_path)?;
             schemas.push(schema);
             
             file_infos.push(file_info);
@@ -120,31 +131,10 @@ impl ParquetValidator {
         Ok(report)
     }
 
-    /// Find all Parquet files in the dataset directory
+    /// Find all Parquet files in the dataset directory, including nested phase and partition directories
     fn find_parquet_files(&self) -> Result<Vec<String>, ValidationError> {
-        let entries = fs::read_dir(&self.dataset_dir)
-            .map_err(|e| ValidationError::DataAccessError {
-                message: format!("Failed to read dataset directory: {}", e),
-            })?;
-
-        let mut parquet_files = Vec::new();
-        for entry in entries {
-            let entry = entry.map_err(|e| ValidationError::DataAccessError {
-                message: format!("Failed to read directory entry: {}", e),
-            })?;
-
-            let path = entry.path();
-            if let Some(extension) = path.extension() {
-                if extension == "parquet" {
-                    if let Some(path_str) = path.to_str() {
-                        parquet_files.push(path_str.to_string());
-                    }
-                }
-            }
-        }
-
-        parquet_files.sort();
-        Ok(parquet_files)
+        let dataset = PartitionedDataset::open(Path::new(&self.dataset_dir))?;
+        Ok(dataset.parts().iter().map(|part| part.path.to_string_lossy().to_string()).collect())
     }
 
     /// Validate a single Parquet file
@@ -386,52 +376,67 @@ impl ParquetValidator {
 
     /// Print validation report
     pub fn print_report(&self, report: &DatasetValidationReport) {
-        println!("\n📊 Dataset Validation Report");
-        println!("{}", "=".repeat(50));
-        println!("Dataset: {}", report.dataset_name);
-        println!("Total files: {}", report.total_files);
-        println!("Total rows: {}", report.total_rows);
-        println!("Total size: {:.2} MB", report.total_size_bytes as f64 / 1024.0 / 1024.0);
-        println!("Schema consistency: {}", if report.schema_consistency { "✅" } else { "❌" });
-
-        println!("\n🎯 Capabilities:");
-        println!("  Viewer: {}", if report.validation_result.viewer { "✅" } else { "❌" });
-        println!("  Preview: {}", if report.validation_result.preview { "✅" } else { "❌" });
-        println!("  Search: {}", if report.validation_result.search { "✅" } else { "❌" });
-        println!("  Filter: {}", if report.validation_result.filter { "✅" } else { "❌" });
-        println!("  Statistics: {}", if report.validation_result.statistics { "✅" } else { "❌" });
-        println!("  Overall Score: {}/5", report.validation_result.capability_count());
-
-        println!("\n📂 Splits:");
-        for (split_name, split_info) in &report.splits {
-            println!("  {}: {} files, {} rows, {:.2} MB", 
-                split_name, 
-                split_info.num_files, 
-                split_info.num_rows,
-                split_info.size_bytes as f64 / 1024.0 / 1024.0
-            );
-        }
+        print_validation_report(report);
+    }
+}
+
+/// Print a validation report produced by any backend
+pub fn print_validation_report(report: &DatasetValidationReport) {
+    println!("\n📊 Dataset Validation Report");
+    println!("{}", "=".repeat(50));
+    println!("Dataset: {}", report.dataset_name);
+    println!("Total files: {}", report.total_files);
+    println!("Total rows: {}", report.total_rows);
+    println!("Total size: {:.2} MB", report.total_size_bytes as f64 / 1024.0 / 1024.0);
+    println!("Schema consistency: {}", if report.schema_consistency { "✅" } else { "❌" });
+
+    println!("\n🎯 Capabilities:");
+    println!("  Viewer: {}", if report.validation_result.viewer { "✅" } else { "❌" });
+    println!("  Preview: {}", if report.validation_result.preview { "✅" } else { "❌" });
+    println!("  Search: {}", if report.validation_result.search { "✅" } else { "❌" });
+    println!("  Filter: {}", if report.validation_result.filter { "✅" } else { "❌" });
+    println!("  Statistics: {}", if report.validation_result.statistics { "✅" } else { "❌" });
+    println!("  Overall Score: {}/5", report.validation_result.capability_count());
+
+    println!("\n📂 Splits:");
+    for (split_name, split_info) in &report.splits {
+        println!("  {}: {} files, {} rows, {:.2} MB", 
+            split_name, 
+            split_info.num_files, 
+            split_info.num_rows,
+            split_info.size_bytes as f64 / 1024.0 / 1024.0
+        );
+    }
 
-        if !report.sample_records.is_empty() {
-            println!("\n🔍 Sample Records:");
-            for (i, record) in report.sample_records.iter().take(3).enumerate() {
-                println!("  Record {}:", i + 1);
-                for (key, value) in record {
-                    let display_value = if value.len() > 50 {
-                        format!("{}...", &value[..47])
-                    } else {
-                        value.clone()
-                    };
-                    println!("    {}: {}", key, display_value);
-                }
+    if !report.sample_records.is_empty() {
+        println!("\n🔍 Sample Records:");
+        for (i, record) in report.sample_records.iter().take(3).enumerate() {
+            println!("  Record {}:", i + 1);
+            for (key, value) in record {
+                let display_value = if value.len() > 50 {
+                    format!("{}...", &value[..47])
+                } else {
+                    value.clone()
+                };
+                println!("    {}: {}", key, display_value);
             }
         }
     }
 }
 
 /// CLI function to validate Parquet dataset
-pub fn validate_parquet_dataset(dataset_dir: &str) -> Result<(), ValidationError> {
-    let validator = ParquetValidator::new(dataset_dir)?;
+///
+/// `s3://bucket/prefix` and `gs://bucket/prefix` URLs are read through the
+/// object store backend, which only fetches Parquet footers. Local files are
+/// read on up to `jobs` threads.
+pub fn validate_parquet_dataset(dataset_dir: &str, jobs: usize) -> Result<(), ValidationError> {
+    if dataset_dir.starts_with("s3://") || dataset_dir.starts_with("gs://") {
+        let report = crate::object_store_access::validate_object_store_dataset(dataset_dir)?;
+        print_validation_report(&report);
+        return Ok(());
+    }
+
+    let validator = ParquetValidator::new(dataset_dir)?.with_jobs(jobs);
     let report = validator.validate_dataset()?;
     validator.print_report(&report);
     
@@ -448,9 +453,450 @@ pub fn validate_parquet_dataset(dataset_dir: &str) -> Result<(), ValidationError
     Ok(())
 }
 
+/// One occurrence of an id: the file it was read from and its row within that file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IdLocation {
+    pub file: String,
+    pub row: usize,
+}
+
+/// An id that occurs more than once in a dataset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateId {
+    pub id: String,
+    pub locations: Vec<IdLocation>,
+}
+
+/// Find ids that occur more than once across all Parquet files under `dataset_dir`
+///
+/// Subdirectories (e.g. per-phase outputs) are searched too, except `delta/`
+/// directories (see `PartitionedDataset::open`), which repeat the changed
+/// records of a `--diff-against` run.
+/// Files without a string `id` column are skipped. Duplicates are returned
+/// sorted by id, with locations in file order.
+pub fn find_duplicate_ids(dataset_dir: &Path) -> Result<Vec<DuplicateId>, ValidationError> {
+    use arrow::array::{Array, StringArray};
+
+    let mut locations: HashMap<String, Vec<IdLocation>> = HashMap::new();
+    for part_batch in PartitionedDataset::open(dataset_dir)?.record_batches() {
+        let PartBatch { part, batch, row_offset } = part_batch?;
+        let Some(ids) = batch.column_by_name("id").and_then(|c| c.as_any().downcast_ref::<StringArray>()) else {
+            continue;
+        };
+        for row in 0..ids.len() {
+            if ids.is_valid(row) {
+                locations.entry(ids.value(row).to_string()).or_default().push(IdLocation {
+                    file: part.relative_path.clone(),
+                    row: row_offset + row,
+                });
+            }
+        }
+    }
+
+    let mut duplicates: Vec<DuplicateId> = locations
+        .into_iter()
+        .filter(|(_, locations)| locations.len() > 1)
+        .map(|(id, locations)| DuplicateId { id, locations })
+        .collect();
+    duplicates.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(duplicates)
+}
+
+/// CLI function to report duplicate ids in a dataset
+///
+/// Duplicate ids break Hugging Face indexing, so any duplicate is an error.
+pub fn check_dataset_ids(dataset_dir: &str) -> Result<(), ValidationError> {
+    if !Path::new(dataset_dir).exists() {
+        return Err(ValidationError::DataAccessError {
+            message: format!("Dataset directory does not exist: {}", dataset_dir),
+        });
+    }
+
+    let duplicates = find_duplicate_ids(Path::new(dataset_dir))?;
+    if duplicates.is_empty() {
+        println!("✅ No duplicate ids in {}", dataset_dir);
+        return Ok(());
+    }
+
+    for duplicate in &duplicates {
+        println!("  ❌ {} ({} occurrences)", duplicate.id, duplicate.locations.len());
+        for location in &duplicate.locations {
+            println!("      {} row {}", location.file, location.row);
+        }
+    }
+    Err(ValidationError::ProcessingError(format!("{} duplicate ids found", duplicates.len())))
+}
+
+/// A `processing_order` value that repeats or goes backwards within a phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingOrderIssue {
+    /// Phase directory, relative to the dataset directory
+    pub phase: String,
+    pub location: IdLocation,
+    pub value: u64,
+    pub message: String,
+}
+
+/// Find duplicate or decreasing `processing_order` values within each phase
+///
+/// Parquet files are grouped by directory (one phase each, including the
+/// phases under `delta/`) and read in file name order, which is the order
+/// split files are written in. Files without a `processing_order` column are
+/// skipped.
+pub fn find_processing_order_issues(dataset_dir: &Path) -> Result<Vec<ProcessingOrderIssue>, ValidationError> {
+    use arrow::array::{Array, UInt64Array};
+
+    let mut issues = Vec::new();
+    // Per phase directory: where each value was first seen, and the last value read
+    let mut phases: HashMap<String, (HashMap<u64, IdLocation>, Option<u64>)> = HashMap::new();
+    for part_batch in PartitionedDataset::open_with_delta(dataset_dir)?.record_batches() {
+        let PartBatch { part, batch, row_offset } = part_batch?;
+        let Some(column) = batch.column_by_name("processing_order") else {
+            continue;
+        };
+        let orders = arrow::compute::cast(column, &arrow::datatypes::DataType::UInt64)
+            .map_err(|e| ValidationError::DataAccessError {
+                message: format!("Invalid processing_order column in {}: {}", part.path.display(), e),
+            })?;
+        let Some(orders) = orders.as_any().downcast_ref::<UInt64Array>() else {
+            continue;
+        };
+        let phase = part.directory().to_string();
+        let (first_seen, previous) = phases.entry(phase.clone()).or_default();
+        for row in 0..orders.len() {
+            if !orders.is_valid(row) {
+                continue;
+            }
+            let value = orders.value(row);
+            let location = IdLocation { file: part.relative_path.clone(), row: row_offset + row };
+            let message = if let Some(first) = first_seen.get(&value) {
+                Some(format!("duplicate of {} row {}", first.file, first.row))
+            } else {
+                previous.filter(|&previous| value < previous)
+                    .map(|previous| format!("decreases from {}", previous))
+            };
+            if let Some(message) = message {
+                issues.push(ProcessingOrderIssue { phase: phase.clone(), location: location.clone(), value, message });
+            }
+            first_seen.entry(value).or_insert(location);
+            *previous = Some(value);
+        }
+    }
+
+    Ok(issues)
+}
+
+/// CLI function to report duplicate or out-of-order `processing_order` values
+pub fn check_processing_order(dataset_dir: &str) -> Result<(), ValidationError> {
+    if !Path::new(dataset_dir).exists() {
+        return Err(ValidationError::DataAccessError {
+            message: format!("Dataset directory does not exist: {}", dataset_dir),
+        });
+    }
+
+    let issues = find_processing_order_issues(Path::new(dataset_dir))?;
+    if issues.is_empty() {
+        println!("✅ processing_order is unique and increasing in every phase of {}", dataset_dir);
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("  ❌ {}: processing_order {} at {} row {} ({})", issue.phase, issue.value, issue.location.file, issue.location.row, issue.message);
+    }
+    Err(ValidationError::ProcessingError(format!("{} processing_order problems found", issues.len())))
+}
+
+/// A list column whose offsets do not describe its values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListOffsetIssue {
+    pub file: String,
+    pub column: String, // Nested lists are named `column.item`
+    pub message: String,
+}
+
+/// Check the offsets of every list column of `batch`, labelling issues with `file`
+///
+/// Offsets must never decrease, and the last one must equal the length of the
+/// values array. A smaller final offset is the typical off-by-one of a
+/// hand-built offset buffer: the array is valid, so it writes and reads back
+/// fine, but rows silently lose their trailing elements. Run this on batches
+/// before writing them; the Parquet reader rebuilds offsets, so a file read
+/// back can only reveal problems its reader let through.
+pub fn check_list_offsets(batch: &RecordBatch, file: &str) -> Vec<ListOffsetIssue> {
+    let mut issues = Vec::new();
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        collect_list_offset_issues(column, field.name(), file, &mut issues);
+    }
+    issues
+}
+
+fn collect_list_offset_issues(array: &arrow::array::ArrayRef, column: &str, file: &str, issues: &mut Vec<ListOffsetIssue>) {
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::DataType;
+
+    let (offsets, values): (Vec<i64>, &arrow::array::ArrayRef) = match array.data_type() {
+        DataType::List(_) => {
+            let list = array.as_list::<i32>();
+            (list.value_offsets().iter().map(|&offset| offset as i64).collect(), list.values())
+        }
+        DataType::LargeList(_) => {
+            let list = array.as_list::<i64>();
+            (list.value_offsets().to_vec(), list.values())
+        }
+        _ => return,
+    };
+
+    let mut issue = |message: String| issues.push(ListOffsetIssue {
+        file: file.to_string(),
+        column: column.to_string(),
+        message,
+    });
+    if let Some(row) = offsets.windows(2).position(|pair| pair[1] < pair[0]) {
+        issue(format!("offset {} ending row {} is below the previous offset {}", offsets[row + 1], row, offsets[row]));
+    }
+    let final_offset = offsets.last().copied().unwrap_or(0);
+    if final_offset != values.len() as i64 {
+        issue(format!("final offset {} does not match the {} values", final_offset, values.len()));
+    }
+    collect_list_offset_issues(values, &format!("{}.item", column), file, issues);
+}
+
+/// Check the list column offsets of every batch of every Parquet file under `dataset_dir`
+pub fn find_list_offset_issues(dataset_dir: &Path) -> Result<Vec<ListOffsetIssue>, ValidationError> {
+    let mut issues = Vec::new();
+    for part_batch in PartitionedDataset::open(dataset_dir)?.record_batches() {
+        let part_batch = part_batch?;
+        issues.extend(check_list_offsets(&part_batch.batch, &part_batch.part.relative_path));
+    }
+    Ok(issues)
+}
+
+/// CLI function to report list columns with malformed offsets
+pub fn check_dataset_list_offsets(dataset_dir: &str) -> Result<(), ValidationError> {
+    if !Path::new(dataset_dir).exists() {
+        return Err(ValidationError::DataAccessError {
+            message: format!("Dataset directory does not exist: {}", dataset_dir),
+        });
+    }
+
+    let issues = find_list_offset_issues(Path::new(dataset_dir))?;
+    if issues.is_empty() {
+        println!("✅ List column offsets are well-formed in every file of {}", dataset_dir);
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("  ❌ {} column {}: {}", issue.file, issue.column, issue.message);
+    }
+    Err(ValidationError::ProcessingError(format!("{} malformed list columns found", issues.len())))
+}
+
+/// How far ahead of the validating machine's clock a `timestamp` may be, by default
+pub const DEFAULT_TIMESTAMP_FUTURE_MARGIN_SECS: u64 = 24 * 60 * 60;
+
+/// A `timestamp` value that cannot be a real generation time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimestampIssue {
+    pub location: IdLocation,
+    pub value: i64,
+    pub message: String,
+}
+
+/// Find records whose `timestamp` (Unix seconds) is zero or more than `future_margin` ahead of now
+///
+/// Zero is what an unset or broken fixed clock produces; future values point
+/// at clock skew on the generating machine. Files without an integer
+/// `timestamp` column are skipped.
+pub fn find_implausible_timestamps(dataset_dir: &Path, future_margin: std::time::Duration) -> Result<Vec<TimestampIssue>, ValidationError> {
+    use arrow::array::{Array, Int64Array};
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+    let latest = now.saturating_add(future_margin.as_secs() as i64);
+
+    let mut issues = Vec::new();
+    for part_batch in PartitionedDataset::open(dataset_dir)?.record_batches() {
+        let PartBatch { part, batch, row_offset } = part_batch?;
+        let Some(column) = batch.column_by_name("timestamp").filter(|c| c.data_type().is_integer()) else {
+            continue;
+        };
+        let timestamps = arrow::compute::cast(column, &arrow::datatypes::DataType::Int64)
+            .map_err(|e| ValidationError::DataAccessError {
+                message: format!("Invalid timestamp column in {}: {}", part.path.display(), e),
+            })?;
+        let Some(timestamps) = timestamps.as_any().downcast_ref::<Int64Array>() else {
+            continue;
+        };
+        for row in 0..timestamps.len() {
+            if !timestamps.is_valid(row) {
+                continue;
+            }
+            let value = timestamps.value(row);
+            let message = if value == 0 {
+                "zero (unset clock)".to_string()
+            } else if value > latest {
+                format!("{}s in the future", value - now)
+            } else {
+                continue;
+            };
+            issues.push(TimestampIssue {
+                location: IdLocation { file: part.relative_path.clone(), row: row_offset + row },
+                value,
+                message,
+            });
+        }
+    }
+    Ok(issues)
+}
+
+/// CLI function to report zero or future `timestamp` values
+pub fn check_timestamps(dataset_dir: &str, future_margin_secs: u64) -> Result<(), ValidationError> {
+    if !Path::new(dataset_dir).exists() {
+        return Err(ValidationError::DataAccessError {
+            message: format!("Dataset directory does not exist: {}", dataset_dir),
+        });
+    }
+
+    let issues = find_implausible_timestamps(Path::new(dataset_dir), std::time::Duration::from_secs(future_margin_secs))?;
+    if issues.is_empty() {
+        println!("✅ Every timestamp in {} is plausible (non-zero, at most {}s ahead)", dataset_dir, future_margin_secs);
+        return Ok(());
+    }
+
+    for issue in &issues {
+        println!("  ❌ timestamp {} at {} row {} ({})", issue.value, issue.location.file, issue.location.row, issue.message);
+    }
+    Err(ValidationError::ProcessingError(format!("{} implausible timestamps found", issues.len())))
+}
+
+/// `filter` attribute of each path according to `git check-attr`
+///
+/// Returns `None` when `dataset_dir` is not inside a git work tree or git is
+/// unavailable, so the caller can fall back to reading `.gitattributes`.
+fn git_filter_attributes(dataset_dir: &Path, relative_paths: &[String]) -> Option<HashMap<String, String>> {
+    let inside_work_tree = std::process::Command::new("git")
+        .arg("-C").arg(dataset_dir)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .is_some_and(|output| String::from_utf8_lossy(&output.stdout).trim() == "true");
+    if !inside_work_tree {
+        return None;
+    }
+
+    let output = std::process::Command::new("git")
+        .arg("-C").arg(dataset_dir)
+        .args(["check-attr", "filter", "--"])
+        .args(relative_paths)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    // Lines look like `train/data.parquet: filter: lfs`
+    Some(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (path, value) = line.rsplit_once(": filter: ")?;
+            Some((path.to_string(), value.trim().to_string()))
+        })
+        .collect())
+}
+
+/// Whether the `.gitattributes` lines give `relative_path` the `filter=lfs` attribute
+///
+/// Patterns without a slash match the file name at any depth; others match
+/// the path relative to the dataset directory. Later lines override earlier ones.
+fn gitattributes_track_lfs(gitattributes: &str, relative_path: &str) -> bool {
+    let file_name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+    let mut tracked = false;
+    for line in gitattributes.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(pattern) = parts.next().filter(|pattern| !pattern.starts_with('#')) else {
+            continue;
+        };
+        let matcher = match globset::Glob::new(pattern.trim_start_matches('/')) {
+            Ok(glob) => glob.compile_matcher(),
+            Err(_) => continue,
+        };
+        let matches = if pattern.contains('/') {
+            matcher.is_match(relative_path)
+        } else {
+            matcher.is_match(file_name)
+        };
+        if !matches {
+            continue;
+        }
+        for attribute in parts {
+            if attribute == "filter=lfs" {
+                tracked = true;
+            } else if attribute.starts_with("filter=") || attribute == "-filter" || attribute == "!filter" {
+                tracked = false;
+            }
+        }
+    }
+    tracked
+}
+
+/// Check that every Parquet file of a dataset would be stored in Git LFS
+///
+/// Inside a git work tree the attributes git actually applies are queried
+/// with `git check-attr`; otherwise the dataset's `.gitattributes` is read.
+/// Returns the warnings that were printed, one per untracked file.
+pub fn check_lfs_tracking(dataset_dir: &Path) -> Result<Vec<String>, ValidationError> {
+    // Delta files are committed too, so they need LFS as much as the full data
+    let relative_paths: Vec<String> = PartitionedDataset::open_with_delta(dataset_dir)?
+        .parts()
+        .iter()
+        .map(|part| part.relative_path.clone())
+        .collect();
+    if relative_paths.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let untracked: Vec<&String> = match git_filter_attributes(dataset_dir, &relative_paths) {
+        Some(filters) => relative_paths.iter()
+            .filter(|path| filters.get(*path).map(String::as_str) != Some("lfs"))
+            .collect(),
+        None => {
+            let gitattributes = fs::read_to_string(dataset_dir.join(".gitattributes")).unwrap_or_default();
+            relative_paths.iter()
+                .filter(|path| !gitattributes_track_lfs(&gitattributes, path))
+                .collect()
+        }
+    };
+
+    let warnings: Vec<String> = untracked.into_iter()
+        .map(|path| format!("{} is not tracked by Git LFS (add `*.parquet filter=lfs diff=lfs merge=lfs -text` to .gitattributes)", path))
+        .collect();
+    for warning in &warnings {
+        println!("  ⚠️  {}", warning);
+    }
+    Ok(warnings)
+}
+
+/// CLI function to check that a dataset directory is ready to push to the Hugging Face Hub
+pub fn validate_hf_ready(dataset_dir: &str) -> Result<(), ValidationError> {
+    if !Path::new(dataset_dir).is_dir() {
+        return Err(ValidationError::DataAccessError {
+            message: format!("Dataset directory does not exist: {}", dataset_dir),
+        });
+    }
+
+    let warnings = check_lfs_tracking(Path::new(dataset_dir))?;
+    if warnings.is_empty() {
+        println!("✅ All Parquet files in {} are tracked by Git LFS", dataset_dir);
+    } else {
+        println!("⚠️  {} Parquet files would be committed without Git LFS", warnings.len());
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::write_parquet;
 
     #[test]
     fn test_parquet_validator() {
@@ -469,4 +915,166 @@ mod tests {
         assert!(report.total_rows > 0);
         assert!(report.validation_result.viewer);
     }
+
+    #[test]
+    fn test_list_offsets_off_by_one_flagged() {
+        use arrow::array::{ListArray, StringArray};
+        use arrow::buffer::OffsetBuffer;
+        use arrow::datatypes::{DataType, Field, Schema};
+
+        let item = Arc::new(Field::new("item", DataType::Utf8, true));
+        let values = Arc::new(StringArray::from(vec!["a", "b", "c"]));
+        let schema = Arc::new(Schema::new(vec![Field::new("semantic_names", DataType::List(item.clone()), true)]));
+        let batch_with_offsets = |offsets: Vec<i32>| {
+            let list = ListArray::try_new(item.clone(), OffsetBuffer::new(offsets.into()), values.clone(), None).unwrap();
+            RecordBatch::try_new(schema.clone(), vec![Arc::new(list)]).unwrap()
+        };
+
+        assert!(check_list_offsets(&batch_with_offsets(vec![0, 1, 3]), "ok.parquet").is_empty());
+
+        // Off by one: the last value is never referenced, yet the array is valid
+        let issues = check_list_offsets(&batch_with_offsets(vec![0, 1, 2]), "terms.parquet");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].file, "terms.parquet");
+        assert_eq!(issues[0].column, "semantic_names");
+        assert!(issues[0].message.contains("final offset 2"), "{}", issues[0].message);
+
+        // Files written from well-formed batches pass
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let batch = batch_with_offsets(vec![0, 1, 3]);
+        write_parquet(&temp_dir.path().join("data.parquet"), &batch);
+        assert!(find_list_offset_issues(temp_dir.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_zero_and_future_timestamps_flagged() {
+        use arrow::array::UInt64Array;
+        use arrow::datatypes::{DataType, Field, Schema};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        let ten_years = 10 * 365 * 24 * 60 * 60;
+        let schema = Arc::new(Schema::new(vec![Field::new("timestamp", DataType::UInt64, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(UInt64Array::from(vec![now, 0, now + ten_years, now + 60]))]).unwrap();
+        write_parquet(&temp_dir.path().join("parsing-phase/data.parquet"), &batch);
+
+        let margin = std::time::Duration::from_secs(DEFAULT_TIMESTAMP_FUTURE_MARGIN_SECS);
+        let issues = find_implausible_timestamps(temp_dir.path(), margin).unwrap();
+        let flagged: Vec<(usize, i64)> = issues.iter().map(|issue| (issue.location.row, issue.value)).collect();
+        assert_eq!(flagged, vec![(1, 0), (2, (now + ten_years) as i64)]);
+        assert_eq!(issues[0].location.file, Path::new("parsing-phase").join("data.parquet").display().to_string());
+        assert!(issues[1].message.contains("in the future"), "{}", issues[1].message);
+    }
+
+    #[test]
+    fn test_lfs_tracking_warns_without_parquet_pattern() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("parsing-phase")).unwrap();
+        fs::write(temp_dir.path().join("parsing-phase/data.parquet"), b"PAR1").unwrap();
+        fs::write(temp_dir.path().join(".gitattributes"), "*.json filter=lfs diff=lfs merge=lfs -text\n").unwrap();
+
+        let warnings = check_lfs_tracking(temp_dir.path()).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("parsing-phase/data.parquet is not tracked by Git LFS"), "{}", warnings[0]);
+
+        fs::write(temp_dir.path().join(".gitattributes"), "*.parquet filter=lfs diff=lfs merge=lfs -text\n").unwrap();
+        assert!(check_lfs_tracking(temp_dir.path()).unwrap().is_empty());
+
+        // A later line can switch the filter off again
+        fs::write(temp_dir.path().join(".gitattributes"), "*.parquet filter=lfs diff=lfs merge=lfs -text\nparsing-phase/*.parquet -filter\n").unwrap();
+        assert_eq!(check_lfs_tracking(temp_dir.path()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_find_duplicate_ids() {
+        use arrow::array::StringArray;
+        use arrow::datatypes::{DataType, Field, Schema};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let write_ids = |relative: &str, ids: Vec<&str>| {
+            let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, false)]));
+            let batch = RecordBatch::try_new(schema, vec![Arc::new(StringArray::from(ids))]).unwrap();
+            write_parquet(&temp_dir.path().join(relative), &batch);
+        };
+        write_ids("parsing-phase/data.parquet", vec!["src/lib.rs:1:parsing", "src/lib.rs:2:parsing"]);
+        write_ids("name_resolution-phase/data.parquet", vec!["src/lib.rs:1:name_resolution", "src/lib.rs:2:parsing"]);
+
+        let dataset_dir = temp_dir.path().to_str().unwrap();
+        assert!(check_dataset_ids(dataset_dir).is_err());
+
+        let duplicates = find_duplicate_ids(temp_dir.path()).unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].id, "src/lib.rs:2:parsing");
+        let files: Vec<&str> = duplicates[0].locations.iter().map(|l| l.file.as_str()).collect();
+        assert_eq!(files, vec![
+            Path::new("name_resolution-phase").join("data.parquet").to_str().unwrap(),
+            Path::new("parsing-phase").join("data.parquet").to_str().unwrap(),
+        ]);
+        assert_eq!(duplicates[0].locations[0].row, 1);
+        assert_eq!(duplicates[0].locations[1].row, 1);
+    }
+
+    #[test]
+    fn test_check_ids_ignores_delta_of_diff_run() {
+        use crate::rust_analyzer_extractor::{PartitionBy, ProcessingPhase, RustAnalyzerExtractor};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        for partition_by in [PartitionBy::Phase, PartitionBy::SourceDir] {
+            fs::write(source_dir.join("a.rs"), "fn a() {}\n").unwrap();
+            let output_dir = temp_dir.path().join(format!("{:?}", partition_by));
+            RustAnalyzerExtractor::new().unwrap()
+                .with_partition_by(partition_by)
+                .process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &output_dir).unwrap();
+
+            // The changed record is written to the phase data and again under delta/
+            fs::write(source_dir.join("a.rs"), "fn a() -> u8 { 1 }\n").unwrap();
+            RustAnalyzerExtractor::new().unwrap()
+                .with_partition_by(partition_by)
+                .with_diff_against(&output_dir)
+                .process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &output_dir).unwrap();
+            assert!(PartitionedDataset::open_with_delta(&output_dir).unwrap().parts().iter().any(|part| part.is_delta));
+
+            assert!(find_duplicate_ids(&output_dir).unwrap().is_empty());
+            assert!(check_dataset_ids(output_dir.to_str().unwrap()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_processing_order_duplicates_flagged() {
+        use arrow::array::UInt32Array;
+        use arrow::datatypes::{DataType, Field, Schema};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let write_orders = |relative: &str, orders: Vec<u32>| {
+            let schema = Arc::new(Schema::new(vec![Field::new("processing_order", DataType::UInt32, false)]));
+            let batch = RecordBatch::try_new(schema, vec![Arc::new(UInt32Array::from(orders))]).unwrap();
+            write_parquet(&temp_dir.path().join(relative), &batch);
+        };
+        // Orders continue across phases; each phase on its own is increasing
+        write_orders("parsing-phase/data-00000-of-00002.parquet", vec![1, 2, 3]);
+        write_orders("parsing-phase/data-00001-of-00002.parquet", vec![4, 5]);
+        write_orders("name_resolution-phase/data.parquet", vec![6, 7, 9]);
+        let dataset_dir = temp_dir.path().to_str().unwrap();
+        assert!(find_processing_order_issues(temp_dir.path()).unwrap().is_empty());
+        assert!(check_processing_order(dataset_dir).is_ok());
+
+        // Inject a duplicate of a value from the first split file
+        write_orders("parsing-phase/data-00001-of-00002.parquet", vec![4, 2, 5]);
+        let issues = find_processing_order_issues(temp_dir.path()).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].phase, "parsing-phase");
+        assert_eq!(issues[0].value, 2);
+        assert_eq!(issues[0].location.row, 1);
+        assert!(issues[0].message.starts_with("duplicate of"), "{}", issues[0].message);
+        assert!(check_processing_order(dataset_dir).is_err());
+
+        // A value lower than its predecessor is flagged even when unique
+        write_orders("name_resolution-phase/data.parquet", vec![6, 9, 8]);
+        let issues = find_processing_order_issues(temp_dir.path()).unwrap();
+        let decrease = issues.iter().find(|issue| issue.phase == "name_resolution-phase").unwrap();
+        assert_eq!(decrease.value, 8);
+        assert_eq!(decrease.message, "decreases from 9");
+    }
 }
@@ -0,0 +1,397 @@
+//! # Partitioned Datasets: one reader for Parquet dataset directories
+//!
+//! Extraction writes Parquet files in several layouts:
+//!
+//! - `{phase}-phase/data.parquet`, split into `data-00000-of-00003.parquet` when large
+//! - `src_dir={dir}/{phase}-phase/...` with `--partition-by source-dir`
+//! - `{phase}.parquet` directly in the root with `--partition-by none`
+//! - `delta/{phase}-phase/...` next to a full dataset with `--diff-against`
+//!
+//! `PartitionedDataset` finds every part file under a root once, tags it with
+//! its phase, split and `key=value` partitions, and reads it back as schemas,
+//! row counts or a stream of record batches. Hidden directories (`.git`) are
+//! skipped. Parts are ordered by path, which keeps split files in write order.
+//!
+//! Delta parts repeat records that are also in the full phase data, so `open`
+//! leaves them out; callers that look at files rather than records (LFS
+//! tracking, per-directory ordering) use `open_with_delta`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use parquet::arrow::ProjectionMask;
+
+use crate::validator::ValidationError;
+
+/// One Parquet file of a dataset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatasetPart {
+    pub path: PathBuf,
+    /// Path relative to the dataset root, `/`-separated
+    pub relative_path: String,
+    /// Phase from the nearest `{phase}-phase` directory, or a root-level `{phase}.parquet`
+    pub phase: Option<String>,
+    /// File stem without the `-00000-of-00003` shard suffix (`data`, `train`, ...)
+    pub split: String,
+    /// `key=value` directories between the root and the file, outermost first
+    pub partitions: Vec<(String, String)>,
+    /// Under a `delta/` directory written by `--diff-against`
+    pub is_delta: bool,
+}
+
+impl DatasetPart {
+    fn from_path(root: &Path, path: PathBuf) -> Self {
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        let relative_path = relative.to_string_lossy().replace('\\', "/");
+        let components: Vec<&str> = relative_path.split('/').collect();
+        let (directories, file_name) = components.split_at(components.len() - 1);
+
+        let stem = file_name[0].strip_suffix(".parquet").unwrap_or(file_name[0]);
+        let split = Self::strip_shard_suffix(stem).to_string();
+        let phase = match directories.iter().rev().find_map(|dir| dir.strip_suffix("-phase")) {
+            Some(phase) => Some(phase.to_string()),
+            None if directories.is_empty() => Some(split.clone()),
+            None => None,
+        };
+        let partitions = directories.iter()
+            .filter_map(|dir| dir.split_once('='))
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        let is_delta = directories.contains(&"delta");
+
+        Self { path, relative_path, phase, split, partitions, is_delta }
+    }
+
+    /// `data-00000-of-00003` -> `data`
+    fn strip_shard_suffix(stem: &str) -> &str {
+        let Some((head, total)) = stem.rsplit_once("-of-") else { return stem };
+        let Some((name, index)) = head.rsplit_once('-') else { return stem };
+        let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+        if is_number(index) && is_number(total) { name } else { stem }
+    }
+
+    /// Directory holding the part, relative to the dataset root (empty at the root)
+    pub fn directory(&self) -> &str {
+        self.relative_path.rsplit_once('/').map_or("", |(dir, _)| dir)
+    }
+
+    /// Arrow schema of the part, read from the file footer
+    pub fn schema(&self) -> Result<SchemaRef, ValidationError> {
+        Ok(self.reader_builder()?.schema().clone())
+    }
+
+    /// Row count of the part, read from the file footer
+    pub fn num_rows(&self) -> Result<usize, ValidationError> {
+        Ok(self.reader_builder()?.metadata().file_metadata().num_rows().max(0) as usize)
+    }
+
+    /// Read the part decoding only `columns` (every column for `None`)
+    /// 
+    /// Returns `Ok(None)` when the part lacks one of the columns.
+    pub fn reader(&self, columns: Option<&[&str]>) -> Result<Option<ParquetRecordBatchReader>, ValidationError> {
+        let mut builder = self.reader_builder()?;
+        if let Some(columns) = columns {
+            let mut indices = Vec::new();
+            for column in columns {
+                match builder.schema().index_of(column) {
+                    Ok(index) => indices.push(index),
+                    Err(_) => return Ok(None),
+                }
+            }
+            let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+            builder = builder.with_projection(mask);
+        }
+        builder.build().map(Some).map_err(|e| self.read_error(e))
+    }
+
+    fn reader_builder(&self) -> Result<ParquetRecordBatchReaderBuilder<fs::File>, ValidationError> {
+        let file = fs::File::open(&self.path).map_err(|e| ValidationError::DataAccessError {
+            message: format!("Failed to open {}: {}", self.path.display(), e),
+        })?;
+        ParquetRecordBatchReaderBuilder::try_new(file).map_err(|e| self.read_error(e))
+    }
+
+    fn read_error(&self, error: impl std::fmt::Display) -> ValidationError {
+        ValidationError::DataAccessError {
+            message: format!("Failed to read {}: {}", self.path.display(), error),
+        }
+    }
+}
+
+/// A record batch read from one part, with the file row of its first record
+pub struct PartBatch<'a> {
+    pub part: &'a DatasetPart,
+    pub batch: RecordBatch,
+    pub row_offset: usize,
+}
+
+/// The Parquet part files under a dataset root
+#[derive(Debug, Clone)]
+pub struct PartitionedDataset {
+    root: PathBuf,
+    parts: Vec<DatasetPart>,
+}
+
+impl PartitionedDataset {
+    /// Discover the part files under `root` (recursively, in path order), without delta parts
+    pub fn open(root: &Path) -> Result<Self, ValidationError> {
+        let mut dataset = Self::open_with_delta(root)?;
+        dataset.parts.retain(|part| !part.is_delta);
+        Ok(dataset)
+    }
+
+    /// Discover every part file under `root`, including the `delta/` parts of a `--diff-against` run
+    pub fn open_with_delta(root: &Path) -> Result<Self, ValidationError> {
+        if !root.is_dir() {
+            return Err(ValidationError::DataAccessError {
+                message: format!("Dataset directory does not exist: {}", root.display()),
+            });
+        }
+        let mut paths: Vec<PathBuf> = walkdir::WalkDir::new(root)
+            .into_iter()
+            .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'))
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.into_path())
+            .filter(|path| path.is_file() && path.extension().and_then(|e| e.to_str()) == Some("parquet"))
+            .collect();
+        paths.sort();
+
+        let parts = paths.into_iter().map(|path| DatasetPart::from_path(root, path)).collect();
+        Ok(Self { root: root.to_path_buf(), parts })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn parts(&self) -> &[DatasetPart] {
+        &self.parts
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Distinct phases of the parts, in order of first appearance
+    pub fn phases(&self) -> Vec<&str> {
+        let mut phases: Vec<&str> = Vec::new();
+        for phase in self.parts.iter().filter_map(|part| part.phase.as_deref()) {
+            if !phases.contains(&phase) {
+                phases.push(phase);
+            }
+        }
+        phases
+    }
+
+    /// Arrow schema of every part, read from the file footers
+    pub fn schemas(&self) -> Result<Vec<(&DatasetPart, SchemaRef)>, ValidationError> {
+        self.parts.iter()
+            .map(|part| Ok((part, part.schema()?)))
+            .collect()
+    }
+
+    /// Row count of every part, read from the file footers
+    pub fn row_counts(&self) -> Result<Vec<(&DatasetPart, usize)>, ValidationError> {
+        self.parts.iter()
+            .map(|part| Ok((part, part.num_rows()?)))
+            .collect()
+    }
+
+    /// Total rows across all parts
+    pub fn total_rows(&self) -> Result<usize, ValidationError> {
+        Ok(self.row_counts()?.iter().map(|(_, rows)| rows).sum())
+    }
+
+    /// Stream the record batches of every part in order, opening one file at a time
+    pub fn record_batches(&self) -> RecordBatches<'_> {
+        RecordBatches { parts: self.parts.iter(), current: None }
+    }
+}
+
+/// Iterator returned by `PartitionedDataset::record_batches`
+pub struct RecordBatches<'a> {
+    parts: std::slice::Iter<'a, DatasetPart>,
+    current: Option<(&'a DatasetPart, ParquetRecordBatchReader, usize)>,
+}
+
+impl<'a> Iterator for RecordBatches<'a> {
+    type Item = Result<PartBatch<'a>, ValidationError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((part, reader, row_offset)) = &mut self.current {
+                if let Some(batch) = reader.next() {
+                    let part: &'a DatasetPart = *part;
+                    return Some(match batch {
+                        Ok(batch) => {
+                            let first_row = *row_offset;
+                            *row_offset += batch.num_rows();
+                            Ok(PartBatch { part, batch, row_offset: first_row })
+                        }
+                        Err(e) => Err(ValidationError::DataAccessError {
+                            message: format!("Failed to read batch from {}: {}", part.path.display(), e),
+                        }),
+                    });
+                }
+                self.current = None;
+            }
+
+            let part = self.parts.next()?;
+            match part.reader(None) {
+                Ok(reader) => self.current = reader.map(|reader| (part, reader, 0)),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// CLI function to summarize a dataset's parts: rows per phase and partition, and schema drift
+///
+/// Parts of one phase directory should share a schema; any part whose schema
+/// differs from the first part of its directory is reported.
+pub fn print_dataset_stats(dataset_dir: &str) -> Result<(), ValidationError> {
+    let dataset = PartitionedDataset::open(Path::new(dataset_dir))?;
+    if dataset.is_empty() {
+        println!("⚠️  No Parquet files found in {}", dataset.root().display());
+        return Ok(());
+    }
+
+    let row_counts = dataset.row_counts()?;
+    println!("📊 {} Parquet files, {} rows in {}", dataset.parts().len(), dataset.total_rows()?, dataset.root().display());
+    let phases = dataset.phases().into_iter().map(Some).chain(std::iter::once(None));
+    for phase in phases {
+        let phase_rows: Vec<&(&DatasetPart, usize)> = row_counts.iter()
+            .filter(|(part, _)| part.phase.as_deref() == phase)
+            .collect();
+        if phase_rows.is_empty() {
+            continue;
+        }
+        println!("  {}: {} rows in {} files", phase.unwrap_or("(no phase)"), phase_rows.iter().map(|(_, rows)| rows).sum::<usize>(), phase_rows.len());
+        for (part, rows) in phase_rows {
+            let partitions: Vec<String> = part.partitions.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+            let partitions = if partitions.is_empty() { String::new() } else { format!(" [{}]", partitions.join(", ")) };
+            println!("    {} ({}){}: {} rows", part.relative_path, part.split, partitions, rows);
+        }
+    }
+
+    let mut first_schemas: Vec<(&str, SchemaRef)> = Vec::new();
+    let mut drifted = 0;
+    for (part, schema) in dataset.schemas()? {
+        match first_schemas.iter().find(|(directory, _)| *directory == part.directory()) {
+            Some((_, first)) if first.fields() != schema.fields() => {
+                println!("  ⚠️  {} has a different schema than the other files in {}/", part.relative_path, part.directory());
+                drifted += 1;
+            }
+            Some(_) => {}
+            None => first_schemas.push((part.directory(), schema)),
+        }
+    }
+    if drifted == 0 {
+        println!("✅ Files in each directory share one schema");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{Array, StringArray, UInt32Array};
+    use arrow::datatypes::{DataType, Field, Schema};
+
+    use crate::test_support::write_parquet;
+
+    #[test]
+    fn test_multi_phase_dataset_parts_and_rows() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("line", DataType::UInt32, false),
+        ]));
+        let write_part = |relative: &str, rows: u32| {
+            let ids: Vec<String> = (0..rows).map(|row| format!("{}:{}", relative, row)).collect();
+            let batch = RecordBatch::try_new(schema.clone(), vec![
+                Arc::new(StringArray::from(ids)),
+                Arc::new(UInt32Array::from((1..=rows).collect::<Vec<_>>())),
+            ]).unwrap();
+            write_parquet(&temp_dir.path().join(relative), &batch);
+        };
+        write_part("parsing-phase/data-00000-of-00002.parquet", 5);
+        write_part("parsing-phase/data-00001-of-00002.parquet", 3);
+        write_part("name_resolution-phase/data.parquet", 4);
+        write_part("src_dir=core/type_inference-phase/data.parquet", 2);
+        fs::create_dir_all(temp_dir.path().join(".git")).unwrap();
+        fs::write(temp_dir.path().join(".git/ignored.parquet"), b"not parquet").unwrap();
+        fs::write(temp_dir.path().join("parsing-phase/README.md"), "# Parsing").unwrap();
+
+        let dataset = PartitionedDataset::open(temp_dir.path()).unwrap();
+        let relative_paths: Vec<&str> = dataset.parts().iter().map(|part| part.relative_path.as_str()).collect();
+        assert_eq!(relative_paths, vec![
+            "name_resolution-phase/data.parquet",
+            "parsing-phase/data-00000-of-00002.parquet",
+            "parsing-phase/data-00001-of-00002.parquet",
+            "src_dir=core/type_inference-phase/data.parquet",
+        ]);
+        assert_eq!(dataset.phases(), vec!["name_resolution", "parsing", "type_inference"]);
+        assert!(dataset.parts().iter().all(|part| part.split == "data"));
+        assert_eq!(dataset.parts()[3].partitions, vec![("src_dir".to_string(), "core".to_string())]);
+        assert_eq!(dataset.parts()[3].directory(), "src_dir=core/type_inference-phase");
+
+        let row_counts: Vec<usize> = dataset.row_counts().unwrap().into_iter().map(|(_, rows)| rows).collect();
+        assert_eq!(row_counts, vec![4, 5, 3, 2]);
+        assert_eq!(dataset.total_rows().unwrap(), 14);
+        assert!(dataset.schemas().unwrap().iter().all(|(_, part_schema)| part_schema.fields() == schema.fields()));
+
+        // Streaming yields every row once, with offsets restarting per file
+        let mut streamed = 0;
+        for part_batch in dataset.record_batches() {
+            let part_batch = part_batch.unwrap();
+            let ids = part_batch.batch.column(0).as_any().downcast_ref::<StringArray>().unwrap();
+            assert_eq!(ids.value(0), format!("{}:{}", part_batch.part.relative_path, part_batch.row_offset));
+            streamed += part_batch.batch.num_rows();
+        }
+        assert_eq!(streamed, 14);
+    }
+
+    #[test]
+    fn test_flat_layout_phase_from_file_name() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(StringArray::from(vec!["a"]))]).unwrap();
+        write_parquet(&temp_dir.path().join("parsing.parquet"), &batch);
+
+        let dataset = PartitionedDataset::open(temp_dir.path()).unwrap();
+        assert_eq!(dataset.parts()[0].phase.as_deref(), Some("parsing"));
+        assert_eq!(dataset.parts()[0].directory(), "");
+        assert!(PartitionedDataset::open(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_delta_parts_only_on_request() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Utf8, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(StringArray::from(vec!["a"]))]).unwrap();
+        write_parquet(&temp_dir.path().join("parsing-phase/data.parquet"), &batch);
+        write_parquet(&temp_dir.path().join("delta/parsing-phase/data.parquet"), &batch);
+        write_parquet(&temp_dir.path().join("src_dir=core/delta/parsing-phase/data.parquet"), &batch);
+
+        let dataset = PartitionedDataset::open(temp_dir.path()).unwrap();
+        let relative_paths: Vec<&str> = dataset.parts().iter().map(|part| part.relative_path.as_str()).collect();
+        assert_eq!(relative_paths, vec!["parsing-phase/data.parquet"]);
+        assert_eq!(dataset.total_rows().unwrap(), 1);
+
+        let with_delta = PartitionedDataset::open_with_delta(temp_dir.path()).unwrap();
+        let delta_flags: Vec<(&str, bool)> = with_delta.parts().iter().map(|part| (part.relative_path.as_str(), part.is_delta)).collect();
+        assert_eq!(delta_flags, vec![
+            ("delta/parsing-phase/data.parquet", true),
+            ("parsing-phase/data.parquet", false),
+            ("src_dir=core/delta/parsing-phase/data.parquet", true),
+        ]);
+        assert_eq!(with_delta.parts()[0].phase.as_deref(), Some("parsing"));
+    }
+}
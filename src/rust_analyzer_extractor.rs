@@ -18,12 +18,15 @@
  * # Output Format
  * 
  * The extractor generates Parquet files with the following schema:
- * - Identification: id, file_path, line, column
+ * - Identification: id, file_path, line, column, source_hash
  * - Phase information: phase, processing_order
  * - Element details: element_type, element_name, element_signature
- * - Semantic data: syntax_data, symbol_data, type_data, diagnostic_data (JSON)
+ * - Semantic data: syntax_data, symbol_data, type_data, diagnostic_data, signature_data, generics_data, lifetime_data (JSON)
+ * - Parse quality: parse_fallback (file could not be parsed by `syn`)
+ * - Scope: enclosing_path (`module::Type::function` around the element)
  * - Metadata: processing_time_ms, timestamp, rust_version, analyzer_version
  * - Context: source_snippet, context_before, context_after
+ * - Provenance: blame_author, blame_timestamp (git blame, opt-in)
  * 
  * # Usage
  * 
@@ -34,15 +37,23 @@
  * ```
  */
 
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH, Instant};
+use std::time::Instant;
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context};
-use arrow::array::{StringArray, UInt32Array, UInt64Array};
-use arrow::record_batch::RecordBatch;
+use arrow::array::{BooleanArray, Int64Array, StringArray, UInt32Array, UInt64Array};
 use parquet::arrow::ArrowWriter;
 use parquet::file::properties::WriterProperties;
 use std::sync::Arc;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use quote::ToTokens;
+
+use crate::dataset_io::{
+    build_record_batch, optional_string, optional_value, print_skipped_summary, read_parquet_batches, select_columns,
+    typed_column, write_provenance, write_run_manifest, Provenance, SkippedFile, ThroughputStats,
+};
+use crate::source_scan::mask_comments_and_strings;
 
 // Import rust-analyzer components (these would need to be added to Cargo.toml)
 // use ra_ide::{Analysis, AnalysisHost, FileId, FilePosition};
@@ -126,6 +137,22 @@ impl ProcessingPhase {
             ProcessingPhase::FindReferences => "find_references",
         }
     }
+
+    /// Look up a phase from its string representation (the inverse of `as_str`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "parsing" => Some(ProcessingPhase::Parsing),
+            "name_resolution" => Some(ProcessingPhase::NameResolution),
+            "type_inference" => Some(ProcessingPhase::TypeInference),
+            "hir_generation" => Some(ProcessingPhase::HirGeneration),
+            "diagnostics" => Some(ProcessingPhase::Diagnostics),
+            "completions" => Some(ProcessingPhase::Completions),
+            "hover" => Some(ProcessingPhase::Hover),
+            "goto_definition" => Some(ProcessingPhase::GotoDefinition),
+            "find_references" => Some(ProcessingPhase::FindReferences),
+            _ => None,
+        }
+    }
 }
 
 /// Main data structure representing a single semantic analysis record
@@ -133,11 +160,11 @@ impl ProcessingPhase {
 /// Each record captures one semantic analysis event during rust-analyzer processing.
 /// This could be parsing a single line, resolving a symbol, inferring a type, etc.
 /// The record includes both the analysis results and metadata about the process.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RustAnalyzerRecord {
     // === Identification Fields ===
     /// Unique identifier for this analysis record
-    /// Format: "file_path:line:phase" for easy tracking
+    /// Format: "file_path:line:phase" by default, see `RecordIdTemplate`
     pub id: String,
     
     /// Path to the source file being analyzed
@@ -149,6 +176,11 @@ pub struct RustAnalyzerRecord {
     /// Column number in the source file (1-based)
     pub column: u32,
     
+    /// Stable hash of the record's content (everything except processing
+    /// order and timing), used to detect changed records between runs
+    #[serde(default)]
+    pub source_hash: String,
+    
     // === Phase Information ===
     /// Which processing phase generated this record
     pub phase: String,
@@ -170,6 +202,11 @@ pub struct RustAnalyzerRecord {
     /// For functions: full signature, for types: full definition
     pub element_signature: Option<String>,
     
+    /// Path of the modules, impls, traits and functions enclosing the element
+    /// within its file, e.g. `bar::Foo::method` (None at file level)
+    #[serde(default)]
+    pub enclosing_path: Option<String>,
+    
     // === Semantic Analysis Data (JSON-serialized) ===
     /// Syntax tree and parsing information
     /// Contains AST nodes, token information, parse errors
@@ -187,6 +224,26 @@ pub struct RustAnalyzerRecord {
     /// Contains error messages, severity levels, suggested fixes
     pub diagnostic_data: Option<String>,
     
+    /// Structured function signature (functions only)
+    /// Contains parameter names/types and the return type
+    #[serde(default)]
+    pub signature_data: Option<String>,
+    
+    /// Generic parameters with their bounds (generic items only)
+    /// Merges inline bounds and where-clause predicates per parameter
+    #[serde(default)]
+    pub generics_data: Option<String>,
+    
+    /// Lifetime parameters, what uses them and whether elision applies
+    /// (functions and structs that mention lifetimes only)
+    #[serde(default)]
+    pub lifetime_data: Option<String>,
+    
+    /// True when `syn` could not parse the file and the record comes from
+    /// line-based extraction alone (structured fields are best-effort)
+    #[serde(default)]
+    pub parse_fallback: bool,
+    
     // === Processing Metadata ===
     /// Time taken to perform this analysis step (in milliseconds)
     pub processing_time_ms: u64,
@@ -209,6 +266,32 @@ pub struct RustAnalyzerRecord {
     
     /// Source code from the line after (for context)
     pub context_after: Option<String>,
+    
+    // === Provenance (only with `with_blame`) ===
+    /// Author of the commit that last changed the element's line (git blame)
+    #[serde(default)]
+    pub blame_author: Option<String>,
+    
+    /// Unix timestamp (seconds) of the commit that last changed the line
+    #[serde(default)]
+    pub blame_timestamp: Option<i64>,
+}
+
+impl RustAnalyzerRecord {
+    /// Hash of the record's content, ignoring processing order, timing, and the hash itself
+    /// 
+    /// Uses FNV-1a over the JSON form so hashes stay comparable across runs and builds.
+    pub fn content_hash(&self) -> String {
+        let mut content = self.clone();
+        content.source_hash.clear();
+        content.processing_order = 0;
+        content.processing_time_ms = 0;
+        content.timestamp = 0;
+        let json = serde_json::to_string(&content).unwrap_or_default();
+
+        let hash = crate::validator::fnv1a(crate::validator::FNV1A_OFFSET_BASIS, json.as_bytes());
+        format!("{:016x}", hash)
+    }
 }
 
 // Phase-specific data structures for detailed semantic information
@@ -266,6 +349,115 @@ pub struct SymbolInfo {
     pub signature: Option<String>,       // Full signature if applicable
 }
 
+/// Information about an attribute applied to an item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttributeInfo {
+    pub path: String,               // Attribute path (derive, serde, tokio::main, etc.)
+    pub arguments: Vec<String>,     // Top-level comma-separated arguments (if any)
+}
+
+/// A `macro_rules!` definition and its match arms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacroRulesInfo {
+    pub name: String,               // Macro name
+    pub line: u32,                  // Line of the `macro_rules!` keyword (1-based)
+    pub arm_patterns: Vec<String>,  // Matcher of each arm, including its delimiters
+}
+
+/// A fenced code block in a doc comment, which rustdoc compiles as a doctest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctestInfo {
+    pub line: u32,                  // Line of the opening fence (1-based)
+    pub code: String,               // Block contents, including `# ` hidden lines
+    pub attributes: Vec<String>,    // Fence info tokens (`rust`, `no_run`, ...)
+    pub no_run: bool,               // Compiled but not run
+    pub ignore: bool,               // Neither compiled nor run
+    pub should_panic: bool,         // Run and expected to panic
+}
+
+/// A closure expression, found by scanning the source outside comments and strings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClosureInfo {
+    pub line: u32,                  // Line of the closure start (`move` or the opening `|`), 1-based
+    pub column: u32,                // Column of the closure start, 1-based
+    pub is_move: bool,              // `move` closure: captures take ownership
+    pub params: String,             // Parameter list between the pipes, as written
+    pub param_count: usize,         // Number of parameters
+    pub body_length: usize,         // Characters in the body (trimmed)
+    pub body_lines: usize,          // Lines spanned by the body
+}
+
+/// A `match` expression, found by scanning the source outside comments and strings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchInfo {
+    pub line: u32,                  // Line of the `match` keyword, 1-based
+    pub column: u32,                // Column of the `match` keyword, 1-based
+    pub arm_count: usize,           // Number of arms
+    pub has_wildcard: bool,         // An unguarded `_` arm: exhaustive whatever the scrutinee (a hint only)
+}
+
+/// A method, associated type or associated const declared in a trait
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraitMemberInfo {
+    pub trait_name: String,         // Enclosing trait
+    pub kind: String,               // `method`, `type` or `const`
+    pub name: String,               // Member name
+    pub signature: String,          // Declaration without body or default (`fn f(&self) -> u32`, `type Item: Clone`)
+    pub has_default: bool,          // Default body, type or value provided by the trait
+}
+
+/// Parameters and return type of a function signature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureInfo {
+    pub params: Vec<ParamInfo>,     // Parameters in declaration order (receiver first, if any)
+    pub ret: String,                // Return type, `()` when omitted
+}
+
+/// A single function parameter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParamInfo {
+    pub name: String,               // Binding pattern (`self` for receivers)
+    #[serde(rename = "type")]
+    pub ty: String,                 // Parameter type as written
+}
+
+/// A field of a struct definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldInfo {
+    pub field: String,              // Field name, or its index (`0`, `1`) for tuple structs
+    #[serde(rename = "type")]
+    pub ty: String,                 // Field type as written
+    #[serde(rename = "pub")]
+    pub is_pub: bool,               // Declared plain `pub` (restricted `pub(..)` counts as false)
+}
+
+/// Bounds on one generic parameter (or other where-clause bounded type)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenericBoundInfo {
+    pub param: String,              // `T`, `'a`, or a bounded type like `Vec<T>`
+    pub bounds: Vec<String>,        // Inline bounds followed by where-clause bounds
+}
+
+/// Lifetime parameters of a function or struct (the `lifetime_data` column)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifetimeData {
+    pub lifetimes: Vec<LifetimeParamInfo>, // Declared lifetime parameters, in order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_lifetimes: Option<Vec<String>>, // Named lifetimes in the return type (functions only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub return_bound_to: Option<Vec<String>>, // Parameters sharing a lifetime with the return type
+    pub elidable: bool,             // Whether the annotations could be dropped under the elision rules
+    pub elision_rule: Option<String>, // `no_output`, `self_receiver` or `single_input` when elidable
+}
+
+/// One declared lifetime parameter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifetimeParamInfo {
+    pub name: String,               // `'a`
+    pub bounds: Vec<String>,        // Outlives bounds (`'a: 'b`), inline and where-clause
+    pub used_by: Vec<String>,       // Parameters (functions) or fields (structs) whose type mentions it
+}
+
 /// Information about lexical scopes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScopeInfo {
@@ -340,6 +532,698 @@ pub struct InferredTypeInfo {
     pub inference_method: String,   // How the type was inferred
 }
 
+/// File written to the output directory when a run exceeds its `RunLimits`
+pub const CHECKPOINT_FILE: &str = "checkpoint.json";
+
+/// Where an extraction run stopped after exceeding `--max-duration` or `--max-memory`
+/// 
+/// A report for the operator, not a resume point: nothing reads it back, and
+/// the next run removes it and extracts every phase again. Completed phases
+/// are fully written, so their output is usable as is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionCheckpoint {
+    pub reason: String,                 // Which limit was exceeded
+    pub completed_phases: Vec<String>,  // Phases fully extracted and written
+    pub interrupted_phase: String,      // Phase being extracted when the run stopped (not written)
+    pub files_done: usize,              // Files of the interrupted phase already extracted
+    pub total_files: usize,
+    pub next_file: Option<String>,      // First file not yet extracted in the interrupted phase
+    pub elapsed_secs: f64,
+}
+
+/// What to do with source files larger than the configured byte limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OversizeAction {
+    /// Leave the file out and list it among the skipped files
+    Skip,
+    /// Analyze only the first N bytes (cut back to the last complete line)
+    Truncate,
+}
+
+/// Source line → ids of the records derived from it, per source file
+pub type LineCoverage = BTreeMap<String, BTreeMap<u32, Vec<String>>>;
+
+/// Add the lines of `records` to `coverage`
+pub fn add_line_coverage(coverage: &mut LineCoverage, records: &[RustAnalyzerRecord]) {
+    for record in records {
+        coverage.entry(record.file_path.clone())
+            .or_default()
+            .entry(record.line)
+            .or_default()
+            .push(record.id.clone());
+    }
+}
+
+/// Codec for the data pages of written Parquet files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParquetCodec {
+    /// Fast to read and write; the default
+    #[default]
+    Snappy,
+    /// Smaller files at a tunable CPU cost, for archival datasets
+    Zstd,
+    Uncompressed,
+}
+
+impl ParquetCodec {
+    /// Parse a codec name as accepted on the command line
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snappy" => Some(ParquetCodec::Snappy),
+            "zstd" => Some(ParquetCodec::Zstd),
+            "none" | "uncompressed" => Some(ParquetCodec::Uncompressed),
+            _ => None,
+        }
+    }
+}
+
+/// Directory layout of written Parquet files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartitionBy {
+    /// `{phase}-phase/data.parquet`; the default
+    #[default]
+    Phase,
+    /// `src_dir={dir}/{phase}-phase/data.parquet`, where `dir` is the first path
+    /// component below the analyzed directory (`_root` for files directly in it)
+    SourceDir,
+    /// `{phase}.parquet` directly in the output directory
+    None,
+}
+
+impl PartitionBy {
+    /// Parse a layout name as accepted on the command line
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "phase" => Some(PartitionBy::Phase),
+            "source-dir" => Some(PartitionBy::SourceDir),
+            "none" => Some(PartitionBy::None),
+            _ => None,
+        }
+    }
+}
+
+/// Partition value for files directly in the analyzed directory
+const ROOT_PARTITION: &str = "_root";
+
+/// Group records by the first path component of their file below `source_root`
+fn group_by_source_dir(records: &[RustAnalyzerRecord], source_root: &Path) -> BTreeMap<String, Vec<RustAnalyzerRecord>> {
+    let mut partitions: BTreeMap<String, Vec<RustAnalyzerRecord>> = BTreeMap::new();
+    for record in records {
+        let file_path = Path::new(&record.file_path);
+        let relative = file_path.strip_prefix(source_root).unwrap_or(file_path);
+        let mut components = relative.components();
+        let partition = match (components.next(), components.next()) {
+            (Some(dir), Some(_)) => dir.as_os_str().to_string_lossy().into_owned(),
+            _ => ROOT_PARTITION.to_string(),
+        };
+        partitions.entry(partition).or_default().push(record.clone());
+    }
+    partitions
+}
+
+/// Compression and encoding settings for written Parquet files
+#[derive(Debug, Clone, Default)]
+pub struct ParquetWriteOptions {
+    pub compression: ParquetCodec,
+    /// ZSTD level (1–22); ignored by the other codecs. None = the codec default
+    pub compression_level: Option<i32>,
+    /// Restrict dictionary encoding to these columns; every other column is
+    /// plain encoded, and empty means no dictionary anywhere. None = the
+    /// writer default: dictionaries everywhere, falling back to plain
+    /// encoding once a column's dictionary grows too large
+    pub dictionary_columns: Option<Vec<String>>,
+}
+
+impl ParquetWriteOptions {
+    pub fn writer_properties(&self) -> Result<WriterProperties> {
+        use parquet::basic::{Compression, ZstdLevel};
+        use parquet::schema::types::ColumnPath;
+
+        let compression = match self.compression {
+            ParquetCodec::Snappy => Compression::SNAPPY,
+            ParquetCodec::Uncompressed => Compression::UNCOMPRESSED,
+            ParquetCodec::Zstd => Compression::ZSTD(match self.compression_level {
+                Some(level) => ZstdLevel::try_new(level).with_context(|| format!("Invalid ZSTD level {}", level))?,
+                None => ZstdLevel::default(),
+            }),
+        };
+        let mut builder = WriterProperties::builder().set_compression(compression);
+        if let Some(columns) = &self.dictionary_columns {
+            builder = builder.set_dictionary_enabled(false);
+            for column in columns {
+                builder = builder.set_column_dictionary_enabled(ColumnPath::from(column.as_str()), true);
+            }
+        }
+        Ok(builder.build())
+    }
+}
+
+/// Format of record ids, e.g. `{path}:{line}:{col}:{phase}:{order}`
+/// 
+/// Placeholders:
+/// - `{path}`: source file path
+/// - `{line}`: 1-based line
+/// - `{col}`: 1-based column
+/// - `{phase}`: processing phase (`parsing`, `name_resolution`, ...)
+/// - `{order}`: processing order within the run
+/// 
+/// The default `{path}:{line}:{phase}` collides when a phase emits several
+/// records for one line; adding `{order}` always makes ids unique within a
+/// run, but such ids change between runs, so `--diff-against` sees every
+/// record as changed. Closure and match records, which start inside the
+/// line of another record, get `:{kind}:{col}` appended under templates
+/// without `{col}` or `{order}`, e.g. `src/lib.rs:3:parsing:closure:17`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordIdTemplate {
+    template: String,
+}
+
+impl Default for RecordIdTemplate {
+    fn default() -> Self {
+        Self { template: Self::DEFAULT.to_string() }
+    }
+}
+
+impl RecordIdTemplate {
+    pub const DEFAULT: &'static str = "{path}:{line}:{phase}";
+    const PLACEHOLDERS: [&'static str; 5] = ["path", "line", "col", "phase", "order"];
+
+    /// Check that every `{...}` in `template` is a known placeholder
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            let close = rest[open..].find('}')
+                .with_context(|| format!("Unclosed placeholder in id template: {}", template))?;
+            let name = &rest[open + 1..open + close];
+            if !Self::PLACEHOLDERS.contains(&name) {
+                return Err(anyhow::anyhow!(
+                    "Unknown placeholder {{{}}} in id template (expected one of {})",
+                    name,
+                    Self::PLACEHOLDERS.iter().map(|p| format!("{{{}}}", p)).collect::<Vec<_>>().join(", ")
+                ));
+            }
+            rest = &rest[open + close + 1..];
+        }
+        if rest.contains('}') {
+            return Err(anyhow::anyhow!("Unmatched '}}' in id template: {}", template));
+        }
+        Ok(Self { template: template.to_string() })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Element types emitted as extra records within another record's line
+    const EXPRESSION_ELEMENT_TYPES: [&'static str; 2] = ["closure", "match"];
+
+    /// Id of `record` under this template
+    pub fn render(&self, record: &RustAnalyzerRecord) -> String {
+        let id = self.template
+            .replace("{path}", &record.file_path)
+            .replace("{line}", &record.line.to_string())
+            .replace("{col}", &record.column.to_string())
+            .replace("{phase}", &record.phase)
+            .replace("{order}", &record.processing_order.to_string());
+        let distinguished = self.template.contains("{col}") || self.template.contains("{order}");
+        if !distinguished && Self::EXPRESSION_ELEMENT_TYPES.contains(&record.element_type.as_str()) {
+            format!("{}:{}:{}", id, record.element_type, record.column)
+        } else {
+            id
+        }
+    }
+}
+
+/// 1-based character column of byte `byte_offset` in `line`
+fn column_at(line: &str, byte_offset: usize) -> u32 {
+    line[..byte_offset].chars().count() as u32 + 1
+}
+
+/// 1-based character column of the first non-whitespace character of `line`
+fn indent_column(line: &str) -> u32 {
+    column_at(line, line.len() - line.trim_start().len())
+}
+
+/// Write `checkpoint` to `checkpoint_path` and build the error that stops the run
+fn stop_with_checkpoint(checkpoint_path: &Path, checkpoint: ExtractionCheckpoint) -> Result<anyhow::Error> {
+    std::fs::write(checkpoint_path, serde_json::to_string_pretty(&checkpoint)?)?;
+    Ok(anyhow::anyhow!(
+        "Extraction stopped: {}; checkpoint written to {}",
+        checkpoint.reason, checkpoint_path.display()
+    ))
+}
+
+/// Wait for a phase written on another thread; returns the ids it found removed
+fn join_phase_write(handle: std::thread::ScopedJoinHandle<'_, Result<Vec<String>>>) -> Result<Vec<String>> {
+    handle.join().map_err(|_| anyhow::anyhow!("Parquet writer thread panicked"))?
+}
+
+/// Approximate in-memory size of `records`: struct sizes plus string contents
+pub fn estimate_records_bytes(records: &[RustAnalyzerRecord]) -> u64 {
+    let optional_len = |value: &Option<String>| value.as_ref().map_or(0, |s| s.len());
+    records.iter().map(|record| {
+        let strings = record.id.len() + record.file_path.len() + record.source_hash.len() + record.phase.len()
+            + record.element_type.len() + optional_len(&record.element_name) + optional_len(&record.element_signature)
+            + optional_len(&record.enclosing_path)
+            + optional_len(&record.syntax_data) + optional_len(&record.symbol_data) + optional_len(&record.type_data)
+            + optional_len(&record.diagnostic_data) + optional_len(&record.signature_data) + optional_len(&record.generics_data)
+            + optional_len(&record.lifetime_data)
+            + record.rust_version.len() + record.analyzer_version.len() + record.source_snippet.len()
+            + optional_len(&record.context_before) + optional_len(&record.context_after) + optional_len(&record.blame_author);
+        (std::mem::size_of::<RustAnalyzerRecord>() + strings) as u64
+    }).sum()
+}
+
+/// Number of records whose id was already used by an earlier record
+pub fn count_duplicate_ids(records: &[RustAnalyzerRecord]) -> usize {
+    let mut seen = HashSet::new();
+    records.iter().filter(|record| !seen.insert(record.id.as_str())).count()
+}
+
+/// Read the `.rs` files of a commit straight from the git object database
+/// 
+/// Nothing is checked out, so the working tree is left untouched. Hidden and
+/// `target` directories are skipped as in a filesystem walk. Returns the
+/// resolved commit id and the file contents keyed by repository-relative path.
+pub fn read_rust_sources_at_revision(repo_path: &Path, revision: &str) -> Result<(String, BTreeMap<PathBuf, Vec<u8>>)> {
+    let repo = git2::Repository::open(repo_path)
+        .with_context(|| format!("Failed to open git repository {}", repo_path.display()))?;
+    let commit = repo.revparse_single(revision)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("Failed to resolve revision '{}'", revision))?;
+
+    let mut blobs = Vec::new();
+    commit.tree()?.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+        let name = entry.name().unwrap_or_default();
+        match entry.kind() {
+            Some(git2::ObjectType::Tree) if name.starts_with('.') || name == "target" => git2::TreeWalkResult::Skip,
+            Some(git2::ObjectType::Blob) if name.ends_with(".rs") => {
+                blobs.push((PathBuf::from(format!("{}{}", root, name)), entry.id()));
+                git2::TreeWalkResult::Ok
+            }
+            _ => git2::TreeWalkResult::Ok,
+        }
+    })?;
+
+    let mut sources = BTreeMap::new();
+    for (path, oid) in blobs {
+        sources.insert(path, repo.find_blob(oid)?.content().to_vec());
+    }
+    Ok((commit.id().to_string(), sources))
+}
+
+/// `{phase}-phase/data.json` files of a JSON dataset, in directory name order
+/// 
+/// Directories whose name is not a known phase are reported and skipped.
+fn json_phase_files(dataset_dir: &Path) -> Result<Vec<(ProcessingPhase, PathBuf)>> {
+    let mut phase_dirs: Vec<PathBuf> = std::fs::read_dir(dataset_dir)
+        .with_context(|| format!("Failed to read dataset directory: {}", dataset_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_dir() && path.join("data.json").exists())
+        .collect();
+    phase_dirs.sort();
+
+    let mut phase_files = Vec::new();
+    for phase_dir in phase_dirs {
+        let dir_name = phase_dir.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        match dir_name.strip_suffix("-phase").and_then(ProcessingPhase::from_name) {
+            Some(phase) => phase_files.push((phase, phase_dir.join("data.json"))),
+            None => println!("Skipping unrecognized phase directory: {}", phase_dir.display()),
+        }
+    }
+    Ok(phase_files)
+}
+
+/// Records of one `data.json` phase file
+fn read_json_phase(json_file: &Path) -> Result<Vec<RustAnalyzerRecord>> {
+    let json_content = std::fs::read_to_string(json_file)
+        .with_context(|| format!("Failed to read {}", json_file.display()))?;
+    serde_json::from_str(&json_content)
+        .with_context(|| format!("Failed to parse {}", json_file.display()))
+}
+
+/// Concatenate the `{phase}-phase/data.json` files of a JSON dataset into one NDJSON file
+/// 
+/// Phases are written in directory name order, one record per line. Records
+/// keep their `phase` field, so the stream can be split again by phase.
+/// Returns the number of records written.
+pub fn merge_json_phases_to_jsonl(dataset_dir: &Path, output_file: &Path) -> Result<usize> {
+    use std::io::Write;
+
+    let phase_files = json_phase_files(dataset_dir)?;
+    if let Some(parent) = output_file.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(output_file)
+        .with_context(|| format!("Failed to create {}", output_file.display()))?);
+
+    let mut total_records = 0;
+    for (phase, json_file) in phase_files {
+        let records = read_json_phase(&json_file)?;
+        println!("Merging {} records for phase {:?}", records.len(), phase);
+        for record in &records {
+            serde_json::to_writer(&mut writer, record)?;
+            writer.write_all(b"\n")?;
+        }
+        total_records += records.len();
+    }
+    writer.flush()?;
+    Ok(total_records)
+}
+
+/// Author name and commit time (Unix seconds) per line of a `git2` blame, indexed from 0
+/// 
+/// Lines no commit accounts for (uncommitted edits) are `None`.
+fn blame_by_line(blame: &git2::Blame, source: &str) -> Vec<Option<(String, i64)>> {
+    (1..=source.lines().count())
+        .map(|line| {
+            blame.get_line(line)
+                .filter(|hunk| !hunk.final_commit_id().is_zero())
+                .map(|hunk| {
+                    let signature = hunk.final_signature();
+                    (signature.name().unwrap_or_default().to_string(), signature.when().seconds())
+                })
+        })
+        .collect()
+}
+
+/// Blame the working-tree `source` of `file_path` against the repository containing it
+pub fn blame_lines(file_path: &Path, source: &str) -> Result<Vec<Option<(String, i64)>>> {
+    let repo = git2::Repository::discover(file_path.parent().unwrap_or(Path::new(".")))
+        .with_context(|| format!("{} is not inside a git repository", file_path.display()))?;
+    let workdir = repo.workdir().context("Repository has no working tree")?.canonicalize()?;
+    let absolute_path = file_path.canonicalize()?;
+    let relative_path = absolute_path.strip_prefix(&workdir)
+        .with_context(|| format!("{} is outside {}", file_path.display(), workdir.display()))?;
+
+    // Blaming the buffer maps HEAD's history onto the file as it is on disk
+    let blame = repo.blame_file(relative_path, None)?.blame_buffer(source.as_bytes())?;
+    Ok(blame_by_line(&blame, source))
+}
+
+/// Blame `file_path` (a path under `repo_path`) as of `commit_id`
+pub fn blame_lines_at_revision(repo_path: &Path, commit_id: &str, file_path: &Path, source: &str) -> Result<Vec<Option<(String, i64)>>> {
+    let repo = git2::Repository::open(repo_path)
+        .with_context(|| format!("Failed to open git repository {}", repo_path.display()))?;
+    let relative_path = file_path.strip_prefix(repo_path)
+        .with_context(|| format!("{} is outside {}", file_path.display(), repo_path.display()))?;
+    let mut options = git2::BlameOptions::new();
+    options.newest_commit(git2::Oid::from_str(commit_id)?);
+    let blame = repo.blame_file(relative_path, Some(&mut options))?;
+    Ok(blame_by_line(&blame, source))
+}
+
+/// A public item of a crate, identified by its path within the crate
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PublicItem {
+    pub path: String,         // e.g. `shapes::Circle::area` (module from the file, then enclosing scopes)
+    pub element_type: String, // `function`, `struct`
+    pub file_path: String,    // Repository-relative
+}
+
+/// Public API surface difference between two revisions, written as `api_changes.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiChanges {
+    pub old_revision: String,
+    pub new_revision: String,
+    pub changed: bool,
+    pub added: Vec<PublicItem>,
+    pub removed: Vec<PublicItem>,
+}
+
+/// Module path of a file relative to `src/`: `shapes/mod.rs` and `shapes.rs` → `shapes`
+fn module_path_of(relative: &Path) -> Vec<String> {
+    let mut components: Vec<String> = relative.with_extension("")
+        .iter()
+        .map(|component| component.to_string_lossy().into_owned())
+        .collect();
+    if matches!(components.last().map(String::as_str), Some("lib" | "main" | "mod")) {
+        components.pop();
+    }
+    components
+}
+
+/// Element types reported as public items; other records are never API items
+const PUBLIC_ITEM_ELEMENT_TYPES: &[&str] = &["function", "struct"];
+
+/// Item path prefix of a parsing record: crate, file module and enclosing scopes
+/// 
+/// Files under `<member>/src/` of a workspace are prefixed with the member's
+/// directory name; files of the root package (`src/`) get no prefix. `None`
+/// for files outside any `src/` directory (tests, examples, build scripts).
+fn record_scope_path(repo_path: &Path, record: &RustAnalyzerRecord) -> Option<(String, Vec<String>)> {
+    let relative = Path::new(&record.file_path).strip_prefix(repo_path).ok()?;
+    let components: Vec<&std::ffi::OsStr> = relative.iter().collect();
+    let src_index = components.iter().position(|component| *component == "src")?;
+    let mut path: Vec<String> = components[..src_index].last()
+        .map(|member| member.to_string_lossy().into_owned())
+        .into_iter()
+        .collect();
+    path.extend(module_path_of(&components[src_index + 1..].iter().collect::<PathBuf>()));
+    path.extend(record.enclosing_path.iter().flat_map(|scopes| scopes.split("::")).map(str::to_string));
+    Some((relative.display().to_string(), path))
+}
+
+/// Name of the module declared by `line` and whether it is plain `pub`
+/// 
+/// `pub mod shapes;` gives `("shapes", true)`, `pub(crate) mod util {` gives `("util", false)`.
+fn module_declaration(line: &str) -> Option<(String, bool)> {
+    let line = line.trim_start();
+    let (rest, public) = match line.strip_prefix("pub") {
+        Some(rest) if rest.starts_with(' ') => (rest.trim_start(), true),
+        Some(rest) if rest.starts_with('(') => (rest[rest.find(')')? + 1..].trim_start(), false),
+        _ => (line, false),
+    };
+    let name: String = rest.strip_prefix("mod ")?
+        .trim_start()
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    (!name.is_empty()).then_some((name, public))
+}
+
+/// Named `pub` items (functions and structs) reachable from a crate root at `revision`
+/// 
+/// Only plain `pub` counts; `pub(crate)` and friends are not part of the API,
+/// and neither is a `pub` item inside a module that is not itself `pub`.
+/// Workspace members under `<member>/src/` are included with their item paths
+/// prefixed by the member directory (`shapes_core::Circle`), since the package
+/// name is not read from the member's `Cargo.toml`. Items are sorted by path.
+pub fn public_items_at_revision(repo_path: &Path, revision: &str) -> Result<Vec<PublicItem>> {
+    let mut extractor = RustAnalyzerExtractor::new()?.with_git_revision(repo_path, revision)?;
+    let records = extractor.process_codebase(repo_path, &[ProcessingPhase::Parsing])?;
+
+    // Modules declared without plain `pub`, by full path; their items are not exported
+    let private_modules: HashSet<Vec<String>> = records.iter()
+        .filter_map(|record| {
+            let (name, public) = module_declaration(&record.source_snippet)?;
+            let (_, mut path) = record_scope_path(repo_path, record)?;
+            path.push(name);
+            (!public).then_some(path)
+        })
+        .collect();
+
+    let mut items: Vec<PublicItem> = records.iter()
+        .filter(|record| PUBLIC_ITEM_ELEMENT_TYPES.contains(&record.element_type.as_str()))
+        .filter(|record| record.source_snippet.trim_start().starts_with("pub "))
+        .filter_map(|record| {
+            // Generic parameters are not part of the name: `fn map<T>(` → `map`
+            let name = record.element_name.as_deref()?.split('<').next()?.trim();
+            let (file_path, mut path) = record_scope_path(repo_path, record)?;
+            if (1..=path.len()).any(|len| private_modules.contains(&path[..len])) {
+                return None;
+            }
+            path.push(name.to_string());
+            (!name.is_empty()).then(|| PublicItem {
+                path: path.join("::"),
+                element_type: record.element_type.clone(),
+                file_path,
+            })
+        })
+        .collect();
+    items.sort();
+    items.dedup();
+    Ok(items)
+}
+
+/// Public items added and removed between `old_revision` and `new_revision`
+/// 
+/// Items are matched by path and element type, so moving an item to another
+/// file without changing its module path is not a change.
+pub fn compare_api_at_revisions(repo_path: &Path, old_revision: &str, new_revision: &str) -> Result<ApiChanges> {
+    let by_key = |items: Vec<PublicItem>| -> BTreeMap<(String, String), PublicItem> {
+        items.into_iter().map(|item| ((item.path.clone(), item.element_type.clone()), item)).collect()
+    };
+    let old_items = by_key(public_items_at_revision(repo_path, old_revision)?);
+    let new_items = by_key(public_items_at_revision(repo_path, new_revision)?);
+
+    let added: Vec<PublicItem> = new_items.iter()
+        .filter(|(key, _)| !old_items.contains_key(*key))
+        .map(|(_, item)| item.clone())
+        .collect();
+    let removed: Vec<PublicItem> = old_items.iter()
+        .filter(|(key, _)| !new_items.contains_key(*key))
+        .map(|(_, item)| item.clone())
+        .collect();
+    Ok(ApiChanges {
+        old_revision: old_revision.to_string(),
+        new_revision: new_revision.to_string(),
+        changed: !added.is_empty() || !removed.is_empty(),
+        added,
+        removed,
+    })
+}
+
+/// Read `id` → `source_hash` from the Parquet files of one phase directory
+/// 
+/// A missing directory yields an empty map; datasets written before
+/// `source_hash` existed map every id to an empty hash.
+pub fn load_phase_hashes(phase_dir: &Path) -> Result<HashMap<String, String>> {
+    use arrow::array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let mut hashes = HashMap::new();
+    if !phase_dir.is_dir() {
+        return Ok(hashes);
+    }
+    for entry in std::fs::read_dir(phase_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("parquet") {
+            continue;
+        }
+        let file = std::fs::File::open(&path).with_context(|| format!("Failed to open {}", path.display()))?;
+        for batch in ParquetRecordBatchReaderBuilder::try_new(file)?.build()? {
+            let batch = batch?;
+            let string_column = |name: &str| batch.column_by_name(name)
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+                .cloned();
+            let ids = string_column("id")
+                .ok_or_else(|| anyhow::anyhow!("{} has no id column", path.display()))?;
+            let source_hashes = string_column("source_hash");
+            for row in 0..batch.num_rows() {
+                let hash = source_hashes.as_ref()
+                    .filter(|hashes| !hashes.is_null(row))
+                    .map(|hashes| hashes.value(row).to_string())
+                    .unwrap_or_default();
+                hashes.insert(ids.value(row).to_string(), hash);
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+/// `load_phase_hashes` for every phase directory of `dataset_dir`, keyed by its relative path
+/// 
+/// Keys look like `parsing-phase` or `src_dir=src/parsing-phase`; the
+/// dataset's own `delta/` is left out. Loaded before a run writes anything,
+/// so diffing still works when the previous dataset is the output directory.
+pub fn load_dataset_hashes(dataset_dir: &Path) -> Result<HashMap<String, HashMap<String, String>>> {
+    let mut hashes = HashMap::new();
+    if !dataset_dir.is_dir() {
+        return Ok(hashes);
+    }
+    for part in crate::partitioned_dataset::PartitionedDataset::open(dataset_dir)?.parts() {
+        let directory = part.directory();
+        if !directory.ends_with("-phase") {
+            continue;
+        }
+        if !hashes.contains_key(directory) {
+            hashes.insert(directory.to_string(), load_phase_hashes(&dataset_dir.join(directory))?);
+        }
+    }
+    Ok(hashes)
+}
+
+/// Split a phase's records against a previous run into added/changed records and removed ids
+pub fn diff_records(records: &[RustAnalyzerRecord], previous: &HashMap<String, String>) -> (Vec<RustAnalyzerRecord>, Vec<String>) {
+    let changed: Vec<RustAnalyzerRecord> = records.iter()
+        .filter(|record| previous.get(&record.id) != Some(&record.source_hash))
+        .cloned()
+        .collect();
+    let current: std::collections::HashSet<&str> = records.iter().map(|r| r.id.as_str()).collect();
+    let mut removed: Vec<String> = previous.keys()
+        .filter(|id| !current.contains(id.as_str()))
+        .cloned()
+        .collect();
+    removed.sort();
+    (changed, removed)
+}
+
+/// Format an element type histogram as `function: 1200, variable: 3400, ...`, most frequent first
+pub fn format_element_type_histogram(counts: &BTreeMap<String, usize>) -> String {
+    let mut entries: Vec<(&String, &usize)> = counts.iter().collect();
+    entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    entries.iter()
+        .map(|(element_type, count)| format!("{}: {}", element_type, count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Confidence levels assigned to mock type inference results
+/// 
+/// Without a real type checker the extractor can only guess types from the
+/// source text, so each guess is tagged with how certain it is. The defaults
+/// reflect that an explicit annotation is authoritative, a literal is nearly
+/// so (integer/float defaults aside), a well-known std constructor leaves
+/// generic parameters open, and anything else is not deducible.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypeConfidenceModel {
+    pub explicit_annotation: f32,   // `let x: T` or `-> T`
+    pub literal: f32,               // `let x = 5;`, `let c = 'a';`
+    pub std_constructor: f32,       // `let v = Vec::new();`, `let s = format!(..);`
+    pub unknown: f32,               // Nothing deducible from the text
+}
+
+impl Default for TypeConfidenceModel {
+    fn default() -> Self {
+        Self {
+            explicit_annotation: 1.0,
+            literal: 0.8,
+            std_constructor: 0.6,
+            unknown: 0.0,
+        }
+    }
+}
+
+/// Glob-based predicate selecting which discovered Rust files are analyzed
+/// 
+/// Patterns are matched against paths relative to the codebase root
+/// (e.g. `src/**/handlers/*.rs`). A file is kept when it matches any include
+/// pattern (or no include patterns were given) and matches no exclude pattern.
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl FileFilter {
+    /// Build a filter from include/exclude glob patterns
+    pub fn new(include_globs: &[String], exclude_globs: &[String]) -> Result<Self> {
+        Ok(Self {
+            include: Self::build_set(include_globs)?,
+            exclude: Self::build_set(exclude_globs)?,
+        })
+    }
+
+    fn build_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+        if patterns.is_empty() {
+            return Ok(None);
+        }
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            builder.add(Glob::new(pattern).with_context(|| format!("Invalid glob pattern: {}", pattern))?);
+        }
+        Ok(Some(builder.build()?))
+    }
+
+    /// Whether a file (relative to the codebase root) should be analyzed
+    pub fn matches(&self, relative_path: &Path) -> bool {
+        let included = self.include.as_ref().map_or(true, |set| set.is_match(relative_path));
+        let excluded = self.exclude.as_ref().map_or(false, |set| set.is_match(relative_path));
+        included && !excluded
+    }
+}
+
 /// Main extractor for rust-analyzer semantic analysis data
 /// 
 /// This is the primary interface for extracting semantic analysis information
@@ -350,6 +1234,30 @@ pub struct RustAnalyzerExtractor {
     rust_version: String,            // Version of Rust toolchain
     analyzer_version: String,        // Version of rust-analyzer
     processing_order: u32,           // Counter for processing order
+    confidence_model: TypeConfidenceModel, // Confidence levels for type inference
+    files_found: usize,              // Number of source files found in the last run
+    skipped_files: Vec<SkippedFile>, // Files skipped or truncated in the last run
+    output: ParquetOutput,           // Columns, schemas and compression of written Parquet files
+    file_filter: Option<FileFilter>, // Include/exclude globs applied to discovered files
+    phases_run: Vec<ProcessingPhase>, // Phases requested in the last run (for provenance)
+    fail_fast: bool,                 // Abort on the first per-file error instead of skipping
+    element_type_counts: BTreeMap<String, usize>, // Records per element_type in the last run
+    diff_against: Option<PathBuf>,   // Previous dataset to diff against (writes delta/)
+    max_file_bytes: Option<u64>,     // Size cap for source files (None = unlimited)
+    oversize_action: OversizeAction, // Handling of files over the size cap
+    in_memory_sources: Option<BTreeMap<PathBuf, Vec<u8>>>, // Sources read from a git revision instead of disk
+    git_revision: Option<(PathBuf, String)>, // Repository and commit id of the analyzed revision
+    min_records: Option<usize>,      // Fail the run when fewer records are produced across all phases
+    element_types: Option<HashSet<String>>, // Only emit records of these element types (None = all)
+    excluded_element_types: HashSet<String>, // Never emit records of these element types
+    emit_coverage: bool,             // Write line_coverage.json mapping source lines to record ids
+    phase_write_budget: Option<u64>, // Write phases concurrently, holding at most this many record bytes (None = sequential)
+    jobs: usize,                     // Threads busy at once, extraction included (1 = everything serial)
+    run_limits: crate::config::RunLimits, // Time and memory bounds checked between files
+    throughput: Option<ThroughputStats>, // Volume and speed of the last run
+    id_template: RecordIdTemplate,   // Format of record ids
+    with_blame: bool,                // Fill blame_author/blame_timestamp from git blame
+    blame_cache: HashMap<PathBuf, Vec<Option<(String, i64)>>>, // Per-line blame of files seen in this run
 }
 
 impl RustAnalyzerExtractor {
@@ -364,39 +1272,274 @@ impl RustAnalyzerExtractor {
             rust_version: Self::get_rust_version()?,
             analyzer_version: Self::get_analyzer_version()?,
             processing_order: 0,
+            confidence_model: TypeConfidenceModel::default(),
+            files_found: 0,
+            skipped_files: Vec::new(),
+            output: ParquetOutput::default(),
+            file_filter: None,
+            phases_run: Vec::new(),
+            fail_fast: false,
+            element_type_counts: BTreeMap::new(),
+            diff_against: None,
+            max_file_bytes: None,
+            oversize_action: OversizeAction::Skip,
+            in_memory_sources: None,
+            git_revision: None,
+            min_records: None,
+            element_types: None,
+            excluded_element_types: HashSet::new(),
+            emit_coverage: false,
+            phase_write_budget: None,
+            jobs: crate::config::default_jobs(),
+            run_limits: crate::config::RunLimits::default(),
+            throughput: None,
+            id_template: RecordIdTemplate::default(),
+            with_blame: false,
+            blame_cache: HashMap::new(),
         })
     }
 
-    /// Get the current Rust toolchain version
-    /// 
-    /// In a real implementation, this would query the actual Rust installation
-    /// to get the precise version being used for analysis.
-    fn get_rust_version() -> Result<String> {
-        // TODO: Query actual Rust version with: rustc --version
-        Ok("1.86.0".to_string())
+    /// Use a custom confidence model for type inference records
+    pub fn with_confidence_model(mut self, confidence_model: TypeConfidenceModel) -> Self {
+        self.confidence_model = confidence_model;
+        self
     }
 
-    /// Get the current rust-analyzer version
-    /// 
-    /// In a real implementation, this would query the rust-analyzer binary
-    /// or library to get the exact version being used.
-    fn get_analyzer_version() -> Result<String> {
-        // TODO: Query actual rust-analyzer version
-        Ok("0.3.2000".to_string())
+    /// Abort the run on the first unreadable or unparseable file instead of skipping it
+    pub fn with_fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
     }
 
-    /// Process a Rust codebase and generate Parquet files for HuggingFace dataset
+    /// Skip or truncate source files larger than `max_file_bytes`
     /// 
-    /// This is the main entry point for generating semantic analysis datasets.
-    /// It processes all Rust files in the given codebase through the specified
-    /// analysis phases and outputs the results as Parquet files suitable for
-    /// machine learning applications.
+    /// Keeps a single huge generated file from dominating the dataset.
+    pub fn with_max_file_bytes(mut self, max_file_bytes: u64, oversize_action: OversizeAction) -> Self {
+        self.max_file_bytes = Some(max_file_bytes);
+        self.oversize_action = oversize_action;
+        self
+    }
+
+    /// Fail the run when fewer than `min_records` records are produced across all phases
     /// 
-    /// # Arguments
+    /// Catches extractions that silently produce almost nothing, such as a bad
+    /// path or a parser regression. The dataset and manifest are still written.
+    pub fn with_min_records(mut self, min_records: usize) -> Self {
+        self.min_records = Some(min_records);
+        self
+    }
+
+    /// Also write `line_coverage.json`, mapping each source line of each file to the ids of its records
     /// 
-    /// * `codebase_path` - Path to the root of the Rust codebase to analyze
-    /// * `phases` - List of processing phases to run (e.g., parsing, name resolution)
-    /// * `output_dir` - Directory where Parquet files will be written
+    /// Lets tools overlay the dataset on the source, e.g. to highlight lines
+    /// that produced no records.
+    pub fn with_coverage(mut self, emit_coverage: bool) -> Self {
+        self.emit_coverage = emit_coverage;
+        self
+    }
+
+    /// Write each phase's Parquet files on a background thread while the next phase is extracted
+    /// 
+    /// Records waiting to be written are kept under `memory_budget_bytes`
+    /// (estimated from their string contents) by waiting for earlier writes.
+    /// The output is identical to a sequential run.
+    pub fn with_concurrent_phase_writes(mut self, memory_budget_bytes: u64) -> Self {
+        self.phase_write_budget = Some(memory_budget_bytes);
+        self
+    }
+
+    /// Keep at most `jobs` threads busy, counting the extracting thread
+    /// 
+    /// Bounds the phase writes in flight under `with_concurrent_phase_writes`
+    /// to `jobs - 1`; `jobs = 1` writes every phase on the calling thread.
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// Stop `process_codebase_to_parquet` with a checkpoint once `limits` are exceeded
+    pub fn with_run_limits(mut self, limits: crate::config::RunLimits) -> Self {
+        self.run_limits = limits;
+        self
+    }
+
+    /// Format record ids with `id_template` instead of `{path}:{line}:{phase}`
+    pub fn with_id_template(mut self, id_template: RecordIdTemplate) -> Self {
+        self.id_template = id_template;
+        self
+    }
+
+    /// Record the author and time of the last commit touching each record's line
+    /// 
+    /// Runs `git blame` once per file, so it is off by default.
+    pub fn with_blame(mut self, with_blame: bool) -> Self {
+        self.with_blame = with_blame;
+        self
+    }
+
+    /// Lay out written Parquet files by `partition_by` instead of one directory per phase
+    pub fn with_partition_by(mut self, partition_by: PartitionBy) -> Self {
+        self.output.partition_by = partition_by;
+        self
+    }
+
+    /// Compress written Parquet files with these settings instead of Snappy
+    pub fn with_write_options(mut self, write_options: ParquetWriteOptions) -> Self {
+        self.output.write_options = write_options;
+        self
+    }
+
+    /// Only emit records whose `element_type` is in `element_types` (e.g. `function`, `struct`)
+    pub fn with_element_types(mut self, element_types: impl IntoIterator<Item = String>) -> Self {
+        self.element_types = Some(element_types.into_iter().collect());
+        self
+    }
+
+    /// Drop records whose `element_type` is in `element_types`
+    pub fn with_excluded_element_types(mut self, element_types: impl IntoIterator<Item = String>) -> Self {
+        self.excluded_element_types = element_types.into_iter().collect();
+        self
+    }
+
+    /// Whether records of `element_type` pass the allowlist and denylist
+    fn keeps_element_type(&self, element_type: &str) -> bool {
+        self.element_types.as_ref().is_none_or(|allowed| allowed.contains(element_type))
+            && !self.excluded_element_types.contains(element_type)
+    }
+
+    /// Analyze the `.rs` files of `revision` in the repository at `repo_path` instead of the working tree
+    /// 
+    /// Sources are read from the object database, so nothing is checked out.
+    /// Record paths are the same as for a checkout at `repo_path`.
+    pub fn with_git_revision(mut self, repo_path: &Path, revision: &str) -> Result<Self> {
+        let (commit_id, sources) = read_rust_sources_at_revision(repo_path, revision)?;
+        println!("Reading {} Rust files from {} at {}", sources.len(), repo_path.display(), commit_id);
+        self.in_memory_sources = Some(sources.into_iter().map(|(path, content)| (repo_path.join(path), content)).collect());
+        self.git_revision = Some((repo_path.to_path_buf(), commit_id));
+        Ok(self)
+    }
+
+    /// Number of records per `element_type` produced during the last run
+    pub fn element_type_counts(&self) -> &BTreeMap<String, usize> {
+        &self.element_type_counts
+    }
+
+    /// Files that could not be processed (or were truncated) during the last run, with reasons
+    pub fn skipped_files(&self) -> &[SkippedFile] {
+        &self.skipped_files
+    }
+
+    /// Warn when the id template produced the same id for several records
+    fn warn_duplicate_ids(&self, records: &[RustAnalyzerRecord]) {
+        let duplicates = count_duplicate_ids(records);
+        if duplicates > 0 {
+            println!(
+                "⚠️  {} records share an id under the template {}; add {{col}} or {{order}} to make ids unique",
+                duplicates, self.id_template.as_str()
+            );
+        }
+    }
+
+    /// Records, source bytes and rates of the last run
+    pub fn throughput(&self) -> Option<&ThroughputStats> {
+        self.throughput.as_ref()
+    }
+
+    /// Compute and report the throughput of a run over `rust_files` started at `started`
+    /// 
+    /// Skipped files contribute no bytes; truncated files count only up to the cap.
+    fn finish_throughput(&mut self, rust_files: &[PathBuf], total_records: usize, started: Instant) {
+        let source_bytes = rust_files.iter()
+            .filter(|file| !self.skipped_files.iter().any(|skipped| !skipped.truncated && skipped.path == file.to_string_lossy()))
+            .map(|file| {
+                let size_bytes = self.source_len(file).unwrap_or(0);
+                match (self.max_file_bytes, self.oversize_action) {
+                    (Some(max_file_bytes), OversizeAction::Truncate) => size_bytes.min(max_file_bytes),
+                    _ => size_bytes,
+                }
+            })
+            .sum();
+        let throughput = ThroughputStats::new(total_records, source_bytes, started.elapsed());
+        println!("Throughput: {}", throughput.summary());
+        self.throughput = Some(throughput);
+    }
+
+    /// Write the manifest (including skipped files) and provenance for the last run into `output_dir`
+    pub fn write_manifest(&self, output_dir: &Path) -> Result<()> {
+        write_run_manifest(output_dir, self.files_found, &self.skipped_files, &self.element_type_counts, self.throughput.as_ref())?;
+        let phases: Vec<&str> = self.phases_run.iter().map(ProcessingPhase::as_str).collect();
+        write_provenance(output_dir, &Provenance::capture(&phases, &self.rust_version))
+    }
+
+    /// Extract one phase from one file, recording the file as skipped on failure
+    /// 
+    /// Returns an empty record set for skipped files so a single unreadable or
+    /// unparseable file does not abort the whole run, unless fail-fast is enabled.
+    fn extract_phase_data_or_skip(&mut self, file_path: &Path, phase: &ProcessingPhase) -> Result<Vec<RustAnalyzerRecord>> {
+        if let Some(max_file_bytes) = self.max_file_bytes {
+            let size_bytes = self.source_len(file_path).unwrap_or(0);
+            if size_bytes > max_file_bytes {
+                let path = file_path.to_string_lossy();
+                if !self.skipped_files.iter().any(|f| f.path == path) {
+                    self.skipped_files.push(match self.oversize_action {
+                        OversizeAction::Skip => SkippedFile::oversized(file_path, size_bytes, max_file_bytes),
+                        OversizeAction::Truncate => SkippedFile::truncated(file_path, size_bytes, max_file_bytes),
+                    });
+                }
+                if self.oversize_action == OversizeAction::Skip {
+                    return Ok(Vec::new());
+                }
+            }
+        }
+
+        match self.extract_phase_data(file_path, phase) {
+            Ok(records) => {
+                for record in &records {
+                    *self.element_type_counts.entry(record.element_type.clone()).or_insert(0) += 1;
+                }
+                Ok(records)
+            }
+            Err(e) if self.fail_fast => Err(e.context(format!("Aborting on {} (--fail-fast)", file_path.display()))),
+            Err(e) => {
+                let path = file_path.to_string_lossy();
+                if !self.skipped_files.iter().any(|f| f.path == path) {
+                    self.skipped_files.push(SkippedFile::from_error(file_path, &e));
+                }
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Get the current Rust toolchain version
+    /// 
+    /// In a real implementation, this would query the actual Rust installation
+    /// to get the precise version being used for analysis.
+    fn get_rust_version() -> Result<String> {
+        // TODO: Query actual Rust version with: rustc --version
+        Ok("1.86.0".to_string())
+    }
+
+    /// Get the current rust-analyzer version
+    /// 
+    /// In a real implementation, this would query the rust-analyzer binary
+    /// or library to get the exact version being used.
+    fn get_analyzer_version() -> Result<String> {
+        // TODO: Query actual rust-analyzer version
+        Ok("0.3.2000".to_string())
+    }
+
+    /// Process a Rust codebase and generate Parquet files for HuggingFace dataset
+    /// 
+    /// This is the main entry point for generating semantic analysis datasets.
+    /// It processes all Rust files in the given codebase through the specified
+    /// analysis phases and outputs the results as Parquet files suitable for
+    /// machine learning applications.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `codebase_path` - Path to the root of the Rust codebase to analyze
+    /// * `phases` - List of processing phases to run (e.g., parsing, name resolution)
+    /// * `output_dir` - Directory where Parquet files will be written
     /// 
     /// # File Organization
     /// 
@@ -412,148 +1555,273 @@ impl RustAnalyzerExtractor {
     ///     └── data.parquet
     /// ```
     /// 
+    /// With `with_diff_against`, a `delta/` directory additionally holds the
+    /// same phase layout with only added/changed records, plus `removed_ids.json`.
+    /// 
+    /// `with_partition_by` changes the layout: `PartitionBy::SourceDir` nests
+    /// the phase directories under `src_dir={dir}/` per top-level source
+    /// directory, `PartitionBy::None` writes `{phase}.parquet` files directly.
+    /// 
     /// # Performance Considerations
     /// 
     /// - Files are automatically split if they exceed 9MB to stay under Git LFS limits
     /// - Processing is done in batches to manage memory usage
     /// - Progress is reported every 100 files for large codebases
+    /// - With `with_concurrent_phase_writes`, each phase is written on its own
+    ///   thread while the next phase is extracted, up to `with_jobs - 1` at once
+    /// - With `with_run_limits`, limits are checked after every file and before
+    ///   each phase after the first; on a breach pending writes finish,
+    ///   `checkpoint.json` is written and an error returned
     pub fn process_codebase_to_parquet(&mut self, codebase_path: &Path, phases: &[ProcessingPhase], output_dir: &Path) -> Result<()> {
+        let started = Instant::now();
         let rust_files = self.find_rust_files(codebase_path)?;
         println!("Found {} Rust files to process", rust_files.len());
+        self.files_found = rust_files.len();
+        self.skipped_files.clear();
+        self.element_type_counts.clear();
+        self.blame_cache.clear();
+        self.phases_run = phases.to_vec();
 
         // Create output directory structure
         std::fs::create_dir_all(output_dir)?;
+        // A checkpoint left by an earlier interrupted run no longer describes this output
+        let checkpoint_path = output_dir.join(CHECKPOINT_FILE);
+        if checkpoint_path.exists() {
+            std::fs::remove_file(&checkpoint_path)?;
+        }
 
-        // Process each phase separately to manage memory usage
-        // and allow for phase-specific optimizations
-        for phase in phases {
-            println!("Processing phase: {:?}", phase);
-            let mut phase_records = Vec::new();
-
-            // Process all files for this phase
-            for (file_index, rust_file) in rust_files.iter().enumerate() {
-                // Report progress for large codebases
-                if file_index % 100 == 0 {
-                    println!("Processing file {}/{}: {}", file_index + 1, rust_files.len(), rust_file.display());
+        // Read the previous dataset before writing, as it may be `output_dir` itself
+        let previous_hashes = self.diff_against.as_deref().map(load_dataset_hashes).transpose()?;
+        let previous_hashes = previous_hashes.as_ref();
+
+        // Ids present in the previous run but not this one (diff mode only)
+        let mut removed_ids: Vec<String> = Vec::new();
+        let mut total_records = 0;
+        let mut coverage = LineCoverage::new();
+
+        // Phases are extracted one after another, so processing_order is the
+        // same whether or not the writes run concurrently
+        std::thread::scope(|scope| -> Result<()> {
+            // Concurrent writes still in progress, oldest first, with their estimated record bytes
+            let mut pending_writes = VecDeque::new();
+            let mut pending_bytes = 0u64;
+
+            // Process each phase separately to manage memory usage
+            // and allow for phase-specific optimizations
+            for (phase_index, phase) in phases.iter().enumerate() {
+                // Also checked between phases, so a run over its limits does not
+                // start the next phase once the previous one has been written
+                if phase_index > 0 {
+                    if let Some(reason) = self.run_limits.breach(started) {
+                        for (_, handle) in pending_writes.drain(..) {
+                            removed_ids.extend(join_phase_write(handle)?);
+                        }
+                        return Err(stop_with_checkpoint(&checkpoint_path, ExtractionCheckpoint {
+                            reason,
+                            completed_phases: phases[..phase_index].iter().map(|p| p.as_str().to_string()).collect(),
+                            interrupted_phase: phase.as_str().to_string(),
+                            files_done: 0,
+                            total_files: rust_files.len(),
+                            next_file: rust_files.first().map(|f| f.display().to_string()),
+                            elapsed_secs: started.elapsed().as_secs_f64(),
+                        })?);
+                    }
                 }
-                
-                // Extract semantic analysis data for this phase
-                let file_records = self.extract_phase_data(rust_file, phase)?;
-                phase_records.extend(file_records);
+
+                println!("Processing phase: {:?}", phase);
+                let mut phase_records = Vec::new();
+
+                // Process all files for this phase
+                for (file_index, rust_file) in rust_files.iter().enumerate() {
+                    // Report progress for large codebases
+                    if file_index % 100 == 0 {
+                        println!("Processing file {}/{}: {}", file_index + 1, rust_files.len(), rust_file.display());
+                    }
+
+                    // Extract semantic analysis data for this phase
+                    let file_records = self.extract_phase_data_or_skip(rust_file, phase)?;
+                    phase_records.extend(file_records);
+
+                    if let Some(reason) = self.run_limits.breach(started) {
+                        // Earlier phases finish writing, so they count as completed
+                        for (_, handle) in pending_writes.drain(..) {
+                            removed_ids.extend(join_phase_write(handle)?);
+                        }
+                        return Err(stop_with_checkpoint(&checkpoint_path, ExtractionCheckpoint {
+                            reason,
+                            completed_phases: phases[..phase_index].iter().map(|p| p.as_str().to_string()).collect(),
+                            interrupted_phase: phase.as_str().to_string(),
+                            files_done: file_index + 1,
+                            total_files: rust_files.len(),
+                            next_file: rust_files.get(file_index + 1).map(|f| f.display().to_string()),
+                            elapsed_secs: started.elapsed().as_secs_f64(),
+                        })?);
+                    }
+                }
+
+                println!("Generated {} records for phase {:?}", phase_records.len(), phase);
+                self.warn_duplicate_ids(&phase_records);
+                total_records += phase_records.len();
+                if self.emit_coverage {
+                    add_line_coverage(&mut coverage, &phase_records);
+                }
+
+                // Write records to Parquet files (automatically split if needed)
+                let Some(memory_budget) = self.phase_write_budget.filter(|_| self.jobs > 1) else {
+                    let removed = self.output.write_phase_with_delta(&phase_records, phase, output_dir, previous_hashes, codebase_path)?;
+                    removed_ids.extend(removed);
+                    continue;
+                };
+
+                // Wait for earlier phases to be written until this one fits the budget
+                // and a job is free; a phase larger than the whole budget is written
+                // once nothing else is pending
+                let phase_bytes = estimate_records_bytes(&phase_records);
+                while pending_bytes.saturating_add(phase_bytes) > memory_budget || pending_writes.len() >= self.jobs - 1 {
+                    let Some((bytes, handle)) = pending_writes.pop_front() else {
+                        break;
+                    };
+                    pending_bytes -= bytes;
+                    removed_ids.extend(join_phase_write(handle)?);
+                }
+
+                let output = self.output.clone();
+                let phase = phase.clone();
+                let handle = scope.spawn(move || {
+                    output.write_phase_with_delta(&phase_records, &phase, output_dir, previous_hashes, codebase_path)
+                });
+                pending_bytes += phase_bytes;
+                pending_writes.push_back((phase_bytes, handle));
             }
 
-            println!("Generated {} records for phase {:?}", phase_records.len(), phase);
+            // Joined in phase order, so removed ids are listed as in a sequential run
+            for (_, handle) in pending_writes {
+                removed_ids.extend(join_phase_write(handle)?);
+            }
+            Ok(())
+        })?;
 
-            // Write records to Parquet files (automatically split if needed)
-            self.write_phase_to_parquet(&phase_records, phase, output_dir)?;
+        if self.diff_against.is_some() {
+            let delta_dir = output_dir.join("delta");
+            std::fs::create_dir_all(&delta_dir)?;
+            std::fs::write(delta_dir.join("removed_ids.json"), serde_json::to_string_pretty(&removed_ids)?)?;
         }
 
-        Ok(())
-    }
-
-    /// Write phase records to Parquet files, splitting if they exceed size limits
-    /// 
-    /// This method handles the conversion from our internal record format to
-    /// Parquet files suitable for Git LFS and HuggingFace datasets. It automatically
-    /// splits large datasets into multiple files to stay under the 10MB Git LFS
-    /// recommended limit.
-    /// 
-    /// # Size Management Strategy
-    /// 
-    /// 1. Write a small sample to estimate bytes per record
-    /// 2. Calculate maximum records per file based on 9MB limit (90% of 10MB for safety)
-    /// 3. Split into multiple files if necessary
-    /// 4. Use consistent naming: data.parquet or data-00000-of-00003.parquet
-    /// 
-    /// # Compression
-    /// 
-    /// Uses Snappy compression for optimal balance of compression ratio and
-    /// decompression speed, which is ideal for ML workloads.
-    fn write_phase_to_parquet(&self, records: &[RustAnalyzerRecord], phase: &ProcessingPhase, output_dir: &Path) -> Result<()> {
-        const MAX_FILE_SIZE_MB: usize = 9; // Stay under 10MB for Git LFS
-//        const RECORDS_PER_BATCH: usize = 1000; // Process in batches to estimate size
+        if self.emit_coverage {
+            std::fs::write(output_dir.join("line_coverage.json"), serde_json::to_string_pretty(&coverage)?)?;
+        }
 
-        let phase_dir = output_dir.join(format!("{}-phase", phase.as_str()));
-        std::fs::create_dir_all(&phase_dir)?;
+        // Report coverage gaps and throughput, and record them in the manifest
+        println!("Element types: {}", format_element_type_histogram(&self.element_type_counts));
+        print_skipped_summary(&self.skipped_files);
+        self.finish_throughput(&rust_files, total_records, started);
+        self.write_manifest(output_dir)?;
 
-        if records.is_empty() {
-            println!("No records for phase {:?}, skipping", phase);
-            return Ok(());
+        if let Some(min_records) = self.min_records.filter(|&min| total_records < min) {
+            return Err(anyhow::anyhow!(
+                "Extraction produced {} records across {} phases, below the minimum of {} (from {} Rust files in {})",
+                total_records, phases.len(), min_records, rust_files.len(), codebase_path.display()
+            ));
         }
 
-        // Estimate size per record by writing a small sample
-        // This helps us determine how many records can fit in each file
-        let sample_size = std::cmp::min(100, records.len());
-        let sample_records = &records[0..sample_size];
-        
-        let temp_file = phase_dir.join("temp_sample.parquet");
-        self.write_records_to_parquet(sample_records, &temp_file)?;
-        
-        let sample_size_bytes = std::fs::metadata(&temp_file)?.len();
-        std::fs::remove_file(&temp_file)?;
-        
-        // Calculate maximum records per file with 10% safety margin
-        let bytes_per_record = sample_size_bytes as f64 / sample_size as f64;
-        let max_records_per_file = ((MAX_FILE_SIZE_MB * 1024 * 1024) as f64 * 0.9 / bytes_per_record) as usize;
-        
-        println!("Estimated {} bytes per record, max {} records per file", bytes_per_record as usize, max_records_per_file);
+        Ok(())
+    }
 
-        if records.len() <= max_records_per_file {
-            // Single file case - all records fit in one file
-            let output_file = phase_dir.join("data.parquet");
-            self.write_records_to_parquet(records, &output_file)?;
-            
-            let file_size_mb = std::fs::metadata(&output_file)?.len() as f64 / (1024.0 * 1024.0);
-            println!("Created single file: {} ({:.2} MB)", output_file.display(), file_size_mb);
-        } else {
-            // Multiple files case - split into chunks
-            let num_files = (records.len() + max_records_per_file - 1) / max_records_per_file;
-            
-            for (file_idx, chunk) in records.chunks(max_records_per_file).enumerate() {
-                let output_file = phase_dir.join(format!("data-{:05}-of-{:05}.parquet", file_idx, num_files));
-                self.write_records_to_parquet(chunk, &output_file)?;
-                
-                let file_size_mb = std::fs::metadata(&output_file)?.len() as f64 / (1024.0 * 1024.0);
-                println!("Created chunk {}/{}: {} ({:.2} MB, {} records)", 
-                    file_idx + 1, num_files, output_file.display(), file_size_mb, chunk.len());
+    /// Project the Parquet output size of `phases` over `codebase_path` without generating it
+    /// 
+    /// Records are counted per file by running the phase's extractor and
+    /// element-type filters, then dropped, so nothing is held for the whole
+    /// codebase and the counts follow the extractors exactly. Full records
+    /// (scopes, blame, ids) are built only for the first files of each phase,
+    /// until there are enough to measure bytes per record the way
+    /// `write_phase` sizes its splits. Unreadable files count as empty.
+    pub fn estimate_dataset_size(&mut self, codebase_path: &Path, phases: &[ProcessingPhase]) -> Result<DatasetSizeEstimate> {
+        let rust_files = self.find_rust_files(codebase_path)?;
+        let scratch_dir = tempfile::TempDir::new()?;
+
+        let mut estimates = Vec::new();
+        for phase in phases {
+            let mut candidate_records = 0;
+            for path in &rust_files {
+                candidate_records += self.extract_filtered_records(path, phase).map_or(0, |records| records.len());
             }
+
+            let mut sample = Vec::new();
+            for path in &rust_files {
+                if sample.len() >= SIZE_SAMPLE_RECORDS {
+                    break;
+                }
+                sample.extend(self.extract_phase_data(path, phase).unwrap_or_default());
+            }
+            let bytes_per_record = if sample.is_empty() {
+                0.0
+            } else {
+                self.output.sample_bytes_per_record(&sample, phase, scratch_dir.path())?
+            };
+
+            estimates.push(PhaseSizeEstimate {
+                phase: phase.as_str().to_string(),
+                candidate_records,
+                bytes_per_record,
+                estimated_bytes: (candidate_records as f64 * bytes_per_record).round() as u64,
+            });
         }
 
-        Ok(())
+        Ok(DatasetSizeEstimate {
+            files: rust_files.len(),
+            total_bytes: estimates.iter().map(|estimate| estimate.estimated_bytes).sum(),
+            phases: estimates,
+        })
     }
 
-    /// Write records to a single Parquet file using Apache Arrow
+    /// Convert JSON phase datasets into Parquet files
     /// 
-    /// This method handles the low-level conversion from our Rust data structures
-    /// to Apache Arrow format and then to Parquet. It defines the schema and
-    /// handles all the type conversions necessary for efficient storage.
+    /// Datasets produced by the JSON path contain `{phase}-phase/data.json` files
+    /// holding a serialized `Vec<RustAnalyzerRecord>`. This reads each of them and
+    /// writes the Parquet split next to it, using the same schema and 9MB splitting
+    /// logic as `process_codebase_to_parquet`.
     /// 
-    /// # Schema Design
+    /// # Arguments
     /// 
-    /// The schema is designed to be:
-    /// - **Strongly typed**: Proper types for numeric and string data
-    /// - **Nullable where appropriate**: Optional fields can be null
-    /// - **ML-friendly**: Easy to load into pandas, polars, or other ML frameworks
-    /// - **Queryable**: Supports efficient filtering and aggregation
+    /// * `dataset_dir` - Directory containing the `{phase}-phase/` subdirectories
     /// 
-    /// # Compression Strategy
+    /// # Returns
     /// 
-    /// Uses Snappy compression which provides:
-    /// - Fast compression/decompression (important for ML workloads)
-    /// - Good compression ratio for text-heavy data
-    /// - Wide compatibility across Arrow/Parquet ecosystems
-    fn write_records_to_parquet(&self, records: &[RustAnalyzerRecord], output_file: &Path) -> Result<()> {
+    /// The total number of records converted across all phases
+    pub fn convert_json_phases_to_parquet(&self, dataset_dir: &Path) -> Result<usize> {
+        let mut total_records = 0;
+        for (phase, json_file) in json_phase_files(dataset_dir)? {
+            let records = read_json_phase(&json_file)?;
+
+            println!("Converting {} records for phase {:?}", records.len(), phase);
+            self.write_phase_to_parquet(&records, &phase, dataset_dir)?;
+            total_records += records.len();
+        }
+
+        Ok(total_records)
+    }
+
+    /// Write phase records to Parquet files, splitting if they exceed size limits (see `ParquetOutput::write_phase`)
+    fn write_phase_to_parquet(&self, records: &[RustAnalyzerRecord], phase: &ProcessingPhase, output_dir: &Path) -> Result<()> {
+        self.output.write_phase(records, phase, output_dir)
+    }
+
+    /// The canonical Arrow schema for rust-analyzer Parquet output
+    /// 
+    /// One column per `RustAnalyzerRecord` field, in declaration order.
+    pub fn parquet_schema() -> Arc<arrow::datatypes::Schema> {
         use arrow::datatypes::{DataType, Field, Schema};
 
         // Define the Arrow schema for our dataset
         // This schema is designed to be compatible with HuggingFace datasets
         // and efficient for machine learning workloads
-        let schema = Arc::new(Schema::new(vec![
+        Arc::new(Schema::new(vec![
             // === Identification Fields ===
             Field::new("id", DataType::Utf8, false),                    // Unique record ID
             Field::new("file_path", DataType::Utf8, false),             // Source file path
             Field::new("line", DataType::UInt32, false),                // Line number (1-based)
             Field::new("column", DataType::UInt32, false),              // Column number (1-based)
+            Field::new("source_hash", DataType::Utf8, false),           // Content hash for change detection
             
             // === Phase Information ===
             Field::new("phase", DataType::Utf8, false),                 // Processing phase name
@@ -563,12 +1831,17 @@ impl RustAnalyzerExtractor {
             Field::new("element_type", DataType::Utf8, false),          // Type of code element
             Field::new("element_name", DataType::Utf8, true),           // Element name (nullable)
             Field::new("element_signature", DataType::Utf8, true),      // Full signature (nullable)
+            Field::new("enclosing_path", DataType::Utf8, true),         // Enclosing module/impl/fn path (nullable)
             
             // === Semantic Analysis Data (JSON) ===
             Field::new("syntax_data", DataType::Utf8, true),            // Parsing results (JSON)
             Field::new("symbol_data", DataType::Utf8, true),            // Symbol resolution (JSON)
             Field::new("type_data", DataType::Utf8, true),              // Type inference (JSON)
             Field::new("diagnostic_data", DataType::Utf8, true),        // Diagnostics (JSON)
+            Field::new("signature_data", DataType::Utf8, true),         // Function signature (JSON)
+            Field::new("generics_data", DataType::Utf8, true),          // Generic bounds (JSON)
+            Field::new("lifetime_data", DataType::Utf8, true),          // Lifetimes and elision (JSON)
+            Field::new("parse_fallback", DataType::Boolean, false),     // File failed to parse with syn
             
             // === Processing Metadata ===
             Field::new("processing_time_ms", DataType::UInt64, false),  // Processing time
@@ -580,116 +1853,206 @@ impl RustAnalyzerExtractor {
             Field::new("source_snippet", DataType::Utf8, false),        // Source code line
             Field::new("context_before", DataType::Utf8, true),         // Previous line (nullable)
             Field::new("context_after", DataType::Utf8, true),          // Next line (nullable)
-        ]));
+            
+            // === Provenance ===
+            Field::new("blame_author", DataType::Utf8, true),           // Last commit author (nullable)
+            Field::new("blame_timestamp", DataType::Int64, true),       // Last commit time (nullable)
+        ]))
+    }
 
-        // Convert Rust data structures to Arrow arrays
-        // This is where we transform our semantic analysis data into
-        // the columnar format that Parquet expects
-        
-        // Extract all field values into separate vectors for Arrow conversion
-        let ids: Vec<String> = records.iter().map(|r| r.id.clone()).collect();
-        let file_paths: Vec<String> = records.iter().map(|r| r.file_path.clone()).collect();
-        let lines: Vec<u32> = records.iter().map(|r| r.line).collect();
-        let columns: Vec<u32> = records.iter().map(|r| r.column).collect();
-        let phases: Vec<String> = records.iter().map(|r| r.phase.clone()).collect();
-        let processing_orders: Vec<u32> = records.iter().map(|r| r.processing_order).collect();
-        let element_types: Vec<String> = records.iter().map(|r| r.element_type.clone()).collect();
-        let element_names: Vec<Option<String>> = records.iter().map(|r| r.element_name.clone()).collect();
-        let element_signatures: Vec<Option<String>> = records.iter().map(|r| r.element_signature.clone()).collect();
-        let syntax_data: Vec<Option<String>> = records.iter().map(|r| r.syntax_data.clone()).collect();
-        let symbol_data: Vec<Option<String>> = records.iter().map(|r| r.symbol_data.clone()).collect();
-        let type_data: Vec<Option<String>> = records.iter().map(|r| r.type_data.clone()).collect();
-        let diagnostic_data: Vec<Option<String>> = records.iter().map(|r| r.diagnostic_data.clone()).collect();
-        let processing_times: Vec<u64> = records.iter().map(|r| r.processing_time_ms).collect();
-        let timestamps: Vec<u64> = records.iter().map(|r| r.timestamp).collect();
-        let rust_versions: Vec<String> = records.iter().map(|r| r.rust_version.clone()).collect();
-        let analyzer_versions: Vec<String> = records.iter().map(|r| r.analyzer_version.clone()).collect();
-        let source_snippets: Vec<String> = records.iter().map(|r| r.source_snippet.clone()).collect();
-        let context_befores: Vec<Option<String>> = records.iter().map(|r| r.context_before.clone()).collect();
-        let context_afters: Vec<Option<String>> = records.iter().map(|r| r.context_after.clone()).collect();
+    /// Columns written for `phase` in phase-specialized mode
+    /// 
+    /// Every phase keeps the identification, metadata and context columns;
+    /// of the JSON analysis columns only those the phase populates are kept.
+    pub fn phase_columns(phase: &ProcessingPhase) -> Vec<String> {
+        let phase_specific: &[&str] = match phase {
+            ProcessingPhase::Parsing => &["syntax_data", "diagnostic_data"],
+            ProcessingPhase::NameResolution => &["syntax_data", "symbol_data", "signature_data", "generics_data", "lifetime_data"],
+            ProcessingPhase::TypeInference => &["type_data"],
+            ProcessingPhase::Diagnostics => &["diagnostic_data"],
+            _ => &["syntax_data", "symbol_data", "type_data", "diagnostic_data"],
+        };
+        const ANALYSIS_COLUMNS: &[&str] = &[
+            "syntax_data", "symbol_data", "type_data", "diagnostic_data", "signature_data", "generics_data", "lifetime_data",
+        ];
 
-        // Create Arrow arrays from the extracted data
-        // Arrow arrays are the columnar data structures that Parquet uses internally
-        let id_array = Arc::new(StringArray::from(ids));
-        let file_path_array = Arc::new(StringArray::from(file_paths));
-        let line_array = Arc::new(UInt32Array::from(lines));
-        let column_array = Arc::new(UInt32Array::from(columns));
-        let phase_array = Arc::new(StringArray::from(phases));
-        let processing_order_array = Arc::new(UInt32Array::from(processing_orders));
-        let element_type_array = Arc::new(StringArray::from(element_types));
-        let element_name_array = Arc::new(StringArray::from(element_names));
-        let element_signature_array = Arc::new(StringArray::from(element_signatures));
-        let syntax_data_array = Arc::new(StringArray::from(syntax_data));
-        let symbol_data_array = Arc::new(StringArray::from(symbol_data));
-        let type_data_array = Arc::new(StringArray::from(type_data));
-        let diagnostic_data_array = Arc::new(StringArray::from(diagnostic_data));
-        let processing_time_array = Arc::new(UInt64Array::from(processing_times));
-        let timestamp_array = Arc::new(UInt64Array::from(timestamps));
-        let rust_version_array = Arc::new(StringArray::from(rust_versions));
-        let analyzer_version_array = Arc::new(StringArray::from(analyzer_versions));
-        let source_snippet_array = Arc::new(StringArray::from(source_snippets));
-        let context_before_array = Arc::new(StringArray::from(context_befores));
-        let context_after_array = Arc::new(StringArray::from(context_afters));
+        Self::parquet_schema().fields().iter()
+            .map(|field| field.name().clone())
+            .filter(|name| !ANALYSIS_COLUMNS.contains(&name.as_str()) || phase_specific.contains(&name.as_str()))
+            .collect()
+    }
 
-        // Create a record batch (a chunk of columnar data)
-        // This represents all our records in Arrow's columnar format
-        let batch = RecordBatch::try_new(
-            schema.clone(),
-            vec![
-                id_array,
-                file_path_array,
-                line_array,
-                column_array,
-                phase_array,
-                processing_order_array,
-                element_type_array,
-                element_name_array,
-                element_signature_array,
-                syntax_data_array,
-                symbol_data_array,
-                type_data_array,
-                diagnostic_data_array,
-                processing_time_array,
-                timestamp_array,
-                rust_version_array,
-                analyzer_version_array,
-                source_snippet_array,
-                context_before_array,
-                context_after_array,
-            ],
-        )?;
+    /// The phase-specialized Arrow schema for `phase`
+    pub fn phase_schema(phase: &ProcessingPhase) -> Arc<arrow::datatypes::Schema> {
+        Arc::new(Self::parquet_schema().project(&Self::phase_column_indices(phase))
+            .expect("phase columns are a subset of the canonical schema"))
+    }
 
-        // Write the record batch to a Parquet file
-        // Configure compression and other properties for optimal ML usage
-        let file = std::fs::File::create(output_file)?;
-        let props = WriterProperties::builder()
-            .set_compression(parquet::basic::Compression::SNAPPY)  // Fast compression/decompression
-            .build();
-        
-        let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
-        writer.write(&batch)?;
-        writer.close()?;
+    fn phase_column_indices(phase: &ProcessingPhase) -> Vec<usize> {
+        let schema = Self::parquet_schema();
+        Self::phase_columns(phase).iter()
+            .filter_map(|name| schema.index_of(name).ok())
+            .collect()
+    }
 
-        Ok(())
+    /// Check that a phase's Parquet schema is either the canonical schema or
+    /// the phase-specialized one, naming the columns that differ otherwise
+    pub fn check_phase_schema(phase: &ProcessingPhase, schema: &arrow::datatypes::Schema) -> Result<()> {
+        let names = |schema: &arrow::datatypes::Schema| -> Vec<String> {
+            schema.fields().iter().map(|f| f.name().clone()).collect()
+        };
+        let actual = names(schema);
+        let expected = names(&Self::phase_schema(phase));
+        if actual == names(&Self::parquet_schema()) || actual == expected {
+            return Ok(());
+        }
+
+        let missing: Vec<&String> = expected.iter().filter(|c| !actual.contains(c)).collect();
+        let unexpected: Vec<&String> = actual.iter().filter(|c| !expected.contains(c)).collect();
+        Err(anyhow::anyhow!(
+            "Schema for phase {} matches neither the full nor the phase schema (missing: {:?}, unexpected: {:?})",
+            phase.as_str(), missing, unexpected
+        ))
+    }
+
+    /// Also write the records that changed since the dataset in `previous_dir` into `delta/`
+    /// 
+    /// Records are matched by id and compared by `source_hash`.
+    pub fn with_diff_against(mut self, previous_dir: impl Into<PathBuf>) -> Self {
+        self.diff_against = Some(previous_dir.into());
+        self
+    }
+
+    /// Write only the columns relevant to each phase (see `phase_columns`)
+    pub fn with_phase_schemas(mut self, phase_schemas: bool) -> Self {
+        self.output.phase_schemas = phase_schemas;
+        self
+    }
+
+    /// Restrict Parquet output to a named subset of columns
+    /// 
+    /// Lets consumers pin a schema-stable column set instead of receiving the
+    /// volatile/experimental JSON columns. The names are validated against
+    /// `parquet_schema()` up front so typos fail before any work is done.
+    pub fn with_columns(mut self, columns: Vec<String>) -> Result<Self> {
+        select_columns(&Self::parquet_schema(), &columns)?;
+        self.output.columns = Some(columns);
+        Ok(self)
+    }
+
+    /// Only analyze files matching `include_globs` and not matching `exclude_globs`
+    /// 
+    /// Applied after directory discovery, so the usual `target/` and hidden
+    /// directory skipping still happens first.
+    pub fn with_file_filter(mut self, include_globs: &[String], exclude_globs: &[String]) -> Result<Self> {
+        self.file_filter = Some(FileFilter::new(include_globs, exclude_globs)?);
+        Ok(self)
+    }
+
+    /// Write records to a single Parquet file (see `ParquetOutput::write_records`)
+    fn write_records_to_parquet(&self, records: &[RustAnalyzerRecord], phase: &ProcessingPhase, output_file: &Path) -> Result<()> {
+        self.output.write_records(records, phase, output_file)
+    }
+
+    /// Read records back from a Parquet file written with the full schema
+    ///
+    /// Files written with `--columns` or phase-specialized schemas lack some
+    /// columns and are rejected, naming the first missing column.
+    pub fn read_records_from_parquet(path: &Path) -> Result<Vec<RustAnalyzerRecord>> {
+        let mut records = Vec::new();
+        for batch in read_parquet_batches(path)? {
+            let ids = typed_column::<StringArray>(&batch, "id")?;
+            let file_paths = typed_column::<StringArray>(&batch, "file_path")?;
+            let lines = typed_column::<UInt32Array>(&batch, "line")?;
+            let columns = typed_column::<UInt32Array>(&batch, "column")?;
+            let source_hashes = typed_column::<StringArray>(&batch, "source_hash")?;
+            let phases = typed_column::<StringArray>(&batch, "phase")?;
+            let processing_orders = typed_column::<UInt32Array>(&batch, "processing_order")?;
+            let element_types = typed_column::<StringArray>(&batch, "element_type")?;
+            let element_names = typed_column::<StringArray>(&batch, "element_name")?;
+            let element_signatures = typed_column::<StringArray>(&batch, "element_signature")?;
+            let enclosing_paths = typed_column::<StringArray>(&batch, "enclosing_path")?;
+            let syntax_data = typed_column::<StringArray>(&batch, "syntax_data")?;
+            let symbol_data = typed_column::<StringArray>(&batch, "symbol_data")?;
+            let type_data = typed_column::<StringArray>(&batch, "type_data")?;
+            let diagnostic_data = typed_column::<StringArray>(&batch, "diagnostic_data")?;
+            let signature_data = typed_column::<StringArray>(&batch, "signature_data")?;
+            let generics_data = typed_column::<StringArray>(&batch, "generics_data")?;
+            let lifetime_data = typed_column::<StringArray>(&batch, "lifetime_data")?;
+            let parse_fallbacks = typed_column::<BooleanArray>(&batch, "parse_fallback")?;
+            let processing_times = typed_column::<UInt64Array>(&batch, "processing_time_ms")?;
+            let timestamps = typed_column::<UInt64Array>(&batch, "timestamp")?;
+            let rust_versions = typed_column::<StringArray>(&batch, "rust_version")?;
+            let analyzer_versions = typed_column::<StringArray>(&batch, "analyzer_version")?;
+            let source_snippets = typed_column::<StringArray>(&batch, "source_snippet")?;
+            let context_befores = typed_column::<StringArray>(&batch, "context_before")?;
+            let context_afters = typed_column::<StringArray>(&batch, "context_after")?;
+            let blame_authors = typed_column::<StringArray>(&batch, "blame_author")?;
+            let blame_timestamps = typed_column::<Int64Array>(&batch, "blame_timestamp")?;
+
+            for row in 0..batch.num_rows() {
+                records.push(RustAnalyzerRecord {
+                    id: ids.value(row).to_string(),
+                    file_path: file_paths.value(row).to_string(),
+                    line: lines.value(row),
+                    column: columns.value(row),
+                    source_hash: source_hashes.value(row).to_string(),
+                    phase: phases.value(row).to_string(),
+                    processing_order: processing_orders.value(row),
+                    element_type: element_types.value(row).to_string(),
+                    element_name: optional_string(element_names, row),
+                    element_signature: optional_string(element_signatures, row),
+                    enclosing_path: optional_string(enclosing_paths, row),
+                    syntax_data: optional_string(syntax_data, row),
+                    symbol_data: optional_string(symbol_data, row),
+                    type_data: optional_string(type_data, row),
+                    diagnostic_data: optional_string(diagnostic_data, row),
+                    signature_data: optional_string(signature_data, row),
+                    generics_data: optional_string(generics_data, row),
+                    lifetime_data: optional_string(lifetime_data, row),
+                    parse_fallback: parse_fallbacks.value(row),
+                    processing_time_ms: processing_times.value(row),
+                    timestamp: timestamps.value(row),
+                    rust_version: rust_versions.value(row).to_string(),
+                    analyzer_version: analyzer_versions.value(row).to_string(),
+                    source_snippet: source_snippets.value(row).to_string(),
+                    context_before: optional_string(context_befores, row),
+                    context_after: optional_string(context_afters, row),
+                    blame_author: optional_string(blame_authors, row),
+                    blame_timestamp: optional_value(blame_timestamps, row),
+                });
+            }
+        }
+        Ok(records)
     }
 
     /// Process a Rust codebase and extract data from all phases
     pub fn process_codebase(&mut self, codebase_path: &Path, phases: &[ProcessingPhase]) -> Result<Vec<RustAnalyzerRecord>> {
+        let started = Instant::now();
         let mut records = Vec::new();
         let rust_files = self.find_rust_files(codebase_path)?;
 
         println!("Found {} Rust files to process", rust_files.len());
+        self.files_found = rust_files.len();
+        self.skipped_files.clear();
+        self.element_type_counts.clear();
+        self.blame_cache.clear();
+        self.phases_run = phases.to_vec();
 
         for (file_index, rust_file) in rust_files.iter().enumerate() {
             println!("Processing file {}/{}: {}", file_index + 1, rust_files.len(), rust_file.display());
             
             for phase in phases {
-                let phase_records = self.extract_phase_data(rust_file, phase)?;
+                let phase_records = self.extract_phase_data_or_skip(rust_file, phase)?;
                 records.extend(phase_records);
             }
         }
 
         println!("Generated {} total records", records.len());
+        self.warn_duplicate_ids(&records);
+        println!("Element types: {}", format_element_type_histogram(&self.element_type_counts));
+        print_skipped_summary(&self.skipped_files);
+        self.finish_throughput(&rust_files, records.len(), started);
         Ok(records)
     }
 
@@ -714,7 +2077,13 @@ impl RustAnalyzerExtractor {
     /// sorted for consistent processing order across runs.
     fn find_rust_files(&self, dir: &Path) -> Result<Vec<PathBuf>> {
         let mut rust_files = Vec::new();
-        self.find_rust_files_recursive(dir, &mut rust_files)?;
+        match &self.in_memory_sources {
+            Some(sources) => rust_files.extend(sources.keys().filter(|path| path.starts_with(dir)).cloned()),
+            None => self.find_rust_files_recursive(dir, &mut rust_files)?,
+        }
+        if let Some(filter) = &self.file_filter {
+            rust_files.retain(|path| filter.matches(path.strip_prefix(dir).unwrap_or(path)));
+        }
         rust_files.sort(); // Ensure consistent ordering across runs
         Ok(rust_files)
     }
@@ -797,7 +2166,50 @@ impl RustAnalyzerExtractor {
     fn extract_phase_data(&mut self, file_path: &Path, phase: &ProcessingPhase) -> Result<Vec<RustAnalyzerRecord>> {
         let _start_time = Instant::now();
         
-        match phase {
+        let mut records = self.extract_filtered_records(file_path, phase)?;
+
+        // Scopes span many lines, so they come from one scan of the whole file
+        let enclosing_paths = self.read_source(file_path)
+            .map(|source| Self::find_enclosing_paths(&source))
+            .unwrap_or_default();
+        for record in &mut records {
+            record.enclosing_path = (record.line as usize).checked_sub(1)
+                .and_then(|index| enclosing_paths.get(index).cloned())
+                .flatten();
+        }
+
+        if self.with_blame {
+            let blame = self.file_blame(file_path);
+            for record in &mut records {
+                if let Some((author, timestamp)) = (record.line as usize).checked_sub(1)
+                    .and_then(|index| blame.get(index).cloned())
+                    .flatten()
+                {
+                    record.blame_author = Some(author);
+                    record.blame_timestamp = Some(timestamp);
+                }
+            }
+        }
+
+        // Extraction is line-based, so a file `syn` rejects (partial or malformed
+        // source) still yields records; flag them rather than dropping the file
+        if !records.is_empty() && self.needs_parse_fallback(file_path) {
+            for record in &mut records {
+                record.parse_fallback = true;
+            }
+        }
+        for record in &mut records {
+            record.id = self.id_template.render(record);
+            record.source_hash = record.content_hash();
+        }
+        Ok(records)
+    }
+
+    /// The phase extractor's records for `file_path`, after the element-type filters
+    /// 
+    /// Scopes, blame, parse fallback flags and ids are filled in by `extract_phase_data`.
+    fn extract_filtered_records(&mut self, file_path: &Path, phase: &ProcessingPhase) -> Result<Vec<RustAnalyzerRecord>> {
+        let mut records = match phase {
             ProcessingPhase::Parsing => self.extract_parsing_data(file_path),
             ProcessingPhase::NameResolution => self.extract_name_resolution_data(file_path),
             ProcessingPhase::TypeInference => self.extract_type_inference_data(file_path),
@@ -807,7 +2219,77 @@ impl RustAnalyzerExtractor {
             ProcessingPhase::Hover => self.extract_hover_data(file_path),
             ProcessingPhase::GotoDefinition => self.extract_goto_definition_data(file_path),
             ProcessingPhase::FindReferences => self.extract_find_references_data(file_path),
+        }?;
+        records.retain(|record| self.keeps_element_type(&record.element_type));
+        Ok(records)
+    }
+
+    /// Per-line blame of `file_path`, computed on first use and cached for the run
+    /// 
+    /// Files outside a git repository (or not yet committed) get no blame.
+    fn file_blame(&mut self, file_path: &Path) -> &[Option<(String, i64)>] {
+        if !self.blame_cache.contains_key(file_path) {
+            let blame = self.read_source(file_path).and_then(|source| match &self.git_revision {
+                Some((repo_path, commit_id)) => blame_lines_at_revision(repo_path, commit_id, file_path, &source),
+                None => blame_lines(file_path, &source),
+            });
+            let blame = blame.unwrap_or_else(|e| {
+                println!("⚠️  No git blame for {}: {}", file_path.display(), e);
+                Vec::new()
+            });
+            self.blame_cache.insert(file_path.to_path_buf(), blame);
+        }
+        &self.blame_cache[file_path]
+    }
+
+    /// Whether `syn` fails to parse the file as a whole
+    fn needs_parse_fallback(&self, file_path: &Path) -> bool {
+        self.read_source(file_path)
+            .map(|source| syn::parse_file(&source).is_err())
+            .unwrap_or(true)
+    }
+
+    /// Source bytes for `file_path` when analyzing a git revision
+    fn in_memory_source(&self, file_path: &Path) -> Option<&[u8]> {
+        self.in_memory_sources.as_ref()?.get(file_path).map(Vec::as_slice)
+    }
+
+    /// Size of a source file in bytes, from the git revision or the filesystem
+    fn source_len(&self, file_path: &Path) -> Result<u64> {
+        match self.in_memory_source(file_path) {
+            Some(content) => Ok(content.len() as u64),
+            None => Ok(std::fs::metadata(file_path)?.len()),
+        }
+    }
+
+    /// Read a source file, truncating it when it exceeds the size cap in truncate mode
+    fn read_source(&self, file_path: &Path) -> Result<String> {
+        use std::io::Read;
+
+        let max_file_bytes = match (self.max_file_bytes, self.oversize_action) {
+            (Some(max_file_bytes), OversizeAction::Truncate) if self.source_len(file_path)? > max_file_bytes => max_file_bytes,
+            _ => return match self.in_memory_source(file_path) {
+                Some(content) => Ok(String::from_utf8(content.to_vec())?),
+                None => Ok(std::fs::read_to_string(file_path)?),
+            },
+        };
+
+        let mut bytes = Vec::new();
+        match self.in_memory_source(file_path) {
+            Some(content) => bytes.extend_from_slice(&content[..max_file_bytes as usize]),
+            None => {
+                std::fs::File::open(file_path)?.take(max_file_bytes).read_to_end(&mut bytes)?;
+            }
         }
+        // Drop the trailing partial line, or failing that a split UTF-8 sequence
+        if let Some(last_newline) = bytes.iter().rposition(|&b| b == b'\n') {
+            bytes.truncate(last_newline + 1);
+        } else if let Err(e) = std::str::from_utf8(&bytes) {
+            if e.error_len().is_none() {
+                bytes.truncate(e.valid_up_to());
+            }
+        }
+        String::from_utf8(bytes).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e).into())
     }
 
     /// Extract parsing phase data from a Rust source file
@@ -849,13 +2331,29 @@ impl RustAnalyzerExtractor {
     /// - Detect and fix syntax errors
     /// - Generate syntactically correct code
     fn extract_parsing_data(&mut self, file_path: &Path) -> Result<Vec<RustAnalyzerRecord>> {
-        let source_code = std::fs::read_to_string(file_path)
+        let source_code = self.read_source(file_path)
             .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
 
         // Mock parsing data - in real implementation, this would use rust-analyzer's parser
         let mut records = Vec::new();
         let lines: Vec<&str> = source_code.lines().collect();
 
+        // Closures and matches get their own record after the record of the line
+        // they start on: (column, element_type, syntax_data)
+        let mut expressions_by_line: HashMap<usize, Vec<(u32, &str, serde_json::Value)>> = HashMap::new();
+        for closure in Self::find_closures(&source_code) {
+            expressions_by_line.entry(closure.line as usize - 1).or_default().push((closure.column, "closure", serde_json::json!({
+                "ast_node_type": "closure_expr",
+                "closure": closure,
+            })));
+        }
+        for match_expr in Self::find_matches(&source_code) {
+            expressions_by_line.entry(match_expr.line as usize - 1).or_default().push((match_expr.column, "match", serde_json::json!({
+                "ast_node_type": "match_expr",
+                "match": match_expr,
+            })));
+        }
+
         for (line_num, line) in lines.iter().enumerate() {
             // Skip empty lines as they don't contribute to syntax analysis
             if line.trim().is_empty() {
@@ -866,26 +2364,48 @@ impl RustAnalyzerExtractor {
                 id: format!("{}:{}:parsing", file_path.display(), line_num + 1),
                 file_path: file_path.to_string_lossy().to_string(),
                 line: (line_num + 1) as u32,
-                column: 1,
+                column: indent_column(line),
+                source_hash: String::new(), // Filled in by extract_phase_data
                 phase: ProcessingPhase::Parsing.as_str().to_string(),
                 processing_order: self.next_processing_order(),
                 element_type: self.detect_element_type(line),
                 element_name: self.extract_element_name(line),
                 element_signature: None,
+                enclosing_path: None, // Filled in by extract_phase_data
                 syntax_data: Some(self.create_mock_syntax_data(line)),
                 symbol_data: None,  // Not available during parsing phase
                 type_data: None,    // Not available during parsing phase
                 diagnostic_data: None, // Parse errors would go here in real implementation
+                signature_data: None,  // Extracted during name resolution
+                generics_data: None,   // Extracted during name resolution
+                lifetime_data: None,   // Extracted during name resolution
+                parse_fallback: false, // Set per file by extract_phase_data
                 processing_time_ms: 1, // Mock timing - real implementation would measure actual time
-                timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+                timestamp: crate::config::generation_timestamp(),
                 rust_version: self.rust_version.clone(),
                 analyzer_version: self.analyzer_version.clone(),
                 source_snippet: line.to_string(),
                 context_before: if line_num > 0 { Some(lines[line_num - 1].to_string()) } else { None },
                 context_after: if line_num + 1 < lines.len() { Some(lines[line_num + 1].to_string()) } else { None },
+                blame_author: None, // Filled in by extract_phase_data when blame is enabled
+                blame_timestamp: None,
             };
 
+            let mut expressions = expressions_by_line.remove(&line_num).unwrap_or_default();
+            expressions.sort_by_key(|(column, _, _)| *column);
+            let mut expression_records = Vec::new();
+            for (column, element_type, syntax_data) in expressions {
+                let mut expression_record = record.clone();
+                expression_record.column = column;
+                expression_record.processing_order = self.next_processing_order();
+                expression_record.element_type = element_type.to_string();
+                expression_record.element_name = None;
+                expression_record.syntax_data = Some(syntax_data.to_string());
+                expression_records.push(expression_record);
+            }
+
             records.push(record);
+            records.extend(expression_records);
         }
 
         Ok(records)
@@ -930,38 +2450,189 @@ impl RustAnalyzerExtractor {
     /// - Code navigation features
     /// - Understanding code structure and organization
     fn extract_name_resolution_data(&mut self, file_path: &Path) -> Result<Vec<RustAnalyzerRecord>> {
-        let source_code = std::fs::read_to_string(file_path)?;
+        let source_code = self.read_source(file_path)?;
         let mut records = Vec::new();
 
+        // Declarative macros span several lines, so find them up front keyed by line index
+        let macro_definitions: std::collections::HashMap<usize, MacroRulesInfo> = Self::find_macro_rules(&source_code)
+            .into_iter()
+            .map(|m| (m.line as usize - 1, m))
+            .collect();
+
+        // Attributes seen since the last item, attached to the next definition
+        let mut pending_attributes: Vec<AttributeInfo> = Vec::new();
+        // Text of an attribute that spans multiple lines
+        let mut partial_attribute = String::new();
+        // `///` doc comment lines seen since the last item, with their 0-based line index
+        let mut pending_docs: Vec<(usize, &str)> = Vec::new();
+
+        // Signatures may continue past the definition line
+        let lines: Vec<&str> = source_code.lines().collect();
+
+        // Trait members need the whole trait to be classified, so also find them up front
+        let trait_members = Self::find_trait_members(&lines);
+
         // Mock name resolution - focus on major definition sites
         // In a real implementation, this would use rust-analyzer's name resolution engine
-        for (line_num, line) in source_code.lines().enumerate() {
-            // Look for major definition keywords that create new symbols
-            if line.contains("fn ") || line.contains("struct ") || line.contains("enum ") {
-                let record = RustAnalyzerRecord {
-                    id: format!("{}:{}:name_resolution", file_path.display(), line_num + 1),
-                    file_path: file_path.to_string_lossy().to_string(),
-                    line: (line_num + 1) as u32,
-                    column: 1,
-                    phase: ProcessingPhase::NameResolution.as_str().to_string(),
-                    processing_order: self.next_processing_order(),
-                    element_type: self.detect_element_type(line),
-                    element_name: self.extract_element_name(line),
-                    element_signature: Some(line.trim().to_string()), // Full signature for context
-                    syntax_data: None,  // Syntax data from previous phase
-                    symbol_data: Some(self.create_mock_symbol_data(line)), // Core data for this phase
-                    type_data: None,    // Not available until type inference
-                    diagnostic_data: None, // Name resolution errors would go here
-                    processing_time_ms: 2, // Mock timing - slightly longer than parsing
-                    timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
-                    rust_version: self.rust_version.clone(),
-                    analyzer_version: self.analyzer_version.clone(),
-                    source_snippet: line.to_string(),
-                    context_before: None, // Could include context for better symbol resolution
-                    context_after: None,
+        for (line_num, line) in lines.iter().copied().enumerate() {
+            let trimmed = line.trim();
+
+            // Collect outer doc comments for the next definition
+            if let Some(doc) = trimmed.strip_prefix("///") {
+                pending_docs.push((line_num, doc.strip_prefix(' ').unwrap_or(doc)));
+                continue;
+            }
+
+            // Collect outer attributes (possibly spanning several lines); the
+            // item they annotate may follow on the same line (`#[test] fn t() {}`)
+            let mut item = line.trim_start();
+            let mut attribute_line = false;
+            while !partial_attribute.is_empty() || (item.starts_with("#[") && !item.starts_with("#![")) {
+                attribute_line = true;
+                partial_attribute.push_str(item);
+                let Some((attribute, rest)) = Self::parse_attribute(&partial_attribute) else {
+                    item = "";
+                    break;
                 };
+                // The closing `]` is always on the current line, so `rest` is a suffix of `item`
+                let rest_len = rest.len();
+                pending_attributes.push(attribute);
+                partial_attribute.clear();
+                item = item[item.len() - rest_len..].trim_start();
+            }
+            if attribute_line && item.is_empty() {
+                continue;
+            }
 
-                records.push(record);
+            // Several items may share a line (`struct A; struct B;`); each gets its own record
+            let item_start = line.len() - item.len();
+            for (segment_index, (offset, item)) in Self::split_line_items(item).into_iter().enumerate() {
+                // Look for major definition keywords that create new symbols; macro
+                // definitions and trait members are found per line, so belong to its first item
+                let macro_definition = macro_definitions.get(&line_num).filter(|_| segment_index == 0);
+                let trait_member = trait_members.get(&line_num).filter(|_| segment_index == 0);
+                if macro_definition.is_some() || trait_member.is_some() || item.contains("fn ") || item.contains("struct ") || item.contains("enum ") {
+                    // Byte column of the item itself, after any attributes or items before it on the line
+                    let indent = item_start + offset;
+                    let struct_fields = match macro_definition {
+                        Some(_) => None,
+                        None => Self::parse_struct_fields(&Self::collect_item_text(&lines, line_num, indent)),
+                    };
+                    let element_name = match (macro_definition, trait_member) {
+                        (Some(m), _) => Some(m.name.clone()),
+                        (None, Some(member)) => Some(member.name.clone()),
+                        (None, None) => self.extract_element_name(item),
+                    };
+                    let doc_lines: Vec<&str> = pending_docs.iter().map(|(_, doc)| *doc).collect();
+                    // Only this item's own `fn`, not one of a later item on the line
+                    let signature_text = if item.contains("fn ") {
+                        Self::collect_signature_text(&lines, line_num, indent)
+                    } else {
+                        String::new()
+                    };
+                    let record = RustAnalyzerRecord {
+                        id: format!("{}:{}:name_resolution", file_path.display(), line_num + 1),
+                        file_path: file_path.to_string_lossy().to_string(),
+                        line: (line_num + 1) as u32,
+                        column: column_at(line, indent),
+                        source_hash: String::new(),
+                        phase: ProcessingPhase::NameResolution.as_str().to_string(),
+                        processing_order: self.next_processing_order(),
+                        element_type: match trait_member.map(|member| member.kind.as_str()) {
+                            Some("type") => "associated_type".to_string(),
+                            Some("const") => "associated_const".to_string(),
+                            _ => self.detect_element_type(item),
+                        },
+                        element_name: element_name.clone(),
+                        element_signature: Some(item.trim_end().to_string()), // Full signature for context
+                        enclosing_path: None, // Filled in by extract_phase_data
+                        syntax_data: macro_definition.map(|m| serde_json::json!({
+                            "macro_name": m.name,
+                            "arm_count": m.arm_patterns.len(),
+                            "arm_patterns": m.arm_patterns,
+                        }).to_string()), // Macro arms; other syntax data comes from the parsing phase
+                        symbol_data: Some(self.create_mock_symbol_data(item, &pending_attributes, &doc_lines, struct_fields.as_deref(), trait_member)), // Core data for this phase
+                        type_data: None,    // Not available until type inference
+                        diagnostic_data: None, // Name resolution errors would go here
+                        signature_data: match macro_definition {
+                            Some(_) => None,
+                            None => Self::parse_fn_signature(&signature_text)
+                                .map(|signature| serde_json::to_string(&signature))
+                                .transpose()?,
+                        },
+                        generics_data: match macro_definition {
+                            Some(_) => None,
+                            None => Self::parse_item_generics(&Self::collect_header_text(&lines, line_num, indent))
+                                .map(|generics| serde_json::to_string(&generics))
+                                .transpose()?,
+                        },
+                        lifetime_data: match macro_definition {
+                            Some(_) => None,
+                            None => Self::parse_fn_lifetimes(&signature_text)
+                                .or_else(|| Self::parse_struct_lifetimes(&Self::collect_item_text(&lines, line_num, indent)))
+                                .map(|lifetimes| serde_json::to_string(&lifetimes))
+                                .transpose()?,
+                        },
+                        parse_fallback: false,
+                        processing_time_ms: 2, // Mock timing - slightly longer than parsing
+                        timestamp: crate::config::generation_timestamp(),
+                        rust_version: self.rust_version.clone(),
+                        analyzer_version: self.analyzer_version.clone(),
+                        source_snippet: line.to_string(),
+                        context_before: None, // Could include context for better symbol resolution
+                        context_after: None,
+                        blame_author: None,
+                        blame_timestamp: None,
+                    };
+
+                    records.push(record);
+
+                    // One record per doc example, pairing it with the documented item
+                    for doctest in Self::find_doctests(&pending_docs) {
+                        records.push(RustAnalyzerRecord {
+                            id: format!("{}:{}:name_resolution", file_path.display(), doctest.line),
+                            file_path: file_path.to_string_lossy().to_string(),
+                            line: doctest.line,
+                            column: lines.get(doctest.line as usize - 1).map_or(1, |doc_line| indent_column(doc_line)),
+                            source_hash: String::new(),
+                            phase: ProcessingPhase::NameResolution.as_str().to_string(),
+                            processing_order: self.next_processing_order(),
+                            element_type: "doctest".to_string(),
+                            element_name: element_name.clone(),
+                            element_signature: Some(item.trim_end().to_string()), // Documented item
+                            enclosing_path: None, // Filled in by extract_phase_data
+                            syntax_data: None,
+                            symbol_data: Some(serde_json::json!({
+                                "documented_item": element_name,
+                                "documented_line": line_num + 1,
+                                "doc_comment": doc_lines.join("\n"),
+                                "doctest": doctest,
+                            }).to_string()),
+                            type_data: None,
+                            diagnostic_data: None,
+                            signature_data: None,
+                            generics_data: None,
+                            lifetime_data: None,
+                            parse_fallback: false,
+                            processing_time_ms: 2,
+                            timestamp: crate::config::generation_timestamp(),
+                            rust_version: self.rust_version.clone(),
+                            analyzer_version: self.analyzer_version.clone(),
+                            source_snippet: doctest.code.clone(),
+                            context_before: None,
+                            context_after: None,
+                            blame_author: None,
+                            blame_timestamp: None,
+                        });
+                    }
+
+                    pending_attributes.clear();
+                    pending_docs.clear();
+                } else if !item.trim_end().is_empty() && !item.starts_with("//") {
+                    // Attributes and docs only apply to the item that immediately follows them
+                    pending_attributes.clear();
+                    pending_docs.clear();
+                }
             }
         }
 
@@ -1008,7 +2679,7 @@ impl RustAnalyzerExtractor {
     /// - Generate type-correct code completions
     /// - Perform type-aware refactoring
     fn extract_type_inference_data(&mut self, file_path: &Path) -> Result<Vec<RustAnalyzerRecord>> {
-        let source_code = std::fs::read_to_string(file_path)?;
+        let source_code = self.read_source(file_path)?;
         let mut records = Vec::new();
 
         // Mock type inference - focus on type-relevant constructs
@@ -1020,23 +2691,31 @@ impl RustAnalyzerExtractor {
                     id: format!("{}:{}:type_inference", file_path.display(), line_num + 1),
                     file_path: file_path.to_string_lossy().to_string(),
                     line: (line_num + 1) as u32,
-                    column: 1,
+                    column: indent_column(line),
+                    source_hash: String::new(),
                     phase: ProcessingPhase::TypeInference.as_str().to_string(),
                     processing_order: self.next_processing_order(),
                     element_type: "variable_or_return".to_string(), // Specific to type inference context
                     element_name: self.extract_variable_name(line),
                     element_signature: None, // Type information is more important than signature
+                    enclosing_path: None, // Filled in by extract_phase_data
                     syntax_data: None,  // From parsing phase
                     symbol_data: None,  // From name resolution phase
                     type_data: Some(self.create_mock_type_data(line)), // Core data for this phase
                     diagnostic_data: None, // Type errors would be recorded here
+                    signature_data: None,  // From name resolution phase
+                    generics_data: None,   // From name resolution phase
+                    lifetime_data: None,   // From name resolution phase
+                    parse_fallback: false,
                     processing_time_ms: 3, // Mock timing - type inference is typically slower
-                    timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+                    timestamp: crate::config::generation_timestamp(),
                     rust_version: self.rust_version.clone(),
                     analyzer_version: self.analyzer_version.clone(),
                     source_snippet: line.to_string(),
                     context_before: None, // Type context could be valuable for inference
                     context_after: None,
+                    blame_author: None,
+                    blame_timestamp: None,
                 };
 
                 records.push(record);
@@ -1243,6 +2922,7 @@ impl RustAnalyzerExtractor {
     /// 
     /// # Recognized Patterns
     /// 
+    /// - **macro_rules**: `macro_rules!` - Declarative macro definitions
     /// - **function**: `fn ` - Function definitions
     /// - **struct**: `struct ` - Struct type definitions  
     /// - **enum**: `enum ` - Enum type definitions
@@ -1265,7 +2945,9 @@ impl RustAnalyzerExtractor {
     /// This is a simplified pattern matcher. A full implementation would
     /// use rust-analyzer's syntax tree to get precise element types.
     fn detect_element_type(&self, line: &str) -> String {
-        if line.contains("fn ") {
+        if line.contains("macro_rules!") {
+            "macro_rules".to_string()
+        } else if line.contains("fn ") {
             "function".to_string()
         } else if line.contains("struct ") {
             "struct".to_string()
@@ -1446,18 +3128,27 @@ impl RustAnalyzerExtractor {
     ///   "definition_location": {
     ///     "line": 1,
     ///     "column": 1
-    ///   }
+    ///   },
+    ///   "attributes": [
+    ///     { "path": "derive", "arguments": ["Debug", "Clone"] }
+    ///   ],
+    ///   "doc_comment": "Adds two numbers."
     /// }
     /// ```
     /// 
     /// # Arguments
     /// 
     /// * `line` - The source code line to generate symbol data for
+    /// * `attributes` - Attributes applied to the item defined on this line
+    /// * `doc_lines` - `///` doc comment lines preceding the item (without the marker)
+    /// * `fields` - Fields of a struct definition (`None` for other items)
+    /// * `trait_member` - Trait method/type/const declared on this line, if any
     /// 
     /// # Returns
     /// 
     /// A JSON string containing mock symbol resolution data including
-    /// symbol kind, visibility, and definition location.
+    /// symbol kind, visibility, definition location, attributes, `cfg`
+    /// conditions and doc comment.
     /// 
     /// # Real Implementation Notes
     /// 
@@ -1467,123 +3158,3000 @@ impl RustAnalyzerExtractor {
     /// - Exact definition locations with file paths
     /// - Symbol references and usage information
     /// - Scope and namespace information
-    fn create_mock_symbol_data(&self, line: &str) -> String {
+    fn create_mock_symbol_data(&self, line: &str, attributes: &[AttributeInfo], doc_lines: &[&str], fields: Option<&[FieldInfo]>, trait_member: Option<&TraitMemberInfo>) -> String {
+        let doc_comment = if doc_lines.is_empty() { None } else { Some(doc_lines.join("\n")) };
         serde_json::json!({
             "symbol_kind": self.detect_element_type(line),
             "visibility": "public",
             "definition_location": {
                 "line": 1,
                 "column": 1
-            }
+            },
+            "attributes": attributes,
+            "cfg_conditions": Self::cfg_conditions(attributes),
+            "doc_comment": doc_comment,
+            "fields": fields,
+            "trait_member": trait_member
         }).to_string()
     }
 
-    /// Create mock type inference data in JSON format
-    /// 
-    /// This method generates realistic type inference data that simulates
-    /// what rust-analyzer would produce during type checking. This data
-    /// is essential for training models that understand Rust's type system.
-    /// 
-    /// # Type Detection Strategy
-    /// 
-    /// The mock implementation uses simple string matching to detect types:
-    /// - `String` - Rust's owned string type
-    /// - `i32` - 32-bit signed integer
-    /// - `bool` - Boolean type
-    /// - `unknown` - Fallback for unrecognized patterns
-    /// 
-    /// # Generated Data Structure
-    /// 
-    /// ```json
-    /// {
-    ///   "inferred_type": "String",
-    ///   "confidence": 0.95,
-    ///   "inference_method": "explicit"
-    /// }
-    /// ```
-    /// 
-    /// # Arguments
-    /// 
-    /// * `line` - The source code line to generate type data for
-    /// 
-    /// # Returns
+    /// Predicates of the `#[cfg(...)]` attributes gating an item
+    /// 
+    /// `#[cfg(feature = "full")]` → `feature = "full"`; several `cfg`
+    /// attributes on one item are all required, so each is listed.
+    fn cfg_conditions(attributes: &[AttributeInfo]) -> Vec<String> {
+        attributes.iter()
+            .filter(|attribute| attribute.path == "cfg")
+            .map(|attribute| attribute.arguments.join(", "))
+            .collect()
+    }
+
+    /// Parse an outer attribute such as `#[derive(Debug, Clone)]`
     /// 
-    /// A JSON string containing mock type inference data including
-    /// the inferred type, confidence level, and inference method.
+    /// Returns the attribute and the text after its closing `]`, or `None`
+    /// while the brackets are still unbalanced, so callers can keep appending
+    /// lines for attributes that span multiple lines. Brackets and commas
+    /// inside string and char literals (`#[error("unclosed (")]`) are ignored.
     /// 
-    /// # Real Implementation Notes
+    /// # Examples
     /// 
-    /// In a full implementation, this would provide:
-    /// - Accurate type inference using Rust's type system
-    /// - Complex generic types and trait bounds
-    /// - Type error information and suggestions
-    /// - Inference confidence based on available information
-    /// - Multiple possible types for ambiguous cases
-    fn create_mock_type_data(&self, line: &str) -> String {
-        let inferred_type = if line.contains("String") {
-            "String"
-        } else if line.contains("i32") {
-            "i32"
-        } else if line.contains("bool") {
-            "bool"
-        } else {
-            "unknown"
-        };
+    /// - `#[derive(Debug, Clone)]` → path `derive`, arguments `["Debug", "Clone"]`
+    /// - `#[serde(rename = "id")]` → path `serde`, arguments `["rename = \"id\""]`
+    /// - `#[tokio::main]` → path `tokio::main`, no arguments
+    fn parse_attribute(text: &str) -> Option<(AttributeInfo, &str)> {
+        let inner = text.trim_start().strip_prefix("#[")?;
+        let chars: Vec<char> = inner.chars().collect();
+        // Same positions as `chars`, with literal contents blanked
+        let masked = mask_comments_and_strings(inner);
 
-        serde_json::json!({
-            "inferred_type": inferred_type,
-            "confidence": 0.95,
-            "inference_method": "explicit"
-        }).to_string()
-    }
-}
+        // Find the closing bracket that balances the opening `#[`
+        let mut depth = 1;
+        let mut end = None;
+        for (idx, ch) in masked.iter().enumerate() {
+            match ch {
+                '[' | '(' | '{' => depth += 1,
+                ']' | ')' | '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(idx);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let end = end?;
+        let rest_start = inner.char_indices().nth(end + 1).map_or(inner.len(), |(idx, _)| idx);
+        let rest = &inner[rest_start..];
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tempfile::TempDir;
+        let first = (0..end).find(|&idx| !chars[idx].is_whitespace()).unwrap_or(end);
+        let last = (first..end).rev().find(|&idx| !chars[idx].is_whitespace()).map_or(first, |idx| idx + 1);
+        let (body, masked_body) = (&chars[first..last], &masked[first..last]);
 
-    #[test]
-    fn test_rust_analyzer_extractor_creation() {
-        let extractor = RustAnalyzerExtractor::new();
-        assert!(extractor.is_ok());
+        let split = masked_body.iter().position(|c| matches!(c, '(' | '=' | '[' | '{')).unwrap_or(body.len());
+        let path: String = body[..split].iter().collect();
+        let (args, masked_args) = (&body[split..], &masked_body[split..]);
+
+        // Split `(a, b(c, d), e)` into top-level arguments
+        let mut arguments = Vec::new();
+        if args.len() >= 2 && args[0] == '(' && args[args.len() - 1] == ')' {
+            let inside = 1..args.len() - 1;
+            let mut depth = 0;
+            let mut current = String::new();
+            for (&ch, &masked_ch) in args[inside.clone()].iter().zip(&masked_args[inside]) {
+                match masked_ch {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => depth -= 1,
+                    ',' if depth == 0 => {
+                        if !current.trim().is_empty() {
+                            arguments.push(current.trim().to_string());
+                        }
+                        current.clear();
+                        continue;
+                    }
+                    _ => {}
+                }
+                current.push(ch);
+            }
+            if !current.trim().is_empty() {
+                arguments.push(current.trim().to_string());
+            }
+        } else if args.first() == Some(&'=') {
+            arguments.push(args[1..].iter().collect::<String>().trim().to_string());
+        }
+
+        Some((AttributeInfo {
+            path: path.trim().to_string(),
+            arguments,
+        }, rest))
     }
 
-    #[test]
-    fn test_find_rust_files() {
-        let temp_dir = TempDir::new().unwrap();
-        let rust_file = temp_dir.path().join("test.rs");
-        fs::write(&rust_file, "fn main() {}").unwrap();
+    /// Find every closure expression in `source`, in source order
+    /// 
+    /// A `|` starts a closure when it follows `move`, `async`, `return`, an
+    /// opening bracket, `,`, `=`, `;`, `:` or `=>` (after an operand it is a
+    /// bitwise/logical or, or a pattern alternative). The body runs to the
+    /// matching `}` for blocks, otherwise to the next `,` / `;` or unmatched
+    /// closing bracket. Nested closures are reported too.
+    fn find_closures(source: &str) -> Vec<ClosureInfo> {
+        let chars = mask_comments_and_strings(source);
+        let position = |index: usize| {
+            let line_start = chars[..index].iter().rposition(|&c| c == '\n').map_or(0, |pos| pos + 1);
+            let line = chars[..index].iter().filter(|&&c| c == '\n').count() + 1;
+            (line as u32, (index - line_start + 1) as u32)
+        };
+        let previous_word = |end: usize| {
+            let start = chars[..end].iter().rposition(|c| !(c.is_alphanumeric() || *c == '_')).map_or(0, |pos| pos + 1);
+            chars[start..end].iter().collect::<String>()
+        };
+        // First index at or after `from` where `stop` holds at bracket depth 0, or where a bracket closes unmatched
+        let scan = |from: usize, stop: &dyn Fn(char) -> bool| {
+            let mut depth = 0i32;
+            for (j, &c) in chars.iter().enumerate().skip(from) {
+                match c {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' if depth == 0 => return j,
+                    ')' | ']' | '}' => depth -= 1,
+                    _ if depth == 0 && stop(c) => return j,
+                    _ => {}
+                }
+            }
+            chars.len()
+        };
 
-        let extractor = RustAnalyzerExtractor::new().unwrap();
-        let rust_files = extractor.find_rust_files(temp_dir.path()).unwrap();
-        
-        assert_eq!(rust_files.len(), 1);
-        assert_eq!(rust_files[0], rust_file);
+        let mut closures = Vec::new();
+        for i in 0..chars.len() {
+            if chars[i] != '|' || (i > 0 && chars[i - 1] == '|') {
+                continue;
+            }
+            let Some(before) = chars[..i].iter().rposition(|c| !c.is_whitespace()) else {
+                continue;
+            };
+            let (start, is_move) = match chars[before] {
+                '(' | '[' | '{' | ',' | '=' | ';' | ':' => (i, false),
+                '>' if before > 0 && chars[before - 1] == '=' => (i, false),
+                c if c.is_alphanumeric() || c == '_' => {
+                    let word = previous_word(before + 1);
+                    match word.as_str() {
+                        "move" => (before + 1 - word.len(), true),
+                        "async" | "return" => (i, false),
+                        _ => continue,
+                    }
+                }
+                _ => continue,
+            };
+            // `move` may itself follow `async`
+            let start = if is_move {
+                let word_end = chars[..start].iter().rposition(|c| !c.is_whitespace()).map_or(0, |pos| pos + 1);
+                if previous_word(word_end) == "async" { word_end - 5 } else { start }
+            } else {
+                start
+            };
+
+            // Parameters: `||` has none, otherwise up to the closing pipe
+            let (params, params_end) = if chars.get(i + 1) == Some(&'|') {
+                (String::new(), i + 2)
+            } else {
+                let close = scan(i + 1, &|c| c == '|');
+                if chars.get(close) != Some(&'|') {
+                    continue;
+                }
+                (chars[i + 1..close].iter().collect::<String>(), close + 1)
+            };
+            let param_count = if params.trim().is_empty() {
+                0
+            } else {
+                let mut angle_depth = 0i32;
+                let mut bracket_depth = 0i32;
+                let mut commas = 0;
+                for c in params.trim().trim_end_matches(',').chars() {
+                    match c {
+                        '<' => angle_depth += 1,
+                        '>' => angle_depth -= 1,
+                        '(' | '[' | '{' => bracket_depth += 1,
+                        ')' | ']' | '}' => bracket_depth -= 1,
+                        ',' if angle_depth == 0 && bracket_depth == 0 => commas += 1,
+                        _ => {}
+                    }
+                }
+                commas + 1
+            };
+
+            // Body: a block (required after `-> Type`) or an expression
+            let Some(body_start) = (params_end..chars.len()).find(|&j| !chars[j].is_whitespace()) else {
+                continue;
+            };
+            let body_start = if chars[body_start] == '-' && chars.get(body_start + 1) == Some(&'>') {
+                match (body_start..chars.len()).find(|&j| chars[j] == '{') {
+                    Some(brace) => brace,
+                    None => continue,
+                }
+            } else {
+                body_start
+            };
+            let body_end = if chars[body_start] == '{' {
+                (scan(body_start + 1, &|_| false) + 1).min(chars.len())
+            } else {
+                scan(body_start, &|c| c == ',' || c == ';')
+            };
+            let body: String = source.chars().skip(body_start).take(body_end - body_start).collect();
+            let body = body.trim();
+
+            let (line, column) = position(start);
+            closures.push(ClosureInfo {
+                line,
+                column,
+                is_move,
+                params: params.trim().to_string(),
+                param_count,
+                body_length: body.chars().count(),
+                body_lines: body.lines().count().max(1),
+            });
+        }
+        closures
     }
 
-    #[test]
-    fn test_extract_parsing_data() {
-        let temp_dir = TempDir::new().unwrap();
-        let rust_file = temp_dir.path().join("test.rs");
-        fs::write(&rust_file, "fn main() {\n    println!(\"Hello, world!\");\n}").unwrap();
+    /// Find every `match` expression in `source`, in source order
+    /// 
+    /// The body is the first `{` after the keyword outside brackets. An arm
+    /// is counted per `=>` at the top level of the body; its pattern runs from
+    /// the end of the previous arm (a `,` or the closing `}` of a block body).
+    fn find_matches(source: &str) -> Vec<MatchInfo> {
+        let chars = mask_comments_and_strings(source);
+        let is_word = |c: char| c.is_alphanumeric() || c == '_';
 
-        let mut extractor = RustAnalyzerExtractor::new().unwrap();
-        let records = extractor.extract_parsing_data(&rust_file).unwrap();
-        
-        assert!(!records.is_empty());
-        assert_eq!(records[0].phase, "parsing");
-        assert_eq!(records[0].element_type, "function");
+        let mut matches = Vec::new();
+        for i in 0..chars.len() {
+            let is_keyword = chars[i..].starts_with(&['m', 'a', 't', 'c', 'h'])
+                && (i == 0 || !is_word(chars[i - 1]))
+                && !chars.get(i + 5).copied().is_some_and(is_word);
+            if !is_keyword {
+                continue;
+            }
+
+            // Body brace, skipping brackets in the scrutinee
+            let mut depth = 0i32;
+            let mut open = None;
+            for (j, &c) in chars.iter().enumerate().skip(i + 5) {
+                match c {
+                    '(' | '[' => depth += 1,
+                    ')' | ']' => depth -= 1,
+                    '{' if depth == 0 => {
+                        open = Some(j);
+                        break;
+                    }
+                    ';' | '}' if depth == 0 => break,
+                    _ => {}
+                }
+            }
+            let Some(open) = open else {
+                continue;
+            };
+
+            let mut arm_count = 0;
+            let mut has_wildcard = false;
+            let mut arm_start = open + 1;
+            // Inside an arm body, and whether that body is a block
+            let mut body: Option<bool> = None;
+            let mut depth = 0i32;
+            let mut j = open + 1;
+            while j < chars.len() {
+                match chars[j] {
+                    '(' | '[' | '{' => {
+                        if depth == 0 && body == Some(false) && chars[j] == '{' && chars[arm_start..j].iter().all(|c| c.is_whitespace()) {
+                            body = Some(true);
+                        }
+                        depth += 1;
+                    }
+                    '}' if depth == 0 => break,
+                    ')' | ']' | '}' => {
+                        depth -= 1;
+                        if depth == 0 && chars[j] == '}' && body == Some(true) {
+                            body = None;
+                            arm_start = j + 1;
+                        }
+                    }
+                    ',' if depth == 0 => {
+                        body = None;
+                        arm_start = j + 1;
+                    }
+                    '=' if depth == 0 && chars.get(j + 1) == Some(&'>') => {
+                        // Block-like expression bodies (`if`, `match`) need no comma;
+                        // the pattern then starts after their last `}`
+                        let pattern_start = match body {
+                            Some(_) => chars[arm_start..j].iter().rposition(|&c| c == '}').map_or(arm_start, |pos| arm_start + pos + 1),
+                            None => arm_start,
+                        };
+                        let pattern: String = chars[pattern_start..j].iter().collect();
+                        let pattern = pattern.split_whitespace().collect::<Vec<_>>().join(" ");
+                        // A guarded `_ if ...` arm does not cover the remaining values
+                        if !pattern.contains(" if ") && pattern.split('|').any(|alternative| alternative.trim() == "_") {
+                            has_wildcard = true;
+                        }
+                        arm_count += 1;
+                        j += 2;
+                        arm_start = j;
+                        body = Some(false);
+                        continue;
+                    }
+                    _ => {}
+                }
+                j += 1;
+            }
+
+            let line_start = chars[..i].iter().rposition(|&c| c == '\n').map_or(0, |pos| pos + 1);
+            matches.push(MatchInfo {
+                line: chars[..i].iter().filter(|&&c| c == '\n').count() as u32 + 1,
+                column: (i - line_start + 1) as u32,
+                arm_count,
+                has_wildcard,
+            });
+        }
+        matches
     }
 
-    #[test]
-    fn test_element_type_detection() {
-        let extractor = RustAnalyzerExtractor::new().unwrap();
-        
-        assert_eq!(extractor.detect_element_type("fn main() {"), "function");
-        assert_eq!(extractor.detect_element_type("struct Point {"), "struct");
-        assert_eq!(extractor.detect_element_type("enum Color {"), "enum");
-        assert_eq!(extractor.detect_element_type("let x = 5;"), "variable");
+    /// Enclosing scope path of each line (0-based), e.g. `bar::Foo::method`
+    /// 
+    /// Braces are tracked outside comments and strings: the `{` opening a
+    /// `mod`, `trait`, `impl` or `fn` body pushes its name (for `impl`, the
+    /// self type without generics), any other `{` pushes nothing. A line's
+    /// path is the scopes open where it starts, so an item's header line
+    /// belongs to the scope around it. `None` at file level.
+    fn find_enclosing_paths(source: &str) -> Vec<Option<String>> {
+        fn join_scopes(scopes: &[Option<String>]) -> Option<String> {
+            let names: Vec<&str> = scopes.iter().flatten().map(String::as_str).collect();
+            (!names.is_empty()).then(|| names.join("::"))
+        }
+
+        let chars = mask_comments_and_strings(source);
+        let mut paths = Vec::new();
+        let mut scopes: Vec<Option<String>> = Vec::new();
+        // Name of the item whose body `{` is still to come, and where a pending `impl` header starts
+        let mut pending: Option<String> = None;
+        let mut impl_header: Option<usize> = None;
+        let mut paren_depth = 0i32;
+        let mut at_line_start = true;
+        let mut i = 0;
+        while i < chars.len() {
+            if at_line_start {
+                paths.push(join_scopes(&scopes));
+                at_line_start = false;
+            }
+
+            if chars[i].is_alphabetic() || chars[i] == '_' {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                // Keywords inside a pending header (`-> impl Trait`) or parentheses are not items
+                let free = pending.is_none() && impl_header.is_none() && paren_depth == 0;
+                match chars[start..i].iter().collect::<String>().as_str() {
+                    "mod" | "trait" | "fn" if free => {
+                        let name_start = (i..chars.len()).find(|&j| !chars[j].is_whitespace()).unwrap_or(chars.len());
+                        let name_end = (name_start..chars.len())
+                            .find(|&j| !(chars[j].is_alphanumeric() || chars[j] == '_'))
+                            .unwrap_or(chars.len());
+                        // `fn(u32) -> u32` is a function pointer type
+                        if name_end > name_start {
+                            pending = Some(chars[name_start..name_end].iter().collect());
+                        }
+                    }
+                    "impl" if free => impl_header = Some(i),
+                    _ => {}
+                }
+                continue;
+            }
+
+            match chars[i] {
+                '\n' => at_line_start = true,
+                '(' | '[' => paren_depth += 1,
+                ')' | ']' => paren_depth -= 1,
+                // `mod tests;` or a trait method without a body
+                ';' if paren_depth == 0 => {
+                    pending = None;
+                    impl_header = None;
+                }
+                '{' => {
+                    let name = match impl_header.take() {
+                        Some(header_start) => Self::impl_self_type(&chars[header_start..i].iter().collect::<String>()),
+                        None => pending.take(),
+                    };
+                    scopes.push(name);
+                }
+                '}' => {
+                    scopes.pop();
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        paths
+    }
+
+    /// Self type name of an `impl` header (the text between `impl` and `{`)
+    /// 
+    /// `<T: Clone> Display for path::Wrapper<T> where T: Debug` gives `Wrapper`.
+    fn impl_self_type(header: &str) -> Option<String> {
+        let header = header.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut rest = header.as_str();
+        // Skip the impl's own generic parameters (`->` inside them is not a closing bracket)
+        if rest.starts_with('<') {
+            let mut depth = 0;
+            let mut previous = ' ';
+            let mut end = None;
+            for (idx, c) in rest.char_indices() {
+                match c {
+                    '<' => depth += 1,
+                    '>' if previous != '-' => depth -= 1,
+                    _ => {}
+                }
+                previous = c;
+                if depth == 0 {
+                    end = Some(idx);
+                    break;
+                }
+            }
+            rest = rest[end? + 1..].trim_start();
+        }
+        let rest = rest.split(" where ").next().unwrap_or(rest);
+        let self_type = rest.split_once(" for ").map_or(rest, |(_, self_type)| self_type);
+        let name = self_type.split('<').next()?
+            .split_whitespace().last()?
+            .rsplit("::").next()?
+            .trim_start_matches(['&', '*']);
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    /// Find the members of every trait definition, keyed by 0-based line index
+    /// 
+    /// Each trait is parsed as a whole with `syn`; members are then located by
+    /// scanning the trait's lines in order for `fn name`, `type name` or
+    /// `const name`. Traits that fail to parse contribute no members.
+    fn find_trait_members(lines: &[&str]) -> HashMap<usize, TraitMemberInfo> {
+        let mut members = HashMap::new();
+        for (line_num, line) in lines.iter().enumerate() {
+            let trimmed = line.trim_start();
+            let is_trait_header = trimmed.split_whitespace()
+                .find(|token| !matches!(*token, "pub" | "unsafe" | "auto") && !token.starts_with("pub("))
+                == Some("trait");
+            if !is_trait_header {
+                continue;
+            }
+
+            let indent = line.len() - trimmed.len();
+            let Ok(item) = syn::parse_str::<syn::ItemTrait>(Self::collect_item_text(lines, line_num, indent).trim()) else {
+                continue;
+            };
+
+            let mut cursor = line_num + 1;
+            for trait_item in &item.items {
+                let (kind, name, signature, has_default) = match trait_item {
+                    syn::TraitItem::Fn(method) => {
+                        let params: Vec<String> = method.sig.inputs.iter().map(|input| match input {
+                            syn::FnArg::Receiver(receiver) => Self::compact_tokens(&receiver.to_token_stream().to_string()),
+                            syn::FnArg::Typed(typed) => format!(
+                                "{}: {}",
+                                Self::compact_tokens(&typed.pat.to_token_stream().to_string()),
+                                Self::compact_tokens(&typed.ty.to_token_stream().to_string())
+                            ),
+                        }).collect();
+                        let ret = match &method.sig.output {
+                            syn::ReturnType::Default => String::new(),
+                            syn::ReturnType::Type(_, ty) => format!(" -> {}", Self::compact_tokens(&ty.to_token_stream().to_string())),
+                        };
+                        let name = method.sig.ident.to_string();
+                        let signature = format!("fn {}({}){}", name, params.join(", "), ret);
+                        ("method", name, signature, method.default.is_some())
+                    }
+                    syn::TraitItem::Type(assoc) => {
+                        let name = assoc.ident.to_string();
+                        let bounds: Vec<String> = assoc.bounds.iter()
+                            .map(|bound| Self::compact_tokens(&bound.to_token_stream().to_string()))
+                            .collect();
+                        let signature = if bounds.is_empty() {
+                            format!("type {}", name)
+                        } else {
+                            format!("type {}: {}", name, bounds.join(" + "))
+                        };
+                        ("type", name, signature, assoc.default.is_some())
+                    }
+                    syn::TraitItem::Const(assoc) => {
+                        let name = assoc.ident.to_string();
+                        let signature = format!("const {}: {}", name, Self::compact_tokens(&assoc.ty.to_token_stream().to_string()));
+                        ("const", name, signature, assoc.default.is_some())
+                    }
+                    _ => continue,
+                };
+
+                let keyword = if kind == "method" { "fn" } else { kind };
+                let declares_member = |candidate: &str| candidate.match_indices(&format!("{} {}", keyword, name)).any(|(idx, matched)| {
+                    let before_ok = idx == 0 || !candidate[..idx].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+                    let after_ok = !candidate[idx + matched.len()..].starts_with(|c: char| c.is_alphanumeric() || c == '_');
+                    before_ok && after_ok
+                });
+                let Some(member_line) = (cursor..lines.len()).find(|&idx| declares_member(lines[idx])) else {
+                    break;
+                };
+                cursor = member_line + 1;
+
+                members.insert(member_line, TraitMemberInfo {
+                    trait_name: item.ident.to_string(),
+                    kind: kind.to_string(),
+                    name,
+                    signature,
+                    has_default,
+                });
+            }
+        }
+        members
+    }
+
+    /// Find the fenced code blocks of a doc comment that rustdoc runs as doctests
+    /// 
+    /// `doc_lines` are `(0-based line index, text after "/// ")` pairs. A block
+    /// is a doctest when its fence has no info string or only rustdoc's own
+    /// tokens (`rust`, `no_run`, `ignore`, `should_panic`, `compile_fail`,
+    /// `editionNNNN`); blocks tagged `text`, `sh`, etc. are prose.
+    fn find_doctests(doc_lines: &[(usize, &str)]) -> Vec<DoctestInfo> {
+        let is_rustdoc_token = |token: &str| {
+            matches!(token, "rust" | "no_run" | "ignore" | "should_panic" | "compile_fail" | "test_harness")
+                || token.starts_with("edition")
+                || token.starts_with("ignore-")
+        };
+
+        let mut doctests = Vec::new();
+        // Opening fence (its marker, line and info tokens) and the lines collected so far
+        let mut open: Option<(&str, usize, Vec<String>, Vec<&str>)> = None;
+        for &(line_index, text) in doc_lines {
+            let trimmed = text.trim_start();
+            let fence = ["```", "~~~"].into_iter().find(|marker| trimmed.starts_with(marker));
+
+            let closes = matches!(
+                (&open, fence),
+                (Some((marker, ..)), Some(closing)) if *marker == closing && trimmed[closing.len()..].trim().is_empty()
+            );
+            if closes {
+                let (_, start, attributes, code) = open.take().unwrap();
+                if attributes.iter().all(|token| is_rustdoc_token(token)) {
+                    let has = |name: &str| attributes.iter().any(|token| token == name);
+                    doctests.push(DoctestInfo {
+                        line: start as u32 + 1,
+                        code: code.join("\n"),
+                        no_run: has("no_run"),
+                        ignore: has("ignore") || attributes.iter().any(|token| token.starts_with("ignore-")),
+                        should_panic: has("should_panic"),
+                        attributes,
+                    });
+                }
+            } else if let Some((.., code)) = open.as_mut() {
+                code.push(text);
+            } else if let Some(marker) = fence {
+                let attributes: Vec<String> = trimmed[marker.len()..]
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|token| !token.is_empty())
+                    .map(|token| token.to_string())
+                    .collect();
+                open = Some((marker, line_index, attributes, Vec::new()));
+            }
+        }
+        doctests
+    }
+
+    /// Find all `macro_rules!` definitions in a source file
+    /// 
+    /// Walks each definition body with a small delimiter-matching scanner:
+    /// every arm is a delimited matcher, `=>`, and a delimited transcriber,
+    /// optionally followed by `;`. The matcher text is kept as the arm pattern.
+    /// 
+    /// # Limitations
+    /// 
+    /// Delimiters inside string or char literals in the macro body are not
+    /// special-cased, so unbalanced literals like `"{"` can confuse the scanner.
+    fn find_macro_rules(source: &str) -> Vec<MacroRulesInfo> {
+        const KEYWORD: &str = "macro_rules!";
+        let chars: Vec<(usize, char)> = source.char_indices().collect();
+        let mut macros = Vec::new();
+
+        // Return the index just past the group whose opening delimiter is at `start`
+        let skip_group = |start: usize| -> Option<usize> {
+            let mut depth = 0;
+            for (idx, &(_, ch)) in chars.iter().enumerate().skip(start) {
+                match ch {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(idx + 1);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None
+        };
+        let skip_whitespace = |mut idx: usize| {
+            while idx < chars.len() && chars[idx].1.is_whitespace() {
+                idx += 1;
+            }
+            idx
+        };
+        let byte_offset = |idx: usize| chars.get(idx).map_or(source.len(), |&(offset, _)| offset);
+
+        let mut search_from = 0;
+        while let Some(found) = source[search_from..].find(KEYWORD) {
+            let keyword_offset = search_from + found;
+            search_from = keyword_offset + KEYWORD.len();
+
+            // Ignore mentions inside line comments
+            let line_start = source[..keyword_offset].rfind('\n').map_or(0, |p| p + 1);
+            if source[line_start..keyword_offset].contains("//") {
+                continue;
+            }
+
+            let mut idx = skip_whitespace(chars.partition_point(|&(offset, _)| offset < search_from));
+            let name_start = idx;
+            while idx < chars.len() && (chars[idx].1.is_alphanumeric() || chars[idx].1 == '_') {
+                idx += 1;
+            }
+            if idx == name_start {
+                continue;
+            }
+            let name = source[byte_offset(name_start)..byte_offset(idx)].to_string();
+
+            idx = skip_whitespace(idx);
+            let body_end = match chars.get(idx) {
+                Some(&(_, '(')) | Some(&(_, '[')) | Some(&(_, '{')) => match skip_group(idx) {
+                    Some(end) => end - 1,
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            // Scan arms: matcher => transcriber [;]
+            let mut arm_patterns = Vec::new();
+            idx += 1;
+            loop {
+                idx = skip_whitespace(idx);
+                while idx < body_end && chars[idx].1 == ';' {
+                    idx = skip_whitespace(idx + 1);
+                }
+                if idx >= body_end || !matches!(chars[idx].1, '(' | '[' | '{') {
+                    break;
+                }
+                let matcher_end = match skip_group(idx) {
+                    Some(end) if end <= body_end => end,
+                    _ => break,
+                };
+                arm_patterns.push(source[byte_offset(idx)..byte_offset(matcher_end)].to_string());
+
+                idx = skip_whitespace(matcher_end);
+                if source[byte_offset(idx)..].starts_with("=>") {
+                    idx = skip_whitespace(idx + 2);
+                    if idx < body_end && matches!(chars[idx].1, '(' | '[' | '{') {
+                        idx = skip_group(idx).unwrap_or(body_end);
+                    }
+                }
+            }
+
+            macros.push(MacroRulesInfo {
+                name,
+                line: (source[..keyword_offset].matches('\n').count() + 1) as u32,
+                arm_patterns,
+            });
+        }
+
+        macros
+    }
+
+    /// Split a line into the items it holds, as (byte offset, text) pairs
+    /// 
+    /// Most lines hold a single item; `struct A; struct B;` holds two. A new
+    /// item starts at the next word after a `;` or a closing `}` at bracket
+    /// depth 0. Comments and literals are ignored, and the last item runs to
+    /// the end of the line.
+    fn split_line_items(line: &str) -> Vec<(usize, &str)> {
+        let masked = mask_comments_and_strings(line);
+        let mut starts = vec![0];
+        let mut depth = 0i32;
+        let mut boundary = false;
+        for ((offset, _), &c) in line.char_indices().zip(&masked) {
+            if boundary && (c.is_alphanumeric() || c == '_' || c == '#') {
+                starts.push(offset);
+            }
+            if !c.is_whitespace() {
+                boundary = false;
+            }
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' => depth -= 1,
+                '}' => {
+                    depth -= 1;
+                    boundary = depth == 0;
+                }
+                ';' => boundary = depth == 0,
+                _ => {}
+            }
+        }
+        starts.iter().enumerate().map(|(index, &start)| {
+            let end = starts.get(index + 1).copied().unwrap_or(line.len());
+            (start, &line[start..end])
+        }).collect()
+    }
+
+    /// Gather the text of a function signature starting at `start_line`
+    /// 
+    /// Starts at the first `fn` keyword from byte `start_col` and continues
+    /// across lines until the body `{` or a trailing `;` outside of any
+    /// parentheses/brackets. Returns an empty string when there is no `fn` keyword.
+    fn collect_signature_text(lines: &[&str], start_line: usize, start_col: usize) -> String {
+        let first = lines[start_line];
+        let fn_start = first.match_indices("fn ").map(|(idx, _)| idx).find(|&idx| {
+            idx >= start_col && (idx == 0 || !first[..idx].ends_with(|c: char| c.is_alphanumeric() || c == '_'))
+        });
+        match fn_start {
+            Some(idx) => Self::collect_header_text(lines, start_line, idx),
+            None => String::new(),
+        }
+    }
+
+    /// Gather item declaration text from byte `start_col` of `start_line`
+    /// 
+    /// Continues across lines until the body `{` or a trailing `;` outside of
+    /// any parentheses/brackets, dropping `//` comments along the way.
+    fn collect_header_text(lines: &[&str], start_line: usize, start_col: usize) -> String {
+        const MAX_SIGNATURE_LINES: usize = 20;
+        let mut text = String::new();
+        let mut depth = 0i32;
+        for (offset, line) in lines[start_line..].iter().take(MAX_SIGNATURE_LINES).enumerate() {
+            let line = if offset == 0 { &line[start_col..] } else { *line };
+            let line = line.split("//").next().unwrap_or(line);
+            for ch in line.chars() {
+                match ch {
+                    '(' | '[' => depth += 1,
+                    ')' | ']' => depth -= 1,
+                    '{' | ';' if depth <= 0 => return text,
+                    _ => {}
+                }
+                text.push(ch);
+            }
+            text.push(' ');
+        }
+        text
+    }
+
+    /// Gather the full text of an item, body included, from byte `start_col` of `start_line`
+    /// 
+    /// Ends once the first `{ ... }` block closes, or at a `;` outside any
+    /// delimiters for items without a body (tuple and unit structs).
+    fn collect_item_text(lines: &[&str], start_line: usize, start_col: usize) -> String {
+        const MAX_ITEM_LINES: usize = 200;
+        let mut text = String::new();
+        let mut depth = 0i32;
+        for (offset, line) in lines[start_line..].iter().take(MAX_ITEM_LINES).enumerate() {
+            let line = if offset == 0 { &line[start_col..] } else { *line };
+            let line = line.split("//").next().unwrap_or(line);
+            for ch in line.chars() {
+                text.push(ch);
+                match ch {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' => depth -= 1,
+                    '}' => {
+                        depth -= 1;
+                        if depth <= 0 {
+                            return text;
+                        }
+                    }
+                    ';' if depth <= 0 => return text,
+                    _ => {}
+                }
+            }
+            text.push(' ');
+        }
+        text
+    }
+
+    /// Parse the fields of a struct definition with `syn`
+    /// 
+    /// Tuple struct fields are named by index; unit structs have no fields.
+    /// Returns `None` when the text is not a complete struct.
+    fn parse_struct_fields(item_text: &str) -> Option<Vec<FieldInfo>> {
+        let item: syn::ItemStruct = syn::parse_str(item_text.trim()).ok()?;
+        Some(item.fields.iter().enumerate().map(|(index, field)| FieldInfo {
+            field: field.ident.as_ref().map(|ident| ident.to_string()).unwrap_or_else(|| index.to_string()),
+            ty: Self::compact_tokens(&field.ty.to_token_stream().to_string()),
+            is_pub: matches!(field.vis, syn::Visibility::Public(_)),
+        }).collect())
+    }
+
+    /// Parse a function signature (`fn name(params) -> ret`) with `syn`
+    /// 
+    /// Returns `None` for text that is not a complete signature, e.g. a
+    /// mention of `fn ` in an expression or a signature we failed to collect.
+    fn parse_fn_signature(signature_text: &str) -> Option<SignatureInfo> {
+        let signature: syn::Signature = syn::parse_str(signature_text.trim()).ok()?;
+
+        let params = signature.inputs.iter().map(|input| match input {
+            syn::FnArg::Receiver(receiver) => ParamInfo {
+                name: "self".to_string(),
+                ty: Self::compact_tokens(&receiver.ty.to_token_stream().to_string()),
+            },
+            syn::FnArg::Typed(typed) => ParamInfo {
+                name: Self::compact_tokens(&typed.pat.to_token_stream().to_string()),
+                ty: Self::compact_tokens(&typed.ty.to_token_stream().to_string()),
+            },
+        }).collect();
+
+        let ret = match &signature.output {
+            syn::ReturnType::Default => "()".to_string(),
+            syn::ReturnType::Type(_, ty) => Self::compact_tokens(&ty.to_token_stream().to_string()),
+        };
+
+        Some(SignatureInfo { params, ret })
+    }
+
+    /// Parse the generic parameters and where-clause of an item header with `syn`
+    /// 
+    /// The header (e.g. `pub struct Wrapper<T: Clone> where T: Send`) is completed
+    /// with an empty body so functions, structs, enums, traits and impls all parse
+    /// as items. Where-clause bounds are merged into the matching parameter.
+    /// Returns `None` for non-generic items and headers that fail to parse.
+    fn parse_item_generics(header: &str) -> Option<Vec<GenericBoundInfo>> {
+        let header = header.trim();
+        let item: syn::Item = syn::parse_str(&format!("{} {{}}", header))
+            .or_else(|_| syn::parse_str(&format!("{};", header)))
+            .ok()?;
+        let generics = match &item {
+            syn::Item::Fn(item) => &item.sig.generics,
+            syn::Item::Struct(item) => &item.generics,
+            syn::Item::Enum(item) => &item.generics,
+            syn::Item::Union(item) => &item.generics,
+            syn::Item::Trait(item) => &item.generics,
+            syn::Item::Impl(item) => &item.generics,
+            syn::Item::Type(item) => &item.generics,
+            _ => return None,
+        };
+
+        let bound_strings = |bounds: &syn::punctuated::Punctuated<syn::TypeParamBound, syn::Token![+]>| -> Vec<String> {
+            bounds.iter().map(|b| Self::compact_tokens(&b.to_token_stream().to_string())).collect()
+        };
+
+        let mut params: Vec<GenericBoundInfo> = Vec::new();
+        for param in &generics.params {
+            match param {
+                syn::GenericParam::Type(type_param) => params.push(GenericBoundInfo {
+                    param: type_param.ident.to_string(),
+                    bounds: bound_strings(&type_param.bounds),
+                }),
+                syn::GenericParam::Lifetime(lifetime_param) => params.push(GenericBoundInfo {
+                    param: lifetime_param.lifetime.to_string(),
+                    bounds: lifetime_param.bounds.iter().map(|l| l.to_string()).collect(),
+                }),
+                syn::GenericParam::Const(_) => {} // Const generics carry a type, not bounds
+            }
+        }
+
+        if let Some(where_clause) = &generics.where_clause {
+            for predicate in &where_clause.predicates {
+                let (param, bounds) = match predicate {
+                    syn::WherePredicate::Type(predicate) => (
+                        Self::compact_tokens(&predicate.bounded_ty.to_token_stream().to_string()),
+                        bound_strings(&predicate.bounds),
+                    ),
+                    syn::WherePredicate::Lifetime(predicate) => (
+                        predicate.lifetime.to_string(),
+                        predicate.bounds.iter().map(|l| l.to_string()).collect(),
+                    ),
+                    _ => continue,
+                };
+                match params.iter_mut().find(|p| p.param == param) {
+                    Some(existing) => existing.bounds.extend(bounds),
+                    None => params.push(GenericBoundInfo { param, bounds }),
+                }
+            }
+        }
+
+        if params.is_empty() {
+            None
+        } else {
+            Some(params)
+        }
+    }
+
+    /// Named lifetimes (`'a`, `'_`, `'static`) mentioned in a type, in order of appearance
+    fn lifetimes_in(ty: &str) -> Vec<String> {
+        let mut lifetimes = Vec::new();
+        for (pos, _) in ty.match_indices('\'') {
+            let name: String = ty[pos + 1..].chars().take_while(|c| c.is_alphanumeric() || *c == '_').collect();
+            if !name.is_empty() {
+                lifetimes.push(format!("'{}", name));
+            }
+        }
+        lifetimes
+    }
+
+    /// Outlives bounds of each declared lifetime, inline and from the where-clause
+    fn declared_lifetimes(generics: &syn::Generics) -> Vec<LifetimeParamInfo> {
+        let mut lifetimes: Vec<LifetimeParamInfo> = generics.lifetimes().map(|param| LifetimeParamInfo {
+            name: param.lifetime.to_string(),
+            bounds: param.bounds.iter().map(|l| l.to_string()).collect(),
+            used_by: Vec::new(),
+        }).collect();
+        for predicate in generics.where_clause.iter().flat_map(|w| &w.predicates) {
+            if let syn::WherePredicate::Lifetime(predicate) = predicate {
+                if let Some(lifetime) = lifetimes.iter_mut().find(|l| l.name == predicate.lifetime.to_string()) {
+                    lifetime.bounds.extend(predicate.bounds.iter().map(|l| l.to_string()));
+                }
+            }
+        }
+        lifetimes
+    }
+
+    /// Lifetime parameters of a function signature and whether elision would cover them
+    /// 
+    /// Elision follows the reference rules: with no references in the return
+    /// type every input lifetime may be distinct (`no_output`); otherwise the
+    /// output borrows from `&self` (`self_receiver`) or from the only input
+    /// lifetime (`single_input`). Signatures outside these rules need their
+    /// annotations, e.g. `fn f<'a, 'b>(x: &'a str, y: &'b str) -> &'a str`.
+    /// Returns `None` for signatures without named lifetimes.
+    fn parse_fn_lifetimes(signature_text: &str) -> Option<LifetimeData> {
+        let signature: syn::Signature = syn::parse_str(signature_text.trim()).ok()?;
+        let inputs: Vec<(String, String)> = signature.inputs.iter().map(|input| match input {
+            syn::FnArg::Receiver(receiver) => ("self".to_string(), Self::compact_tokens(&receiver.ty.to_token_stream().to_string())),
+            syn::FnArg::Typed(typed) => (
+                Self::compact_tokens(&typed.pat.to_token_stream().to_string()),
+                Self::compact_tokens(&typed.ty.to_token_stream().to_string()),
+            ),
+        }).collect();
+        let ret = match &signature.output {
+            syn::ReturnType::Default => String::new(),
+            syn::ReturnType::Type(_, ty) => Self::compact_tokens(&ty.to_token_stream().to_string()),
+        };
+
+        let mut lifetimes = Self::declared_lifetimes(&signature.generics);
+        let mentions_lifetime = inputs.iter().any(|(_, ty)| !Self::lifetimes_in(ty).is_empty()) || !Self::lifetimes_in(&ret).is_empty();
+        if lifetimes.is_empty() && !mentions_lifetime {
+            return None;
+        }
+        for lifetime in &mut lifetimes {
+            lifetime.used_by = inputs.iter()
+                .filter(|(_, ty)| Self::lifetimes_in(ty).contains(&lifetime.name))
+                .map(|(name, _)| name.clone())
+                .collect();
+        }
+
+        // Input lifetime positions: named non-'static lifetimes plus bare `&`
+        let positions = |ty: &str| {
+            let named = Self::lifetimes_in(ty).iter().filter(|l| *l != "'static").count();
+            let bare = ty.match_indices('&').filter(|(pos, _)| !ty[pos + 1..].starts_with('\'')).count();
+            named + bare
+        };
+        let return_lifetimes: Vec<String> = Self::lifetimes_in(&ret).into_iter().filter(|l| l != "'static").collect();
+        let return_bound_to: Vec<String> = inputs.iter()
+            .filter(|(_, ty)| Self::lifetimes_in(ty).iter().any(|l| return_lifetimes.contains(l)))
+            .map(|(name, _)| name.clone())
+            .collect();
+        let receiver = inputs.iter().find(|(name, _)| name == "self").filter(|(_, ty)| ty.starts_with('&'));
+        let input_positions: usize = inputs.iter().map(|(_, ty)| positions(ty)).sum();
+
+        let elision_rule = if positions(&ret) == 0 {
+            // Each declared lifetime must appear once, free to become its own elided lifetime
+            let uses = |name: &str| inputs.iter()
+                .map(|(_, ty)| Self::lifetimes_in(ty).iter().filter(|l| *l == name).count())
+                .sum::<usize>();
+            lifetimes.iter().all(|l| l.bounds.is_empty() && uses(&l.name) == 1).then_some("no_output")
+        } else if let Some((_, self_ty)) = receiver {
+            let self_lifetimes = Self::lifetimes_in(self_ty);
+            (return_lifetimes.iter().all(|l| self_lifetimes.contains(l)) && lifetimes.iter().all(|l| l.bounds.is_empty()))
+                .then_some("self_receiver")
+        } else {
+            (input_positions == 1 && lifetimes.iter().all(|l| l.bounds.is_empty())).then_some("single_input")
+        };
+
+        Some(LifetimeData {
+            lifetimes,
+            return_lifetimes: Some(return_lifetimes),
+            return_bound_to: Some(return_bound_to),
+            elidable: elision_rule.is_some(),
+            elision_rule: elision_rule.map(str::to_string),
+        })
+    }
+
+    /// Lifetime parameters of a struct definition and the fields using them
+    /// 
+    /// Struct lifetimes are never elided. Returns `None` for structs without
+    /// lifetime parameters and for text that is not a struct.
+    fn parse_struct_lifetimes(item_text: &str) -> Option<LifetimeData> {
+        let item: syn::ItemStruct = syn::parse_str(item_text.trim()).ok()?;
+        let mut lifetimes = Self::declared_lifetimes(&item.generics);
+        if lifetimes.is_empty() {
+            return None;
+        }
+        for lifetime in &mut lifetimes {
+            lifetime.used_by = item.fields.iter().enumerate()
+                .filter(|(_, field)| Self::lifetimes_in(&Self::compact_tokens(&field.ty.to_token_stream().to_string())).contains(&lifetime.name))
+                .map(|(index, field)| field.ident.as_ref().map(|ident| ident.to_string()).unwrap_or_else(|| index.to_string()))
+                .collect();
+        }
+        Some(LifetimeData { lifetimes, return_lifetimes: None, return_bound_to: None, elidable: false, elision_rule: None })
+    }
+
+    /// Remove the spaces `quote` puts between every token (`& str` -> `&str`)
+    fn compact_tokens(tokens: &str) -> String {
+        let chars: Vec<char> = tokens.chars().collect();
+        let mut compact = String::with_capacity(tokens.len());
+        for (idx, &ch) in chars.iter().enumerate() {
+            if ch == ' ' {
+                let prev = compact.chars().last();
+                let next = chars.get(idx + 1).copied();
+                if matches!(prev, Some('&' | '<' | '(' | '[' | ':' | '*' | '?'))
+                    || matches!(next, Some('>' | ')' | ']' | ',' | ';' | ':' | '<' | '(' | '['))
+                {
+                    continue;
+                }
+            }
+            compact.push(ch);
+        }
+        compact
+    }
+
+    /// Create mock type inference data in JSON format
+    /// 
+    /// This method generates realistic type inference data that simulates
+    /// what rust-analyzer would produce during type checking. This data
+    /// is essential for training models that understand Rust's type system.
+    /// 
+    /// # Type Detection Strategy
+    /// 
+    /// The mock implementation inspects the source text, in order of certainty:
+    /// - **explicit_annotation**: `let x: T = ...` or `fn f() -> T`
+    /// - **literal**: string, char, integer, float, bool and array literals
+    ///   (honouring suffixes such as `5u8` or `1.0f32`)
+    /// - **std_constructor**: common std types such as `String::from`, `vec![]`,
+    ///   `Vec::new()`, `HashMap::new()`, `Some(..)`, `Ok(..)` and `Box::new(..)`
+    /// - **unknown**: nothing deducible, e.g. `let x = foo();`
+    /// 
+    /// Confidence for each method comes from the extractor's `TypeConfidenceModel`.
+    /// 
+    /// # Generated Data Structure
+    /// 
+    /// ```json
+    /// {
+    ///   "inferred_type": "String",
+    ///   "confidence": 1.0,
+    ///   "inference_method": "explicit_annotation"
+    /// }
+    /// ```
+    /// 
+    /// # Arguments
+    /// 
+    /// * `line` - The source code line to generate type data for
+    /// 
+    /// # Returns
+    /// 
+    /// A JSON string containing mock type inference data including
+    /// the inferred type, confidence level, and inference method.
+    /// 
+    /// # Real Implementation Notes
+    /// 
+    /// In a full implementation, this would provide:
+    /// - Accurate type inference using Rust's type system
+    /// - Complex generic types and trait bounds
+    /// - Type error information and suggestions
+    /// - Inference confidence based on available information
+    /// - Multiple possible types for ambiguous cases
+    fn create_mock_type_data(&self, line: &str) -> String {
+        let (inferred_type, inference_method) = Self::infer_type_from_text(line);
+        let confidence = match inference_method {
+            "explicit_annotation" => self.confidence_model.explicit_annotation,
+            "literal" => self.confidence_model.literal,
+            "std_constructor" => self.confidence_model.std_constructor,
+            _ => self.confidence_model.unknown,
+        };
+
+        let mut type_data = serde_json::json!({
+            "inferred_type": inferred_type,
+            "confidence": confidence,
+            "inference_method": inference_method
+        });
+        if let Some(return_kind) = Self::classify_return_type(line) {
+            type_data["return_kind"] = serde_json::json!(return_kind);
+        }
+        type_data.to_string()
+    }
+
+    /// Text of the explicit return type in `fn f() -> T {`, if the line has one
+    fn return_type_text(code: &str) -> Option<&str> {
+        let arrow_pos = code.find("-> ")?;
+        let return_type = code[arrow_pos + 3..]
+            .split(['{', ';'])
+            .next()
+            .unwrap_or("")
+            .split(" where ")
+            .next()
+            .unwrap_or("")
+            .trim();
+        (!return_type.is_empty()).then_some(return_type)
+    }
+
+    /// Names of the type and const parameters declared in `fn name<...>(` on this line
+    fn fn_generic_params(code: &str) -> Vec<&str> {
+        let Some(after_fn) = code.find("fn ").map(|pos| &code[pos + 3..]) else { return Vec::new() };
+        let (Some(open), Some(paren)) = (after_fn.find('<'), after_fn.find('(')) else { return Vec::new() };
+        if open > paren {
+            return Vec::new();
+        }
+        let mut depth = 0;
+        let mut params = Vec::new();
+        let mut start = open + 1;
+        let mut previous = ' ';
+        for (offset, c) in after_fn[open..].char_indices() {
+            let pos = open + offset;
+            let after_dash = std::mem::replace(&mut previous, c) == '-';
+            match c {
+                '<' => depth += 1,
+                '>' if after_dash => {} // `Fn() -> T` bound
+                '>' if depth == 1 => {
+                    params.push(&after_fn[start..pos]);
+                    break;
+                }
+                '>' => depth -= 1,
+                ',' if depth == 1 => {
+                    params.push(&after_fn[start..pos]);
+                    start = pos + 1;
+                }
+                _ => {}
+            }
+        }
+        params.into_iter()
+            .map(|param| param.trim().trim_start_matches("const ").trim())
+            .filter(|param| !param.starts_with('\''))
+            .filter_map(|param| param.split([':', '=']).next().map(str::trim))
+            .filter(|name| !name.is_empty())
+            .collect()
+    }
+
+    /// Classify the explicit return type on this line
+    /// 
+    /// - `impl_trait`: `-> impl Iterator<Item = u32>` (opaque type)
+    /// - `dyn_trait`: `-> Box<dyn Error>` (trait object)
+    /// - `generic`: mentions a type parameter of the function, e.g. `-> Option<T>`
+    /// - `concrete`: anything else
+    /// 
+    /// Only the function's own parameters are known, so a return type naming
+    /// a parameter of the enclosing impl counts as concrete.
+    fn classify_return_type(line: &str) -> Option<&'static str> {
+        let code = line.split("//").next().unwrap_or("");
+        let return_type = Self::return_type_text(code)?;
+        let words: Vec<&str> = return_type
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .filter(|word| !word.is_empty())
+            .collect();
+        let generic_params = Self::fn_generic_params(code);
+        Some(if words.contains(&"impl") {
+            "impl_trait"
+        } else if words.contains(&"dyn") {
+            "dyn_trait"
+        } else if words.iter().any(|word| generic_params.contains(word)) {
+            "generic"
+        } else {
+            "concrete"
+        })
+    }
+
+    /// Guess the type of a `let` binding or function return from its source text
+    /// 
+    /// Returns the inferred type and the method used to infer it
+    /// (`explicit_annotation`, `literal`, `std_constructor` or `unknown`).
+    fn infer_type_from_text(line: &str) -> (String, &'static str) {
+        let code = line.split("//").next().unwrap_or("").trim();
+
+        // Explicit return type: `fn f() -> T {`
+        if let Some(return_type) = Self::return_type_text(code) {
+            return (return_type.to_string(), "explicit_annotation");
+        }
+
+        let binding = match code.find("let ") {
+            Some(let_pos) => &code[let_pos + 4..],
+            None => return ("unknown".to_string(), "unknown"),
+        };
+        let (pattern, value) = match binding.find('=') {
+            Some(eq_pos) => (&binding[..eq_pos], binding[eq_pos + 1..].trim().trim_end_matches(';').trim()),
+            None => (binding.trim_end_matches(';'), ""),
+        };
+
+        // Explicit annotation: `let x: T = ...`
+        if let Some(colon_pos) = pattern.find(':') {
+            let annotation = pattern[colon_pos + 1..].trim();
+            if !annotation.is_empty() {
+                return (annotation.to_string(), "explicit_annotation");
+            }
+        }
+
+        if let Some(literal_type) = Self::literal_type(value) {
+            return (literal_type, "literal");
+        }
+
+        const STD_CONSTRUCTORS: &[(&str, &str)] = &[
+            ("String::from(", "String"),
+            ("String::new()", "String"),
+            ("format!(", "String"),
+            ("vec![", "Vec<_>"),
+            ("Vec::new()", "Vec<_>"),
+            ("Vec::with_capacity(", "Vec<_>"),
+            ("HashMap::new()", "HashMap<_, _>"),
+            ("HashSet::new()", "HashSet<_>"),
+            ("BTreeMap::new()", "BTreeMap<_, _>"),
+            ("PathBuf::from(", "PathBuf"),
+            ("Box::new(", "Box<_>"),
+            ("Rc::new(", "Rc<_>"),
+            ("Arc::new(", "Arc<_>"),
+            ("Some(", "Option<_>"),
+            ("Ok(", "Result<_, _>"),
+            ("Err(", "Result<_, _>"),
+        ];
+        for (prefix, type_name) in STD_CONSTRUCTORS {
+            if value.starts_with(prefix) {
+                return (type_name.to_string(), "std_constructor");
+            }
+        }
+        if value == "None" {
+            return ("Option<_>".to_string(), "std_constructor");
+        }
+        if value.starts_with('"') && value.ends_with(".to_string()") {
+            return ("String".to_string(), "std_constructor");
+        }
+
+        ("unknown".to_string(), "unknown")
+    }
+
+    /// Determine the type of a literal expression, if `value` is one
+    fn literal_type(value: &str) -> Option<String> {
+        if value.is_empty() {
+            return None;
+        }
+        if value.starts_with('"') && value.ends_with('"') && value.len() >= 2 {
+            return Some("&str".to_string());
+        }
+        if value.starts_with("b\"") && value.ends_with('"') {
+            return Some("&[u8]".to_string());
+        }
+        if value.starts_with('\'') && value.ends_with('\'') && value.len() >= 3 {
+            return Some("char".to_string());
+        }
+        if value == "true" || value == "false" {
+            return Some("bool".to_string());
+        }
+
+        // Array literals: `[1, 2, 3]` or `[0u8; 16]`
+        if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            if let Some((element, count)) = inner.split_once(';') {
+                let element_type = Self::literal_type(element.trim()).unwrap_or_else(|| "_".to_string());
+                return Some(format!("[{}; {}]", element_type, count.trim()));
+            }
+            let elements: Vec<&str> = inner.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()).collect();
+            let element_type = elements.first()
+                .and_then(|e| Self::literal_type(e))
+                .unwrap_or_else(|| "_".to_string());
+            return Some(format!("[{}; {}]", element_type, elements.len()));
+        }
+
+        // Numeric literals, honouring type suffixes
+        let digits = value.trim_start_matches('-');
+        if digits.chars().next().map_or(false, |c| c.is_ascii_digit()) {
+            const SUFFIXES: &[&str] = &[
+                "i8", "i16", "i32", "i64", "i128", "isize",
+                "u8", "u16", "u32", "u64", "u128", "usize",
+                "f32", "f64",
+            ];
+            // `f32`/`f64` at the end of `0x1f32` are hex digits, not a float suffix
+            let radix_prefixed = digits.starts_with("0x") || digits.starts_with("0o") || digits.starts_with("0b");
+            for suffix in SUFFIXES {
+                if radix_prefixed && suffix.starts_with('f') {
+                    continue;
+                }
+                if let Some(number) = digits.strip_suffix(suffix) {
+                    if !number.is_empty() && number.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+                        return Some(suffix.to_string());
+                    }
+                }
+            }
+            if radix_prefixed {
+                return Some("i32".to_string());
+            }
+            if digits.chars().all(|c| c.is_ascii_digit() || c == '_') {
+                return Some("i32".to_string());
+            }
+            if digits.chars().all(|c| c.is_ascii_digit() || c == '_' || c == '.' || c == 'e' || c == 'E' || c == '-' || c == '+') {
+                return Some("f64".to_string());
+            }
+        }
+
+        None
+    }
+}
+
+/// Records written to a scratch file to measure Parquet bytes per record
+const SIZE_SAMPLE_RECORDS: usize = 100;
+
+/// Projected output size of one phase (see `RustAnalyzerExtractor::estimate_dataset_size`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseSizeEstimate {
+    pub phase: String,
+    pub candidate_records: usize,   // Records the dry scan expects the phase to produce
+    pub bytes_per_record: f64,      // Parquet bytes per record, measured on a sample
+    pub estimated_bytes: u64,
+}
+
+/// Projected output size of a whole generation run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetSizeEstimate {
+    pub files: usize,
+    pub phases: Vec<PhaseSizeEstimate>,
+    pub total_bytes: u64,
+}
+
+/// Columns, schemas and compression of written Parquet files
+/// 
+/// Kept apart from the extractor so that phases can be written on other
+/// threads while the extractor (which needs `&mut self`) extracts the next phase.
+#[derive(Debug, Clone, Default)]
+struct ParquetOutput {
+    columns: Option<Vec<String>>,    // Column subset to write (None = all columns)
+    phase_schemas: bool,             // Write phase-specialized schemas instead of the full one
+    write_options: ParquetWriteOptions, // Compression of the written Parquet files
+    partition_by: PartitionBy,       // Directory layout of the written files
+}
+
+impl ParquetOutput {
+    /// Write phase records to Parquet files, splitting if they exceed size limits
+    /// 
+    /// This method handles the conversion from our internal record format to
+    /// Parquet files suitable for Git LFS and HuggingFace datasets. It automatically
+    /// splits large datasets into multiple files to stay under the 10MB Git LFS
+    /// recommended limit.
+    /// 
+    /// # Size Management Strategy
+    /// 
+    /// 1. Write a small sample to estimate bytes per record
+    /// 2. Calculate maximum records per file based on 9MB limit (90% of 10MB for safety)
+    /// 3. Split into multiple files if necessary
+    /// 4. Use consistent naming: data.parquet or data-00000-of-00003.parquet
+    /// 
+    /// # Compression
+    /// 
+    /// Uses Snappy compression for optimal balance of compression ratio and
+    /// decompression speed, which is ideal for ML workloads, unless other
+    /// `ParquetWriteOptions` are configured.
+    fn write_phase(&self, records: &[RustAnalyzerRecord], phase: &ProcessingPhase, output_dir: &Path) -> Result<()> {
+        const MAX_FILE_SIZE_MB: usize = 9; // Stay under 10MB for Git LFS
+//        const RECORDS_PER_BATCH: usize = 1000; // Process in batches to estimate size
+
+        let (phase_dir, file_stem) = match self.partition_by {
+            PartitionBy::None => (output_dir.to_path_buf(), phase.as_str()),
+            PartitionBy::Phase | PartitionBy::SourceDir => (output_dir.join(format!("{}-phase", phase.as_str())), "data"),
+        };
+        std::fs::create_dir_all(&phase_dir)?;
+
+        if records.is_empty() {
+            println!("No records for phase {:?}, skipping", phase);
+            return Ok(());
+        }
+
+        // Estimate size per record by writing a small sample
+        // This helps us determine how many records can fit in each file
+        let bytes_per_record = self.sample_bytes_per_record(records, phase, &phase_dir)?;
+        
+        // Calculate maximum records per file with 10% safety margin
+        let max_records_per_file = ((MAX_FILE_SIZE_MB * 1024 * 1024) as f64 * 0.9 / bytes_per_record) as usize;
+        
+        println!("Estimated {} bytes per record, max {} records per file", bytes_per_record as usize, max_records_per_file);
+
+        if records.len() <= max_records_per_file {
+            // Single file case - all records fit in one file
+            let output_file = phase_dir.join(format!("{}.parquet", file_stem));
+            self.write_records(records, phase, &output_file)?;
+            
+            let file_size_mb = std::fs::metadata(&output_file)?.len() as f64 / (1024.0 * 1024.0);
+            println!("Created single file: {} ({:.2} MB)", output_file.display(), file_size_mb);
+        } else {
+            // Multiple files case - split into chunks
+            let num_files = (records.len() + max_records_per_file - 1) / max_records_per_file;
+            
+            for (file_idx, chunk) in records.chunks(max_records_per_file).enumerate() {
+                let output_file = phase_dir.join(format!("{}-{:05}-of-{:05}.parquet", file_stem, file_idx, num_files));
+                self.write_records(chunk, phase, &output_file)?;
+                
+                let file_size_mb = std::fs::metadata(&output_file)?.len() as f64 / (1024.0 * 1024.0);
+                println!("Created chunk {}/{}: {} ({:.2} MB, {} records)", 
+                    file_idx + 1, num_files, output_file.display(), file_size_mb, chunk.len());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Parquet bytes per record, measured by writing up to 100 of `records` to a scratch file in `dir`
+    /// 
+    /// `records` must not be empty.
+    fn sample_bytes_per_record(&self, records: &[RustAnalyzerRecord], phase: &ProcessingPhase, dir: &Path) -> Result<f64> {
+        let sample_records = &records[..records.len().min(SIZE_SAMPLE_RECORDS)];
+        let temp_file = dir.join(format!("temp_sample-{}.parquet", phase.as_str()));
+        self.write_records(sample_records, phase, &temp_file)?;
+
+        let sample_size_bytes = std::fs::metadata(&temp_file)?.len();
+        std::fs::remove_file(&temp_file)?;
+        Ok(sample_size_bytes as f64 / sample_records.len() as f64)
+    }
+
+    /// Write records to a single Parquet file using Apache Arrow
+    /// 
+    /// This method handles the low-level conversion from our Rust data structures
+    /// to Apache Arrow format and then to Parquet. It defines the schema and
+    /// handles all the type conversions necessary for efficient storage.
+    /// 
+    /// # Schema Design
+    /// 
+    /// The schema is designed to be:
+    /// - **Strongly typed**: Proper types for numeric and string data
+    /// - **Nullable where appropriate**: Optional fields can be null
+    /// - **ML-friendly**: Easy to load into pandas, polars, or other ML frameworks
+    /// - **Queryable**: Supports efficient filtering and aggregation
+    /// 
+    /// # Compression Strategy
+    /// 
+    /// Uses Snappy compression by default, which provides:
+    /// - Fast compression/decompression (important for ML workloads)
+    /// - Good compression ratio for text-heavy data
+    /// - Wide compatibility across Arrow/Parquet ecosystems
+    /// 
+    /// ZSTD with a configurable level can be selected via `with_write_options`.
+    fn write_records(&self, records: &[RustAnalyzerRecord], phase: &ProcessingPhase, output_file: &Path) -> Result<()> {
+        // Use the canonical Arrow schema for our dataset
+        let schema = RustAnalyzerExtractor::parquet_schema();
+
+        // Convert Rust data structures to Arrow arrays
+        // This is where we transform our semantic analysis data into
+        // the columnar format that Parquet expects
+        
+        // Extract all field values into separate vectors for Arrow conversion
+        let ids: Vec<String> = records.iter().map(|r| r.id.clone()).collect();
+        let file_paths: Vec<String> = records.iter().map(|r| r.file_path.clone()).collect();
+        let lines: Vec<u32> = records.iter().map(|r| r.line).collect();
+        let columns: Vec<u32> = records.iter().map(|r| r.column).collect();
+        let source_hashes: Vec<String> = records.iter().map(|r| r.source_hash.clone()).collect();
+        let phases: Vec<String> = records.iter().map(|r| r.phase.clone()).collect();
+        let processing_orders: Vec<u32> = records.iter().map(|r| r.processing_order).collect();
+        let element_types: Vec<String> = records.iter().map(|r| r.element_type.clone()).collect();
+        let element_names: Vec<Option<String>> = records.iter().map(|r| r.element_name.clone()).collect();
+        let element_signatures: Vec<Option<String>> = records.iter().map(|r| r.element_signature.clone()).collect();
+        let enclosing_paths: Vec<Option<String>> = records.iter().map(|r| r.enclosing_path.clone()).collect();
+        let syntax_data: Vec<Option<String>> = records.iter().map(|r| r.syntax_data.clone()).collect();
+        let symbol_data: Vec<Option<String>> = records.iter().map(|r| r.symbol_data.clone()).collect();
+        let type_data: Vec<Option<String>> = records.iter().map(|r| r.type_data.clone()).collect();
+        let diagnostic_data: Vec<Option<String>> = records.iter().map(|r| r.diagnostic_data.clone()).collect();
+        let signature_data: Vec<Option<String>> = records.iter().map(|r| r.signature_data.clone()).collect();
+        let generics_data: Vec<Option<String>> = records.iter().map(|r| r.generics_data.clone()).collect();
+        let lifetime_data: Vec<Option<String>> = records.iter().map(|r| r.lifetime_data.clone()).collect();
+        let parse_fallbacks: Vec<bool> = records.iter().map(|r| r.parse_fallback).collect();
+        let processing_times: Vec<u64> = records.iter().map(|r| r.processing_time_ms).collect();
+        let timestamps: Vec<u64> = records.iter().map(|r| r.timestamp).collect();
+        let rust_versions: Vec<String> = records.iter().map(|r| r.rust_version.clone()).collect();
+        let analyzer_versions: Vec<String> = records.iter().map(|r| r.analyzer_version.clone()).collect();
+        let source_snippets: Vec<String> = records.iter().map(|r| r.source_snippet.clone()).collect();
+        let context_befores: Vec<Option<String>> = records.iter().map(|r| r.context_before.clone()).collect();
+        let context_afters: Vec<Option<String>> = records.iter().map(|r| r.context_after.clone()).collect();
+        let blame_authors: Vec<Option<String>> = records.iter().map(|r| r.blame_author.clone()).collect();
+        let blame_timestamps: Vec<Option<i64>> = records.iter().map(|r| r.blame_timestamp).collect();
+
+        // Create Arrow arrays from the extracted data
+        // Arrow arrays are the columnar data structures that Parquet uses internally
+        let id_array = Arc::new(StringArray::from(ids));
+        let file_path_array = Arc::new(StringArray::from(file_paths));
+        let line_array = Arc::new(UInt32Array::from(lines));
+        let column_array = Arc::new(UInt32Array::from(columns));
+        let source_hash_array = Arc::new(StringArray::from(source_hashes));
+        let phase_array = Arc::new(StringArray::from(phases));
+        let processing_order_array = Arc::new(UInt32Array::from(processing_orders));
+        let element_type_array = Arc::new(StringArray::from(element_types));
+        let element_name_array = Arc::new(StringArray::from(element_names));
+        let element_signature_array = Arc::new(StringArray::from(element_signatures));
+        let enclosing_path_array = Arc::new(StringArray::from(enclosing_paths));
+        let syntax_data_array = Arc::new(StringArray::from(syntax_data));
+        let symbol_data_array = Arc::new(StringArray::from(symbol_data));
+        let type_data_array = Arc::new(StringArray::from(type_data));
+        let diagnostic_data_array = Arc::new(StringArray::from(diagnostic_data));
+        let signature_data_array = Arc::new(StringArray::from(signature_data));
+        let generics_data_array = Arc::new(StringArray::from(generics_data));
+        let lifetime_data_array = Arc::new(StringArray::from(lifetime_data));
+        let parse_fallback_array = Arc::new(BooleanArray::from(parse_fallbacks));
+        let processing_time_array = Arc::new(UInt64Array::from(processing_times));
+        let timestamp_array = Arc::new(UInt64Array::from(timestamps));
+        let rust_version_array = Arc::new(StringArray::from(rust_versions));
+        let analyzer_version_array = Arc::new(StringArray::from(analyzer_versions));
+        let source_snippet_array = Arc::new(StringArray::from(source_snippets));
+        let context_before_array = Arc::new(StringArray::from(context_befores));
+        let context_after_array = Arc::new(StringArray::from(context_afters));
+        let blame_author_array = Arc::new(StringArray::from(blame_authors));
+        let blame_timestamp_array = Arc::new(Int64Array::from(blame_timestamps));
+
+        // Create a record batch (a chunk of columnar data)
+        // This represents all our records in Arrow's columnar format
+        let batch = build_record_batch(
+            schema.clone(),
+            vec![
+                id_array,
+                file_path_array,
+                line_array,
+                column_array,
+                source_hash_array,
+                phase_array,
+                processing_order_array,
+                element_type_array,
+                element_name_array,
+                element_signature_array,
+                enclosing_path_array,
+                syntax_data_array,
+                symbol_data_array,
+                type_data_array,
+                diagnostic_data_array,
+                signature_data_array,
+                generics_data_array,
+                lifetime_data_array,
+                parse_fallback_array,
+                processing_time_array,
+                timestamp_array,
+                rust_version_array,
+                analyzer_version_array,
+                source_snippet_array,
+                context_before_array,
+                context_after_array,
+                blame_author_array,
+                blame_timestamp_array,
+            ],
+            records.len(),
+        )?;
+
+        // Project to the selected column subset, if any; an explicit
+        // --columns list takes precedence over phase-specialized schemas
+        let batch = match &self.columns {
+            Some(columns) => batch.project(&select_columns(&schema, columns)?)?,
+            None if self.phase_schemas => batch.project(&RustAnalyzerExtractor::phase_column_indices(phase))?,
+            None => batch,
+        };
+
+        // Write the record batch to a Parquet file
+        // Configure compression and other properties for optimal ML usage
+        let file = std::fs::File::create(output_file)?;
+        let props = self.write_options.writer_properties()?;
+        
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        Ok(())
+    }
+
+    /// Write a phase and, when diffing against a previous dataset, its changed records into `delta/`
+    /// 
+    /// `previous` holds the previous dataset's hashes from `load_dataset_hashes`.
+    /// The diff covers the phase across all partitions, so a file that moved
+    /// to another `src_dir=` partition only counts as changed, and the records
+    /// of a partition that is gone entirely are still reported as removed.
+    /// Under `PartitionBy::SourceDir` each partition gets its own `delta/`.
+    /// Returns the ids of the previous dataset's records that are gone.
+    fn write_phase_with_delta(
+        &self,
+        records: &[RustAnalyzerRecord],
+        phase: &ProcessingPhase,
+        output_dir: &Path,
+        previous: Option<&HashMap<String, HashMap<String, String>>>,
+        source_root: &Path,
+    ) -> Result<Vec<String>> {
+        if previous.is_some() && self.partition_by == PartitionBy::None {
+            return Err(anyhow::anyhow!("Diffing against a previous dataset needs per-phase directories; it is not supported with PartitionBy::None"));
+        }
+
+        let phase_dir = format!("{}-phase", phase.as_str());
+        let diff = previous.map(|hashes| {
+            let previous_phase: HashMap<String, String> = hashes.iter()
+                .filter(|(directory, _)| directory.rsplit('/').next() == Some(phase_dir.as_str()))
+                .flat_map(|(_, phase_hashes)| phase_hashes.clone())
+                .collect();
+            diff_records(records, &previous_phase)
+        });
+        if let Some((changed, removed)) = &diff {
+            println!("Delta for phase {:?}: {} added/changed, {} removed", phase, changed.len(), removed.len());
+        }
+
+        if self.partition_by != PartitionBy::SourceDir {
+            self.write_phase(records, phase, output_dir)?;
+            if let Some((changed, _)) = &diff {
+                self.write_phase(changed, phase, &output_dir.join("delta"))?;
+            }
+        } else {
+            let mut changed_by_partition = diff.as_ref()
+                .map(|(changed, _)| group_by_source_dir(changed, source_root));
+            for (partition, partition_records) in group_by_source_dir(records, source_root) {
+                let partition_dir = output_dir.join(format!("src_dir={}", partition));
+                self.write_phase(&partition_records, phase, &partition_dir)?;
+                if let Some(changed_by_partition) = &mut changed_by_partition {
+                    let changed = changed_by_partition.remove(&partition).unwrap_or_default();
+                    self.write_phase(&changed, phase, &partition_dir.join("delta"))?;
+                }
+            }
+        }
+        Ok(diff.map(|(_, removed)| removed).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{commit_all, commit_all_as};
+    use proptest::prelude::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rust_analyzer_extractor_creation() {
+        let extractor = RustAnalyzerExtractor::new();
+        assert!(extractor.is_ok());
+    }
+
+    #[test]
+    fn test_find_rust_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "fn main() {}").unwrap();
+
+        let extractor = RustAnalyzerExtractor::new().unwrap();
+        let rust_files = extractor.find_rust_files(temp_dir.path()).unwrap();
+        
+        assert_eq!(rust_files.len(), 1);
+        assert_eq!(rust_files[0], rust_file);
+    }
+
+    #[test]
+    fn test_find_rust_files_with_include_glob() {
+        let temp_dir = TempDir::new().unwrap();
+        let handlers_dir = temp_dir.path().join("src/api/handlers");
+        fs::create_dir_all(&handlers_dir).unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(temp_dir.path().join("src/api/mod.rs"), "mod handlers;").unwrap();
+        fs::write(handlers_dir.join("users.rs"), "fn list() {}").unwrap();
+        fs::write(handlers_dir.join("orders.rs"), "fn list() {}").unwrap();
+        fs::write(handlers_dir.join("orders_test.rs"), "fn test() {}").unwrap();
+
+        let extractor = RustAnalyzerExtractor::new().unwrap()
+            .with_file_filter(&["src/**/handlers/*.rs".to_string()], &["**/*_test.rs".to_string()])
+            .unwrap();
+        let rust_files = extractor.find_rust_files(temp_dir.path()).unwrap();
+
+        assert_eq!(rust_files, vec![handlers_dir.join("orders.rs"), handlers_dir.join("users.rs")]);
+    }
+
+    #[test]
+    fn test_extract_parsing_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "fn main() {\n    println!(\"Hello, world!\");\n}").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_parsing_data(&rust_file).unwrap();
+        
+        assert!(!records.is_empty());
+        assert_eq!(records[0].phase, "parsing");
+        assert_eq!(records[0].element_type, "function");
+    }
+
+    #[test]
+    fn test_element_type_detection() {
+        let extractor = RustAnalyzerExtractor::new().unwrap();
+        
+        assert_eq!(extractor.detect_element_type("fn main() {"), "function");
+        assert_eq!(extractor.detect_element_type("struct Point {"), "struct");
+        assert_eq!(extractor.detect_element_type("enum Color {"), "enum");
+        assert_eq!(extractor.detect_element_type("let x = 5;"), "variable");
+    }
+
+    #[test]
+    fn test_name_resolution_captures_attributes() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "#[derive(Debug, Clone)]\nstruct Point {\n    x: i32,\n}\n\nfn main() {}").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        assert_eq!(records.len(), 2);
+
+        let symbol_data: serde_json::Value = serde_json::from_str(records[0].symbol_data.as_ref().unwrap()).unwrap();
+        let attributes = symbol_data["attributes"].as_array().unwrap();
+        assert_eq!(attributes.len(), 1);
+        assert_eq!(attributes[0]["path"], "derive");
+        assert_eq!(attributes[0]["arguments"], serde_json::json!(["Debug", "Clone"]));
+
+        // The attribute must not leak onto the following item
+        let symbol_data: serde_json::Value = serde_json::from_str(records[1].symbol_data.as_ref().unwrap()).unwrap();
+        assert!(symbol_data["attributes"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_attribute_on_item_line_and_brackets_in_literals() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "#[test] fn t() {}\n#[derive(Debug)] struct Foo;\n#[error(\"unclosed (\", ']')]\nenum Error {}\n\nfn main() {}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        let items: Vec<_> = records.iter().map(|r| (r.line, r.element_type.as_str())).collect();
+        assert_eq!(items, vec![(1, "function"), (2, "struct"), (4, "enum"), (6, "function")]);
+        assert_eq!(records[0].element_name.as_deref(), Some("t"));
+
+        let attributes = |index: usize| {
+            let symbol_data: serde_json::Value = serde_json::from_str(records[index].symbol_data.as_ref().unwrap()).unwrap();
+            symbol_data["attributes"].clone()
+        };
+        assert_eq!(attributes(0), serde_json::json!([{"path": "test", "arguments": []}]));
+        assert_eq!(attributes(1), serde_json::json!([{"path": "derive", "arguments": ["Debug"]}]));
+        assert_eq!(attributes(2), serde_json::json!([{"path": "error", "arguments": ["\"unclosed (\"", "']'"]}]));
+        assert_eq!(attributes(3), serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_struct_field_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "pub struct Account {\n    pub owner: String,\n    balance: Vec<u64>, // cents\n}\n\nstruct Pair(pub i32, &'static str);\n\nstruct Marker;\n\nfn main() {}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        let fields = |index: usize| {
+            let symbol_data: serde_json::Value = serde_json::from_str(records[index].symbol_data.as_ref().unwrap()).unwrap();
+            symbol_data["fields"].clone()
+        };
+
+        assert_eq!(fields(0), serde_json::json!([
+            {"field": "owner", "type": "String", "pub": true},
+            {"field": "balance", "type": "Vec<u64>", "pub": false},
+        ]));
+        assert_eq!(fields(1), serde_json::json!([
+            {"field": "0", "type": "i32", "pub": true},
+            {"field": "1", "type": "&'static str", "pub": false},
+        ]));
+        assert_eq!(fields(2), serde_json::json!([]));
+        assert_eq!(fields(3), serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_id_template_distinguishes_same_line_items() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "struct A; struct B;\n    fn indented() {}\n").unwrap();
+
+        // With {col}, the two structs on line 1 get distinct ids
+        let template = RecordIdTemplate::parse("{path}:{line}:{col}:{phase}").unwrap();
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_id_template(template);
+        let records = extractor.extract_phase_data(&rust_file, &ProcessingPhase::NameResolution).unwrap();
+        let positions: Vec<(u32, u32)> = records.iter().map(|r| (r.line, r.column)).collect();
+        assert_eq!(positions, vec![(1, 1), (1, 11), (2, 5)]);
+        assert_eq!(records[0].element_signature.as_deref(), Some("struct A;"));
+        assert_eq!(records[1].element_signature.as_deref(), Some("struct B;"));
+        assert_eq!(records[1].id, format!("{}:1:11:name_resolution", rust_file.display()));
+        assert_eq!(count_duplicate_ids(&records), 0);
+
+        // The default template collides for the same pair
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_phase_data(&rust_file, &ProcessingPhase::NameResolution).unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(count_duplicate_ids(&records), 1);
+
+        assert!(RecordIdTemplate::parse("{path}:{column}").is_err());
+        assert!(RecordIdTemplate::parse("{path:{line}").is_err());
+        assert!(RecordIdTemplate::parse("{path}}").is_err());
+    }
+
+    #[test]
+    fn test_doctest_records() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "/// Adds one.\n///\n/// ```\n/// assert_eq!(add_one(1), 2);\n/// ```\n///\n/// ```text\n/// not a doctest\n/// ```\npub fn add_one(x: u32) -> u32 {\n    x + 1\n}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        let doctests: Vec<&RustAnalyzerRecord> = records.iter().filter(|r| r.element_type == "doctest").collect();
+        assert_eq!(doctests.len(), 1);
+
+        let doctest = doctests[0];
+        assert_eq!(doctest.line, 3);
+        assert_eq!(doctest.element_name.as_deref(), Some("add_one"));
+        assert_eq!(doctest.source_snippet, "assert_eq!(add_one(1), 2);");
+        let symbol_data: serde_json::Value = serde_json::from_str(doctest.symbol_data.as_ref().unwrap()).unwrap();
+        assert_eq!(symbol_data["documented_line"], 10);
+        assert_eq!(symbol_data["doctest"]["no_run"], false);
+        assert_eq!(symbol_data["doctest"]["ignore"], false);
+        assert_eq!(symbol_data["doctest"]["should_panic"], false);
+
+        // Fence attributes are flagged
+        let flagged = RustAnalyzerExtractor::find_doctests(&[(0, "```no_run"), (1, "main();"), (2, "```"), (3, "```should_panic,ignore"), (4, "```")]);
+        assert_eq!(flagged.len(), 2);
+        assert!(flagged[0].no_run && !flagged[0].ignore);
+        assert!(flagged[1].should_panic && flagged[1].ignore);
+    }
+
+    #[test]
+    fn test_closure_records() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, r#"fn main() {
+    let data = vec![1, 2, 3];
+    let handle = std::thread::spawn(move || data.len());
+    let doubled: Vec<i32> = [1, 2].iter().map(|x| x * 2).collect();
+    let either = true || false; // not a closure: |y| y
+    let text = "|z| z";
+}
+"#).unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_phase_data(&rust_file, &ProcessingPhase::Parsing).unwrap();
+        let closures: Vec<&RustAnalyzerRecord> = records.iter().filter(|r| r.element_type == "closure").collect();
+        assert_eq!(closures.len(), 2);
+
+        let info = |record: &RustAnalyzerRecord| -> serde_json::Value {
+            serde_json::from_str::<serde_json::Value>(record.syntax_data.as_ref().unwrap()).unwrap()["closure"].clone()
+        };
+        assert_eq!((closures[0].line, closures[0].column), (3, 37));
+        assert_eq!(info(closures[0])["is_move"], true);
+        assert_eq!(info(closures[0])["param_count"], 0);
+        assert_eq!(info(closures[0])["body_length"], "data.len()".len());
+        assert_eq!(closures[1].line, 4);
+        assert_eq!(info(closures[1])["is_move"], false);
+        assert_eq!(info(closures[1])["param_count"], 1);
+        assert_eq!(info(closures[1])["params"], "x");
+        assert_eq!(info(closures[1])["body_length"], "x * 2".len());
+
+        // The closure shares its line with the line record but keeps a distinct id
+        assert_eq!(count_duplicate_ids(&records), 0);
+        assert_eq!(closures[0].id, format!("{}:3:parsing:closure:37", rust_file.display()));
+        let line_record = records.iter().find(|r| r.line == 3 && r.element_type != "closure").unwrap();
+        assert_eq!(line_record.id, format!("{}:3:parsing", rust_file.display()));
+
+        // Templates that already tell same-line records apart are rendered as written
+        let template = RecordIdTemplate::parse("{path}:{line}:{col}:{phase}").unwrap();
+        assert_eq!(template.render(closures[0]), format!("{}:3:37:parsing", rust_file.display()));
+    }
+
+    #[test]
+    fn test_enclosing_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, r#"mod bar {
+    pub struct Foo;
+
+    impl Foo {
+        pub fn method(&self) -> u32 {
+            let x = "}";
+            x.len() as u32
+        }
+    }
+}
+
+fn top() {}
+"#).unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_phase_data(&rust_file, &ProcessingPhase::Parsing).unwrap();
+        let path_at = |line: u32| records.iter().find(|r| r.line == line).unwrap().enclosing_path.clone();
+
+        assert_eq!(path_at(1), None);
+        assert_eq!(path_at(2).as_deref(), Some("bar"));
+        assert_eq!(path_at(5).as_deref(), Some("bar::Foo"));
+        assert_eq!(path_at(6).as_deref(), Some("bar::Foo::method"));
+        assert_eq!(path_at(7).as_deref(), Some("bar::Foo::method"));
+        assert_eq!(path_at(12), None);
+    }
+
+    #[test]
+    fn test_impl_self_type() {
+        assert_eq!(RustAnalyzerExtractor::impl_self_type(" Foo ").as_deref(), Some("Foo"));
+        assert_eq!(
+            RustAnalyzerExtractor::impl_self_type("<T: Fn() -> u32> Display for path::Wrapper<T>\nwhere T: Debug ").as_deref(),
+            Some("Wrapper")
+        );
+        assert_eq!(RustAnalyzerExtractor::impl_self_type("<'a> Trait for &'a Foo ").as_deref(), Some("Foo"));
+    }
+
+    #[test]
+    fn test_match_records() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, r#"fn describe(n: u32, flag: bool) -> &'static str {
+    let _ = match flag { true => 1, false => 0 };
+    match n {
+        0 => "zero",
+        1 | 2 => {
+            "few"
+        }
+        _ => "many",
+    }
+}
+"#).unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_phase_data(&rust_file, &ProcessingPhase::Parsing).unwrap();
+        let matches: Vec<&RustAnalyzerRecord> = records.iter().filter(|r| r.element_type == "match").collect();
+        assert_eq!(matches.len(), 2);
+
+        let info = |record: &RustAnalyzerRecord| -> serde_json::Value {
+            serde_json::from_str::<serde_json::Value>(record.syntax_data.as_ref().unwrap()).unwrap()["match"].clone()
+        };
+        assert_eq!((matches[0].line, matches[0].column), (2, 13));
+        assert_eq!(info(matches[0])["arm_count"], 2);
+        assert_eq!(info(matches[0])["has_wildcard"], false);
+        assert_eq!((matches[1].line, matches[1].column), (3, 5));
+        assert_eq!(info(matches[1])["arm_count"], 3);
+        assert_eq!(info(matches[1])["has_wildcard"], true);
+    }
+
+    #[test]
+    fn test_trait_member_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "pub trait Shape {\n    type Unit: Clone;\n    const SIDES: u32 = 0;\n\n    fn area(&self) -> f64;\n\n    fn describe(&self) -> String {\n        format!(\"{} sides\", Self::SIDES)\n    }\n}\n\nfn outside() {}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        let member = |name: &str| {
+            let record = records.iter().find(|r| r.element_name.as_deref() == Some(name)).unwrap();
+            let symbol_data: serde_json::Value = serde_json::from_str(record.symbol_data.as_ref().unwrap()).unwrap();
+            (record.element_type.clone(), symbol_data["trait_member"].clone())
+        };
+
+        let (element_type, area) = member("area");
+        assert_eq!(element_type, "function");
+        assert_eq!(area["trait_name"], "Shape");
+        assert_eq!(area["kind"], "method");
+        assert_eq!(area["signature"], "fn area(&self) -> f64");
+        assert_eq!(area["has_default"], false);
+
+        let (_, describe) = member("describe");
+        assert_eq!(describe["has_default"], true);
+        assert_eq!(describe["signature"], "fn describe(&self) -> String");
+
+        let (element_type, unit) = member("Unit");
+        assert_eq!(element_type, "associated_type");
+        assert_eq!(unit["signature"], "type Unit: Clone");
+        assert_eq!(unit["has_default"], false);
+
+        let (element_type, sides) = member("SIDES");
+        assert_eq!(element_type, "associated_const");
+        assert_eq!(sides["signature"], "const SIDES: u32");
+        assert_eq!(sides["has_default"], true);
+
+        let (_, outside) = member("outside");
+        assert!(outside.is_null());
+    }
+
+    #[test]
+    fn test_name_resolution_captures_cfg_conditions() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "#[cfg(feature = \"full\")]\n#[inline]\npub fn full_only() {}\n\n#[cfg(target_os = \"linux\")]\n#[cfg(any(unix, windows))]\nfn platform() {}\n\nfn always() {}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        assert_eq!(records.len(), 3);
+
+        let cfg_conditions = |index: usize| {
+            let symbol_data: serde_json::Value = serde_json::from_str(records[index].symbol_data.as_ref().unwrap()).unwrap();
+            symbol_data["cfg_conditions"].clone()
+        };
+        assert_eq!(cfg_conditions(0), serde_json::json!(["feature = \"full\""]));
+        assert_eq!(cfg_conditions(1), serde_json::json!(["target_os = \"linux\"", "any(unix, windows)"]));
+        assert_eq!(cfg_conditions(2), serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_type_confidence_explicit_annotation() {
+        let extractor = RustAnalyzerExtractor::new().unwrap();
+
+        let type_data: serde_json::Value = serde_json::from_str(&extractor.create_mock_type_data("    let x: u64 = compute();")).unwrap();
+        assert_eq!(type_data["inferred_type"], "u64");
+        assert_eq!(type_data["confidence"], 1.0);
+        assert_eq!(type_data["inference_method"], "explicit_annotation");
+
+        let type_data: serde_json::Value = serde_json::from_str(&extractor.create_mock_type_data("fn name(&self) -> Option<String> {")).unwrap();
+        assert_eq!(type_data["inferred_type"], "Option<String>");
+        assert_eq!(type_data["confidence"], 1.0);
+    }
+
+    #[test]
+    fn test_type_confidence_inferred_and_unknown() {
+        let extractor = RustAnalyzerExtractor::new().unwrap();
+
+        let type_data: serde_json::Value = serde_json::from_str(&extractor.create_mock_type_data("    let x = foo();")).unwrap();
+        assert_eq!(type_data["inferred_type"], "unknown");
+        assert_eq!(type_data["confidence"], 0.0);
+
+        let infer = |line: &str| RustAnalyzerExtractor::infer_type_from_text(line);
+        assert_eq!(infer("let f = 1.5;"), ("f64".to_string(), "literal"));
+        assert_eq!(infer("let b = 7u8;"), ("u8".to_string(), "literal"));
+        assert_eq!(infer("let h = 0x1f32;"), ("i32".to_string(), "literal"));
+        assert_eq!(infer("let m = 0xffu8;"), ("u8".to_string(), "literal"));
+        assert_eq!(infer("let c = 'a';"), ("char".to_string(), "literal"));
+        assert_eq!(infer("let a = [1, 2, 3];"), ("[i32; 3]".to_string(), "literal"));
+        assert_eq!(infer("let v = Vec::new();"), ("Vec<_>".to_string(), "std_constructor"));
+    }
+
+    #[test]
+    fn test_return_kind_classification() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("lib.rs");
+        fs::write(&rust_file, "\
+fn count() -> usize { 0 }
+fn evens() -> impl Iterator<Item = u32> { (0..10).step_by(2) }
+fn open() -> Result<(), Box<dyn std::error::Error>> { Ok(()) }
+fn first<T: Clone, const N: usize>(items: [T; N]) -> Option<T> { items.first().cloned() }
+fn pick<'a, Item>(a: &'a str) -> &'a str { a }
+fn main() {
+    let x = 5;
+}
+").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_type_inference_data(&rust_file).unwrap();
+        let return_kind = |line: u32| {
+            let record = records.iter().find(|r| r.line == line).unwrap();
+            let type_data: serde_json::Value = serde_json::from_str(record.type_data.as_ref().unwrap()).unwrap();
+            type_data["return_kind"].as_str().map(str::to_string)
+        };
+        assert_eq!(return_kind(1).as_deref(), Some("concrete"));
+        assert_eq!(return_kind(2).as_deref(), Some("impl_trait"));
+        assert_eq!(return_kind(3).as_deref(), Some("dyn_trait"));
+        assert_eq!(return_kind(4).as_deref(), Some("generic"));
+        // Lifetimes are not type parameters
+        assert_eq!(return_kind(5).as_deref(), Some("concrete"));
+        // `let` bindings have no return type
+        assert_eq!(return_kind(7), None);
+    }
+
+    #[test]
+    fn test_skipped_files_reported() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("good.rs"), "fn main() {}").unwrap();
+        let bad_file = temp_dir.path().join("bad.rs");
+        fs::write(&bad_file, [0xff, 0xfe, 0x00, 0x80]).unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing, ProcessingPhase::NameResolution]).unwrap();
+        assert!(!records.is_empty());
+
+        let skipped = extractor.skipped_files();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, bad_file.to_string_lossy());
+        assert_eq!(skipped[0].reason, "non-UTF8 content");
+
+        let output_dir = temp_dir.path().join("out");
+        extractor.write_manifest(&output_dir).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(output_dir.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest["skipped_count"], 1);
+        assert_eq!(manifest["skipped"][0]["reason"], "non-UTF8 content");
+    }
+
+    #[test]
+    fn test_fail_fast_aborts_on_unreadable_file() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("good.rs"), "fn main() {}").unwrap();
+        fs::write(temp_dir.path().join("bad.rs"), [0xff, 0xfe, 0x00, 0x80]).unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        assert!(extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing]).is_ok());
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_fail_fast(true);
+        let error = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing]).unwrap_err();
+        assert!(format!("{:#}", error).contains("bad.rs"));
+        assert!(extractor.skipped_files().is_empty());
+    }
+
+    #[test]
+    fn test_element_type_histogram() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("lib.rs"), "use std::fmt;\n\nstruct Point;\n\nfn main() {\n    let x = 5;\n    let y = x;\n}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing, ProcessingPhase::NameResolution]).unwrap();
+        let counts = extractor.element_type_counts();
+        assert_eq!(counts.values().sum::<usize>(), records.len());
+        assert_eq!(counts["function"], records.iter().filter(|r| r.element_type == "function").count());
+
+        let output_dir = temp_dir.path().join("out");
+        extractor.write_manifest(&output_dir).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(output_dir.join("manifest.json")).unwrap()).unwrap();
+        let total: u64 = manifest["element_type_counts"].as_object().unwrap().values().map(|v| v.as_u64().unwrap()).sum();
+        assert_eq!(total as usize, records.len());
+
+        let histogram = format_element_type_histogram(&BTreeMap::from([("import".to_string(), 2), ("variable".to_string(), 5)]));
+        assert_eq!(histogram, "variable: 5, import: 2");
+    }
+
+    #[test]
+    fn test_throughput_in_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        let sources = ["fn main() {\n    let x = 5;\n}\n", "struct Point;\n\nfn helper() {}\n"];
+        for (index, source) in sources.iter().enumerate() {
+            fs::write(source_dir.join(format!("file{}.rs", index)), source).unwrap();
+        }
+        let phases = [ProcessingPhase::Parsing, ProcessingPhase::NameResolution];
+
+        let expected_records = RustAnalyzerExtractor::new().unwrap().process_codebase(&source_dir, &phases).unwrap().len();
+        let output_dir = temp_dir.path().join("out");
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        extractor.process_codebase_to_parquet(&source_dir, &phases, &output_dir).unwrap();
+
+        let throughput = extractor.throughput().unwrap();
+        assert_eq!(throughput.total_records, expected_records);
+        assert_eq!(throughput.source_bytes, sources.iter().map(|s| s.len() as u64).sum::<u64>());
+        assert!(throughput.elapsed_secs > 0.0);
+        assert!(throughput.records_per_sec > 0.0);
+        assert!(throughput.mb_per_sec > 0.0);
+
+        let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(output_dir.join("manifest.json")).unwrap()).unwrap();
+        assert_eq!(manifest["throughput"]["total_records"], expected_records);
+        assert_eq!(manifest["throughput"]["source_bytes"], throughput.source_bytes);
+        assert!(manifest["throughput"]["records_per_sec"].as_f64().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_oversized_files_skipped_or_truncated() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("small.rs"), "fn main() {}\n").unwrap();
+        let large_source: String = (0..200).map(|i| format!("fn generated_{}() {{}}\n", i)).collect();
+        let large_file = temp_dir.path().join("generated.rs");
+        fs::write(&large_file, &large_source).unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_max_file_bytes(1024, OversizeAction::Skip);
+        let records = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing]).unwrap();
+        assert!(records.iter().all(|r| !r.file_path.ends_with("generated.rs")));
+        let skipped = extractor.skipped_files();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, large_file.to_string_lossy());
+        assert_eq!(skipped[0].size_bytes, Some(large_source.len() as u64));
+        assert!(skipped[0].reason.contains("too large"));
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_max_file_bytes(1024, OversizeAction::Truncate);
+        let records = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing]).unwrap();
+        let truncated: Vec<_> = records.iter().filter(|r| r.file_path.ends_with("generated.rs")).collect();
+        assert!(!truncated.is_empty() && truncated.len() < 200);
+        assert!(truncated.iter().all(|r| r.source_snippet.ends_with("() {}")));
+        let skipped = extractor.skipped_files();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, large_file.to_string_lossy());
+        assert_eq!(skipped[0].size_bytes, Some(large_source.len() as u64));
+        assert!(skipped[0].truncated && skipped[0].reason.starts_with("truncated"));
+
+        // Truncated files are listed in the manifest and still count towards throughput
+        let output_dir = temp_dir.path().join("dataset");
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_max_file_bytes(1024, OversizeAction::Truncate);
+        extractor.process_codebase_to_parquet(temp_dir.path(), &[ProcessingPhase::Parsing], &output_dir).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(output_dir.join("manifest.json")).unwrap()).unwrap();
+        let listed = manifest["skipped"].as_array().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0]["truncated"], true);
+        assert_eq!(listed[0]["size_bytes"], large_source.len() as u64);
+        assert_eq!(manifest["files_processed"], 2);
+        assert!(extractor.throughput().unwrap().source_bytes > 1024);
+    }
+
+    #[test]
+    fn test_macro_rules_records() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "#[macro_export]\nmacro_rules! square {\n    ($x:expr) => {\n        $x * $x\n    };\n    ($x:expr, $y:expr) => { ($x * $x, $y * $y) };\n}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].element_type, "macro_rules");
+        assert_eq!(records[0].element_name.as_deref(), Some("square"));
+        assert_eq!(records[0].line, 2);
+
+        let syntax_data: serde_json::Value = serde_json::from_str(records[0].syntax_data.as_ref().unwrap()).unwrap();
+        assert_eq!(syntax_data["arm_count"], 2);
+        assert_eq!(syntax_data["arm_patterns"], serde_json::json!(["($x:expr)", "($x:expr, $y:expr)"]));
+    }
+
+    #[test]
+    fn test_signature_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "fn f(a: i32, b: &str) -> bool {\n    a > 0 && !b.is_empty()\n}\n\nstruct Unit;\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(records[1].signature_data.is_none());
+
+        let signature: serde_json::Value = serde_json::from_str(records[0].signature_data.as_ref().unwrap()).unwrap();
+        assert_eq!(signature["params"], serde_json::json!([
+            {"name": "a", "type": "i32"},
+            {"name": "b", "type": "&str"},
+        ]));
+        assert_eq!(signature["ret"], "bool");
+    }
+
+    #[test]
+    fn test_generics_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "fn f<T: Clone + Send>() where T: Default {}\n\nstruct Plain;\n\npub struct Wrapper<'a, U: ?Sized>\nwhere\n    U: std::fmt::Debug,\n{\n    inner: &'a U,\n}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        assert_eq!(records.len(), 3);
+
+        let generics: serde_json::Value = serde_json::from_str(records[0].generics_data.as_ref().unwrap()).unwrap();
+        assert_eq!(generics, serde_json::json!([{"param": "T", "bounds": ["Clone", "Send", "Default"]}]));
+
+        assert!(records[1].generics_data.is_none());
+
+        let generics: serde_json::Value = serde_json::from_str(records[2].generics_data.as_ref().unwrap()).unwrap();
+        assert_eq!(generics, serde_json::json!([
+            {"param": "'a", "bounds": []},
+            {"param": "U", "bounds": ["?Sized", "std::fmt::Debug"]},
+        ]));
+    }
+
+    #[test]
+    fn test_lifetime_data() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "\
+fn f<'a, 'b>(x: &'a str, y: &'b str) -> &'a str { x }
+fn first<'a>(s: &'a str) -> &'a str { s }
+fn plain(s: &str) -> usize { s.len() }
+struct Parser<'src> {
+    input: &'src str,
+    pos: usize,
+}
+").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_name_resolution_data(&rust_file).unwrap();
+        let lifetimes_of = |line: u32| -> Option<serde_json::Value> {
+            let record = records.iter().find(|r| r.line == line).unwrap();
+            record.lifetime_data.as_ref().map(|data| serde_json::from_str(data).unwrap())
+        };
+
+        assert_eq!(lifetimes_of(1).unwrap(), serde_json::json!({
+            "lifetimes": [
+                {"name": "'a", "bounds": [], "used_by": ["x"]},
+                {"name": "'b", "bounds": [], "used_by": ["y"]},
+            ],
+            "return_lifetimes": ["'a"],
+            "return_bound_to": ["x"],
+            "elidable": false,
+            "elision_rule": null,
+        }));
+
+        let first = lifetimes_of(2).unwrap();
+        assert_eq!(first["elidable"], true);
+        assert_eq!(first["elision_rule"], "single_input");
+
+        assert!(lifetimes_of(3).is_none());
+
+        let parser = lifetimes_of(4).unwrap();
+        assert_eq!(parser["lifetimes"], serde_json::json!([{"name": "'src", "bounds": [], "used_by": ["input"]}]));
+        assert_eq!(parser["elidable"], false);
+    }
+
+    #[test]
+    fn test_parse_fallback_on_syntax_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let broken_file = temp_dir.path().join("broken.rs");
+        fs::write(&broken_file, "fn complete() -> u32 {\n    1\n}\n\nfn truncated(a: i32 {\n    let x = a +\n").unwrap();
+        let valid_file = temp_dir.path().join("valid.rs");
+        fs::write(&valid_file, "fn main() {\n    let x = 5;\n}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        for phase in [ProcessingPhase::Parsing, ProcessingPhase::NameResolution] {
+            let records = extractor.extract_phase_data(&broken_file, &phase).unwrap();
+            assert!(!records.is_empty());
+            assert!(records.iter().all(|r| r.parse_fallback));
+            assert!(records.iter().any(|r| r.element_name.as_deref() == Some("complete")));
+        }
+
+        let records = extractor.extract_phase_data(&valid_file, &ProcessingPhase::Parsing).unwrap();
+        assert!(records.iter().all(|r| !r.parse_fallback));
+    }
+
+    #[test]
+    fn test_provenance_written() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("main.rs"), "fn main() {}").unwrap();
+        let output_dir = temp_dir.path().join("dataset");
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        extractor.process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing, ProcessingPhase::TypeInference], &output_dir).unwrap();
+
+        let provenance: Provenance = serde_json::from_str(&fs::read_to_string(output_dir.join("provenance.json")).unwrap()).unwrap();
+        assert_eq!(provenance.tool_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(provenance.phases, vec!["parsing", "type_inference"]);
+        assert_eq!(provenance.os, std::env::consts::OS);
+        assert!(chrono::DateTime::parse_from_rfc3339(&provenance.generated_at).is_ok());
+        assert_eq!(provenance.tool_git_commit.as_deref(), option_env!("HF_VALIDATOR_GIT_COMMIT"));
+    }
+
+    #[test]
+    fn test_column_subset_export() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "fn main() {}").unwrap();
+
+        let columns: Vec<String> = ["id", "phase", "source_snippet"].iter().map(|c| c.to_string()).collect();
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_columns(columns).unwrap();
+        let records = extractor.extract_parsing_data(&rust_file).unwrap();
+
+        let output_file = temp_dir.path().join("subset.parquet");
+        extractor.write_records_to_parquet(&records, &ProcessingPhase::Parsing, &output_file).unwrap();
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(fs::File::open(&output_file).unwrap()).unwrap();
+        let field_names: Vec<String> = builder.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(field_names, vec!["id", "phase", "source_snippet"]);
+
+        // Unknown columns are rejected up front
+        let result = RustAnalyzerExtractor::new().unwrap().with_columns(vec!["not_a_column".to_string()]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_phase_specialized_schemas() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("main.rs"), "fn main() {\n    let x = 5;\n}").unwrap();
+        let output_dir = temp_dir.path().join("dataset");
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_phase_schemas(true);
+        extractor.process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing, ProcessingPhase::TypeInference], &output_dir).unwrap();
+
+        let read_schema = |phase: &str| {
+            let file = fs::File::open(output_dir.join(format!("{}-phase/data.parquet", phase))).unwrap();
+            ParquetRecordBatchReaderBuilder::try_new(file).unwrap().schema().as_ref().clone()
+        };
+        let parsing_schema = read_schema("parsing");
+        assert!(parsing_schema.index_of("type_data").is_err());
+        assert!(parsing_schema.index_of("syntax_data").is_ok());
+        assert!(parsing_schema.index_of("source_snippet").is_ok());
+        let type_schema = read_schema("type_inference");
+        assert!(type_schema.index_of("syntax_data").is_err());
+
+        RustAnalyzerExtractor::check_phase_schema(&ProcessingPhase::Parsing, &parsing_schema).unwrap();
+        RustAnalyzerExtractor::check_phase_schema(&ProcessingPhase::Parsing, &RustAnalyzerExtractor::parquet_schema()).unwrap();
+        assert!(RustAnalyzerExtractor::check_phase_schema(&ProcessingPhase::TypeInference, &parsing_schema).is_err());
+    }
+
+    #[test]
+    fn test_diff_against_previous_run() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(source_dir.join("b.rs"), "fn b() -> u32 {\n    let x = 1;\n    x\n}\n\nfn other() {}\n").unwrap();
+        fs::write(source_dir.join("c.rs"), "fn c() {}\n").unwrap();
+
+        let first_run = temp_dir.path().join("first");
+        RustAnalyzerExtractor::new().unwrap()
+            .process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &first_run).unwrap();
+
+        fs::write(source_dir.join("b.rs"), "fn b() -> u32 {\n    let x = 2;\n    x\n}\n\nfn other() {}\n").unwrap();
+        fs::remove_file(source_dir.join("c.rs")).unwrap();
+
+        let second_run = temp_dir.path().join("second");
+        RustAnalyzerExtractor::new().unwrap()
+            .with_diff_against(&first_run)
+            .process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &second_run).unwrap();
+
+        // The edited line plus its neighbours, whose context changed
+        let b_file = source_dir.join("b.rs");
+        let mut delta_ids: Vec<String> = load_phase_hashes(&second_run.join("delta/parsing-phase")).unwrap().into_keys().collect();
+        delta_ids.sort();
+        let expected: Vec<String> = [1, 2, 3].iter().map(|line| format!("{}:{}:parsing", b_file.display(), line)).collect();
+        assert_eq!(delta_ids, expected);
+
+        let removed: Vec<String> = serde_json::from_str(&fs::read_to_string(second_run.join("delta/removed_ids.json")).unwrap()).unwrap();
+        assert_eq!(removed, vec![format!("{}:1:parsing", source_dir.join("c.rs").display())]);
+    }
+
+    #[test]
+    fn test_diff_against_own_output_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("a.rs"), "fn a() {}\n").unwrap();
+        fs::write(source_dir.join("c.rs"), "fn c() {}\n").unwrap();
+
+        let output_dir = temp_dir.path().join("dataset");
+        RustAnalyzerExtractor::new().unwrap()
+            .process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &output_dir).unwrap();
+
+        fs::write(source_dir.join("a.rs"), "fn a() -> u8 { 1 }\n").unwrap();
+        fs::remove_file(source_dir.join("c.rs")).unwrap();
+        RustAnalyzerExtractor::new().unwrap()
+            .with_diff_against(&output_dir)
+            .process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &output_dir).unwrap();
+
+        let delta_ids: Vec<String> = load_phase_hashes(&output_dir.join("delta/parsing-phase")).unwrap().into_keys().collect();
+        assert_eq!(delta_ids, vec![format!("{}:1:parsing", source_dir.join("a.rs").display())]);
+        let removed: Vec<String> = serde_json::from_str(&fs::read_to_string(output_dir.join("delta/removed_ids.json")).unwrap()).unwrap();
+        assert_eq!(removed, vec![format!("{}:1:parsing", source_dir.join("c.rs").display())]);
+    }
+
+    #[test]
+    fn test_convert_json_phases_to_parquet() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "fn main() {\n    let x = 5;\n}").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_parsing_data(&rust_file).unwrap();
+
+        let dataset_dir = temp_dir.path().join("dataset");
+        let phase_dir = dataset_dir.join("parsing-phase");
+        fs::create_dir_all(&phase_dir).unwrap();
+        fs::write(phase_dir.join("data.json"), serde_json::to_string_pretty(&records).unwrap()).unwrap();
+
+        let converted = extractor.convert_json_phases_to_parquet(&dataset_dir).unwrap();
+        assert_eq!(converted, records.len());
+
+        let parquet_file = fs::File::open(phase_dir.join("data.parquet")).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(parquet_file).unwrap().build().unwrap();
+        let total_rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        assert_eq!(total_rows, records.len());
+    }
+
+    #[test]
+    fn test_merge_json_phases_to_jsonl() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("test.rs");
+        fs::write(&rust_file, "use std::fmt;\n\nfn main() {\n    let x = 5;\n}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let dataset_dir = temp_dir.path().join("dataset");
+        let mut phase_counts = BTreeMap::new();
+        for phase in [ProcessingPhase::Parsing, ProcessingPhase::NameResolution] {
+            let records = extractor.extract_phase_data(&rust_file, &phase).unwrap();
+            assert!(!records.is_empty());
+            let phase_dir = dataset_dir.join(format!("{}-phase", phase.as_str()));
+            fs::create_dir_all(&phase_dir).unwrap();
+            fs::write(phase_dir.join("data.json"), serde_json::to_string_pretty(&records).unwrap()).unwrap();
+            phase_counts.insert(phase.as_str().to_string(), records.len());
+        }
+
+        let output_file = temp_dir.path().join("merged/all.jsonl");
+        let merged = merge_json_phases_to_jsonl(&dataset_dir, &output_file).unwrap();
+        let lines: Vec<String> = fs::read_to_string(&output_file).unwrap().lines().map(str::to_string).collect();
+        assert_eq!(merged, phase_counts.values().sum::<usize>());
+        assert_eq!(lines.len(), merged);
+
+        let mut merged_counts = BTreeMap::new();
+        for line in &lines {
+            let record: RustAnalyzerRecord = serde_json::from_str(line).unwrap();
+            *merged_counts.entry(record.phase).or_insert(0) += 1;
+        }
+        assert_eq!(merged_counts, phase_counts);
+    }
+
+    fn optional_text() -> impl Strategy<Value = Option<String>> {
+        proptest::option::of(".{0,40}")
+    }
+
+    fn arb_record() -> impl Strategy<Value = RustAnalyzerRecord> {
+        (
+            (".{0,40}", ".{0,40}", any::<u32>(), any::<u32>(), "[0-9a-f]{0,16}", ".{0,20}", any::<u32>(), ".{0,20}"),
+            (optional_text(), optional_text(), optional_text(), optional_text(), optional_text(), optional_text(), optional_text(), optional_text()),
+            (any::<bool>(), any::<u64>(), any::<u64>(), ".{0,20}", ".{0,20}", ".{0,80}", optional_text(), optional_text()),
+            (optional_text(), optional_text(), proptest::option::of(any::<i64>()), optional_text()),
+        )
+            .prop_map(|(
+                (id, file_path, line, column, source_hash, phase, processing_order, element_type),
+                (element_name, element_signature, syntax_data, symbol_data, type_data, diagnostic_data, signature_data, generics_data),
+                (parse_fallback, processing_time_ms, timestamp, rust_version, analyzer_version, source_snippet, context_before, context_after),
+                (enclosing_path, blame_author, blame_timestamp, lifetime_data),
+            )| RustAnalyzerRecord {
+                id,
+                file_path,
+                line,
+                column,
+                source_hash,
+                phase,
+                processing_order,
+                element_type,
+                element_name,
+                element_signature,
+                enclosing_path,
+                syntax_data,
+                symbol_data,
+                type_data,
+                diagnostic_data,
+                signature_data,
+                generics_data,
+                lifetime_data,
+                parse_fallback,
+                processing_time_ms,
+                timestamp,
+                rust_version,
+                analyzer_version,
+                source_snippet,
+                context_before,
+                context_after,
+                blame_author,
+                blame_timestamp,
+            })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn prop_records_roundtrip_through_parquet(records in proptest::collection::vec(arb_record(), 0..20)) {
+            let temp_dir = TempDir::new().unwrap();
+            let output_file = temp_dir.path().join("data.parquet");
+            let extractor = RustAnalyzerExtractor::new().unwrap();
+
+            extractor.write_records_to_parquet(&records, &ProcessingPhase::Parsing, &output_file).unwrap();
+            let read_back = RustAnalyzerExtractor::read_records_from_parquet(&output_file).unwrap();
+            prop_assert_eq!(read_back, records);
+        }
+    }
+
+    #[test]
+    fn test_element_type_allowlist_and_denylist() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("lib.rs");
+        fs::write(&rust_file, "use std::fmt;\n\nstruct Point;\n\nenum Shape {}\n\nfn main() {\n    let x = 5;\n}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap()
+            .with_element_types(["function".to_string()]);
+        let records = extractor.extract_phase_data(&rust_file, &ProcessingPhase::Parsing).unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(records.iter().all(|r| r.element_type == "function"));
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap()
+            .with_excluded_element_types(["function".to_string(), "other".to_string()]);
+        let records = extractor.extract_phase_data(&rust_file, &ProcessingPhase::Parsing).unwrap();
+        let mut element_types: Vec<&str> = records.iter().map(|r| r.element_type.as_str()).collect();
+        element_types.sort();
+        assert_eq!(element_types, vec!["enum", "import", "struct", "variable"]);
+    }
+
+    #[test]
+    fn test_zstd_level_controls_file_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("lib.rs");
+        let source: String = (0..3000)
+            .map(|i| format!("    let value_{} = compute_{}(input_{}, \"label {}\");\n", i, i % 17, i % 5, i % 11))
+            .collect();
+        fs::write(&rust_file, source).unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_parsing_data(&rust_file).unwrap();
+
+        let write_with_level = |level: i32| {
+            let extractor = RustAnalyzerExtractor::new().unwrap().with_write_options(ParquetWriteOptions {
+                compression: ParquetCodec::Zstd,
+                compression_level: Some(level),
+                ..Default::default()
+            });
+            let output_file = temp_dir.path().join(format!("zstd-{}.parquet", level));
+            extractor.write_records_to_parquet(&records, &ProcessingPhase::Parsing, &output_file).unwrap();
+            fs::metadata(&output_file).unwrap().len()
+        };
+        assert!(write_with_level(19) < write_with_level(1));
+
+        let invalid = ParquetWriteOptions { compression: ParquetCodec::Zstd, compression_level: Some(40), ..Default::default() };
+        assert!(invalid.writer_properties().is_err());
+    }
+
+    #[test]
+    fn test_dictionary_encoding_shrinks_repeated_column() {
+        let temp_dir = TempDir::new().unwrap();
+        let rust_file = temp_dir.path().join("lib.rs");
+        let source: String = (0..3000).map(|i| format!("    let value_{} = {};\n", i, i)).collect();
+        fs::write(&rust_file, source).unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.extract_parsing_data(&rust_file).unwrap();
+        assert!(records.len() >= 3000);
+
+        let write_with_dictionary = |name: &str, dictionary_columns: Vec<String>| {
+            let extractor = RustAnalyzerExtractor::new().unwrap().with_write_options(ParquetWriteOptions {
+                compression: ParquetCodec::Uncompressed,
+                compression_level: None,
+                dictionary_columns: Some(dictionary_columns),
+            });
+            let output_file = temp_dir.path().join(format!("{}.parquet", name));
+            extractor.write_records_to_parquet(&records, &ProcessingPhase::Parsing, &output_file).unwrap();
+            fs::metadata(&output_file).unwrap().len()
+        };
+        assert!(write_with_dictionary("dictionary", vec!["phase".to_string()]) < write_with_dictionary("plain", Vec::new()));
+
+        // Without a column list every column keeps the writer's default dictionary
+        let props = ParquetWriteOptions::default().writer_properties().unwrap();
+        let column = |name: &str| parquet::schema::types::ColumnPath::from(name);
+        assert!(props.dictionary_enabled(&column("file_path")));
+        assert!(props.dictionary_enabled(&column("source_hash")));
+    }
+
+    #[test]
+    fn test_line_coverage_maps_lines_to_record_ids() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("lib.rs"), "struct Point;\n\nfn main() {\n    let x = 5;\n}\n").unwrap();
+        fs::write(source_dir.join("util.rs"), "fn helper() -> u32 {\n    let y: u32 = 1;\n    y\n}\n").unwrap();
+
+        let phases = [ProcessingPhase::Parsing, ProcessingPhase::NameResolution, ProcessingPhase::TypeInference];
+        let output_dir = temp_dir.path().join("out");
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_coverage(true);
+        extractor.process_codebase_to_parquet(&source_dir, &phases, &output_dir).unwrap();
+        let records = extractor.process_codebase(&source_dir, &phases).unwrap();
+        assert!(!records.is_empty());
+
+        let coverage: LineCoverage = serde_json::from_str(&fs::read_to_string(output_dir.join("line_coverage.json")).unwrap()).unwrap();
+        for record in &records {
+            let ids = &coverage[&record.file_path][&record.line];
+            assert!(ids.contains(&record.id), "{} missing from line {}", record.id, record.line);
+        }
+        let total_ids: usize = coverage.values().flat_map(|lines| lines.values()).map(Vec::len).sum();
+        assert_eq!(total_ids, records.len());
+        // Blank lines produce no records
+        assert!(!coverage[&*source_dir.join("lib.rs").to_string_lossy()].contains_key(&2));
+    }
+
+    #[test]
+    fn test_min_records_threshold() {
+        let temp_dir = TempDir::new().unwrap();
+        let empty_dir = temp_dir.path().join("empty");
+        fs::create_dir_all(&empty_dir).unwrap();
+        fs::write(empty_dir.join("notes.txt"), "not rust").unwrap();
+        let populated_dir = temp_dir.path().join("populated");
+        fs::create_dir_all(&populated_dir).unwrap();
+        fs::write(populated_dir.join("main.rs"), "fn main() {\n    let x = 5;\n}\n").unwrap();
+
+        let phases = [ProcessingPhase::Parsing, ProcessingPhase::NameResolution];
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_min_records(1);
+        let error = extractor.process_codebase_to_parquet(&empty_dir, &phases, &temp_dir.path().join("out-empty")).unwrap_err();
+        assert!(error.to_string().contains("below the minimum of 1"), "{}", error);
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_min_records(1);
+        extractor.process_codebase_to_parquet(&populated_dir, &phases, &temp_dir.path().join("out-populated")).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_phase_writes_match_sequential() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("lib.rs"), "pub struct Point {\n    pub x: i32,\n}\n\npub fn origin() -> Point {\n    let p = Point { x: 0 };\n    p\n}\n").unwrap();
+        fs::write(source_dir.join("util.rs"), "fn helper(value: u32) -> u32 {\n    value + 1\n}\n").unwrap();
+
+        let phases = [ProcessingPhase::Parsing, ProcessingPhase::NameResolution, ProcessingPhase::TypeInference];
+        let read_output = |output_dir: &Path| -> Vec<RustAnalyzerRecord> {
+            phases.iter().flat_map(|phase| {
+                let path = output_dir.join(format!("{}-phase", phase.as_str())).join("data.parquet");
+                RustAnalyzerExtractor::read_records_from_parquet(&path).unwrap()
+            }).map(|mut record| {
+                // Wall-clock fields differ between runs
+                record.timestamp = 0;
+                record.processing_time_ms = 0;
+                record
+            }).collect()
+        };
+
+        let sequential_dir = temp_dir.path().join("sequential");
+        RustAnalyzerExtractor::new().unwrap().process_codebase_to_parquet(&source_dir, &phases, &sequential_dir).unwrap();
+        let sequential = read_output(&sequential_dir);
+        assert!(!sequential.is_empty());
+
+        // A generous budget lets all phases be written at once; a 1-byte budget waits for each write
+        for budget in [u64::MAX, 1] {
+            let concurrent_dir = temp_dir.path().join(format!("concurrent-{}", budget));
+            RustAnalyzerExtractor::new().unwrap()
+                .with_concurrent_phase_writes(budget)
+                .with_jobs(4)
+                .process_codebase_to_parquet(&source_dir, &phases, &concurrent_dir)
+                .unwrap();
+            assert_eq!(read_output(&concurrent_dir), sequential);
+        }
+    }
+
+    #[test]
+    fn test_size_estimate_close_to_generated_size() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        for index in 0..20 {
+            fs::write(source_dir.join(format!("module_{}.rs", index)), format!("\
+/// Scales a value.
+pub fn scale_{index}(value: u64) -> u64 {{
+    let factor = {index} + 1;
+    let doubled: Vec<u64> = [value].iter().map(|v| v * factor).collect();
+    match doubled.first() {{
+        Some(first) => *first,
+        None => 0,
+    }}
+}}
+
+pub struct Config{index} {{
+    pub name: String,
+}}
+")).unwrap();
+        }
+        let phases = [ProcessingPhase::Parsing, ProcessingPhase::NameResolution, ProcessingPhase::TypeInference];
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let estimate = extractor.estimate_dataset_size(&source_dir, &phases).unwrap();
+        assert_eq!(estimate.files, 20);
+
+        let output_dir = temp_dir.path().join("dataset");
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.process_codebase(&source_dir, &phases).unwrap();
+        extractor.process_codebase_to_parquet(&source_dir, &phases, &output_dir).unwrap();
+
+        for (phase, phase_estimate) in phases.iter().zip(&estimate.phases) {
+            let actual = records.iter().filter(|r| r.phase == phase.as_str()).count();
+            assert_eq!(phase_estimate.candidate_records, actual, "{}", phase.as_str());
+        }
+        let actual_bytes: u64 = walkdir::WalkDir::new(&output_dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().and_then(|e| e.to_str()) == Some("parquet"))
+            .map(|entry| entry.metadata().unwrap().len())
+            .sum();
+        let ratio = estimate.total_bytes as f64 / actual_bytes as f64;
+        assert!((0.25..=4.0).contains(&ratio), "estimated {} bytes, generated {}", estimate.total_bytes, actual_bytes);
+    }
+
+    #[test]
+    fn test_size_estimate_counts_follow_filters_doctests_and_macros() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("lib.rs"), "\
+/// Doubles a value.
+///
+/// ```
+/// assert_eq!(double(2), 4);
+/// ```
+pub fn double(value: u64) -> u64 { value * 2 }
+
+macro_rules! triple {
+    ($x:expr) => { $x * 3 };
+}
+
+#[derive(Debug)] pub struct Wrapper(u64);
+
+#[inline] pub fn hidden() {}
+").unwrap();
+        let phases = [ProcessingPhase::Parsing, ProcessingPhase::NameResolution];
+        let new_extractor = || RustAnalyzerExtractor::new().unwrap()
+            .with_excluded_element_types(vec!["struct".to_string()]);
+
+        let estimate = new_extractor().estimate_dataset_size(&source_dir, &phases).unwrap();
+        let records = new_extractor().process_codebase(&source_dir, &phases).unwrap();
+
+        assert!(records.iter().all(|r| r.element_type != "struct"));
+        for (phase, phase_estimate) in phases.iter().zip(&estimate.phases) {
+            let actual = records.iter().filter(|r| r.phase == phase.as_str()).count();
+            assert!(actual > 0, "{}", phase.as_str());
+            assert_eq!(phase_estimate.candidate_records, actual, "{}", phase.as_str());
+        }
+    }
+
+    #[test]
+    fn test_partition_by_source_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("crates");
+        fs::create_dir_all(source_dir.join("foo/src")).unwrap();
+        fs::create_dir_all(source_dir.join("bar/src")).unwrap();
+        fs::write(source_dir.join("foo/src/lib.rs"), "pub fn foo() {}\n").unwrap();
+        fs::write(source_dir.join("bar/src/lib.rs"), "pub fn bar() {}\npub fn baz() {}\n").unwrap();
+        fs::write(source_dir.join("build.rs"), "fn main() {}\n").unwrap();
+
+        let output_dir = temp_dir.path().join("dataset");
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_partition_by(PartitionBy::SourceDir);
+        extractor.process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &output_dir).unwrap();
+        assert!(!output_dir.join("parsing-phase").exists());
+
+        let partition_files = |partition: &str| -> Vec<String> {
+            let records = RustAnalyzerExtractor::read_records_from_parquet(&output_dir.join(partition).join("parsing-phase/data.parquet")).unwrap();
+            let mut files: Vec<String> = records.into_iter().map(|r| r.file_path).collect();
+            files.dedup();
+            files
+        };
+        assert_eq!(partition_files("src_dir=foo"), vec![source_dir.join("foo/src/lib.rs").to_string_lossy().to_string()]);
+        assert_eq!(partition_files("src_dir=bar"), vec![source_dir.join("bar/src/lib.rs").to_string_lossy().to_string()]);
+        assert_eq!(partition_files("src_dir=_root"), vec![source_dir.join("build.rs").to_string_lossy().to_string()]);
+    }
+
+    #[test]
+    fn test_diff_reports_removed_partition() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("crates");
+        fs::create_dir_all(source_dir.join("foo/src")).unwrap();
+        fs::create_dir_all(source_dir.join("bar/src")).unwrap();
+        fs::write(source_dir.join("foo/src/lib.rs"), "pub fn foo() {}\n").unwrap();
+        fs::write(source_dir.join("bar/src/lib.rs"), "pub fn bar() {}\n").unwrap();
+
+        let first_run = temp_dir.path().join("first");
+        RustAnalyzerExtractor::new().unwrap().with_partition_by(PartitionBy::SourceDir)
+            .process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &first_run).unwrap();
+
+        fs::remove_dir_all(source_dir.join("bar")).unwrap();
+        let second_run = temp_dir.path().join("second");
+        RustAnalyzerExtractor::new().unwrap().with_partition_by(PartitionBy::SourceDir)
+            .with_diff_against(&first_run)
+            .process_codebase_to_parquet(&source_dir, &[ProcessingPhase::Parsing], &second_run).unwrap();
+
+        let removed: Vec<String> = serde_json::from_str(&fs::read_to_string(second_run.join("delta/removed_ids.json")).unwrap()).unwrap();
+        assert_eq!(removed, vec![format!("{}:1:parsing", source_dir.join("bar/src/lib.rs").display())]);
+        assert!(load_phase_hashes(&second_run.join("src_dir=foo/delta/parsing-phase")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_partition_by_none_writes_flat_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("lib.rs"), "pub fn foo() {}\n").unwrap();
+        let output_dir = temp_dir.path().join("dataset");
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_partition_by(PartitionBy::None);
+        extractor.process_codebase_to_parquet(
+            &source_dir,
+            &[ProcessingPhase::Parsing, ProcessingPhase::NameResolution],
+            &output_dir,
+        ).unwrap();
+        assert!(output_dir.join("parsing.parquet").is_file());
+        assert!(output_dir.join("name_resolution.parquet").is_file());
+        assert!(!output_dir.join("parsing-phase").exists());
+    }
+
+    #[test]
+    fn test_run_limits_stop_with_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        for name in ["a", "b", "c"] {
+            fs::write(source_dir.join(format!("{}.rs", name)), "fn main() {\n    let x = 1;\n}\n").unwrap();
+        }
+        let output_dir = temp_dir.path().join("dataset");
+        let phases = [ProcessingPhase::Parsing, ProcessingPhase::NameResolution];
+
+        let limits = crate::config::RunLimits { max_duration: Some(std::time::Duration::from_nanos(1)), max_memory_bytes: None };
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_run_limits(limits);
+        let error = extractor.process_codebase_to_parquet(&source_dir, &phases, &output_dir).unwrap_err();
+        assert!(error.to_string().contains("--max-duration"), "{}", error);
+
+        let checkpoint: ExtractionCheckpoint = serde_json::from_str(
+            &fs::read_to_string(output_dir.join(CHECKPOINT_FILE)).unwrap()
+        ).unwrap();
+        assert!(checkpoint.completed_phases.is_empty());
+        assert_eq!(checkpoint.interrupted_phase, "parsing");
+        assert_eq!((checkpoint.files_done, checkpoint.total_files), (1, 3));
+        assert!(checkpoint.next_file.is_some());
+        // The interrupted phase is not written
+        assert!(!output_dir.join("parsing-phase").exists());
+
+        // A complete run clears the stale checkpoint
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        extractor.process_codebase_to_parquet(&source_dir, &phases, &output_dir).unwrap();
+        assert!(!output_dir.join(CHECKPOINT_FILE).exists());
+        assert!(output_dir.join("parsing-phase").exists());
+    }
+
+    #[test]
+    fn test_api_changes_between_revisions() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        let lib = "pub mod shapes;\nmod internal;\n\npub fn existing() {}\n\npub(crate) mod util {\n    pub fn helper() {}\n}\n";
+        fs::write(temp_dir.path().join("src/lib.rs"), lib).unwrap();
+        fs::write(temp_dir.path().join("src/shapes.rs"), "pub struct Circle {\n    pub radius: f64,\n}\n\nimpl Circle {\n    pub fn area(&self) -> f64 {\n        self.radius * self.radius\n    }\n}\n").unwrap();
+        // `pub` items of private modules are not exported
+        fs::write(temp_dir.path().join("src/internal.rs"), "pub fn hidden() {}\n").unwrap();
+        // Workspace members are prefixed with their directory
+        fs::create_dir_all(temp_dir.path().join("crates/shapes_core/src")).unwrap();
+        fs::write(temp_dir.path().join("crates/shapes_core/src/lib.rs"), "pub struct Point {\n    pub x: f64,\n}\n").unwrap();
+        commit_all(&repo, "Initial commit");
+        fs::write(temp_dir.path().join("src/lib.rs"), format!("{}\npub fn added_helper() -> u32 {{\n    1\n}}\n\nfn private_helper() {{}}\n", lib)).unwrap();
+        commit_all(&repo, "Add helper");
+
+        let old_items = public_items_at_revision(temp_dir.path(), "HEAD~1").unwrap();
+        let old_paths: Vec<&str> = old_items.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(old_paths, vec!["existing", "shapes::Circle", "shapes::Circle::area", "shapes_core::Point"]);
+        assert_eq!(old_items[3].file_path, "crates/shapes_core/src/lib.rs");
+
+        let changes = compare_api_at_revisions(temp_dir.path(), "HEAD~1", "HEAD").unwrap();
+        assert!(changes.changed);
+        assert_eq!(changes.added, vec![PublicItem {
+            path: "added_helper".to_string(),
+            element_type: "function".to_string(),
+            file_path: "src/lib.rs".to_string(),
+        }]);
+        assert!(changes.removed.is_empty());
+    }
+
+    #[test]
+    fn test_blame_author_matches_line_committer() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+        let commit_as = |name: &str, seconds: i64, message: &str| {
+            let signature = git2::Signature::new(name, &format!("{}@example.com", name.to_lowercase()), &git2::Time::new(seconds, 0)).unwrap();
+            commit_all_as(&repo, &signature, message);
+        };
+
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/lib.rs"), "fn first() {}\n").unwrap();
+        commit_as("Alice", 1_700_000_000, "Add first");
+        fs::write(temp_dir.path().join("src/lib.rs"), "fn first() {}\nfn second() {}\n").unwrap();
+        commit_as("Bob", 1_700_100_000, "Add second");
+        fs::write(temp_dir.path().join("src/lib.rs"), "fn first() {}\nfn second() {}\nfn third() {}\n").unwrap();
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap().with_blame(true);
+        let records = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing]).unwrap();
+        let blame_of = |name: &str| {
+            let record = records.iter().find(|r| r.element_name.as_deref() == Some(name)).unwrap();
+            (record.blame_author.as_deref(), record.blame_timestamp)
+        };
+        assert_eq!(blame_of("first"), (Some("Alice"), Some(1_700_000_000)));
+        assert_eq!(blame_of("second"), (Some("Bob"), Some(1_700_100_000)));
+        // Uncommitted lines have no author yet
+        assert_eq!(blame_of("third"), (None, None));
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        let records = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing]).unwrap();
+        assert!(records.iter().all(|r| r.blame_author.is_none() && r.blame_timestamp.is_none()));
+    }
+
+    #[test]
+    fn test_extract_from_git_revision() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/lib.rs"), "fn main() {}\n").unwrap();
+        fs::write(temp_dir.path().join("src/legacy.rs"), "fn old_helper() {}\n").unwrap();
+        commit_all(&repo, "Initial commit");
+        fs::remove_file(temp_dir.path().join("src/legacy.rs")).unwrap();
+        commit_all(&repo, "Remove legacy module");
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap()
+            .with_git_revision(temp_dir.path(), "HEAD~1")
+            .unwrap();
+        let records = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing]).unwrap();
+
+        let legacy = temp_dir.path().join("src/legacy.rs");
+        assert!(records.iter().any(|r| r.file_path == legacy.to_string_lossy() && r.element_name.as_deref() == Some("old_helper")));
+        // The working tree is untouched
+        assert!(!legacy.exists());
+
+        let mut extractor = RustAnalyzerExtractor::new().unwrap()
+            .with_git_revision(temp_dir.path(), "HEAD")
+            .unwrap();
+        let records = extractor.process_codebase(temp_dir.path(), &[ProcessingPhase::Parsing]).unwrap();
+        assert!(!records.is_empty());
+        assert!(records.iter().all(|r| !r.file_path.ends_with("legacy.rs")));
     }
 }
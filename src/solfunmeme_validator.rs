@@ -1,18 +1,18 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 // Import our unified validator types
 use crate::validator::{
     DataAccess, EntityIdentifier, ParquetMetadata, ValidationError, ValidationResult,
     CachedResponse, ValidationLevel,
 //    DatasetValidator, 
-    validate_split, validate_config, validate_dataset
+    validate_split, validate_config, validate_dataset, fingerprint_strings
 };
 
 /// Structure representing a term in the solfunmeme-index dataset
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct IndexTerm {
     pub term: String,
     pub count: u32,
@@ -32,6 +32,205 @@ pub struct IndexTerm {
     pub last_seen_timestamp: Option<u64>,
 }
 
+/// Default tolerance for the L2 norm of a normalized embedding
+pub const DEFAULT_NORM_TOLERANCE: f64 = 1e-3;
+
+/// Most co-occurring terms kept per term by `CooccurrenceIndex::neighbours`
+///
+/// Linking a whole group is quadratic in its size; members of larger groups
+/// are linked to the first terms of the group by id only.
+pub const MAX_COOCCURRENCE_NEIGHBOURS: usize = 256;
+
+/// Size and lowest term ids of one category or semantic-name group
+#[derive(Debug, Default)]
+struct GroupHead {
+    size: usize,
+    first_ids: BTreeSet<String>,
+}
+
+impl GroupHead {
+    fn insert(&mut self, term_id: &str) {
+        self.size += 1;
+        self.first_ids.insert(term_id.to_string());
+        // One extra member, so every term still gets the cap after leaving itself out
+        if self.first_ids.len() > MAX_COOCCURRENCE_NEIGHBOURS + 1 {
+            self.first_ids.pop_last();
+        }
+    }
+}
+
+/// Category and semantic-name groups of the terms, for linking co-occurring terms
+///
+/// Two terms co-occur when they share the same non-empty category or have at
+/// least one semantic name in common. Only the lowest ids of each group are
+/// kept, so memory grows with the number of distinct categories and semantic
+/// names rather than with the adjacency, and neighbours can be looked up one
+/// batch of terms at a time.
+#[derive(Debug, Default)]
+pub struct CooccurrenceIndex {
+    by_category: HashMap<String, GroupHead>,
+    by_semantic_name: HashMap<String, GroupHead>,
+}
+
+impl CooccurrenceIndex {
+    /// Add a term to the groups of its category and semantic names
+    pub fn insert(&mut self, term_id: &str, term: &IndexTerm) {
+        if !term.category.is_empty() {
+            self.by_category.entry(term.category.clone()).or_default().insert(term_id);
+        }
+        for name in term.semantic_names.iter().flatten() {
+            self.by_semantic_name.entry(name.clone()).or_default().insert(term_id);
+        }
+    }
+
+    /// Ids of the terms co-occurring with an indexed term
+    ///
+    /// At most `MAX_COOCCURRENCE_NEIGHBOURS`, lowest ids first.
+    pub fn neighbours(&self, term_id: &str, term: &IndexTerm) -> Vec<String> {
+        let category = Some(&term.category).filter(|category| !category.is_empty())
+            .and_then(|category| self.by_category.get(category));
+        let names = term.semantic_names.iter().flatten().filter_map(|name| self.by_semantic_name.get(name));
+        let neighbours: BTreeSet<&String> = category.into_iter().chain(names)
+            .flat_map(|group| &group.first_ids)
+            .filter(|id| id.as_str() != term_id)
+            .collect();
+        neighbours.into_iter().take(MAX_COOCCURRENCE_NEIGHBOURS).cloned().collect()
+    }
+
+    /// Groups with more members than are linked, as `(name, size)`
+    fn capped_groups(&self) -> impl Iterator<Item = (&String, usize)> {
+        self.by_category.iter().chain(&self.by_semantic_name)
+            .filter(|(_, group)| group.size > group.first_ids.len())
+            .map(|(name, group)| (name, group.size))
+    }
+}
+
+/// A term whose embedding failed validation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingIssue {
+    pub term_id: String,
+    pub reason: String,
+}
+
+/// Check that an embedding is finite and, optionally, unit-normalized
+/// Returns a description of the problem, or None if the embedding is valid
+pub fn check_embedding(vector: &[f64], require_normalized: bool, tolerance: f64) -> Option<String> {
+    if vector.is_empty() {
+        return Some("embedding is empty".to_string());
+    }
+    if let Some(index) = vector.iter().position(|v| !v.is_finite()) {
+        return Some(format!("non-finite value {} at index {}", vector[index], index));
+    }
+    if require_normalized {
+        let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+        if (norm - 1.0).abs() > tolerance {
+            return Some(format!("L2 norm {:.6} deviates from 1.0 by more than {}", norm, tolerance));
+        }
+    }
+    None
+}
+
+/// Scale an embedding to unit L2 norm in place
+/// Returns false (leaving the vector untouched) if it is zero or non-finite
+pub fn normalize_embedding(vector: &mut [f64]) -> bool {
+    let norm = vector.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if !norm.is_finite() || norm == 0.0 {
+        return false;
+    }
+    for value in vector.iter_mut() {
+        *value /= norm;
+    }
+    true
+}
+
+/// Terms of the synthetic fixture dataset as `(character_dir, term_id, term)`
+///
+/// Covers every `IndexTerm` field shape: all optionals set, all unset, empty
+/// lists, zero values, and train/validation/test character groups.
+fn fixture_terms() -> Vec<(&'static str, &'static str, IndexTerm)> {
+    let base = |term: &str, count: u32, category: &str| IndexTerm {
+        term: term.to_string(),
+        count,
+        category: category.to_string(),
+        significance: String::new(),
+        vibe: String::new(),
+        action_suggestion: String::new(),
+        emoji_representation: None,
+        semantic_names: None,
+        osi_layer: None,
+        prime_factor: None,
+        is_power_of_two: None,
+        numerical_address: None,
+        embedding_vectors: None,
+        versions: Vec::new(),
+        first_seen_timestamp: None,
+        last_seen_timestamp: None,
+    };
+
+    vec![
+        ("a", "1001", IndexTerm {
+            significance: "high".to_string(),
+            vibe: "capable".to_string(),
+            action_suggestion: "document".to_string(),
+            emoji_representation: Some("💪".to_string()),
+            semantic_names: Some(vec!["skill".to_string(), "capacity".to_string()]),
+            osi_layer: Some("application".to_string()),
+            prime_factor: Some(2),
+            is_power_of_two: Some(true),
+            numerical_address: Some("0x1001".to_string()),
+            embedding_vectors: Some(vec![0.6, 0.8]),
+            versions: vec!["1.0.0".to_string(), "1.1.0".to_string()],
+            first_seen_timestamp: Some(1_700_000_000),
+            last_seen_timestamp: Some(1_700_086_400),
+            ..base("abilities", 64, "noun")
+        }),
+        ("a", "1002", base("async", 17, "keyword")),
+        ("s", "1003", IndexTerm {
+            semantic_names: Some(Vec::new()),
+            prime_factor: Some(0),
+            is_power_of_two: Some(false),
+            embedding_vectors: Some(Vec::new()),
+            first_seen_timestamp: Some(0),
+            last_seen_timestamp: Some(0),
+            ..base("struct", 0, "keyword")
+        }),
+        ("s", "1004", IndexTerm {
+            emoji_representation: Some("📦".to_string()),
+            semantic_names: Some(vec!["skill".to_string()]),
+            ..base("serde", 9, "crate")
+        }),
+        ("7", "1005", IndexTerm {
+            numerical_address: Some("7".to_string()),
+            prime_factor: Some(7),
+            ..base("7bit", 3, "encoding")
+        }),
+        ("λ", "1006", IndexTerm {
+            osi_layer: Some("presentation".to_string()),
+            embedding_vectors: Some(vec![1.0, 0.0, 0.0]),
+            ..base("λ-calculus", 1, "noun")
+        }),
+    ]
+}
+
+/// Write a small deterministic solfunmeme-style dataset under `base_path`
+///
+/// Lets the converter and validator run without the real index. Returns the
+/// number of terms written.
+pub fn generate_fixture(base_path: &Path) -> Result<usize, ValidationError> {
+    let terms = fixture_terms();
+    for (character, term_id, term) in &terms {
+        let char_dir = base_path.join("terms").join(character);
+        fs::create_dir_all(&char_dir).map_err(|e| ValidationError::DataAccessError {
+            message: format!("Failed to create directory {}: {}", char_dir.display(), e),
+        })?;
+        let term_path = char_dir.join(format!("{}.json", term_id));
+        fs::write(&term_path, serde_json::to_string_pretty(term)?).map_err(|e| ValidationError::DataAccessError {
+            message: format!("Failed to write term file {}: {}", term_path.display(), e),
+        })?;
+    }
+    Ok(terms.len())
+}
+
 /// Real implementation of DataAccess for the solfunmeme-index dataset
 #[derive(Clone)]
 pub struct SolfunmemeDataAccess {
@@ -79,6 +278,23 @@ impl SolfunmemeDataAccess {
         })
     }
 
+    /// Stream every term as `(term_id, term)`, reading each file only when it is reached
+    ///
+    /// Character directories and the files in each are visited in name order,
+    /// so at most one directory listing is held in memory. Unreadable or
+    /// malformed files are yielded as errors and iteration continues.
+    pub fn iter_terms(&self) -> impl Iterator<Item = Result<(String, IndexTerm), ValidationError>> {
+        walk_terms(Path::new(&self.base_path).join("terms"), 2)
+    }
+
+    /// Stream the terms of one character group as `(term_id, term)`, in name order
+    ///
+    /// Same streaming and error behaviour as `iter_terms`, limited to
+    /// `terms/<character>/`.
+    pub fn iter_group_terms(&self, character: &str) -> impl Iterator<Item = Result<(String, IndexTerm), ValidationError>> {
+        walk_terms(Path::new(&self.base_path).join("terms").join(character), 1)
+    }
+
     /// Load a term from a specific character directory (more efficient if you know the character)
     // pub fn load_term_from_char(&self, term_id: &str, char_dir: &str) -> Result<IndexTerm, ValidationError> {
     //     let term_path = format!("{}/terms/{}/{}.json", self.base_path, char_dir, term_id);
@@ -241,6 +457,56 @@ impl SolfunmemeDataAccess {
         false
     }
 
+    /// Index the category and semantic-name groups of every term
+    ///
+    /// Terms are streamed with `iter_terms`; groups larger than the neighbour
+    /// cap are logged.
+    pub fn build_cooccurrence_index(&self) -> Result<CooccurrenceIndex, ValidationError> {
+        let mut index = CooccurrenceIndex::default();
+        for loaded in self.iter_terms() {
+            match loaded {
+                Ok((term_id, term)) => index.insert(&term_id, &term),
+                Err(e) => eprintln!("Warning: Failed to load term: {}", e),
+            }
+        }
+        for (name, size) in index.capped_groups() {
+            eprintln!(
+                "Warning: {} terms share '{}'; linking each to the first {} by id",
+                size, name, MAX_COOCCURRENCE_NEIGHBOURS
+            );
+        }
+        Ok(index)
+    }
+
+    /// Validate the embedding vectors of every term that has them
+    /// Flags embeddings containing NaN/Inf and, when `require_normalized` is set,
+    /// embeddings whose L2 norm deviates from 1.0 by more than `tolerance`.
+    /// Terms that cannot be loaded are reported as issues too.
+    pub fn validate_embeddings(&self, require_normalized: bool, tolerance: f64) -> Result<Vec<EmbeddingIssue>, ValidationError> {
+        let mut issues = Vec::new();
+
+        for char_dir in self.get_available_chars()? {
+            let first_char = char_dir.chars().next().unwrap_or('a');
+            for term_id in self.get_term_ids_for_char(first_char)? {
+                let term = match self.load_term(&term_id) {
+                    Ok(term) => term,
+                    Err(e) => {
+                        issues.push(EmbeddingIssue { term_id, reason: format!("failed to load term: {}", e) });
+                        continue;
+                    }
+                };
+
+                if let Some(vector) = &term.embedding_vectors {
+                    if let Some(reason) = check_embedding(vector, require_normalized, tolerance) {
+                        issues.push(EmbeddingIssue { term_id, reason });
+                    }
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+
     /// Perform health check on the dataset
     pub fn health_check(&self) -> Result<(), ValidationError> {
         // Check if the base directory exists and is accessible
@@ -357,6 +623,7 @@ impl DataAccess for SolfunmemeDataAccess {
                             search: !term.term.is_empty(),
                             filter: term.count > 0 || !term.category.is_empty(),
                             statistics: term.count > 0 || term.first_seen_timestamp.is_some(),
+                            schema_fingerprint: None,
                         },
                         Err(_) => ValidationResult::new(),
                     }
@@ -375,6 +642,7 @@ impl DataAccess for SolfunmemeDataAccess {
                             search: true,
                             filter: true,
                             statistics: true,
+                            schema_fingerprint: None,
                         },
                         _ => ValidationResult::new(),
                     }
@@ -395,6 +663,24 @@ impl DataAccess for SolfunmemeDataAccess {
         // Check if we have string columns that can be indexed for search
         features.values().any(|v| v.contains("string"))
     }
+
+    fn content_fingerprint(&self) -> Option<String> {
+        // Name, size and modification time of every term file stand in for its content
+        let mut entries = Vec::new();
+        for character in self.get_available_chars().ok()? {
+            let dir_path = format!("{}/terms/{}", self.base_path, character);
+            for entry in fs::read_dir(&dir_path).ok()?.flatten() {
+                let metadata = entry.metadata().ok()?;
+                let modified = metadata.modified().ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0);
+                entries.push(format!("{}/{}:{}:{}", character, entry.file_name().to_string_lossy(), metadata.len(), modified));
+            }
+        }
+        entries.sort();
+        Some(fingerprint_strings(&entries))
+    }
 }
 
 /// Convenience function to create a validator for the solfunmeme dataset
@@ -470,10 +756,87 @@ pub fn test_solfunmeme_dataset() -> Result<(), ValidationError> {
     Ok(())
 }
 
+/// Walk term files `depth` levels below `root` in name order, parsing each as it is reached
+fn walk_terms(root: PathBuf, depth: usize) -> impl Iterator<Item = Result<(String, IndexTerm), ValidationError>> {
+    walkdir::WalkDir::new(root)
+        .min_depth(depth)
+        .max_depth(depth)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(ValidationError::DataAccessError {
+                    message: format!("Failed to read terms directory: {}", e),
+                })),
+            };
+            let term_id = entry.file_name().to_str()?.strip_suffix(".json")?.to_string();
+            if !entry.file_type().is_file() {
+                return None;
+            }
+
+            let term = fs::read_to_string(entry.path())
+                .map_err(|e| ValidationError::DataAccessError {
+                    message: format!("Failed to read term file {}: {}", entry.path().display(), e),
+                })
+                .and_then(|content| serde_json::from_str(&content).map_err(|e| ValidationError::DataAccessError {
+                    message: format!("Failed to parse term JSON {}: {}", entry.path().display(), e),
+                }));
+            Some(term.map(|term| (term_id, term)))
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_iter_terms_yields_each_term_once() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let written = generate_fixture(temp_dir.path()).unwrap();
+        // Non-term files are ignored
+        fs::write(temp_dir.path().join("terms/a/README.txt"), "notes").unwrap();
+
+        let data_access = SolfunmemeDataAccess::new(temp_dir.path().to_str().unwrap());
+        let terms: Vec<(String, IndexTerm)> = data_access.iter_terms().collect::<Result<_, _>>().unwrap();
+        assert_eq!(terms.len(), written);
+
+        let mut ids: Vec<&str> = terms.iter().map(|(id, _)| id.as_str()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids, vec!["1001", "1002", "1003", "1004", "1005", "1006"]);
+
+        for (term_id, term) in &terms {
+            assert_eq!(&data_access.load_term(term_id).unwrap(), term);
+        }
+
+        // A malformed file is reported without stopping the iteration
+        fs::write(temp_dir.path().join("terms/s/broken.json"), "{").unwrap();
+        let results: Vec<_> = data_access.iter_terms().collect();
+        assert_eq!(results.len(), written + 1);
+        assert_eq!(results.iter().filter(|result| result.is_err()).count(), 1);
+    }
+
+    #[test]
+    fn test_iter_group_terms_matches_directory_listing() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let written = generate_fixture(temp_dir.path()).unwrap();
+
+        let data_access = SolfunmemeDataAccess::new(temp_dir.path().to_str().unwrap());
+        let mut streamed = 0;
+        for character in data_access.get_available_chars().unwrap() {
+            let mut listed = data_access.get_term_ids_for_char(character.chars().next().unwrap()).unwrap();
+            listed.sort();
+            let ids: Vec<String> = data_access
+                .iter_group_terms(&character)
+                .map(|loaded| loaded.unwrap().0)
+                .collect();
+            assert_eq!(ids, listed, "group {}", character);
+            streamed += ids.len();
+        }
+        assert_eq!(streamed, written);
+    }
+
     #[test]
     fn test_solfunmeme_data_access() {
         let base_path = "/home/mdupont/2025/08/07/solfunmeme-index";
@@ -535,4 +898,91 @@ mod tests {
         assert_eq!(progress, 1.0); // Dataset validation should be complete
         assert!(validation_result.has_any_capability());
     }
+
+    #[test]
+    fn test_cooccurrence_index() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let write_term = |char_dir: &str, id: &str, term: &str, category: &str| {
+            let dir = temp_dir.path().join("terms").join(char_dir);
+            fs::create_dir_all(&dir).unwrap();
+            let json = serde_json::json!({
+                "term": term,
+                "count": 1,
+                "category": category,
+                "significance": "",
+                "vibe": "",
+                "action_suggestion": "",
+                "versions": []
+            });
+            fs::write(dir.join(format!("{}.json", id)), json.to_string()).unwrap();
+        };
+
+        write_term("a", "1", "alpha", "greek");
+        write_term("b", "2", "beta", "greek");
+        write_term("c", "3", "crate", "rust");
+
+        let data_access = SolfunmemeDataAccess::new(temp_dir.path().to_str().unwrap());
+        let compute_cooccurrence = || {
+            let index = data_access.build_cooccurrence_index().unwrap();
+            data_access.iter_terms()
+                .map(|loaded| {
+                    let (id, term) = loaded.unwrap();
+                    let neighbours = index.neighbours(&id, &term);
+                    (id, neighbours)
+                })
+                .collect::<HashMap<String, Vec<String>>>()
+        };
+        let cooccurrence = compute_cooccurrence();
+
+        assert_eq!(cooccurrence["1"], vec!["2".to_string()]);
+        assert_eq!(cooccurrence["2"], vec!["1".to_string()]);
+        assert!(cooccurrence["3"].is_empty());
+
+        // Members of a category shared by too many terms are linked to the first ids only
+        let mut common_ids: Vec<String> = (0..MAX_COOCCURRENCE_NEIGHBOURS + 2).map(|index| format!("w{}", index)).collect();
+        for id in &common_ids {
+            write_term("w", id, &format!("word-{}", id), "common");
+        }
+        common_ids.sort();
+        let cooccurrence = compute_cooccurrence();
+        for id in &common_ids {
+            assert_eq!(cooccurrence[id].len(), MAX_COOCCURRENCE_NEIGHBOURS, "{}", id);
+        }
+        let last_id = common_ids.last().unwrap();
+        assert!(!cooccurrence[&common_ids[0]].contains(last_id));
+        assert_eq!(cooccurrence[last_id], common_ids[..MAX_COOCCURRENCE_NEIGHBOURS]);
+        assert_eq!(cooccurrence["1"], vec!["2".to_string()]);
+    }
+
+    #[test]
+    fn test_embedding_validation() {
+        assert!(check_embedding(&[0.5, f64::NAN, 0.1], false, DEFAULT_NORM_TOLERANCE).is_some());
+        assert!(check_embedding(&[f64::INFINITY], false, DEFAULT_NORM_TOLERANCE).is_some());
+        assert!(check_embedding(&[3.0, 4.0], false, DEFAULT_NORM_TOLERANCE).is_none());
+        assert!(check_embedding(&[3.0, 4.0], true, DEFAULT_NORM_TOLERANCE).is_some());
+
+        let mut vector = vec![3.0, 4.0];
+        assert!(normalize_embedding(&mut vector));
+        assert_eq!(vector, vec![0.6, 0.8]);
+        assert!(check_embedding(&vector, true, DEFAULT_NORM_TOLERANCE).is_none());
+
+        let mut nan_vector = vec![f64::NAN, 1.0];
+        assert!(!normalize_embedding(&mut nan_vector));
+    }
+
+    #[test]
+    fn test_validate_embeddings_reports_unloadable_terms() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        generate_fixture(temp_dir.path()).unwrap();
+        fs::write(temp_dir.path().join("terms/s/broken.json"), "{").unwrap();
+
+        let data_access = SolfunmemeDataAccess::new(temp_dir.path().to_str().unwrap());
+        let mut issues = data_access.validate_embeddings(true, DEFAULT_NORM_TOLERANCE).unwrap();
+        issues.sort_by(|a, b| a.term_id.cmp(&b.term_id));
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].term_id, "1003");
+        assert_eq!(issues[0].reason, "embedding is empty");
+        assert_eq!(issues[1].term_id, "broken");
+        assert!(issues[1].reason.starts_with("failed to load term"), "{}", issues[1].reason);
+    }
 }
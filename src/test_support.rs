@@ -0,0 +1,84 @@
+//! # Test Support: fixtures shared by the unit tests of several modules
+//!
+//! - `commit_all` / `commit_all_as`: commit a temporary git work tree
+//! - `write_parquet`: write a record batch as a Parquet file
+//! - `spawn_mock_http`: a local HTTP server standing in for crates.io and GitHub
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::ArrowWriter;
+
+/// Stage every change in the work tree of `repo` (deletions included) and commit it on HEAD
+pub fn commit_all_as(repo: &git2::Repository, signature: &git2::Signature, message: &str) {
+    let mut index = repo.index().unwrap();
+    index.add_all(["*"], git2::IndexAddOption::DEFAULT, None).unwrap();
+    index.update_all(["*"], None).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let parents: Vec<git2::Commit> = repo.head().ok().and_then(|h| h.peel_to_commit().ok()).into_iter().collect();
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    repo.commit(Some("HEAD"), signature, signature, message, &tree, &parent_refs).unwrap();
+}
+
+/// `commit_all_as` with a fixed test author, timestamped now
+pub fn commit_all(repo: &git2::Repository, message: &str) {
+    let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+    commit_all_as(repo, &signature, message);
+}
+
+/// Write `batch` as a single-batch Parquet file at `path`, creating parent directories
+pub fn write_parquet(path: &Path, batch: &RecordBatch) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    let mut writer = ArrowWriter::try_new(fs::File::create(path).unwrap(), batch.schema(), None).unwrap();
+    writer.write(batch).unwrap();
+    writer.close().unwrap();
+}
+
+/// Serve HTTP on a local port, answering each request with `respond(path)`; returns the base URL
+///
+/// `respond` gives the status code and JSON body. `None` holds the connection
+/// open without answering, for timeout tests. Connections are handled on their
+/// own threads and closed after one response.
+pub fn spawn_mock_http<F>(respond: F) -> String
+where
+    F: Fn(&str) -> Option<(u16, String)> + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mock_url = format!("http://{}", listener.local_addr().unwrap());
+    let respond = Arc::new(respond);
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let respond = respond.clone();
+            std::thread::spawn(move || {
+                let mut buf = [0u8; 1024];
+                let read = stream.read(&mut buf).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..read]);
+                let path = request.split_whitespace().nth(1).unwrap_or_default().to_string();
+                let Some((status, body)) = respond(&path) else {
+                    std::thread::sleep(Duration::from_secs(30));
+                    return;
+                };
+                let reason = match status {
+                    200 => "OK",
+                    404 => "Not Found",
+                    429 => "Too Many Requests",
+                    _ => "Status",
+                };
+                let response = format!(
+                    "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status, reason, body.len(), body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            });
+        }
+    });
+    mock_url
+}
@@ -0,0 +1,317 @@
+//! # Training Pairs: seq2seq (input, output) text pairs from rust-analyzer datasets
+//!
+//! Derives two-column datasets from the phase records written by
+//! `generate-hf-dataset` / `analyze-rust-project`, for training models that map
+//! one view of a code element to another.
+//!
+//! ## Modes
+//!
+//! - **snippet-type**: `source_snippet` → `inferred_type` from type inference records
+//!   (records whose type could not be inferred are skipped)
+//! - **signature-doc**: `element_signature` → `doc_comment` from name resolution
+//!   records of documented items
+//!
+//! Output is JSONL (`{"input": ..., "output": ...}` per line) unless the output
+//! path ends in `.parquet`, in which case a two-column Parquet file is written.
+//! Pairs keep dataset order unless shuffled with a seed (see `crate::config`).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use rand::seq::SliceRandom;
+
+use crate::config::component_rng;
+use crate::dataset_io::build_record_batch;
+use crate::rust_analyzer_extractor::{ProcessingPhase, RustAnalyzerRecord};
+
+/// Which pair of record fields to emit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PairMode {
+    /// `source_snippet` → inferred type (type inference phase)
+    SnippetToType,
+    /// `element_signature` → doc comment (name resolution phase)
+    SignatureToDoc,
+}
+
+impl PairMode {
+    /// Parse a mode name as accepted on the command line
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snippet-type" => Some(PairMode::SnippetToType),
+            "signature-doc" => Some(PairMode::SignatureToDoc),
+            _ => None,
+        }
+    }
+
+    /// Phase whose records this mode reads
+    pub fn phase(&self) -> ProcessingPhase {
+        match self {
+            PairMode::SnippetToType => ProcessingPhase::TypeInference,
+            PairMode::SignatureToDoc => ProcessingPhase::NameResolution,
+        }
+    }
+}
+
+/// A single (input, output) training example
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextPair {
+    pub input: String,
+    pub output: String,
+}
+
+/// The record fields the pair modes need, read from either JSON or Parquet
+#[derive(Debug, Default)]
+struct PairSource {
+    source_snippet: String,
+    element_signature: Option<String>,
+    type_data: Option<String>,
+    symbol_data: Option<String>,
+}
+
+impl From<RustAnalyzerRecord> for PairSource {
+    fn from(record: RustAnalyzerRecord) -> Self {
+        Self {
+            source_snippet: record.source_snippet,
+            element_signature: record.element_signature,
+            type_data: record.type_data,
+            symbol_data: record.symbol_data,
+        }
+    }
+}
+
+/// Form the pair for one record, if the record has both halves
+fn pair_from_source(source: &PairSource, mode: PairMode) -> Option<TextPair> {
+    let json_field = |json: &Option<String>, key: &str| -> Option<String> {
+        let value: serde_json::Value = serde_json::from_str(json.as_deref()?).ok()?;
+        value.get(key)?.as_str().map(|s| s.to_string())
+    };
+
+    match mode {
+        PairMode::SnippetToType => {
+            let inferred_type = json_field(&source.type_data, "inferred_type")?;
+            if inferred_type == "unknown" {
+                return None;
+            }
+            Some(TextPair { input: source.source_snippet.trim().to_string(), output: inferred_type })
+        }
+        PairMode::SignatureToDoc => {
+            let doc_comment = json_field(&source.symbol_data, "doc_comment")?;
+            let signature = source.element_signature.as_ref()?;
+            Some(TextPair { input: signature.clone(), output: doc_comment })
+        }
+    }
+}
+
+/// Load the records of one phase directory, preferring Parquet over `data.json`
+fn load_phase_sources(phase_dir: &Path) -> Result<Vec<PairSource>> {
+    let mut parquet_files: Vec<PathBuf> = std::fs::read_dir(phase_dir)
+        .with_context(|| format!("Failed to read {}", phase_dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("parquet"))
+        .collect();
+    parquet_files.sort();
+
+    if parquet_files.is_empty() {
+        let json_file = phase_dir.join("data.json");
+        if !json_file.exists() {
+            return Ok(Vec::new());
+        }
+        let json_content = std::fs::read_to_string(&json_file)
+            .with_context(|| format!("Failed to read {}", json_file.display()))?;
+        let records: Vec<RustAnalyzerRecord> = serde_json::from_str(&json_content)
+            .with_context(|| format!("Failed to parse {}", json_file.display()))?;
+        return Ok(records.into_iter().map(PairSource::from).collect());
+    }
+
+    let mut sources = Vec::new();
+    for parquet_file in parquet_files {
+        let file = std::fs::File::open(&parquet_file)
+            .with_context(|| format!("Failed to open {}", parquet_file.display()))?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(file)?.build()?;
+        for batch in reader {
+            let batch = batch?;
+            // Columns may be missing when the dataset was written with --columns
+            let column = |name: &str| batch.column_by_name(name)
+                .and_then(|c| c.as_any().downcast_ref::<StringArray>())
+                .cloned();
+            let value = |array: &Option<StringArray>, row: usize| array.as_ref()
+                .filter(|a| !a.is_null(row))
+                .map(|a| a.value(row).to_string());
+
+            let source_snippets = column("source_snippet");
+            let element_signatures = column("element_signature");
+            let type_data = column("type_data");
+            let symbol_data = column("symbol_data");
+            for row in 0..batch.num_rows() {
+                sources.push(PairSource {
+                    source_snippet: value(&source_snippets, row).unwrap_or_default(),
+                    element_signature: value(&element_signatures, row),
+                    type_data: value(&type_data, row),
+                    symbol_data: value(&symbol_data, row),
+                });
+            }
+        }
+    }
+    Ok(sources)
+}
+
+/// Build the pairs for `mode` from a rust-analyzer dataset directory
+pub fn collect_pairs(dataset_dir: &Path, mode: PairMode) -> Result<Vec<TextPair>> {
+    let phase_dir = dataset_dir.join(format!("{}-phase", mode.phase().as_str()));
+    if !phase_dir.is_dir() {
+        return Err(anyhow::anyhow!("Phase directory not found: {}", phase_dir.display()));
+    }
+
+    Ok(load_phase_sources(&phase_dir)?
+        .iter()
+        .filter_map(|source| pair_from_source(source, mode))
+        .collect())
+}
+
+/// Write pairs as JSONL, or as Parquet when `output` ends in `.parquet`
+pub fn write_pairs(pairs: &[TextPair], output: &Path) -> Result<()> {
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if output.extension().and_then(|e| e.to_str()) == Some("parquet") {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("input", DataType::Utf8, false),
+            Field::new("output", DataType::Utf8, false),
+        ]));
+        let inputs: ArrayRef = Arc::new(StringArray::from_iter_values(pairs.iter().map(|p| p.input.as_str())));
+        let outputs: ArrayRef = Arc::new(StringArray::from_iter_values(pairs.iter().map(|p| p.output.as_str())));
+        let batch = build_record_batch(schema.clone(), vec![inputs, outputs], pairs.len())?;
+
+        let file = std::fs::File::create(output)
+            .with_context(|| format!("Failed to create {}", output.display()))?;
+        let props = WriterProperties::builder()
+            .set_compression(parquet::basic::Compression::SNAPPY)
+            .build();
+        let mut writer = ArrowWriter::try_new(file, schema, Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+    } else {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(output)
+            .with_context(|| format!("Failed to create {}", output.display()))?);
+        for pair in pairs {
+            writeln!(writer, "{}", serde_json::to_string(pair)?)?;
+        }
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Collect and write the pairs for `mode`, returning how many were written
+///
+/// With `shuffle_seed`, pairs are written in a seeded random order.
+pub fn emit_pairs(dataset_dir: &Path, mode: PairMode, output: &Path, shuffle_seed: Option<u64>) -> Result<usize> {
+    let mut pairs = collect_pairs(dataset_dir, mode)?;
+    if let Some(seed) = shuffle_seed {
+        pairs.shuffle(&mut component_rng(seed, "training_pairs"));
+    }
+    write_pairs(&pairs, output)?;
+    Ok(pairs.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rust_analyzer_extractor::RustAnalyzerExtractor;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_emit_pairs_from_parquet_dataset() {
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        fs::write(source_dir.join("lib.rs"), "\
+/// Adds two numbers.
+/// Wraps on overflow.
+pub fn add(a: u32, b: u32) -> u32 {
+    let total: u32 = a.wrapping_add(b);
+    let label = \"sum\";
+    let mystery = compute();
+    total
+}
+
+fn undocumented() {}
+").unwrap();
+
+        let dataset_dir = temp_dir.path().join("dataset");
+        let mut extractor = RustAnalyzerExtractor::new().unwrap();
+        extractor.process_codebase_to_parquet(
+            &source_dir,
+            &[ProcessingPhase::NameResolution, ProcessingPhase::TypeInference],
+            &dataset_dir,
+        ).unwrap();
+
+        let pairs = collect_pairs(&dataset_dir, PairMode::SignatureToDoc).unwrap();
+        assert_eq!(pairs, vec![TextPair {
+            input: "pub fn add(a: u32, b: u32) -> u32 {".to_string(),
+            output: "Adds two numbers.\nWraps on overflow.".to_string(),
+        }]);
+
+        let pairs = collect_pairs(&dataset_dir, PairMode::SnippetToType).unwrap();
+        assert!(pairs.contains(&TextPair { input: "let total: u32 = a.wrapping_add(b);".to_string(), output: "u32".to_string() }));
+        assert!(pairs.contains(&TextPair { input: "let label = \"sum\";".to_string(), output: "&str".to_string() }));
+        assert!(pairs.iter().all(|p| !p.input.contains("mystery")));
+
+        let output = temp_dir.path().join("pairs.jsonl");
+        let count = emit_pairs(&dataset_dir, PairMode::SnippetToType, &output, None).unwrap();
+        assert_eq!(fs::read_to_string(&output).unwrap().lines().count(), count);
+    }
+
+    #[test]
+    fn test_seeded_generation_is_reproducible() {
+        // Pin the clock to the current second, so tests running alongside still see "now"
+        std::env::set_var("SOURCE_DATE_EPOCH", crate::config::generation_timestamp().to_string());
+        let temp_dir = TempDir::new().unwrap();
+        let source_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&source_dir).unwrap();
+        let source: String = (0..12)
+            .map(|i| format!("fn f{i}() {{\n    let value{i}: u{} = {i};\n}}\n", 8 << (i % 4)))
+            .collect();
+        fs::write(source_dir.join("lib.rs"), source).unwrap();
+
+        let generate = |name: &str, seed: u64| {
+            let dataset_dir = temp_dir.path().join(name);
+            let mut extractor = RustAnalyzerExtractor::new().unwrap();
+            extractor.process_codebase_to_parquet(&source_dir, &[ProcessingPhase::TypeInference], &dataset_dir).unwrap();
+            let output = dataset_dir.join("pairs.jsonl");
+            emit_pairs(&dataset_dir, PairMode::SnippetToType, &output, Some(seed)).unwrap();
+            dataset_dir
+        };
+
+        let first = generate("first", 7);
+        let second = generate("second", 7);
+        let other = generate("other", 8);
+
+        let pairs_of = |dir: &Path| fs::read_to_string(dir.join("pairs.jsonl")).unwrap();
+        assert!(pairs_of(&first).lines().count() >= 10);
+        assert_eq!(pairs_of(&first), pairs_of(&second));
+        assert_ne!(pairs_of(&first), pairs_of(&other));
+
+        let records_of = |dir: &Path| {
+            let phase_dir = dir.join("type_inference-phase");
+            let mut records = Vec::new();
+            for entry in fs::read_dir(&phase_dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.extension().and_then(|e| e.to_str()) == Some("parquet") {
+                    records.extend(RustAnalyzerExtractor::read_records_from_parquet(&path).unwrap());
+                }
+            }
+            records
+        };
+        assert_eq!(records_of(&first), records_of(&second));
+    }
+}
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use thiserror::Error;
 
 // ============================================================================
@@ -24,6 +28,9 @@ pub struct ValidationResult {
     pub search: bool,
     pub filter: bool,
     pub statistics: bool,
+    /// `ParquetMetadata::schema_fingerprint` of the validated data, when known
+    #[serde(default)]
+    pub schema_fingerprint: Option<String>,
 }
 
 impl ValidationResult {
@@ -37,6 +44,9 @@ impl ValidationResult {
         self.search |= other.search;
         self.filter |= other.filter;
         self.statistics |= other.statistics;
+        if self.schema_fingerprint.is_none() {
+            self.schema_fingerprint = other.schema_fingerprint.clone();
+        }
     }
 
 //    pub fn has_any_capability(&self) -> bool {
@@ -49,6 +59,21 @@ impl ValidationResult {
             .filter(|&&x| x)
             .count()
     }
+
+    /// Capabilities that differ from `other`: `+name` if only `other` has it, `-name` if only `self` does
+    pub fn diff(&self, other: &ValidationResult) -> Vec<String> {
+        [
+            ("viewer", self.viewer, other.viewer),
+            ("preview", self.preview, other.preview),
+            ("search", self.search, other.search),
+            ("filter", self.filter, other.filter),
+            ("statistics", self.statistics, other.statistics),
+        ]
+        .iter()
+        .filter(|(_, before, after)| before != after)
+        .map(|(name, _, after)| format!("{}{}", if *after { "+" } else { "-" }, name))
+        .collect()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -90,6 +115,40 @@ impl EntityIdentifier {
         }
     }
 
+    /// Parse a Hugging Face dataset link into an identifier
+    ///
+    /// Accepts `https://huggingface.co/datasets/org/name` and `hf://datasets/org/name`
+    /// (optionally with an `@revision`), plus a trailing `/viewer/config[/split]`
+    /// to target a config or split. Query strings, fragments and other trailing
+    /// path segments (e.g. `/tree/main`) are ignored.
+    pub fn from_url(url: &str) -> Result<Self, ValidationError> {
+        let invalid = |reason: &str| ValidationError::InvalidEntityIdentifier {
+            message: format!("{}: {}", reason, url),
+        };
+
+        let trimmed = url.trim();
+        let path = ["hf://datasets/", "https://huggingface.co/datasets/", "http://huggingface.co/datasets/", "huggingface.co/datasets/"]
+            .iter()
+            .find_map(|prefix| trimmed.strip_prefix(prefix))
+            .ok_or_else(|| invalid("Not a Hugging Face dataset URL"))?;
+        let path = path.split(|c| c == '?' || c == '#').next().unwrap_or("");
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        // Datasets are `org/name`; legacy canonical datasets have no org
+        let (dataset, rest) = match segments.as_slice() {
+            [org, name, rest @ ..] if *name != "viewer" => (format!("{}/{}", org, name), rest),
+            [name, rest @ ..] => (name.to_string(), rest),
+            [] => return Err(invalid("Missing dataset name")),
+        };
+        let dataset = dataset.split('@').next().unwrap_or(&dataset).to_string();
+
+        Ok(match rest {
+            ["viewer", config, split, ..] => Self::new_split(dataset, config.to_string(), split.to_string()),
+            ["viewer", config] => Self::new_config(dataset, config.to_string()),
+            _ => Self::new_dataset(dataset),
+        })
+    }
+
     pub fn infer_level(&self) -> ValidationLevel {
         match (&self.config, &self.split) {
             (Some(_), Some(_)) => ValidationLevel::Split,
@@ -159,6 +218,18 @@ impl ParquetMetadata {
         self.num_rows = Some(num_rows);
         self
     }
+
+    /// Stable hex fingerprint of the feature set (sorted `name:type` pairs)
+    ///
+    /// Independent of feature order. Uses FNV-1a so producers and consumers
+    /// built with different Rust versions agree on it.
+    pub fn schema_fingerprint(&self) -> String {
+        let mut entries: Vec<String> = self.features.iter()
+            .map(|(name, data_type)| format!("{}:{}", name, data_type))
+            .collect();
+        entries.sort();
+        format!("{:016x}", fnv1a(FNV1A_OFFSET_BASIS, entries.join("\n").as_bytes()))
+    }
 }
 
 // ============================================================================
@@ -217,6 +288,12 @@ pub trait DataAccess: Send + Sync {
     fn get_config_names(&self, dataset: &str) -> Result<Vec<String>, ValidationError>;
     fn get_cached_validation(&self, kind: &str, entity: &EntityIdentifier) -> Result<CachedResponse, ValidationError>;
     fn has_indexable_columns(&self, features: &HashMap<String, String>) -> bool;
+
+    /// Hash of the underlying data, used to key persisted validation results.
+    /// `None` (the default) disables result caching for this access.
+    fn content_fingerprint(&self) -> Option<String> {
+        None
+    }
 }
 
 // ============================================================================
@@ -230,6 +307,7 @@ pub struct MockDataAccess {
     split_names: HashMap<String, Vec<String>>,
     config_names: HashMap<String, Vec<String>>,
     cached_validations: HashMap<String, CachedResponse>,
+    call_count: Arc<AtomicUsize>,
 }
 
 impl MockDataAccess {
@@ -240,9 +318,19 @@ impl MockDataAccess {
             split_names: HashMap::new(),
             config_names: HashMap::new(),
             cached_validations: HashMap::new(),
+            call_count: Arc::new(AtomicUsize::new(0)),
         }
     }
 
+    /// Number of `DataAccess` lookups made so far (shared between clones)
+    pub fn call_count(&self) -> usize {
+        self.call_count.load(Ordering::SeqCst)
+    }
+
+    fn record_call(&self) {
+        self.call_count.fetch_add(1, Ordering::SeqCst);
+    }
+
     pub fn setup_default_data(&mut self) {
         let datasets = vec!["user/repo", "org/dataset", "mock/dataset"];
         let configs = vec!["default", "extra"];
@@ -273,6 +361,7 @@ impl MockDataAccess {
                         search: true,
                         filter: true,
                         statistics: split != &"validation".to_string(),
+                        schema_fingerprint: None,
                     };
                     
                     self.cached_validations.insert(entity.cache_key("split-is-valid"), CachedResponse::new(200, result, 1.0));
@@ -285,6 +374,7 @@ impl MockDataAccess {
                     search: true,
                     filter: true,
                     statistics: true,
+                    schema_fingerprint: None,
                 };
                 self.cached_validations.insert(config_entity.cache_key("config-is-valid"), CachedResponse::new(200, config_result, 1.0));
             }
@@ -302,6 +392,7 @@ impl Default for MockDataAccess {
 
 impl DataAccess for MockDataAccess {
     fn check_successful_response(&self, kind: &str, entity: &EntityIdentifier) -> Result<bool, ValidationError> {
+        self.record_call();
         let key = entity.cache_key(kind);
         self.successful_responses.get(&key)
             .copied()
@@ -311,6 +402,7 @@ impl DataAccess for MockDataAccess {
     }
 
     fn get_parquet_metadata(&self, dataset: &str, config: &str) -> Result<ParquetMetadata, ValidationError> {
+        self.record_call();
         let key = format!("{}:{}", dataset, config);
         self.parquet_metadata.get(&key)
             .cloned()
@@ -320,6 +412,7 @@ impl DataAccess for MockDataAccess {
     }
 
     fn get_split_names(&self, dataset: &str, config: &str) -> Result<Vec<String>, ValidationError> {
+        self.record_call();
         let key = format!("{}:{}", dataset, config);
         self.split_names.get(&key)
             .cloned()
@@ -329,6 +422,7 @@ impl DataAccess for MockDataAccess {
     }
 
     fn get_config_names(&self, dataset: &str) -> Result<Vec<String>, ValidationError> {
+        self.record_call();
         self.config_names.get(dataset)
             .cloned()
             .ok_or_else(|| ValidationError::DataAccessError {
@@ -337,6 +431,7 @@ impl DataAccess for MockDataAccess {
     }
 
     fn get_cached_validation(&self, kind: &str, entity: &EntityIdentifier) -> Result<CachedResponse, ValidationError> {
+        self.record_call();
         let key = entity.cache_key(kind);
         self.cached_validations.get(&key)
             .cloned()
@@ -348,27 +443,117 @@ impl DataAccess for MockDataAccess {
     fn has_indexable_columns(&self, features: &HashMap<String, String>) -> bool {
         features.values().any(|v| v.contains("string") || v.contains("text"))
     }
+
+    fn content_fingerprint(&self) -> Option<String> {
+        // HashMap iteration order is random, so hash sorted renderings of each entry
+        let mut entries: Vec<String> = Vec::new();
+        entries.extend(self.successful_responses.iter().map(|(k, v)| format!("response:{}={}", k, v)));
+        entries.extend(self.parquet_metadata.iter().map(|(k, v)| {
+            let mut features: Vec<_> = v.features.iter().collect();
+            features.sort();
+            format!("metadata:{}={:?}/{:?}", k, features, v.num_rows)
+        }));
+        entries.extend(self.split_names.iter().map(|(k, v)| format!("splits:{}={:?}", k, v)));
+        entries.extend(self.config_names.iter().map(|(k, v)| format!("configs:{}={:?}", k, v)));
+        entries.extend(self.cached_validations.iter().map(|(k, v)| format!("cached:{}={:?}", k, v)));
+        entries.sort();
+        Some(fingerprint_strings(&entries))
+    }
+}
+
+/// Hash a list of strings into a hex fingerprint
+///
+/// FNV-1a over each entry's length and bytes, so the fingerprint is stable
+/// across runs, builds and Rust versions (`DefaultHasher` is not).
+pub fn fingerprint_strings(entries: &[String]) -> String {
+    let hash = entries.iter().fold(FNV1A_OFFSET_BASIS, |hash, entry| {
+        fnv1a(fnv1a(hash, &(entry.len() as u64).to_le_bytes()), entry.as_bytes())
+    });
+    format!("{:016x}", hash)
+}
+
+/// Starting state of an FNV-1a hash
+pub const FNV1A_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+/// Continue an FNV-1a hash over `bytes`
+pub fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
 }
 
 // ============================================================================
 // Validator Implementation
 // ============================================================================
 
+/// Validation results persisted between runs, valid for one content fingerprint
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ValidationCache {
+    pub fingerprint: String,
+    pub entries: HashMap<String, (ValidationResult, f64)>,
+}
+
+impl ValidationCache {
+    /// Load a cache file, returning an empty cache if it is missing, unreadable
+    /// or was written for different data
+    pub fn load(path: &Path, fingerprint: &str) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<ValidationCache>(&content).ok())
+            .filter(|cache| cache.fingerprint == fingerprint)
+            .unwrap_or_else(|| ValidationCache {
+                fingerprint: fingerprint.to_string(),
+                entries: HashMap::new(),
+            })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ValidationError> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content).map_err(|e| ValidationError::CacheError {
+            message: format!("Failed to write validation cache {}: {}", path.display(), e),
+        })
+    }
+}
+
 pub struct DatasetValidator<D: DataAccess> {
     pub data_access: D,
+    cache_path: Option<PathBuf>,
 }
 
 impl<D: DataAccess> DatasetValidator<D> {
     pub fn new(data_access: D) -> Self {
-        Self { data_access }
+        Self { data_access, cache_path: None }
+    }
+
+    /// Persist validation results to `cache_path`, keyed by the data access's
+    /// content fingerprint. Results are reused until the fingerprint changes;
+    /// accesses without a fingerprint are never cached.
+    pub fn with_cache(mut self, cache_path: impl Into<PathBuf>) -> Self {
+        self.cache_path = Some(cache_path.into());
+        self
     }
 
     pub fn validate(&self, entity: &EntityIdentifier, level: ValidationLevel) -> Result<(ValidationResult, f64), ValidationError> {
-        match level {
+        let cache = match (&self.cache_path, self.data_access.content_fingerprint()) {
+            (Some(path), Some(fingerprint)) => Some((path, ValidationCache::load(path, &fingerprint))),
+            _ => None,
+        };
+        let key = entity.cache_key(&format!("{:?}", level));
+        if let Some((_, cache)) = &cache {
+            if let Some(cached) = cache.entries.get(&key) {
+                return Ok(cached.clone());
+            }
+        }
+
+        let outcome = match level {
             ValidationLevel::Split => self.validate_split(entity),
             ValidationLevel::Config => self.validate_config(entity),
             ValidationLevel::Dataset => self.validate_dataset(entity),
+        }?;
+
+        if let Some((path, mut cache)) = cache {
+            cache.entries.insert(key, outcome.clone());
+            cache.save(path)?;
         }
+        Ok(outcome)
     }
 
     fn validate_split(&self, entity: &EntityIdentifier) -> Result<(ValidationResult, f64), ValidationError> {
@@ -390,6 +575,7 @@ impl<D: DataAccess> DatasetValidator<D> {
             Ok(metadata) => {
                 result.filter = true;
                 result.search = self.data_access.has_indexable_columns(&metadata.features);
+                result.schema_fingerprint = Some(metadata.schema_fingerprint());
             }
             Err(_) => {
                 result.filter = false;
@@ -413,6 +599,9 @@ impl<D: DataAccess> DatasetValidator<D> {
         let mut pending = 0;
 
         let splits = self.data_access.get_split_names(dataset, config)?;
+        if let Ok(metadata) = self.data_access.get_parquet_metadata(dataset, config) {
+            result.schema_fingerprint = Some(metadata.schema_fingerprint());
+        }
 
         for split in &splits {
             total += 1;
@@ -505,3 +694,102 @@ pub fn validate_dataset<D: DataAccess>(
     let entity = EntityIdentifier::new_dataset(dataset.to_string());
     validator.validate(&entity, ValidationLevel::Dataset)
 }
+
+/// Disagreement between two data accesses on one entity
+#[derive(Debug, Clone)]
+pub struct AccessDifference {
+    pub entity: EntityIdentifier,
+    /// `+cap`/`-cap` entries from `ValidationResult::diff`, or the error one side hit
+    pub differences: Vec<String>,
+}
+
+/// Validate each entity with two data accesses and report where they disagree
+///
+/// Intended for debugging: run `MockDataAccess` against a real access to spot
+/// where the mock has drifted. Diffs read from `left` to `right`, so `+search`
+/// means only `right` reports search.
+pub fn compare_access<L: DataAccess, R: DataAccess>(
+    left: L,
+    right: R,
+    entities: &[EntityIdentifier],
+) -> Vec<AccessDifference> {
+    let left = DatasetValidator::new(left);
+    let right = DatasetValidator::new(right);
+
+    let mut report = Vec::new();
+    for entity in entities {
+        let level = entity.infer_level();
+        let differences = match (left.validate(entity, level), right.validate(entity, level)) {
+            (Ok((left_result, _)), Ok((right_result, _))) => left_result.diff(&right_result),
+            (Err(e), Ok(_)) => vec![format!("left error: {}", e)],
+            (Ok(_), Err(e)) => vec![format!("right error: {}", e)],
+            (Err(_), Err(_)) => Vec::new(), // Both sides agree the entity can't be validated
+        };
+        if !differences.is_empty() {
+            report.push(AccessDifference { entity: entity.clone(), differences });
+        }
+    }
+    report
+}
+
+// ============================================================================
+// Output Safety
+// ============================================================================
+
+/// Resolve a path that may not exist yet by canonicalizing its nearest existing ancestor
+fn resolve_path(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().unwrap_or_default().join(path)
+    };
+
+    let mut existing = absolute.as_path();
+    let mut remainder = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                remainder.push(name.to_owned());
+                existing = parent;
+            }
+            _ => break,
+        }
+    }
+
+    let mut resolved = existing.canonicalize().unwrap_or_else(|_| existing.to_path_buf());
+    for name in remainder.iter().rev() {
+        resolved.push(name);
+    }
+    resolved
+}
+
+/// Refuse to write output into (or around) the project being analyzed
+///
+/// Writing into a directory that is, or is inside, the source project can clobber
+/// source files; writing into a parent of the project can do the same via phase
+/// subdirectories. Pass `force = true` (the `--force` flag) to skip the check.
+pub fn check_output_dir_safety(project_path: &Path, output_dir: &Path, force: bool) -> Result<(), ValidationError> {
+    if force {
+        return Ok(());
+    }
+
+    let project = resolve_path(project_path);
+    let output = resolve_path(output_dir);
+
+    if output.starts_with(&project) {
+        return Err(ValidationError::InvalidInput(format!(
+            "Output directory {} is inside the analyzed project {}; choose another location or pass --force",
+            output.display(),
+            project.display()
+        )));
+    }
+    if project.starts_with(&output) {
+        return Err(ValidationError::InvalidInput(format!(
+            "Analyzed project {} is inside the output directory {}; choose another location or pass --force",
+            project.display(),
+            output.display()
+        )));
+    }
+
+    Ok(())
+}